@@ -2,7 +2,7 @@ use std::num::NonZero;
 
 use tidec_abi::size_and_align::Size;
 use tidec_builder::body::{
-    CallConv, DefId, Linkage, TirBody, TirBodyKind, TirBodyMetadata, TirItemKind, TirUnit,
+    CallConv, Linkage, TirBody, TirBodyKind, TirBodyMetadata, TirItemKind, TirUnit,
     TirUnitMetadata, UnnamedAddress, Visibility,
 };
 use tidec_builder::syntax::{
@@ -24,7 +24,7 @@ fn build_example_return10<'a>(tir_ctx: &TirCtx<'a>) -> TirUnit<'a> {
     let i32_ty = builder_ctx.i32();
 
     let metadata = TirBodyMetadata {
-        def_id: DefId(0),
+        def_id: tir_ctx.fresh_def_id(),
         name: "main".to_string(),
         kind: TirBodyKind::Item(TirItemKind::Function),
         inlined: false,
@@ -34,6 +34,7 @@ fn build_example_return10<'a>(tir_ctx: &TirCtx<'a>) -> TirUnit<'a> {
         call_conv: CallConv::C,
         is_varargs: false,
         is_declaration: false,
+        entry_point: None,
     };
 
     let bodies = IdxVec::from_raw(vec![TirBody {
@@ -42,12 +43,14 @@ fn build_example_return10<'a>(tir_ctx: &TirCtx<'a>) -> TirUnit<'a> {
             ty: i32_ty,
             mutable: false,
         }]),
+        arg_count: 0,
         locals: IdxVec::new(),
         basic_blocks: IdxVec::from_raw(vec![BasicBlockData {
+            params: vec![],
             statements: vec![Statement::Assign(Box::new((
                 Place {
                     local: RETURN_LOCAL,
-                    projection: vec![],
+                    projection: &[],
                 },
                 RValue::UnaryOp(
                     UnaryOp::Pos,
@@ -81,7 +84,7 @@ fn build_example_printf<'a>(tir_ctx: &TirCtx<'a>) -> TirUnit<'a> {
     let i32_ty = builder_ctx.i32();
 
     // Declare printf (external, variadic)
-    let printf_def_id = DefId(0);
+    let printf_def_id = tir_ctx.fresh_def_id();
     let printf_body = TirBody {
         metadata: TirBodyMetadata {
             def_id: printf_def_id,
@@ -94,6 +97,7 @@ fn build_example_printf<'a>(tir_ctx: &TirCtx<'a>) -> TirUnit<'a> {
             call_conv: CallConv::C,
             is_varargs: true,
             is_declaration: true,
+            entry_point: None,
         },
         ret_and_args: IdxVec::from_raw(vec![
             LocalData {
@@ -105,6 +109,7 @@ fn build_example_printf<'a>(tir_ctx: &TirCtx<'a>) -> TirUnit<'a> {
                 mutable: false,
             },
         ]),
+        arg_count: 1,
         locals: IdxVec::new(),
         basic_blocks: IdxVec::new(),
     };
@@ -115,7 +120,7 @@ fn build_example_printf<'a>(tir_ctx: &TirCtx<'a>) -> TirUnit<'a> {
     // Define main
     let main_body = TirBody {
         metadata: TirBodyMetadata {
-            def_id: DefId(1),
+            def_id: tir_ctx.fresh_def_id(),
             name: "main".to_string(),
             kind: TirBodyKind::Item(TirItemKind::Function),
             inlined: false,
@@ -125,11 +130,13 @@ fn build_example_printf<'a>(tir_ctx: &TirCtx<'a>) -> TirUnit<'a> {
             call_conv: CallConv::C,
             is_varargs: false,
             is_declaration: false,
+            entry_point: None,
         },
         ret_and_args: IdxVec::from_raw(vec![LocalData {
             ty: i32_ty,
             mutable: false,
         }]),
+        arg_count: 0,
         locals: IdxVec::from_raw(vec![LocalData {
             ty: i32_ty,
             mutable: false,
@@ -137,6 +144,7 @@ fn build_example_printf<'a>(tir_ctx: &TirCtx<'a>) -> TirUnit<'a> {
         basic_blocks: IdxVec::from_raw(vec![
             // bb0: call printf, then jump to bb1
             BasicBlockData {
+                params: vec![],
                 statements: vec![],
                 terminator: Terminator::Call {
                     func: Operand::Const(ConstOperand::Value(
@@ -164,17 +172,19 @@ fn build_example_printf<'a>(tir_ctx: &TirCtx<'a>) -> TirUnit<'a> {
                     ],
                     destination: Place {
                         local: Local::new(1),
-                        projection: vec![],
+                        projection: &[],
                     },
                     target: BasicBlock::new(1),
+                    block_args: vec![],
                 },
             },
             // bb1: return 0
             BasicBlockData {
+                params: vec![],
                 statements: vec![Statement::Assign(Box::new((
                     Place {
                         local: RETURN_LOCAL,
-                        projection: vec![],
+                        projection: &[],
                     },
                     RValue::UnaryOp(
                         UnaryOp::Pos,
@@ -206,33 +216,25 @@ fn build_example_printf<'a>(tir_ctx: &TirCtx<'a>) -> TirUnit<'a> {
 /// Tiny argument parser for the tidec demo CLI.
 ///
 /// Usage:
-///   tidec [--emit=object|assembly|llvm-ir|llvm-bc|exe] [--example=printf|return10]
+///   tidec [--emit=object|assembly|llvm-ir|bitcode|executable] [--example=printf|return10]
 fn parse_args() -> (CompileConfig, &'static str) {
     let mut config = CompileConfig::default();
     let mut example = "printf";
 
     for arg in std::env::args().skip(1) {
         if let Some(value) = arg.strip_prefix("--emit=") {
-            config.emit = match value {
-                "object" | "obj" | "o" => EmitKind::Object,
-                "assembly" | "asm" | "s" => EmitKind::Assembly,
-                "llvm-ir" | "ir" | "ll" => EmitKind::LlvmIr,
-                "llvm-bc" | "bc" => EmitKind::LlvmBitcode,
-                "exe" | "executable" => EmitKind::Executable,
-                other => {
-                    eprintln!("Unknown emit kind: {other}");
-                    eprintln!("Valid options: object, assembly, llvm-ir, llvm-bc, exe");
+            config.emit = match value.parse::<EmitKind>() {
+                Ok(emit) => emit,
+                Err(err) => {
+                    eprintln!("{err}");
                     std::process::exit(1);
                 }
             };
         } else if let Some(value) = arg.strip_prefix("--backend=") {
-            config.backend = match value {
-                "llvm" => BackendKind::Llvm,
-                "cranelift" => BackendKind::Cranelift,
-                "gcc" => BackendKind::Gcc,
-                other => {
-                    eprintln!("Unknown backend: {other}");
-                    eprintln!("Valid options: llvm, cranelift, gcc");
+            config.backend = match value.parse::<BackendKind>() {
+                Ok(backend) => backend,
+                Err(err) => {
+                    eprintln!("{err}");
                     std::process::exit(1);
                 }
             };
@@ -253,7 +255,7 @@ fn parse_args() -> (CompileConfig, &'static str) {
             println!("  tidec [OPTIONS]");
             println!();
             println!("Options:");
-            println!("  --emit=<kind>       Output kind: object (default), assembly, llvm-ir, llvm-bc, exe");
+            println!("  --emit=<kind>       Output kind: object (default), assembly, llvm-ir, bitcode, executable");
             println!("  --backend=<name>    Backend: llvm (default), cranelift, gcc");
             println!("  --example=<name>    Example program: printf (default), return10");
             println!("  -h, --help          Show this help message");
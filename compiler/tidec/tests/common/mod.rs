@@ -13,14 +13,24 @@
 //! cargo test -p tide_tests -- --test-threads=1
 //! ```
 
+use std::num::NonZero;
 use std::path::PathBuf;
 use std::process::Command;
 use std::sync::Mutex;
 
 use tidec_abi::target::{BackendKind, TirTarget};
+use tidec_builder::BuilderCtx;
 use tidec_driver::{compile_unit_with_ctx, CompileConfig};
-use tidec_tir::body::TirUnit;
+use tidec_tir::body::{
+    CallConv, DefId, Linkage, TirBody, TirBodyKind, TirBodyMetadata, TirItemKind, TirUnit,
+    TirUnitMetadata, UnnamedAddress, Visibility,
+};
 use tidec_tir::ctx::{EmitKind, TirArena, TirArgs, TirCtx};
+use tidec_tir::syntax::{
+    BasicBlockData, ConstOperand, ConstScalar, ConstValue, LocalData, Operand, Place, RValue,
+    RawScalarValue, Statement, Terminator, UnaryOp, RETURN_LOCAL,
+};
+use tidec_utils::index_vec::IdxVec;
 
 /// Global mutex to serialize tests that change the current directory.
 pub static TEST_MUTEX: Mutex<()> = Mutex::new(());
@@ -60,6 +70,22 @@ impl TestRunner {
         self.test_dir.join("main.o")
     }
 
+    /// Get the path for the assembly file.
+    /// Note: Like the object file, it is named after the module ("main").
+    pub fn assembly_path(&self) -> PathBuf {
+        self.test_dir.join("main.s")
+    }
+
+    /// Get the path for the LLVM IR file, named after the module ("main").
+    pub fn llvm_ir_path(&self) -> PathBuf {
+        self.test_dir.join("main.ll")
+    }
+
+    /// Get the path for the LLVM bitcode file, named after the module ("main").
+    pub fn bitcode_path(&self) -> PathBuf {
+        self.test_dir.join("main.bc")
+    }
+
     /// Get the path for the executable.
     pub fn executable_path(&self) -> PathBuf {
         self.test_dir.join(&self.test_name)
@@ -70,27 +96,40 @@ impl TestRunner {
     /// Note: This acquires a global mutex because changing the current directory
     /// affects all threads in the process.
     pub fn compile<'a>(&self, tir_ctx: TirCtx<'a>, tir_unit: TirUnit<'a>) {
+        self.compile_with_config(tir_ctx, tir_unit, &CompileConfig::llvm_object());
+
+        // Verify the object file was created
+        assert!(
+            self.object_path().exists(),
+            "Object file was not created at {:?}",
+            self.object_path()
+        );
+    }
+
+    /// Compile TIR with the given configuration, writing the output into the
+    /// test directory.
+    ///
+    /// The emit kind of `tir_ctx` must match `config.emit`, because the
+    /// backend reads it from the context.
+    pub fn compile_with_config<'a>(
+        &self,
+        tir_ctx: TirCtx<'a>,
+        tir_unit: TirUnit<'a>,
+        config: &CompileConfig,
+    ) {
         // Acquire the mutex to prevent concurrent directory changes
         let _guard = TEST_MUTEX.lock().expect("Failed to acquire test mutex");
 
-        // Change to test directory so the object file is written there
+        // Change to test directory so the output file is written there
         let original_dir = std::env::current_dir().expect("Failed to get current directory");
         std::env::set_current_dir(&self.test_dir).expect("Failed to change to test directory");
 
         // Compile via tidec_driver
-        let config = CompileConfig::llvm_object();
-        compile_unit_with_ctx(tir_ctx, tir_unit, &config)
+        compile_unit_with_ctx(tir_ctx, tir_unit, config)
             .expect("Compilation via tidec_driver failed");
 
         // Change back
         std::env::set_current_dir(original_dir).expect("Failed to restore directory");
-
-        // Verify the object file was created
-        assert!(
-            self.object_path().exists(),
-            "Object file was not created at {:?}",
-            self.object_path()
-        );
     }
 
     /// Link the object file into an executable.
@@ -179,10 +218,16 @@ pub struct TestContext<'ctx> {
 impl<'ctx> TestContext<'ctx> {
     /// Create a new test context.
     pub fn new() -> Self {
+        Self::with_emit_kind(EmitKind::Object)
+    }
+
+    /// Create a new test context emitting the given kind of output.
+    pub fn with_emit_kind(emit_kind: EmitKind) -> Self {
         Self {
             target: TirTarget::new(BackendKind::Llvm),
             arguments: TirArgs {
-                emit_kind: EmitKind::Object,
+                emit_kind,
+                ..Default::default()
             },
             arena: TirArena::default(),
         }
@@ -194,3 +239,61 @@ impl Default for TestContext<'_> {
         Self::new()
     }
 }
+
+/// Create a simple main function that returns 0.
+pub fn create_return_zero<'a>(tir_ctx: &TirCtx<'a>) -> TirUnit<'a> {
+    let builder_ctx = BuilderCtx::new(*tir_ctx);
+    let i32_ty = builder_ctx.i32();
+
+    let main_metadata = TirBodyMetadata {
+        def_id: DefId(0),
+        name: "main".to_string(),
+        kind: TirBodyKind::Item(TirItemKind::Function),
+        inlined: false,
+        linkage: Linkage::External,
+        visibility: Visibility::Default,
+        unnamed_address: UnnamedAddress::None,
+        call_conv: CallConv::C,
+        is_varargs: false,
+        is_declaration: false,
+        entry_point: None,
+    };
+
+    let main_body = TirBody {
+        metadata: main_metadata,
+        ret_and_args: IdxVec::from_raw(vec![LocalData {
+            ty: i32_ty,
+            mutable: false,
+        }]),
+        arg_count: 0,
+        locals: IdxVec::new(),
+        basic_blocks: IdxVec::from_raw(vec![BasicBlockData {
+            params: vec![],
+            statements: vec![Statement::Assign(Box::new((
+                Place {
+                    local: RETURN_LOCAL,
+                    projection: &[],
+                },
+                RValue::UnaryOp(
+                    UnaryOp::Pos,
+                    Operand::Const(ConstOperand::Value(
+                        ConstValue::Scalar(ConstScalar::Value(RawScalarValue {
+                            data: 0u128,
+                            size: NonZero::new(4).unwrap(),
+                        })),
+                        i32_ty,
+                    )),
+                ),
+            )))],
+            terminator: Terminator::Return,
+        }]),
+    };
+
+    TirUnit {
+        metadata: TirUnitMetadata {
+            unit_name: "main".to_string(),
+        },
+        globals: IdxVec::new(),
+        bodies: IdxVec::from_raw(vec![main_body]),
+    }
+}
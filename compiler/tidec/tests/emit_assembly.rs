@@ -0,0 +1,29 @@
+//! Integration test: emit textual assembly instead of an object file.
+
+mod common;
+
+use common::{create_return_zero, TestContext, TestRunner};
+use tidec_driver::CompileConfig;
+use tidec_tir::ctx::{EmitKind, InternCtx, TirCtx};
+
+/// Test that `EmitKind::Assembly` writes a `.s` file defining `main`.
+#[test]
+fn test_emit_assembly() {
+    let runner = TestRunner::new("emit_assembly");
+
+    let test_ctx = TestContext::with_emit_kind(EmitKind::Assembly);
+    let intern_ctx = InternCtx::new(&test_ctx.arena);
+    let tir_ctx = TirCtx::new(&test_ctx.target, &test_ctx.arguments, &intern_ctx);
+
+    let tir_unit = create_return_zero(&tir_ctx);
+    runner.compile_with_config(tir_ctx, tir_unit, &CompileConfig::llvm_assembly());
+
+    let asm =
+        std::fs::read_to_string(runner.assembly_path()).expect("Assembly file was not created");
+    // On macOS the symbol carries a leading underscore (`_main:`).
+    assert!(
+        asm.lines().any(|line| line.ends_with("main:")),
+        "Expected a `main` label in the assembly, got:\n{}",
+        asm
+    );
+}
@@ -0,0 +1,49 @@
+//! Integration test: emit LLVM bitcode and textual LLVM IR instead of an
+//! object file.
+
+mod common;
+
+use common::{create_return_zero, TestContext, TestRunner};
+use tidec_driver::CompileConfig;
+use tidec_tir::ctx::{EmitKind, InternCtx, TirCtx};
+
+/// Test that `EmitKind::LlvmBitcode` writes a `.bc` file starting with the
+/// bitcode magic bytes.
+#[test]
+fn test_emit_bitcode() {
+    let runner = TestRunner::new("emit_bitcode");
+
+    let test_ctx = TestContext::with_emit_kind(EmitKind::LlvmBitcode);
+    let intern_ctx = InternCtx::new(&test_ctx.arena);
+    let tir_ctx = TirCtx::new(&test_ctx.target, &test_ctx.arguments, &intern_ctx);
+
+    let tir_unit = create_return_zero(&tir_ctx);
+    runner.compile_with_config(tir_ctx, tir_unit, &CompileConfig::llvm_bitcode());
+
+    let bitcode = std::fs::read(runner.bitcode_path()).expect("Bitcode file was not created");
+    assert!(
+        bitcode.starts_with(b"BC\xC0\xDE"),
+        "Expected the bitcode magic bytes, got: {:02x?}",
+        &bitcode[..bitcode.len().min(4)]
+    );
+}
+
+/// Test that `EmitKind::LlvmIr` writes a `.ll` file defining `main`.
+#[test]
+fn test_emit_llvm_ir() {
+    let runner = TestRunner::new("emit_llvm_ir");
+
+    let test_ctx = TestContext::with_emit_kind(EmitKind::LlvmIr);
+    let intern_ctx = InternCtx::new(&test_ctx.arena);
+    let tir_ctx = TirCtx::new(&test_ctx.target, &test_ctx.arguments, &intern_ctx);
+
+    let tir_unit = create_return_zero(&tir_ctx);
+    runner.compile_with_config(tir_ctx, tir_unit, &CompileConfig::llvm_ir());
+
+    let ir = std::fs::read_to_string(runner.llvm_ir_path()).expect("LLVM IR file was not created");
+    assert!(
+        ir.contains("define i32 @main("),
+        "Expected a `main` definition in the LLVM IR, got:\n{}",
+        ir
+    );
+}
@@ -39,6 +39,7 @@ fn create_printf_hello<'a>(tir_ctx: &TirCtx<'a>) -> TirUnit<'a> {
         call_conv: CallConv::C,
         is_varargs: true,
         is_declaration: true,
+        entry_point: None,
     };
 
     let printf_body = TirBody {
@@ -53,6 +54,7 @@ fn create_printf_hello<'a>(tir_ctx: &TirCtx<'a>) -> TirUnit<'a> {
                 mutable: false,
             },
         ]),
+        arg_count: 1,
         locals: IdxVec::new(),
         basic_blocks: IdxVec::new(),
     };
@@ -73,9 +75,11 @@ fn create_printf_hello<'a>(tir_ctx: &TirCtx<'a>) -> TirUnit<'a> {
         call_conv: CallConv::C,
         is_varargs: false,
         is_declaration: false,
+        entry_point: None,
     };
 
     let bb0 = BasicBlockData {
+        params: vec![],
         statements: vec![],
         terminator: Terminator::Call {
             func: Operand::Const(ConstOperand::Value(
@@ -94,17 +98,19 @@ fn create_printf_hello<'a>(tir_ctx: &TirCtx<'a>) -> TirUnit<'a> {
             ))],
             destination: Place {
                 local: Local::new(1),
-                projection: vec![],
+                projection: &[],
             },
             target: BasicBlock::new(1),
+            block_args: vec![],
         },
     };
 
     let bb1 = BasicBlockData {
+        params: vec![],
         statements: vec![Statement::Assign(Box::new((
             Place {
                 local: RETURN_LOCAL,
-                projection: vec![],
+                projection: &[],
             },
             RValue::UnaryOp(
                 UnaryOp::Pos,
@@ -126,6 +132,7 @@ fn create_printf_hello<'a>(tir_ctx: &TirCtx<'a>) -> TirUnit<'a> {
             ty: i32_ty,
             mutable: false,
         }]),
+        arg_count: 0,
         locals: IdxVec::from_raw(vec![LocalData {
             ty: i32_ty,
             mutable: false,
@@ -33,6 +33,7 @@ fn create_return_255<'a>(tir_ctx: &TirCtx<'a>) -> TirUnit<'a> {
         call_conv: CallConv::C,
         is_varargs: false,
         is_declaration: false,
+        entry_point: None,
     };
 
     let main_body = TirBody {
@@ -41,12 +42,14 @@ fn create_return_255<'a>(tir_ctx: &TirCtx<'a>) -> TirUnit<'a> {
             ty: i32_ty,
             mutable: false,
         }]),
+        arg_count: 0,
         locals: IdxVec::new(),
         basic_blocks: IdxVec::from_raw(vec![BasicBlockData {
+            params: vec![],
             statements: vec![Statement::Assign(Box::new((
                 Place {
                     local: RETURN_LOCAL,
-                    projection: vec![],
+                    projection: &[],
                 },
                 RValue::UnaryOp(
                     UnaryOp::Pos,
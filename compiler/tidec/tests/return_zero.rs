@@ -2,75 +2,8 @@
 
 mod common;
 
-use std::num::NonZero;
-
-use common::{TestContext, TestRunner};
-use tidec_builder::BuilderCtx;
-use tidec_tir::body::{
-    CallConv, DefId, Linkage, TirBody, TirBodyKind, TirBodyMetadata, TirItemKind, TirUnit,
-    TirUnitMetadata, UnnamedAddress, Visibility,
-};
+use common::{create_return_zero, TestContext, TestRunner};
 use tidec_tir::ctx::{InternCtx, TirCtx};
-use tidec_tir::syntax::{
-    BasicBlockData, ConstOperand, ConstScalar, ConstValue, LocalData, Operand, Place, RValue,
-    RawScalarValue, Statement, Terminator, UnaryOp, RETURN_LOCAL,
-};
-use tidec_utils::index_vec::IdxVec;
-
-/// Create a simple main function that returns 0.
-fn create_return_zero<'a>(tir_ctx: &TirCtx<'a>) -> TirUnit<'a> {
-    let builder_ctx = BuilderCtx::new(*tir_ctx);
-    let i32_ty = builder_ctx.i32();
-
-    let main_metadata = TirBodyMetadata {
-        def_id: DefId(0),
-        name: "main".to_string(),
-        kind: TirBodyKind::Item(TirItemKind::Function),
-        inlined: false,
-        linkage: Linkage::External,
-        visibility: Visibility::Default,
-        unnamed_address: UnnamedAddress::None,
-        call_conv: CallConv::C,
-        is_varargs: false,
-        is_declaration: false,
-    };
-
-    let main_body = TirBody {
-        metadata: main_metadata,
-        ret_and_args: IdxVec::from_raw(vec![LocalData {
-            ty: i32_ty,
-            mutable: false,
-        }]),
-        locals: IdxVec::new(),
-        basic_blocks: IdxVec::from_raw(vec![BasicBlockData {
-            statements: vec![Statement::Assign(Box::new((
-                Place {
-                    local: RETURN_LOCAL,
-                    projection: vec![],
-                },
-                RValue::UnaryOp(
-                    UnaryOp::Pos,
-                    Operand::Const(ConstOperand::Value(
-                        ConstValue::Scalar(ConstScalar::Value(RawScalarValue {
-                            data: 0u128,
-                            size: NonZero::new(4).unwrap(),
-                        })),
-                        i32_ty,
-                    )),
-                ),
-            )))],
-            terminator: Terminator::Return,
-        }]),
-    };
-
-    TirUnit {
-        metadata: TirUnitMetadata {
-            unit_name: "main".to_string(),
-        },
-        globals: IdxVec::new(),
-        bodies: IdxVec::from_raw(vec![main_body]),
-    }
-}
 
 /// Test that a program returning 0 compiles and runs correctly.
 #[test]
@@ -1,4 +1,6 @@
-use crate::layout::TyAndLayout;
+use crate::layout::{BackendRepr, TyAndLayout};
+use crate::size_and_align::Size;
+use crate::target::TirTarget;
 
 /// Describes the full application binary interface (ABI) of a function.
 ///
@@ -67,6 +69,7 @@ impl<'ctx, T> ArgAbi<'ctx, T> {
 //     /// The minimum alignment of the pointee, if any.
 //     pub pointee_align: Option<Align>,
 // }
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum PassMode {
     /// The argument is ignored (e.g., a zero-sized type).
     Ignore,
@@ -96,4 +99,74 @@ pub enum PassMode {
     // - `meta_attrs`: Metadata attributes for optimization hints.
     // - `on_stack`: Whether the argument must be passed on the stack.
     Indirect,
+    /// The argument is an aggregate passed by value in integer registers,
+    /// coerced to the integer type described by the [`CastTarget`].
+    ///
+    /// # Example
+    /// On a 64-bit target, a `{ i32, i32 }` struct is passed as a single
+    /// `i64` and a `{ i64, i32 }` struct as `{ i64, i32 }`.
+    Cast(CastTarget),
+}
+
+/// The integer registers that a small aggregate is coerced to when it is
+/// passed with [`PassMode::Cast`].
+///
+/// The aggregate is split into `unit`-sized integer chunks, the last of which
+/// is narrowed to the remaining bytes, so the coerced value has exactly the
+/// size of the aggregate and can be loaded from and stored to its place.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CastTarget {
+    /// The size of an integer register.
+    pub unit: Size,
+    /// The size of the coerced value, that is, the size of the aggregate.
+    pub size: Size,
+}
+
+impl CastTarget {
+    /// Returns the sizes of the integer chunks of the coerced value, in order.
+    pub fn chunks(&self) -> Vec<Size> {
+        let unit = self.unit.bytes();
+        let size = self.size.bytes();
+        (0..size.div_ceil(unit))
+            .map(|i| Size::from_bytes(unit.min(size - i * unit)))
+            .collect()
+    }
+}
+
+/// Classifies how a value with the given layout is passed across a C ABI
+/// function boundary on `target`.
+///
+/// - Zero-sized values are [`PassMode::Ignore`]d.
+/// - Scalars and scalar pairs are passed [`PassMode::Direct`], the latter
+///   in two registers, and so are SIMD vectors, in a vector register.
+/// - Aggregates that fit in two pointer-sized registers are [`PassMode::Cast`]
+///   to integers, as the System V x86-64 and AAPCS64 ABIs do for aggregates
+///   of integers; larger ones are passed [`PassMode::Indirect`]ly through a
+///   pointer to a caller-owned copy.
+///
+/// The same classification applies to return values, where `Indirect` means
+/// the caller passes a hidden `sret` pointer to the result slot.
+// TODO(bruzzone): Pass aggregates of floats in floating point registers
+// (the SSE class of System V x86-64, HFAs on AAPCS64).
+pub fn classify_arg<T>(target: &TirTarget, layout: &TyAndLayout<'_, T>) -> PassMode {
+    if layout.is_zst() {
+        return PassMode::Ignore;
+    }
+
+    match layout.backend_repr {
+        BackendRepr::Scalar(_) | BackendRepr::ScalarPair(_, _) | BackendRepr::Vector { .. } => {
+            PassMode::Direct
+        }
+        BackendRepr::Memory { .. } => {
+            let unit = target.data_layout.pointer_size;
+            if layout.size.bytes() <= unit.bytes() * 2 {
+                PassMode::Cast(CastTarget {
+                    unit,
+                    size: layout.size,
+                })
+            } else {
+                PassMode::Indirect
+            }
+        }
+    }
 }
@@ -17,6 +17,17 @@ pub struct TyAndLayout<'ctx, T> {
     pub layout: crate::Layout<'ctx>,
 }
 
+impl<'ctx, T> TyAndLayout<'ctx, T> {
+    /// Returns the stride of the type: the distance in bytes between two
+    /// consecutive elements of an array of this type.
+    ///
+    /// This is the size rounded up to the ABI alignment, so it differs from
+    /// the size for types whose size is not a multiple of their alignment.
+    pub fn stride(&self) -> Size {
+        self.layout.stride()
+    }
+}
+
 impl<'ctx, T> std::ops::Deref for TyAndLayout<'ctx, T> {
     type Target = crate::Layout<'ctx>;
 
@@ -25,12 +36,12 @@ impl<'ctx, T> std::ops::Deref for TyAndLayout<'ctx, T> {
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 /// Represents the layout of a type in the target architecture.
 ///
-/// This struct contains the size, alignment, and backend representation
-/// of a type, which is essential for code generation and memory layout decisions.
-// TODO(bruzzone): Add fields and variants (tag union, struct, etc.).
+/// This struct contains the size, alignment, field placement and backend
+/// representation of a type, which is essential for code generation and
+/// memory layout decisions.
 pub struct Layout {
     /// The size of the type in bytes.
     pub size: Size,
@@ -45,33 +56,116 @@ pub struct Layout {
     /// Therefore, even when `backend_repr` is not `Memory`, you must still consider
     /// `fields` and `variants` to fully understand and access all parts of the layout.
     pub backend_repr: BackendRepr,
+    /// Where the fields of the type are placed, relative to its start.
+    pub fields: FieldsShape,
+    /// How the variants of the type are told apart.
+    pub variants: Variants,
 }
 
 impl Layout {
     /// Returns true if the layout represents a zero-sized type.
+    ///
+    /// Unsized layouts are never ZSTs, even though their static size is 0.
     pub fn is_zst(&self) -> bool {
         match self.backend_repr {
-            BackendRepr::Scalar(_) /* | BackendRepr::ScalarPair(_, _) */ => false,
-            BackendRepr::Memory => self.size.bytes() == 0,
+            BackendRepr::Scalar(_) | BackendRepr::ScalarPair(_, _) | BackendRepr::Vector { .. } => {
+                false
+            }
+            BackendRepr::Memory { sized } => sized && self.size.bytes() == 0,
         }
     }
 
+    /// Returns true if the size of the type is not known at compile time.
+    ///
+    /// The `size` of such a layout is only the size of its statically known
+    /// prefix, so values of the type can only be handled behind a pointer.
+    pub fn is_unsized(&self) -> bool {
+        matches!(self.backend_repr, BackendRepr::Memory { sized: false })
+    }
+
+    /// Returns the size rounded up to the ABI alignment, that is the spacing
+    /// of consecutive array elements of this layout.
+    pub fn stride(&self) -> Size {
+        self.size.align_to(self.align.abi)
+    }
+
     pub fn is_bool(&self) -> bool {
         matches!(self.backend_repr, BackendRepr::Scalar(Primitive::U8))
     }
 
+    /// Returns true if values of this layout are handled as a single backend
+    /// value: a scalar, or a SIMD vector held in a vector register.
     pub fn is_immediate(&self) -> bool {
         match self.backend_repr {
-            BackendRepr::Scalar(_)  => true,
-            BackendRepr::Memory /* | BackendRepr::ScalarPair(_, _) */ => false,
+            BackendRepr::Scalar(_) | BackendRepr::Vector { .. } => true,
+            BackendRepr::Memory { .. } | BackendRepr::ScalarPair(_, _) => false,
         }
     }
 
     pub fn is_memory(&self) -> bool {
-        matches!(self.backend_repr, BackendRepr::Memory)
+        matches!(self.backend_repr, BackendRepr::Memory { .. })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+/// Describes where the fields of a type are placed.
+///
+/// The offsets are computed once with the layout, so that projecting a field
+/// is a lookup rather than a walk over the preceding fields.
+pub enum FieldsShape {
+    /// Scalars, vectors and other types without fields to project. The
+    /// payload of an enum is reached through a `Downcast` instead.
+    Primitive,
+    /// `count` fields of the same type, `stride` bytes apart.
+    Array { stride: Size, count: u64 },
+    /// Fields at arbitrary offsets, in declaration order, as for structs.
+    Arbitrary { offsets: Vec<Size> },
+}
+
+impl FieldsShape {
+    /// Returns the number of fields.
+    pub fn count(&self) -> u64 {
+        match self {
+            FieldsShape::Primitive => 0,
+            FieldsShape::Array { count, .. } => *count,
+            FieldsShape::Arbitrary { offsets } => offsets.len() as u64,
+        }
+    }
+
+    /// Returns the offset of the field `i`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `i` is out of bounds.
+    pub fn offset(&self, i: usize) -> Size {
+        assert!(
+            (i as u64) < self.count(),
+            "field {i} is out of bounds for {self:?}"
+        );
+        match self {
+            FieldsShape::Primitive => unreachable!(),
+            // The whole array fits in its size, so this cannot overflow.
+            FieldsShape::Array { stride, .. } => Size::from_bytes(stride.bytes() * i as u64),
+            FieldsShape::Arbitrary { offsets } => offsets[i],
+        }
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+/// Describes how the variants of a type are told apart.
+pub enum Variants {
+    /// Every type but an enum has a single variant, which needs no tag.
+    Single,
+    /// An enum whose variant is stored in an explicit integer tag at offset
+    /// 0, as in a C tagged union. Variant `i` is tagged with the value `i`.
+    Tagged {
+        /// The integer type of the tag.
+        tag: Primitive,
+        /// The offset of the payload of every variant, past the tag.
+        payload_offset: Size,
+    },
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 /// Represents how values are passed to the backend during code generation.
 ///
@@ -91,15 +185,22 @@ pub enum BackendRepr {
     Scalar(Primitive),
     /// The value is represented as a memory reference, such as a pointer or
     /// a reference to a struct or array.
-    Memory,
-    // Scalar pair, which is a pair of scalars. It is often used for
-    // returning multiple values from a function. This allows the backend to
-    // optimize the representation of multiple return values. Additionally,
-    // it is used for "fat pointers", which are pointers that include extra
-    // metadata, such as a pointer to a slice or a trait object. For example,
-    // a slice `&str` is represented as a pair of a pointer to the data
-    // and a length.
-    // ScalarPair(Primitive, Primitive),
+    ///
+    /// `sized` is false for types whose size is not known at compile time,
+    /// such as `TirTy::Metadata`.
+    Memory { sized: bool },
+    /// The value is represented as a pair of scalars, such as a two-field
+    /// aggregate like `(i64, i64)`. It is often used for returning multiple
+    /// values from a function, as the pair can be passed and returned in two
+    /// registers instead of through memory. Additionally, it is used for
+    /// "fat pointers", which are pointers that include extra metadata, such as
+    /// a pointer to a slice or a trait object. For example, a slice `&str` is
+    /// represented as a pair of a pointer to the data and a length.
+    ScalarPair(Primitive, Primitive),
+    /// The value is represented as a SIMD vector of `count` elements of the
+    /// same primitive type, such as `<4 x i32>`. The backend keeps it in a
+    /// vector register rather than in memory.
+    Vector { element: Primitive, count: u64 },
 }
 
 impl BackendRepr {
@@ -107,15 +208,39 @@ impl BackendRepr {
     pub fn to_primitive(&self) -> Primitive {
         match self {
             BackendRepr::Scalar(p) => *p,
-            // BackendRepr::ScalarPair(p1, p2) => Some((*p1, *p2)),
-            BackendRepr::Memory => {
+            BackendRepr::ScalarPair(_, _) => {
+                panic!("ScalarPair backend representation does not have a single primitive type")
+            }
+            BackendRepr::Vector { .. } => {
+                panic!("Vector backend representation does not have a single primitive type")
+            }
+            BackendRepr::Memory { .. } => {
                 panic!("Memory backend representation does not have a primitive type")
             }
         }
     }
+
+    /// Returns the representation with every signed integer replaced by the
+    /// unsigned integer of the same size.
+    ///
+    /// Backend values carry no signedness, so two representations that only
+    /// differ in it are held and passed the same way.
+    pub fn erase_signedness(self) -> BackendRepr {
+        match self {
+            BackendRepr::Scalar(p) => BackendRepr::Scalar(p.to_unsigned()),
+            BackendRepr::ScalarPair(a, b) => {
+                BackendRepr::ScalarPair(a.to_unsigned(), b.to_unsigned())
+            }
+            BackendRepr::Vector { element, count } => BackendRepr::Vector {
+                element: element.to_unsigned(),
+                count,
+            },
+            BackendRepr::Memory { sized } => BackendRepr::Memory { sized },
+        }
+    }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 /// Represents primitive types that can be used in the backend representation.
 pub enum Primitive {
     /// A signed integer type.
@@ -168,4 +293,17 @@ impl Primitive {
     pub fn is_pointer(&self) -> bool {
         matches!(self, Primitive::Pointer(_))
     }
+
+    /// Returns the unsigned integer of the same size for signed integers, and
+    /// `self` otherwise.
+    pub fn to_unsigned(self) -> Primitive {
+        match self {
+            Primitive::I8 => Primitive::U8,
+            Primitive::I16 => Primitive::U16,
+            Primitive::I32 => Primitive::U32,
+            Primitive::I64 => Primitive::U64,
+            Primitive::I128 => Primitive::U128,
+            p => p,
+        }
+    }
 }
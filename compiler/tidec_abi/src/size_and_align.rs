@@ -38,8 +38,11 @@ impl Size {
 
     /// Rounds `bits` up to the next-higher byte boundary, if `bits` is
     /// not a multiple of 8.
+    ///
+    /// A bit count that does not fit in a `u64` saturates to `u64::MAX`
+    /// bits instead of panicking.
     pub fn from_bits(bits: impl TryInto<u64>) -> Size {
-        let bits = bits.try_into().ok().unwrap();
+        let bits = bits.try_into().unwrap_or(u64::MAX);
         // Avoid potential overflow from `bits + 7`.
         Size(bits / 8 + (bits % 8).div_ceil(8))
     }
@@ -50,9 +53,71 @@ impl Size {
         Size(bytes.try_into().ok().unwrap())
     }
 
+    /// Creates a `Size` from a byte count in const contexts.
+    ///
+    /// Unlike [`from_bytes`](Self::from_bytes), it only takes a `u64`, so
+    /// it can be used to define layout constants.
+    #[inline]
+    pub const fn from_bytes_const(bytes: u64) -> Size {
+        Size(bytes)
+    }
+
+    /// Rounds `bits` up to the next-higher byte boundary in const contexts,
+    /// like [`from_bits`](Self::from_bits) does for a `u64`.
+    #[inline]
+    pub const fn from_bits_const(bits: u64) -> Size {
+        Size(bits / 8 + (bits % 8).div_ceil(8))
+    }
+
+    /// Creates a `Size` from a byte count, returning `None` if the count
+    /// does not fit in a `u64` or if the size in bits would overflow a `u64`.
+    ///
+    /// Every `Size` returned by this function can be safely converted with
+    /// [`bits`](Self::bits).
+    #[inline]
+    pub fn from_bytes_checked(bytes: impl TryInto<u64>) -> Option<Size> {
+        let bytes = bytes.try_into().ok()?;
+        bytes.checked_mul(8)?;
+        Some(Size(bytes))
+    }
+
+    /// Adds two sizes, returning `None` on overflow.
+    #[inline]
+    pub fn checked_add(self, other: Size) -> Option<Size> {
+        Size::from_bytes_checked(self.0.checked_add(other.0)?)
+    }
+
+    /// Multiplies a size by `count`, returning `None` on overflow.
+    #[inline]
+    pub fn checked_mul(self, count: u64) -> Option<Size> {
+        Size::from_bytes_checked(self.0.checked_mul(count)?)
+    }
+
+    /// Rounds the size up to a multiple of `align`, returning `None` on
+    /// overflow. A zero alignment leaves the size unchanged.
+    #[inline]
+    pub fn checked_align_to(self, align: Align) -> Option<Size> {
+        let align = align.bytes();
+        if align == 0 {
+            return Some(self);
+        }
+        Size::from_bytes_checked(self.0.checked_add(align - 1)? & !(align - 1))
+    }
+
+    /// Rounds the size up to a multiple of `align`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the rounded size overflows.
+    #[inline]
+    pub fn align_to(self, align: Align) -> Size {
+        self.checked_align_to(align)
+            .unwrap_or_else(|| panic!("{self:?} overflows when aligned to {align:?}"))
+    }
+
     #[inline]
     /// Returns the size in bytes.
-    pub fn bytes(&self) -> u64 {
+    pub const fn bytes(&self) -> u64 {
         self.0
     }
 
@@ -69,6 +134,36 @@ impl Size {
             .checked_mul(8)
             .unwrap_or_else(|| overflow(self.bytes()))
     }
+
+    /// Truncates `value` to the low `self.bits()` bits.
+    ///
+    /// The size must be at most 16 bytes.
+    #[inline]
+    pub fn truncate(self, value: u128) -> u128 {
+        let size = self.bits();
+        assert!(size <= 128, "Size::truncate: {size} bits exceed 128");
+        if size == 0 {
+            return 0;
+        }
+        let shift = 128 - size;
+        // Shift the unwanted bits out, then back.
+        (value << shift) >> shift
+    }
+
+    /// Sign-extends the low `self.bits()` bits of `value` to an `i128`.
+    ///
+    /// The size must be at most 16 bytes.
+    #[inline]
+    pub fn sign_extend(self, value: u128) -> i128 {
+        let size = self.bits();
+        assert!(size <= 128, "Size::sign_extend: {size} bits exceed 128");
+        if size == 0 {
+            return 0;
+        }
+        let shift = 128 - size;
+        // Shift the sign bit into the top bit, then arithmetic-shift back.
+        ((value << shift) as i128) >> shift
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -1,5 +1,9 @@
+use std::fmt;
+use std::str::FromStr;
+
 use tracing::{info, instrument};
 
+use crate::layout::Primitive;
 use crate::size_and_align::{AbiAndPrefAlign, Size};
 
 #[derive(Debug)]
@@ -31,6 +35,20 @@ impl TirTarget {
         }
     }
 
+    /// Creates a target for the given triple.
+    ///
+    /// The floating point types supported by the data layout are derived
+    /// from the triple's architecture (see [`TargetTriple::supported_float_types`]).
+    pub fn with_triple(codegen_backend: BackendKind, target_triple: TargetTriple) -> Self {
+        let mut data_layout = TargetDataLayout::new();
+        data_layout.supported_float_types = target_triple.supported_float_types();
+        TirTarget {
+            data_layout,
+            codegen_backend,
+            target_triple: Some(target_triple),
+        }
+    }
+
     // TODO: make it better. Perhaps by using a specific TargetDataLayout for each
     // compiler backend.
     pub fn data_layout_string(&self) -> String {
@@ -69,7 +87,7 @@ impl TirTarget {
     }
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 /// The backend kind for code generation.
 ///
 /// This enum represents the different backends that can be used for code generation.
@@ -84,6 +102,57 @@ pub enum BackendKind {
     Gcc,
 }
 
+impl BackendKind {
+    /// Returns the name of the backend, as accepted by [`BackendKind::from_str`].
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BackendKind::Llvm => "llvm",
+            BackendKind::Cranelift => "cranelift",
+            BackendKind::Gcc => "gcc",
+        }
+    }
+}
+
+impl FromStr for BackendKind {
+    type Err = TargetError;
+
+    /// Parses a backend from its name, ignoring ASCII case.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "llvm" => Ok(BackendKind::Llvm),
+            "cranelift" => Ok(BackendKind::Cranelift),
+            "gcc" => Ok(BackendKind::Gcc),
+            _ => Err(TargetError::UnknownBackend(s.to_string())),
+        }
+    }
+}
+
+impl fmt::Display for BackendKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// An error in the target configuration.
+pub enum TargetError {
+    /// The backend name does not match any [`BackendKind`].
+    UnknownBackend(String),
+}
+
+impl fmt::Display for TargetError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TargetError::UnknownBackend(name) => write!(
+                f,
+                "unknown backend `{name}` (expected one of: llvm, cranelift, gcc)"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for TargetError {}
+
 #[derive(Debug)]
 /// Describes the target platform's data layout, including type alignments, pointer size,
 /// and other ABI-related information used during code generation.
@@ -108,11 +177,20 @@ pub struct TargetDataLayout {
     pub float64_align: AbiAndPrefAlign,
     pub float128_align: AbiAndPrefAlign,
 
+    /// The floating point types the target can generate code for, either in
+    /// hardware or through soft-float routines.
+    pub supported_float_types: Vec<Primitive>,
+
     /// The size of pointers in bytes.
     pub pointer_size: Size,
     /// The ABI and preferred alignment for pointers.
     pub pointer_align: AbiAndPrefAlign,
 
+    /// Pointer sizes and alignments for address spaces other than
+    /// [`AddressSpace::DATA`]. Address spaces not listed here use
+    /// `pointer_size` and `pointer_align`.
+    pub address_space_pointers: Vec<(AddressSpace, Size, AbiAndPrefAlign)>,
+
     /// The minimum and preferred alignment for aggregate types (e.g., structs, arrays).
     pub aggregate_align: AbiAndPrefAlign,
 
@@ -150,8 +228,15 @@ impl Default for TargetDataLayout {
             float32_align: AbiAndPrefAlign::new(4, 4),
             float64_align: AbiAndPrefAlign::new(8, 8),
             float128_align: AbiAndPrefAlign::new(16, 16),
+            supported_float_types: vec![
+                Primitive::F16,
+                Primitive::F32,
+                Primitive::F64,
+                Primitive::F128,
+            ],
             pointer_size: Size::from_bits(64),
             pointer_align: AbiAndPrefAlign::new(8, 8),
+            address_space_pointers: vec![],
             aggregate_align: AbiAndPrefAlign::new(0, 8),
             vector_align: vec![
                 (Size::from_bits(64), AbiAndPrefAlign::new(8, 8)),
@@ -174,10 +259,26 @@ impl TargetDataLayout {
         self.pointer_size
     }
 
+    /// The size of pointers in the given address space.
+    pub fn pointer_size_in(&self, address_space: AddressSpace) -> Size {
+        self.address_space_pointers
+            .iter()
+            .find(|(space, _, _)| *space == address_space)
+            .map_or(self.pointer_size, |(_, size, _)| *size)
+    }
+
+    /// The ABI and preferred alignment of pointers in the given address space.
     pub fn pointer_align(&self, address_space: AddressSpace) -> AbiAndPrefAlign {
-        match address_space {
-            AddressSpace::DATA => self.pointer_align,
-        }
+        self.address_space_pointers
+            .iter()
+            .find(|(space, _, _)| *space == address_space)
+            .map_or(self.pointer_align, |(_, _, align)| *align)
+    }
+
+    /// Returns `true` if the target supports the given floating point type.
+    pub fn supports_float(&self, float: Primitive) -> bool {
+        debug_assert!(float.is_floating_point(), "{float:?} is not a float type");
+        self.supported_float_types.contains(&float)
     }
 
     /// For example, for x86_64-unknown-linux-gnu, the data layout string could be:
@@ -206,6 +307,17 @@ impl TargetDataLayout {
             self.pointer_align.pref.bits()
         ));
 
+        // Pointers in non-default address spaces
+        for (address_space, size, align) in &self.address_space_pointers {
+            s.push_str(&format!(
+                "-p{}:{}:{}:{}",
+                u32::from(address_space),
+                size.bits(),
+                align.abi.bits(),
+                align.pref.bits()
+            ));
+        }
+
         // Format for integer types
         s.push_str(&format_align("i1", &self.int1_align));
         s.push_str(&format_align("i8", &self.int8_align));
@@ -395,6 +507,22 @@ impl TargetTriple {
         }
     }
 
+    /// The floating point types the target architecture can lower.
+    ///
+    /// `f16`, `f32` and `f64` are available everywhere; `f128` is only
+    /// supported on architectures where LLVM can lower it without a soft-float
+    /// runtime library.
+    pub fn supported_float_types(&self) -> Vec<Primitive> {
+        let mut floats = vec![Primitive::F16, Primitive::F32, Primitive::F64];
+        if matches!(
+            self.arch.as_str(),
+            "x86_64" | "aarch64" | "riscv64" | "powerpc64" | "powerpc64le" | "s390x"
+        ) {
+            floats.push(Primitive::F128);
+        }
+        floats
+    }
+
     // ARCHITECTURE-VENDOR-OPERATING_SYSTEM-ENVIRONMENT
     pub fn into_llvm_triple_string(&self) -> String {
         format!(
@@ -412,17 +540,19 @@ impl TargetTriple {
     }
 }
 
-// TODO: Other address spaces.
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
-pub enum AddressSpace {
+/// An address space, identified by its number. Targets such as GPUs or
+/// segmented-memory architectures place pointers in address spaces other
+/// than the default one.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct AddressSpace(pub u32);
+
+impl AddressSpace {
     /// The default address space.
-    DATA = 0,
+    pub const DATA: AddressSpace = AddressSpace(0);
 }
 
 impl From<&AddressSpace> for u32 {
     fn from(addr_space: &AddressSpace) -> Self {
-        match *addr_space {
-            AddressSpace::DATA => 0,
-        }
+        addr_space.0
     }
 }
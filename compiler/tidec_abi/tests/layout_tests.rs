@@ -0,0 +1,73 @@
+use tidec_abi::Layout;
+use tidec_abi::layout::{self, BackendRepr, FieldsShape, TyAndLayout, Variants};
+use tidec_abi::size_and_align::{AbiAndPrefAlign, Size};
+use tidec_utils::interner::Interned;
+
+#[test]
+fn test_stride_rounds_size_up_to_alignment() {
+    // A 3-byte type aligned to 4 bytes, e.g. `[i8; 3]` over-aligned by its
+    // containing struct.
+    let raw = layout::Layout {
+        size: Size::from_bytes(3),
+        align: AbiAndPrefAlign::new(4, 4),
+        backend_repr: BackendRepr::Memory { sized: true },
+        fields: FieldsShape::Array {
+            stride: Size::from_bytes(1),
+            count: 3,
+        },
+        variants: Variants::Single,
+    };
+    let ty_and_layout = TyAndLayout {
+        ty: (),
+        layout: Layout(Interned::new(&raw)),
+    };
+
+    assert_eq!(ty_and_layout.size, Size::from_bytes(3));
+    assert_eq!(ty_and_layout.stride(), Size::from_bytes(4));
+}
+
+#[test]
+fn test_stride_equals_size_when_aligned() {
+    let raw = layout::Layout {
+        size: Size::from_bytes(8),
+        align: AbiAndPrefAlign::new(4, 4),
+        backend_repr: BackendRepr::Memory { sized: true },
+        fields: FieldsShape::Arbitrary {
+            offsets: vec![Size::ZERO, Size::from_bytes(4)],
+        },
+        variants: Variants::Single,
+    };
+    let ty_and_layout = TyAndLayout {
+        ty: (),
+        layout: Layout(Interned::new(&raw)),
+    };
+
+    assert_eq!(ty_and_layout.stride(), ty_and_layout.size);
+}
+
+#[test]
+fn test_fields_shape_offsets() {
+    let array = FieldsShape::Array {
+        stride: Size::from_bytes(8),
+        count: 4,
+    };
+    assert_eq!(array.count(), 4);
+    assert_eq!(array.offset(3), Size::from_bytes(24));
+
+    let arbitrary = FieldsShape::Arbitrary {
+        offsets: vec![Size::ZERO, Size::from_bytes(8), Size::from_bytes(12)],
+    };
+    assert_eq!(arbitrary.count(), 3);
+    assert_eq!(arbitrary.offset(2), Size::from_bytes(12));
+
+    assert_eq!(FieldsShape::Primitive.count(), 0);
+}
+
+#[test]
+#[should_panic(expected = "out of bounds")]
+fn test_fields_shape_offset_out_of_bounds() {
+    let arbitrary = FieldsShape::Arbitrary {
+        offsets: vec![Size::ZERO],
+    };
+    arbitrary.offset(1);
+}
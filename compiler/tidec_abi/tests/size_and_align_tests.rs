@@ -0,0 +1,32 @@
+use tidec_abi::size_and_align::{Align, Size};
+
+#[test]
+fn test_size_from_bytes_const() {
+    const S: Size = Size::from_bytes_const(4);
+    assert_eq!(S.bytes(), 4);
+    assert_eq!(S, Size::from_bytes(4));
+}
+
+#[test]
+fn test_size_from_bits_const_rounds_up() {
+    const BYTE: Size = Size::from_bits_const(8);
+    const BOOL: Size = Size::from_bits_const(1);
+    const I128: Size = Size::from_bits_const(128);
+    assert_eq!(BYTE.bytes(), 1);
+    assert_eq!(BOOL.bytes(), 1);
+    assert_eq!(I128.bytes(), 16);
+    assert_eq!(Size::from_bits_const(u64::MAX), Size::from_bits(u64::MAX));
+}
+
+#[test]
+fn test_size_align_to_rounds_up() {
+    let four = Align::from_bytes(4).unwrap();
+    assert_eq!(Size::from_bytes(3).align_to(four), Size::from_bytes(4));
+    assert_eq!(Size::from_bytes(8).align_to(four), Size::from_bytes(8));
+    assert_eq!(Size::ZERO.align_to(four), Size::ZERO);
+    assert_eq!(
+        Size::from_bytes(3).align_to(Align::from_bytes(0).unwrap()),
+        Size::from_bytes(3)
+    );
+    assert_eq!(Size::from_bytes(u64::MAX / 8).checked_align_to(four), None);
+}
@@ -0,0 +1,50 @@
+use tidec_abi::layout::Primitive;
+use tidec_abi::target::{BackendKind, TargetError, TargetTriple, TirTarget};
+
+#[test]
+fn test_parse_backend_kind() {
+    assert_eq!("llvm".parse::<BackendKind>(), Ok(BackendKind::Llvm));
+    assert_eq!(
+        "cranelift".parse::<BackendKind>(),
+        Ok(BackendKind::Cranelift)
+    );
+    assert_eq!("gcc".parse::<BackendKind>(), Ok(BackendKind::Gcc));
+}
+
+#[test]
+fn test_parse_backend_kind_ignores_case() {
+    assert_eq!("LLVM".parse::<BackendKind>(), Ok(BackendKind::Llvm));
+    assert_eq!(
+        "Cranelift".parse::<BackendKind>(),
+        Ok(BackendKind::Cranelift)
+    );
+}
+
+#[test]
+fn test_parse_unknown_backend_kind() {
+    assert_eq!(
+        "msvc".parse::<BackendKind>(),
+        Err(TargetError::UnknownBackend("msvc".to_string()))
+    );
+}
+
+#[test]
+fn test_backend_kind_display_round_trips() {
+    for backend in [BackendKind::Llvm, BackendKind::Cranelift, BackendKind::Gcc] {
+        assert_eq!(backend.to_string().parse::<BackendKind>(), Ok(backend));
+    }
+}
+
+#[test]
+fn test_target_with_triple_derives_supported_floats() {
+    let x86_64 = TargetTriple::new("x86_64", "unknown", "linux", "gnu", "");
+    let target = TirTarget::with_triple(BackendKind::Llvm, x86_64);
+    assert!(target.data_layout.supports_float(Primitive::F128));
+
+    let arm = TargetTriple::new("armv7", "unknown", "linux", "gnu", "eabihf");
+    let target = TirTarget::with_triple(BackendKind::Llvm, arm);
+    assert!(target.data_layout.supports_float(Primitive::F16));
+    assert!(target.data_layout.supports_float(Primitive::F64));
+    assert!(!target.data_layout.supports_float(Primitive::F128));
+    assert!(target.target_triple.is_some());
+}
@@ -120,6 +120,20 @@ impl<'ctx> BasicBlockBuilder<'ctx> {
         self.push_assign(place, RValue::AddressOf(mutability, source))
     }
 
+    /// Append a discriminant read: `place = discriminant(source)`.
+    pub fn push_assign_discriminant(
+        &mut self,
+        place: Place<'ctx>,
+        source: Place<'ctx>,
+    ) -> &mut Self {
+        self.push_assign(place, RValue::Discriminant(source))
+    }
+
+    /// Append a discriminant write: `discriminant(place) = variant`.
+    pub fn push_set_discriminant(&mut self, place: Place<'ctx>, variant: u32) -> &mut Self {
+        self.push_statement(Statement::SetDiscriminant { place, variant })
+    }
+
     // ───────────────────────── Introspection ─────────────────────
 
     /// Returns the number of statements already pushed.
@@ -140,6 +154,7 @@ impl<'ctx> BasicBlockBuilder<'ctx> {
     /// The builder is consumed by this call.
     pub fn build(self, terminator: Terminator<'ctx>) -> BasicBlockData<'ctx> {
         BasicBlockData {
+            params: vec![],
             statements: self.statements,
             terminator,
         }
@@ -184,6 +199,33 @@ mod tests {
         assert_eq!(bb.len(), 1);
     }
 
+    #[test]
+    fn push_assign_discriminant_reads_source_place() {
+        let mut bb = BasicBlockBuilder::new();
+        bb.push_assign_discriminant(Place::from(Local::new(2)), Place::from(Local::new(1)));
+        let data = bb.build(Terminator::Return);
+
+        let Statement::Assign(assign) = &data.statements[0] else {
+            panic!("expected an assignment");
+        };
+        assert!(matches!(
+            &assign.1,
+            RValue::Discriminant(place) if place.local == Local::new(1)
+        ));
+    }
+
+    #[test]
+    fn push_set_discriminant_writes_variant() {
+        let mut bb = BasicBlockBuilder::new();
+        bb.push_set_discriminant(Place::from(Local::new(1)), 1);
+        let data = bb.build(Terminator::Return);
+
+        assert!(matches!(
+            &data.statements[0],
+            Statement::SetDiscriminant { place, variant: 1 } if place.local == Local::new(1)
+        ));
+    }
+
     #[test]
     fn build_with_goto_terminator() {
         let mut bb = BasicBlockBuilder::new();
@@ -192,12 +234,15 @@ mod tests {
         bb.push_assign_operand(place, operand);
 
         let target = BasicBlock::new(1);
-        let data = bb.build(Terminator::Goto { target });
+        let data = bb.build(Terminator::Goto {
+            target,
+            block_args: vec![],
+        });
 
         assert_eq!(data.statements.len(), 1);
         assert!(matches!(
             data.terminator,
-            Terminator::Goto { target: t } if t == BasicBlock::new(1)
+            Terminator::Goto { target: t, .. } if t == BasicBlock::new(1)
         ));
     }
 
@@ -238,7 +283,11 @@ mod tests {
         let bb = BasicBlockBuilder::new();
         let discr = Operand::Use(Place::from(Local::new(5)));
         let targets = SwitchTargets::if_then(BasicBlock::new(1), BasicBlock::new(2));
-        let data = bb.build(Terminator::SwitchInt { discr, targets });
+        let data = bb.build(Terminator::SwitchInt {
+            discr,
+            targets,
+            block_args: vec![],
+        });
 
         assert!(data.statements.is_empty());
         assert!(matches!(data.terminator, Terminator::SwitchInt { .. }));
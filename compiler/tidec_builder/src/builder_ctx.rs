@@ -27,12 +27,11 @@
 //! });
 //! ```
 
-use std::cell::Cell;
 use std::num::NonZero;
 
 use tidec_abi::layout::TyAndLayout;
 use tidec_abi::size_and_align::Size;
-use tidec_abi::target::{BackendKind, TirTarget};
+use tidec_abi::target::{AddressSpace, BackendKind, TirTarget};
 use tidec_tir::alloc::AllocId;
 use tidec_tir::body::{DefId, GlobalId};
 use tidec_tir::ctx::{EmitKind, InternCtx, TirArena, TirArgs, TirCtx};
@@ -56,8 +55,6 @@ use tidec_tir::body::TirBodyMetadata;
 /// live for `'ctx`.
 pub struct BuilderCtx<'ctx> {
     ctx: TirCtx<'ctx>,
-    /// Monotonically-increasing counter for generating unique [`DefId`]s.
-    next_def_id: Cell<usize>,
 }
 
 impl<'ctx> BuilderCtx<'ctx> {
@@ -66,10 +63,7 @@ impl<'ctx> BuilderCtx<'ctx> {
     /// For most use cases, prefer [`with_default`](Self::with_default) or
     /// [`with_target`](Self::with_target) which handle arena setup automatically.
     pub fn new(ctx: TirCtx<'ctx>) -> Self {
-        Self {
-            ctx,
-            next_def_id: Cell::new(0),
-        }
+        Self { ctx }
     }
 
     /// Run a closure with a default `BuilderCtx`.
@@ -107,7 +101,10 @@ impl<'ctx> BuilderCtx<'ctx> {
         F: for<'a> FnOnce(BuilderCtx<'a>) -> R,
     {
         let target = TirTarget::new(backend);
-        let args = TirArgs { emit_kind: emit };
+        let args = TirArgs {
+            emit_kind: emit,
+            ..Default::default()
+        };
         let arena = TirArena::default();
         let intern_ctx = InternCtx::new(&arena);
         let tir_ctx = TirCtx::new(&target, &args, &intern_ctx);
@@ -129,82 +126,92 @@ impl<'ctx> BuilderCtx<'ctx> {
 
     /// Create the unit type `()`.
     pub fn unit(&self) -> TirTy<'ctx> {
-        self.ctx.intern_ty(ty::TirTy::Unit)
+        self.ctx.mk_unit()
     }
 
     /// Create the boolean type.
     pub fn bool(&self) -> TirTy<'ctx> {
-        self.ctx.intern_ty(ty::TirTy::Bool)
+        self.ctx.mk_bool()
     }
 
     /// Create the `i8` type.
     pub fn i8(&self) -> TirTy<'ctx> {
-        self.ctx.intern_ty(ty::TirTy::I8)
+        self.ctx.mk_i8()
     }
 
     /// Create the `i16` type.
     pub fn i16(&self) -> TirTy<'ctx> {
-        self.ctx.intern_ty(ty::TirTy::I16)
+        self.ctx.mk_i16()
     }
 
     /// Create the `i32` type.
     pub fn i32(&self) -> TirTy<'ctx> {
-        self.ctx.intern_ty(ty::TirTy::I32)
+        self.ctx.mk_i32()
     }
 
     /// Create the `i64` type.
     pub fn i64(&self) -> TirTy<'ctx> {
-        self.ctx.intern_ty(ty::TirTy::I64)
+        self.ctx.mk_i64()
     }
 
     /// Create the `i128` type.
     pub fn i128(&self) -> TirTy<'ctx> {
-        self.ctx.intern_ty(ty::TirTy::I128)
+        self.ctx.mk_i128()
+    }
+
+    /// Create the pointer-sized `isize` type.
+    pub fn isize(&self) -> TirTy<'ctx> {
+        self.ctx.mk_isize()
     }
 
     /// Create the `u8` type.
     pub fn u8(&self) -> TirTy<'ctx> {
-        self.ctx.intern_ty(ty::TirTy::U8)
+        self.ctx.mk_u8()
     }
 
     /// Create the `u16` type.
     pub fn u16(&self) -> TirTy<'ctx> {
-        self.ctx.intern_ty(ty::TirTy::U16)
+        self.ctx.mk_u16()
     }
 
     /// Create the `u32` type.
     pub fn u32(&self) -> TirTy<'ctx> {
-        self.ctx.intern_ty(ty::TirTy::U32)
+        self.ctx.mk_u32()
     }
 
     /// Create the `u64` type.
     pub fn u64(&self) -> TirTy<'ctx> {
-        self.ctx.intern_ty(ty::TirTy::U64)
+        self.ctx.mk_u64()
     }
 
     /// Create the `u128` type.
     pub fn u128(&self) -> TirTy<'ctx> {
-        self.ctx.intern_ty(ty::TirTy::U128)
+        self.ctx.mk_u128()
+    }
+
+    /// Create the pointer-sized `usize` type.
+    pub fn usize(&self) -> TirTy<'ctx> {
+        self.ctx.mk_usize()
     }
 
     /// Create the `f16` type.
     pub fn f16(&self) -> TirTy<'ctx> {
-        self.ctx.intern_ty(ty::TirTy::F16)
+        self.ctx.mk_f16()
     }
 
     /// Create the `f32` type.
     pub fn f32(&self) -> TirTy<'ctx> {
-        self.ctx.intern_ty(ty::TirTy::F32)
+        self.ctx.mk_f32()
     }
 
     /// Create the `f64` type.
     pub fn f64(&self) -> TirTy<'ctx> {
-        self.ctx.intern_ty(ty::TirTy::F64)
+        self.ctx.mk_f64()
     }
 
     /// Create the `f128` type.
     pub fn f128(&self) -> TirTy<'ctx> {
-        self.ctx.intern_ty(ty::TirTy::F128)
+        self.ctx.mk_f128()
     }
 
     /// Create the metadata type.
@@ -218,19 +225,37 @@ impl<'ctx> BuilderCtx<'ctx> {
 
     /// Create an immutable raw pointer type (`*imm T`).
     pub fn ptr_imm(&self, pointee: TirTy<'ctx>) -> TirTy<'ctx> {
-        self.ctx
-            .intern_ty(ty::TirTy::RawPtr(pointee, Mutability::Imm))
+        self.ctx.intern_ty(ty::TirTy::RawPtr(
+            pointee,
+            Mutability::Imm,
+            AddressSpace::DATA,
+        ))
     }
 
     /// Create a mutable raw pointer type (`*mut T`).
     pub fn ptr_mut(&self, pointee: TirTy<'ctx>) -> TirTy<'ctx> {
-        self.ctx
-            .intern_ty(ty::TirTy::RawPtr(pointee, Mutability::Mut))
+        self.ctx.intern_ty(ty::TirTy::RawPtr(
+            pointee,
+            Mutability::Mut,
+            AddressSpace::DATA,
+        ))
     }
 
     /// Create a raw pointer type with explicit mutability.
     pub fn ptr(&self, pointee: TirTy<'ctx>, mutability: Mutability) -> TirTy<'ctx> {
-        self.ctx.intern_ty(ty::TirTy::RawPtr(pointee, mutability))
+        self.ctx
+            .intern_ty(ty::TirTy::RawPtr(pointee, mutability, AddressSpace::DATA))
+    }
+
+    /// Create a raw pointer type in the given address space.
+    pub fn ptr_in(
+        &self,
+        pointee: TirTy<'ctx>,
+        mutability: Mutability,
+        address_space: AddressSpace,
+    ) -> TirTy<'ctx> {
+        self.ctx
+            .intern_ty(ty::TirTy::RawPtr(pointee, mutability, address_space))
     }
 
     /// Create a struct type from field types.
@@ -266,6 +291,36 @@ impl<'ctx> BuilderCtx<'ctx> {
         self.ctx.intern_ty(ty::TirTy::Array(element, len))
     }
 
+    /// Create an enum type from the payload types of its variants.
+    ///
+    /// # Arguments
+    ///
+    /// * `variants` - The payload type of each variant, in discriminant order.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let option_ty = ctx.enum_ty(&[ctx.unit(), ctx.i32()]); // { None, Some(i32) }
+    /// ```
+    pub fn enum_ty(&self, variants: &[TirTy<'ctx>]) -> TirTy<'ctx> {
+        let variants = self.ctx.intern_type_list(variants);
+        self.ctx.intern_ty(ty::TirTy::Enum { variants })
+    }
+
+    /// Create a wide pointer type to a run of `element`s.
+    ///
+    /// This is the `{ *const T, usize }` struct used for slice constants
+    /// (`ConstValue::Slice`); it is laid out as a scalar pair.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let bytes_ty = ctx.slice(ctx.u8()); // &[u8]
+    /// ```
+    pub fn slice(&self, element: TirTy<'ctx>) -> TirTy<'ctx> {
+        self.struct_ty(&[self.ptr_imm(element), self.usize()], false)
+    }
+
     // =========================================================================
     // Type list interning
     // =========================================================================
@@ -324,8 +379,9 @@ impl<'ctx> BuilderCtx<'ctx> {
 
     /// Allocate a fresh, unique [`DefId`].
     ///
-    /// Each call returns a new `DefId` with a monotonically-increasing index.
-    /// This is the recommended way to obtain `DefId`s for multi-function
+    /// Each call returns a new `DefId` with a monotonically-increasing index,
+    /// shared with every other user of the same [`TirCtx`]. This is the
+    /// recommended way to obtain `DefId`s for multi-function
     /// programs instead of tracking the counter manually.
     ///
     /// # Example
@@ -335,9 +391,7 @@ impl<'ctx> BuilderCtx<'ctx> {
     /// let id_b = ctx.fresh_def_id(); // DefId(1)
     /// ```
     pub fn fresh_def_id(&self) -> DefId {
-        let id = self.next_def_id.get();
-        self.next_def_id.set(id + 1);
-        DefId(id)
+        self.ctx.fresh_def_id()
     }
 
     // =========================================================================
@@ -471,6 +525,20 @@ impl<'ctx> BuilderCtx<'ctx> {
         ))
     }
 
+    /// Create a constant byte-slice operand (`&[u8]`), e.g. for a string
+    /// literal.
+    ///
+    /// Unlike [`intern_c_str`](Self::intern_c_str), no NUL terminator is
+    /// added: the length travels with the pointer.
+    pub fn const_bytes(&self, bytes: &[u8]) -> Operand<'ctx> {
+        Operand::Const(ConstOperand::Value(
+            ConstValue::Slice {
+                data: bytes.to_vec(),
+            },
+            self.slice(self.u8()),
+        ))
+    }
+
     // =========================================================================
     // Function operand helper
     // =========================================================================
@@ -619,6 +687,32 @@ mod tests {
         });
     }
 
+    #[test]
+    fn enum_types_are_created_correctly() {
+        BuilderCtx::with_default(|ctx| {
+            let enum_ty = ctx.enum_ty(&[ctx.unit(), ctx.i32()]);
+
+            assert!(enum_ty.is_enum());
+        });
+    }
+
+    #[test]
+    fn slice_types_are_scalar_pairs() {
+        BuilderCtx::with_default(|ctx| {
+            let slice_ty = ctx.slice(ctx.u8());
+            let layout = ctx.layout_of(slice_ty);
+
+            assert!(matches!(
+                layout.backend_repr,
+                tidec_abi::layout::BackendRepr::ScalarPair(_, _)
+            ));
+            assert_eq!(
+                layout.layout.size.bytes(),
+                ctx.target().data_layout.pointer_size.bytes() * 2
+            );
+        });
+    }
+
     #[test]
     fn c_string_interning() {
         BuilderCtx::with_default(|ctx| {
@@ -686,6 +780,7 @@ mod tests {
                 call_conv: CallConv::C,
                 is_varargs: false,
                 is_declaration: false,
+                entry_point: None,
             };
 
             let mut fb = ctx.function_builder(metadata);
@@ -12,7 +12,8 @@
 //!    [`declare_ret`](FunctionBuilder::declare_ret) and
 //!    [`declare_arg`](FunctionBuilder::declare_arg).
 //! 3. Declare additional locals with [`declare_local`](FunctionBuilder::declare_local).
-//! 4. Create basic blocks with [`create_block`](FunctionBuilder::create_block).
+//! 4. Create basic blocks with [`create_block`](FunctionBuilder::create_block),
+//!    or append complete ones with [`add_block`](FunctionBuilder::add_block).
 //! 5. Fill each block using [`block_builder`](FunctionBuilder::block_builder) or
 //!    the convenience methods that operate directly on blocks.
 //! 6. Call [`build`](FunctionBuilder::build) to produce the final [`TirBody`].
@@ -44,7 +45,7 @@
 
 use crate::basic_block_builder::BasicBlockBuilder;
 use std::num::NonZero;
-use tidec_tir::body::{CallConv, Linkage, TirBody, TirBodyMetadata};
+use tidec_tir::body::{CallConv, EntryKind, Linkage, TirBody, TirBodyMetadata};
 use tidec_tir::ctx::TirCtx;
 use tidec_tir::syntax::{
     BasicBlock, BasicBlockData, BinaryOp, ConstOperand, ConstScalar, ConstValue, Local, LocalData,
@@ -57,10 +58,11 @@ use tidec_utils::index_vec::IdxVec;
 
 /// Tracks in-progress basic blocks before they are finalized.
 ///
-/// While the block is being built, it holds accumulated statements and an
-/// optional terminator. The block is not considered complete until a terminator
-/// has been set.
+/// While the block is being built, it holds its parameters, accumulated
+/// statements and an optional terminator. The block is not considered
+/// complete until a terminator has been set.
 struct InProgressBlock<'ctx> {
+    params: Vec<(Local, TirTy<'ctx>)>,
     statements: Vec<Statement<'ctx>>,
     terminator: Option<Terminator<'ctx>>,
 }
@@ -68,6 +70,7 @@ struct InProgressBlock<'ctx> {
 impl<'ctx> InProgressBlock<'ctx> {
     fn new() -> Self {
         Self {
+            params: Vec::new(),
             statements: Vec::new(),
             terminator: None,
         }
@@ -216,6 +219,17 @@ impl<'ctx> FunctionBuilder<'ctx> {
         bb
     }
 
+    /// Append an already complete block and return its [`BasicBlock`] index.
+    ///
+    /// This is a shorthand for [`create_block`](Self::create_block) followed
+    /// by [`apply_block_builder`](Self::apply_block_builder), for blocks built
+    /// with [`BasicBlockData::new`] or a [`BasicBlockBuilder`].
+    pub fn add_block(&mut self, data: BasicBlockData<'ctx>) -> BasicBlock {
+        let bb = self.create_block();
+        self.apply_block_builder(bb, data);
+        bb
+    }
+
     /// Return a [`BasicBlockBuilder`] pre-populated with the statements that
     /// have been pushed into `block` so far.
     ///
@@ -234,14 +248,15 @@ impl<'ctx> FunctionBuilder<'ctx> {
     /// Replace the contents of `block` with the result of a
     /// [`BasicBlockBuilder`].
     ///
-    /// This overwrites any previously pushed statements **and** the
-    /// terminator.
+    /// This overwrites any previously pushed statements, the block
+    /// parameters **and** the terminator.
     ///
     /// # Panics
     ///
     /// Panics if `block` has not been created yet.
     pub fn apply_block_builder(&mut self, block: BasicBlock, data: BasicBlockData<'ctx>) {
         let ip = &mut self.blocks[block];
+        ip.params = data.params;
         ip.statements = data.statements;
         ip.terminator = Some(data.terminator);
     }
@@ -351,6 +366,12 @@ impl<'ctx> FunctionBuilder<'ctx> {
         self
     }
 
+    /// Mark this function as the program entry point of the given kind.
+    pub fn set_entry_point(&mut self, entry_kind: EntryKind) -> &mut Self {
+        self.metadata.entry_point = Some(entry_kind);
+        self
+    }
+
     /// Returns a shared reference to the function metadata.
     pub fn metadata(&self) -> &TirBodyMetadata {
         &self.metadata
@@ -459,7 +480,7 @@ impl<'ctx> FunctionBuilder<'ctx> {
 
     /// Create an [`Operand::Use`] that loads from the given [`Place`].
     pub fn use_place(&self, place: &Place<'ctx>) -> Operand<'ctx> {
-        Operand::Use(place.clone())
+        Operand::Use(*place)
     }
 
     /// Create an [`Operand::Use`] that loads from the given [`Local`].
@@ -504,7 +525,7 @@ impl<'ctx> FunctionBuilder<'ctx> {
     ) -> (Local, Place<'ctx>) {
         let local = self.declare_local(result_ty, false);
         let place = Place::from(local);
-        self.push_assign(block, place.clone(), RValue::BinaryOp(op, lhs, rhs));
+        self.push_assign(block, place, RValue::BinaryOp(op, lhs, rhs));
         (local, place)
     }
 
@@ -525,7 +546,7 @@ impl<'ctx> FunctionBuilder<'ctx> {
     ) -> (Local, Place<'ctx>) {
         let local = self.declare_local(result_ty, false);
         let place = Place::from(local);
-        self.push_assign(block, place.clone(), RValue::UnaryOp(op, src));
+        self.push_assign(block, place, RValue::UnaryOp(op, src));
         (local, place)
     }
 
@@ -547,7 +568,13 @@ impl<'ctx> FunctionBuilder<'ctx> {
     ///
     /// Panics if `block` has not been created yet.
     pub fn emit_goto(&mut self, block: BasicBlock, target: BasicBlock) {
-        self.set_terminator(block, Terminator::Goto { target });
+        self.set_terminator(
+            block,
+            Terminator::Goto {
+                target,
+                block_args: vec![],
+            },
+        );
     }
 
     /// Set the terminator of `block` to a two-arm
@@ -567,7 +594,14 @@ impl<'ctx> FunctionBuilder<'ctx> {
         else_bb: BasicBlock,
     ) {
         let targets = SwitchTargets::if_then(then_bb, else_bb);
-        self.set_terminator(block, Terminator::SwitchInt { discr, targets });
+        self.set_terminator(
+            block,
+            Terminator::SwitchInt {
+                discr,
+                targets,
+                block_args: vec![],
+            },
+        );
     }
 
     /// Set the terminator of `block` to a [`Terminator::Call`].
@@ -595,6 +629,7 @@ impl<'ctx> FunctionBuilder<'ctx> {
                 args,
                 destination,
                 target,
+                block_args: vec![],
             },
         );
     }
@@ -619,6 +654,7 @@ impl<'ctx> FunctionBuilder<'ctx> {
         if self.ret_and_args.is_empty() {
             return Err(BuildError::MissingReturnLocal);
         }
+        let arg_count = self.num_args();
 
         let mut basic_blocks: IdxVec<BasicBlock, BasicBlockData<'ctx>> = IdxVec::new();
         for (bb_idx, ip) in self.blocks.into_iter_enumerated() {
@@ -626,6 +662,7 @@ impl<'ctx> FunctionBuilder<'ctx> {
                 .terminator
                 .ok_or(BuildError::MissingTerminator { block: bb_idx })?;
             basic_blocks.push(BasicBlockData {
+                params: ip.params,
                 statements: ip.statements,
                 terminator,
             });
@@ -633,6 +670,7 @@ impl<'ctx> FunctionBuilder<'ctx> {
 
         Ok(TirBody {
             metadata: self.metadata,
+            arg_count,
             ret_and_args: self.ret_and_args,
             locals: self.locals,
             basic_blocks,
@@ -676,7 +714,7 @@ mod tests {
     use super::*;
     use tidec_abi::target::{BackendKind, TirTarget};
     use tidec_tir::body::*;
-    use tidec_tir::ctx::{EmitKind, InternCtx, TirArena, TirArgs, TirCtx};
+    use tidec_tir::ctx::{InternCtx, TirArena, TirArgs, TirCtx};
 
     use tidec_tir::ty;
 
@@ -686,9 +724,7 @@ mod tests {
         F: for<'ctx> FnOnce(TirCtx<'ctx>) -> R,
     {
         let target = TirTarget::new(BackendKind::Llvm);
-        let args = TirArgs {
-            emit_kind: EmitKind::Object,
-        };
+        let args = TirArgs::default();
         let arena = TirArena::default();
         let intern_ctx = InternCtx::new(&arena);
         let tir_ctx = TirCtx::new(&target, &args, &intern_ctx);
@@ -707,6 +743,7 @@ mod tests {
             call_conv: CallConv::C,
             is_varargs: false,
             is_declaration: false,
+            entry_point: None,
         }
     }
 
@@ -794,7 +831,13 @@ mod tests {
             let entry = fb.create_block();
             let exit = fb.create_block();
 
-            fb.set_terminator(entry, Terminator::Goto { target: exit });
+            fb.set_terminator(
+                entry,
+                Terminator::Goto {
+                    target: exit,
+                    block_args: vec![],
+                },
+            );
             fb.set_terminator(exit, Terminator::Return);
 
             assert_eq!(fb.num_blocks(), 2);
@@ -805,7 +848,7 @@ mod tests {
             assert_eq!(body.basic_blocks.len(), 2);
             assert!(matches!(
                 body.basic_blocks[BasicBlock::new(0)].terminator,
-                Terminator::Goto { target } if target == BasicBlock::new(1)
+                Terminator::Goto { target, .. } if target == BasicBlock::new(1)
             ));
         });
     }
@@ -941,6 +984,7 @@ mod tests {
                     args: vec![Operand::Use(Place::from(arg))],
                     destination: Place::from(dest),
                     target: cont,
+                    block_args: vec![],
                 },
             );
             fb.set_terminator(cont, Terminator::Return);
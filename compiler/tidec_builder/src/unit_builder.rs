@@ -157,7 +157,7 @@ mod tests {
     use crate::BuilderCtx;
     use tidec_abi::target::{BackendKind, TirTarget};
     use tidec_tir::body::*;
-    use tidec_tir::ctx::{EmitKind, InternCtx, TirArena, TirArgs, TirCtx};
+    use tidec_tir::ctx::{InternCtx, TirArena, TirArgs, TirCtx};
     use tidec_tir::syntax::*;
     use tidec_utils::idx::Idx;
 
@@ -167,9 +167,7 @@ mod tests {
         F: for<'ctx> FnOnce(TirCtx<'ctx>) -> R,
     {
         let target = TirTarget::new(BackendKind::Llvm);
-        let args = TirArgs {
-            emit_kind: EmitKind::Object,
-        };
+        let args = TirArgs::default();
         let arena = TirArena::default();
         let intern_ctx = InternCtx::new(&arena);
         let tir_ctx = TirCtx::new(&target, &args, &intern_ctx);
@@ -188,6 +186,7 @@ mod tests {
             call_conv: CallConv::C,
             is_varargs: false,
             is_declaration: false,
+            entry_point: None,
         }
     }
 
@@ -12,6 +12,7 @@ use tidec_tir::body::*;
 use tidec_tir::syntax::*;
 use tidec_tir::ty::Mutability;
 use tidec_utils::idx::Idx;
+use tidec_utils::index_vec::IdxVec;
 
 fn make_metadata(name: &str) -> TirBodyMetadata {
     TirBodyMetadata {
@@ -25,6 +26,7 @@ fn make_metadata(name: &str) -> TirBodyMetadata {
         call_conv: CallConv::C,
         is_varargs: false,
         is_declaration: false,
+        entry_point: None,
     }
 }
 
@@ -146,6 +148,7 @@ fn build_module_with_global_and_branch() {
             Terminator::SwitchInt {
                 discr: Operand::Use(Place::from(cond)),
                 targets: SwitchTargets::if_then(then_bb, else_bb),
+                block_args: vec![],
             },
         );
 
@@ -166,7 +169,10 @@ fn build_module_with_global_and_branch() {
                 Operand::Const(one),
             );
             bb.push_assign_operand(Place::from(RETURN_LOCAL), Operand::Use(Place::from(tmp)));
-            let data = bb.build(Terminator::Goto { target: merge_bb });
+            let data = bb.build(Terminator::Goto {
+                target: merge_bb,
+                block_args: vec![],
+            });
             fb.apply_block_builder(then_bb, data);
         }
 
@@ -176,7 +182,13 @@ fn build_module_with_global_and_branch() {
             Place::from(RETURN_LOCAL),
             RValue::Operand(Operand::Use(Place::from(counter_local))),
         );
-        fb.set_terminator(else_bb, Terminator::Goto { target: merge_bb });
+        fb.set_terminator(
+            else_bb,
+            Terminator::Goto {
+                target: merge_bb,
+                block_args: vec![],
+            },
+        );
 
         // merge_bb: return
         fb.set_terminator(merge_bb, Terminator::Return);
@@ -193,7 +205,7 @@ fn build_module_with_global_and_branch() {
         assert_eq!(then_data.statements.len(), 2);
         assert!(matches!(
             then_data.terminator,
-            Terminator::Goto { target } if target == merge_bb
+            Terminator::Goto { target, .. } if target == merge_bb
         ));
 
         // Verify else_bb has 1 statement (assign).
@@ -275,6 +287,7 @@ fn build_module_with_declaration_and_call() {
                 args: vec![Operand::Use(Place::from(x))],
                 destination: Place::from(dest),
                 target: cont,
+                block_args: vec![],
             },
         );
 
@@ -1083,6 +1096,7 @@ fn fn_operand_used_in_call() {
                 args: vec![ctx.const_i32(10)],
                 destination: Place::from(dest),
                 target: cont,
+                block_args: vec![],
             },
         );
         caller.push_assign(
@@ -1261,6 +1275,7 @@ fn end_to_end_multi_function_with_new_api() {
                 args: vec![Operand::use_local(x), ctx.const_i32(2)],
                 destination: Place::from(call_dest),
                 target: cont,
+                block_args: vec![],
             },
         );
 
@@ -1293,3 +1308,71 @@ fn end_to_end_multi_function_with_new_api() {
         assert_eq!(tir_unit.bodies.raw[1].metadata.def_id, DefId(1));
     });
 }
+
+// ---------------------------------------------------------------------------
+// Test: `add_block` rebuilds the hand-written `main` of the tidec demo.
+// ---------------------------------------------------------------------------
+
+#[test]
+fn add_block_rebuilds_hand_written_main() {
+    BuilderCtx::with_default(|ctx| {
+        let i32_ty = ctx.i32();
+        let ten = || RValue::UnaryOp(UnaryOp::Pos, ctx.const_i32(10));
+
+        // `int main() { return 10; }`, written out by hand.
+        let hand_built = TirBody {
+            metadata: make_metadata("main"),
+            ret_and_args: IdxVec::from_raw(vec![LocalData {
+                ty: i32_ty,
+                mutable: false,
+            }]),
+            arg_count: 0,
+            locals: IdxVec::new(),
+            basic_blocks: IdxVec::from_raw(vec![BasicBlockData {
+                params: vec![],
+                statements: vec![Statement::assign(Place::from(RETURN_LOCAL), ten())],
+                terminator: Terminator::Return,
+            }]),
+        };
+
+        let mut fb = ctx.function_builder(make_metadata("main"));
+        fb.declare_ret(i32_ty, false);
+        let mut entry = BasicBlockData::new(Terminator::Return);
+        entry.push_assign(Place::from(RETURN_LOCAL), ten());
+        assert_eq!(fb.add_block(entry), ENTRY_BLOCK);
+        let built = fb.build();
+
+        assert_eq!(built.metadata.name, hand_built.metadata.name);
+        assert_eq!(built.metadata.def_id, hand_built.metadata.def_id);
+        assert_eq!(built.content_hash(), hand_built.content_hash());
+    });
+}
+
+#[test]
+fn add_block_keeps_block_params() {
+    BuilderCtx::with_default(|ctx| {
+        let i32_ty = ctx.i32();
+
+        let mut fb = ctx.function_builder(make_metadata("join"));
+        fb.declare_ret(i32_ty, false);
+        let param = fb.declare_local(i32_ty, false);
+
+        let entry = fb.add_block(BasicBlockData::new(Terminator::Goto {
+            target: BasicBlock::new(1),
+            block_args: vec![ctx.const_i32(1)],
+        }));
+        let mut join = BasicBlockData::new(Terminator::Return);
+        join.params.push((param, i32_ty));
+        join.push_assign(
+            Place::from(RETURN_LOCAL),
+            RValue::Operand(Operand::use_local(param)),
+        );
+        let join = fb.add_block(join);
+
+        let body = fb.build();
+        assert_eq!(entry, ENTRY_BLOCK);
+        assert!(body.basic_blocks[entry].params.is_empty());
+        assert_eq!(body.basic_blocks[join].params, vec![(param, i32_ty)]);
+        assert_eq!(body.basic_blocks[join].statements.len(), 1);
+    });
+}
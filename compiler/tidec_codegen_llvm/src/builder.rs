@@ -2,28 +2,40 @@ use std::num::NonZeroU32;
 use std::ops::Deref;
 
 use crate::context::CodegenCtx;
-use crate::tir::tir_ty::BasicTypesUtils;
-use inkwell::values::{BasicValue, BasicValueEnum, FunctionValue, ValueKind};
+use inkwell::attributes::{Attribute, AttributeLoc};
+use inkwell::types::BasicTypeEnum;
+use inkwell::values::{BasicValue, BasicValueEnum, FunctionValue, PhiValue, ValueKind};
 use inkwell::{basic_block::BasicBlock, builder::Builder};
 use tidec_abi::layout::{BackendRepr, Primitive, TyAndLayout};
 use tidec_abi::size_and_align::{Align, Size};
 use tidec_codegen_ssa::tir::{OperandRef, OperandVal, PlaceRef, PlaceVal};
-use tidec_codegen_ssa::traits::{BuilderMethods, CodegenBackendTypes};
+use tidec_codegen_ssa::traits::{BackendTypeOf, BuilderMethods, CodegenBackendTypes};
 use tidec_tir::alloc::Allocation;
 use tidec_tir::syntax::ConstScalar;
 use tidec_tir::TirTy;
 use tracing::instrument;
 
 /// Macro for generating arithmetic operation methods
+///
+/// The generated methods also accept two SIMD vectors, on which the
+/// instruction operates lane-wise (e.g. `add <4 x i32>`).
 macro_rules! impl_arithmetic_ops {
     // Integer operations
     (int, $method_name:ident, $llvm_method:ident, $op_name:literal, $doc:literal) => {
         #[doc = $doc]
         fn $method_name(&mut self, lhs: Self::Value, rhs: Self::Value) -> Self::Value {
+            if let (BasicValueEnum::VectorValue(lhs), BasicValueEnum::VectorValue(rhs)) = (lhs, rhs)
+            {
+                return self
+                    .ll_builder
+                    .$llvm_method(lhs, rhs, $op_name)
+                    .expect(concat!("Failed to build ", $op_name))
+                    .into();
+            }
             assert!(lhs.get_type().is_int_type() && rhs.get_type().is_int_type());
             self.ll_builder
                 .$llvm_method(lhs.into_int_value(), rhs.into_int_value(), $op_name)
-                .unwrap()
+                .expect(concat!("Failed to build ", $op_name))
                 .into()
         }
     };
@@ -32,10 +44,18 @@ macro_rules! impl_arithmetic_ops {
     (float, $method_name:ident, $llvm_method:ident, $op_name:literal, $doc:literal) => {
         #[doc = $doc]
         fn $method_name(&mut self, lhs: Self::Value, rhs: Self::Value) -> Self::Value {
+            if let (BasicValueEnum::VectorValue(lhs), BasicValueEnum::VectorValue(rhs)) = (lhs, rhs)
+            {
+                return self
+                    .ll_builder
+                    .$llvm_method(lhs, rhs, $op_name)
+                    .expect(concat!("Failed to build ", $op_name))
+                    .into();
+            }
             assert!(lhs.get_type().is_float_type() && rhs.get_type().is_float_type());
             self.ll_builder
                 .$llvm_method(lhs.into_float_value(), rhs.into_float_value(), $op_name)
-                .unwrap()
+                .expect(concat!("Failed to build ", $op_name))
                 .into()
         }
     };
@@ -44,10 +64,18 @@ macro_rules! impl_arithmetic_ops {
     (int_overflow, $method_name:ident, $llvm_method:ident, $op_name:literal, $doc:literal) => {
         #[doc = $doc]
         fn $method_name(&mut self, lhs: Self::Value, rhs: Self::Value) -> Self::Value {
+            if let (BasicValueEnum::VectorValue(lhs), BasicValueEnum::VectorValue(rhs)) = (lhs, rhs)
+            {
+                return self
+                    .ll_builder
+                    .$llvm_method(lhs, rhs, $op_name)
+                    .expect(concat!("Failed to build ", $op_name))
+                    .into();
+            }
             assert!(lhs.get_type().is_int_type() && rhs.get_type().is_int_type());
             self.ll_builder
                 .$llvm_method(lhs.into_int_value(), rhs.into_int_value(), $op_name)
-                .unwrap()
+                .expect(concat!("Failed to build ", $op_name))
                 .into()
         }
     };
@@ -122,7 +150,7 @@ impl<'a, 'll, 'ctx> BuilderMethods<'a, 'ctx> for CodegenBuilder<'a, 'll, 'ctx> {
             Ok(pointer_value) => {
                 if let Err(err) = pointer_value
                     .as_instruction()
-                    .unwrap()
+                    .expect("alloca is an instruction")
                     .set_alignment(align.bytes() as u32)
                 {
                     panic!("Failed to set alignment: {}", err);
@@ -148,6 +176,10 @@ impl<'a, 'll, 'ctx> BuilderMethods<'a, 'ctx> for CodegenBuilder<'a, 'll, 'ctx> {
         ctx.ll_context.append_basic_block(fn_value, name)
     }
 
+    fn position_at_end(&mut self, bb: Self::BasicBlock) {
+        self.ll_builder.position_at_end(bb);
+    }
+
     #[instrument(level = "trace", skip(self))]
     fn load_operand(
         &mut self,
@@ -159,7 +191,7 @@ impl<'a, 'll, 'ctx> BuilderMethods<'a, 'ctx> for CodegenBuilder<'a, 'll, 'ctx> {
 
         if place_ref.ty_layout.is_immediate() {
             let mut ll_global_const: Option<BasicValueEnum> = None;
-            let llty = place_ref.ty_layout.ty.into_basic_type(self.ctx);
+            let llty = self.ctx.backend_type_of(place_ref.ty_layout.ty);
 
             // ```rust
             // unsafe {
@@ -172,12 +204,19 @@ impl<'a, 'll, 'ctx> BuilderMethods<'a, 'ctx> for CodegenBuilder<'a, 'll, 'ctx> {
             //     }
             // }
             // ```
-            let global_val = self
-                .ll_module
-                .get_global(place_ref.place_val.value.get_name().to_str().unwrap());
+            let global_val = self.ll_module.get_global(
+                place_ref
+                    .place_val
+                    .value
+                    .get_name()
+                    .to_str()
+                    .expect("global names are UTF-8"),
+            );
             if let Some(gv) = global_val {
                 if gv.is_constant() {
-                    let loaded_val = gv.get_initializer().unwrap();
+                    let loaded_val = gv
+                        .get_initializer()
+                        .expect("constant global has an initializer");
                     assert_eq!(loaded_val.get_type(), llty);
                     ll_global_const = Some(loaded_val);
                 }
@@ -237,7 +276,7 @@ impl<'a, 'll, 'ctx> BuilderMethods<'a, 'ctx> for CodegenBuilder<'a, 'll, 'ctx> {
 
         load_inst
             .as_instruction_value()
-            .unwrap()
+            .expect("load is an instruction")
             .set_alignment(align.bytes() as u32)
             .expect("Failed to set alignment");
 
@@ -263,7 +302,7 @@ impl<'a, 'll, 'ctx> BuilderMethods<'a, 'ctx> for CodegenBuilder<'a, 'll, 'ctx> {
         assert!(val.get_type().is_float_type());
         self.ll_builder
             .build_float_neg(val.into_float_value(), "fneg")
-            .unwrap()
+            .expect("Failed to build fneg")
             .into()
     }
 
@@ -271,7 +310,7 @@ impl<'a, 'll, 'ctx> BuilderMethods<'a, 'ctx> for CodegenBuilder<'a, 'll, 'ctx> {
         assert!(val.get_type().is_int_type());
         self.ll_builder
             .build_int_neg(val.into_int_value(), "neg")
-            .unwrap()
+            .expect("Failed to build neg")
             .into()
     }
 
@@ -287,6 +326,101 @@ impl<'a, 'll, 'ctx> BuilderMethods<'a, 'ctx> for CodegenBuilder<'a, 'll, 'ctx> {
     impl_arithmetic_ops!(int, build_udiv, build_int_unsigned_div, "udiv",
         "Unsigned integer division.\n\n`build_int_unsigned_div` is a helper on an LLVM IR builder wrapper that generates an unsigned integer division instruction.");
 
+    /// Integer arithmetic with overflow detection.
+    ///
+    /// Calls the `llvm.{s,u}{add,sub,mul}.with.overflow` intrinsic and splits
+    /// the returned `{ iN, i1 }` pair into the result and the overflow bit.
+    fn build_checked_binop(
+        &mut self,
+        op: tidec_tir::syntax::BinaryOp,
+        lhs: Self::Value,
+        rhs: Self::Value,
+        signed: bool,
+    ) -> (Self::Value, Self::Value) {
+        use inkwell::intrinsics::Intrinsic;
+        use tidec_tir::syntax::BinaryOp;
+
+        assert!(lhs.get_type().is_int_type() && rhs.get_type().is_int_type());
+        let name = match (op, signed) {
+            (BinaryOp::AddChecked, true) => "llvm.sadd.with.overflow",
+            (BinaryOp::AddChecked, false) => "llvm.uadd.with.overflow",
+            (BinaryOp::SubChecked, true) => "llvm.ssub.with.overflow",
+            (BinaryOp::SubChecked, false) => "llvm.usub.with.overflow",
+            (BinaryOp::MulChecked, true) => "llvm.smul.with.overflow",
+            (BinaryOp::MulChecked, false) => "llvm.umul.with.overflow",
+            (op, _) => panic!("build_checked_binop called with non-checked op: {:?}", op),
+        };
+        let intrinsic =
+            Intrinsic::find(name).unwrap_or_else(|| panic!("LLVM intrinsic `{}` not found", name));
+        let decl = intrinsic
+            .get_declaration(&self.ctx.ll_module, &[lhs.get_type()])
+            .unwrap_or_else(|| panic!("Failed to declare LLVM intrinsic `{}`", name));
+
+        let pair = self
+            .ll_builder
+            .build_call(decl, &[lhs.into(), rhs.into()], "checked")
+            .expect("Failed to build checked arithmetic call");
+        let ValueKind::Basic(pair) = pair.try_as_basic_value() else {
+            panic!("`{}` must return a value", name);
+        };
+        let pair = pair.into_struct_value();
+        let result = self
+            .ll_builder
+            .build_extract_value(pair, 0, "checked.val")
+            .expect("Failed to extract checked arithmetic result");
+        let overflow = self
+            .ll_builder
+            .build_extract_value(pair, 1, "checked.ovf")
+            .expect("Failed to extract checked arithmetic overflow bit");
+        (result, overflow)
+    }
+
+    /// Saturating integer arithmetic.
+    ///
+    /// Calls the `llvm.{s,u}{add,sub}.sat` intrinsic, which clamps the result
+    /// to the bounds of the operand type instead of wrapping. The intrinsic is
+    /// overloaded on integer vectors too, where it saturates each lane.
+    fn build_saturating_binop(
+        &mut self,
+        op: tidec_tir::syntax::BinaryOp,
+        lhs: Self::Value,
+        rhs: Self::Value,
+        signed: bool,
+    ) -> Self::Value {
+        use inkwell::intrinsics::Intrinsic;
+        use tidec_tir::syntax::BinaryOp;
+
+        let is_int_or_int_vector = |ty: BasicTypeEnum| {
+            ty.is_int_type()
+                || (ty.is_vector_type() && ty.into_vector_type().get_element_type().is_int_type())
+        };
+        assert!(is_int_or_int_vector(lhs.get_type()) && is_int_or_int_vector(rhs.get_type()));
+        let name = match (op, signed) {
+            (BinaryOp::SaturatingAdd, true) => "llvm.sadd.sat",
+            (BinaryOp::SaturatingAdd, false) => "llvm.uadd.sat",
+            (BinaryOp::SaturatingSub, true) => "llvm.ssub.sat",
+            (BinaryOp::SaturatingSub, false) => "llvm.usub.sat",
+            (op, _) => panic!(
+                "build_saturating_binop called with non-saturating op: {:?}",
+                op
+            ),
+        };
+        let intrinsic =
+            Intrinsic::find(name).unwrap_or_else(|| panic!("LLVM intrinsic `{}` not found", name));
+        let decl = intrinsic
+            .get_declaration(&self.ctx.ll_module, &[lhs.get_type()])
+            .unwrap_or_else(|| panic!("Failed to declare LLVM intrinsic `{}`", name));
+
+        let result = self
+            .ll_builder
+            .build_call(decl, &[lhs.into(), rhs.into()], "sat")
+            .expect("Failed to build saturating arithmetic call");
+        let ValueKind::Basic(result) = result.try_as_basic_value() else {
+            panic!("`{}` must return a value", name);
+        };
+        result
+    }
+
     // Remainder / modulo operations
     impl_arithmetic_ops!(int, build_srem, build_int_signed_rem, "srem",
         "Signed integer remainder.\n\n`build_int_signed_rem` is a helper on an LLVM IR builder wrapper that generates a signed integer remainder instruction.");
@@ -312,6 +446,13 @@ impl<'a, 'll, 'ctx> BuilderMethods<'a, 'ctx> for CodegenBuilder<'a, 'll, 'ctx> {
     /// Uses `build_right_shift` with `sign_extend = false` to produce a
     /// logical shift that fills vacated bits with zeros.
     fn build_lshr(&mut self, lhs: Self::Value, rhs: Self::Value) -> Self::Value {
+        if let (BasicValueEnum::VectorValue(lhs), BasicValueEnum::VectorValue(rhs)) = (lhs, rhs) {
+            return self
+                .ll_builder
+                .build_right_shift(lhs, rhs, false, "lshr")
+                .expect("Failed to build lshr")
+                .into();
+        }
         assert!(lhs.get_type().is_int_type() && rhs.get_type().is_int_type());
         self.ll_builder
             .build_right_shift(
@@ -320,7 +461,7 @@ impl<'a, 'll, 'ctx> BuilderMethods<'a, 'ctx> for CodegenBuilder<'a, 'll, 'ctx> {
                 false, // sign_extend = false → logical shift
                 "lshr",
             )
-            .unwrap()
+            .expect("Failed to build lshr")
             .into()
     }
 
@@ -329,6 +470,13 @@ impl<'a, 'll, 'ctx> BuilderMethods<'a, 'ctx> for CodegenBuilder<'a, 'll, 'ctx> {
     /// Uses `build_right_shift` with `sign_extend = true` to produce an
     /// arithmetic shift that preserves the sign bit.
     fn build_ashr(&mut self, lhs: Self::Value, rhs: Self::Value) -> Self::Value {
+        if let (BasicValueEnum::VectorValue(lhs), BasicValueEnum::VectorValue(rhs)) = (lhs, rhs) {
+            return self
+                .ll_builder
+                .build_right_shift(lhs, rhs, true, "ashr")
+                .expect("Failed to build ashr")
+                .into();
+        }
         assert!(lhs.get_type().is_int_type() && rhs.get_type().is_int_type());
         self.ll_builder
             .build_right_shift(
@@ -337,7 +485,7 @@ impl<'a, 'll, 'ctx> BuilderMethods<'a, 'ctx> for CodegenBuilder<'a, 'll, 'ctx> {
                 true, // sign_extend = true → arithmetic shift
                 "ashr",
             )
-            .unwrap()
+            .expect("Failed to build ashr")
             .into()
     }
 
@@ -348,7 +496,7 @@ impl<'a, 'll, 'ctx> BuilderMethods<'a, 'ctx> for CodegenBuilder<'a, 'll, 'ctx> {
         assert!(val.get_type().is_int_type());
         self.ll_builder
             .build_not(val.into_int_value(), "not")
-            .unwrap()
+            .expect("Failed to build not")
             .into()
     }
 
@@ -358,7 +506,7 @@ impl<'a, 'll, 'ctx> BuilderMethods<'a, 'ctx> for CodegenBuilder<'a, 'll, 'ctx> {
     fn build_trunc(&mut self, val: Self::Value, dest_ty: Self::Type) -> Self::Value {
         self.ll_builder
             .build_int_truncate(val.into_int_value(), dest_ty.into_int_type(), "trunc")
-            .unwrap()
+            .expect("Failed to build trunc")
             .into()
     }
 
@@ -366,7 +514,7 @@ impl<'a, 'll, 'ctx> BuilderMethods<'a, 'ctx> for CodegenBuilder<'a, 'll, 'ctx> {
     fn build_zext(&mut self, val: Self::Value, dest_ty: Self::Type) -> Self::Value {
         self.ll_builder
             .build_int_z_extend(val.into_int_value(), dest_ty.into_int_type(), "zext")
-            .unwrap()
+            .expect("Failed to build zext")
             .into()
     }
 
@@ -374,7 +522,7 @@ impl<'a, 'll, 'ctx> BuilderMethods<'a, 'ctx> for CodegenBuilder<'a, 'll, 'ctx> {
     fn build_sext(&mut self, val: Self::Value, dest_ty: Self::Type) -> Self::Value {
         self.ll_builder
             .build_int_s_extend(val.into_int_value(), dest_ty.into_int_type(), "sext")
-            .unwrap()
+            .expect("Failed to build sext")
             .into()
     }
 
@@ -382,7 +530,7 @@ impl<'a, 'll, 'ctx> BuilderMethods<'a, 'ctx> for CodegenBuilder<'a, 'll, 'ctx> {
     fn build_fptrunc(&mut self, val: Self::Value, dest_ty: Self::Type) -> Self::Value {
         self.ll_builder
             .build_float_trunc(val.into_float_value(), dest_ty.into_float_type(), "fptrunc")
-            .unwrap()
+            .expect("Failed to build fptrunc")
             .into()
     }
 
@@ -390,7 +538,7 @@ impl<'a, 'll, 'ctx> BuilderMethods<'a, 'ctx> for CodegenBuilder<'a, 'll, 'ctx> {
     fn build_fpext(&mut self, val: Self::Value, dest_ty: Self::Type) -> Self::Value {
         self.ll_builder
             .build_float_ext(val.into_float_value(), dest_ty.into_float_type(), "fpext")
-            .unwrap()
+            .expect("Failed to build fpext")
             .into()
     }
 
@@ -398,7 +546,7 @@ impl<'a, 'll, 'ctx> BuilderMethods<'a, 'ctx> for CodegenBuilder<'a, 'll, 'ctx> {
     fn build_sitofp(&mut self, val: Self::Value, dest_ty: Self::Type) -> Self::Value {
         self.ll_builder
             .build_signed_int_to_float(val.into_int_value(), dest_ty.into_float_type(), "sitofp")
-            .unwrap()
+            .expect("Failed to build sitofp")
             .into()
     }
 
@@ -406,7 +554,7 @@ impl<'a, 'll, 'ctx> BuilderMethods<'a, 'ctx> for CodegenBuilder<'a, 'll, 'ctx> {
     fn build_uitofp(&mut self, val: Self::Value, dest_ty: Self::Type) -> Self::Value {
         self.ll_builder
             .build_unsigned_int_to_float(val.into_int_value(), dest_ty.into_float_type(), "uitofp")
-            .unwrap()
+            .expect("Failed to build uitofp")
             .into()
     }
 
@@ -414,7 +562,7 @@ impl<'a, 'll, 'ctx> BuilderMethods<'a, 'ctx> for CodegenBuilder<'a, 'll, 'ctx> {
     fn build_fptosi(&mut self, val: Self::Value, dest_ty: Self::Type) -> Self::Value {
         self.ll_builder
             .build_float_to_signed_int(val.into_float_value(), dest_ty.into_int_type(), "fptosi")
-            .unwrap()
+            .expect("Failed to build fptosi")
             .into()
     }
 
@@ -422,7 +570,7 @@ impl<'a, 'll, 'ctx> BuilderMethods<'a, 'ctx> for CodegenBuilder<'a, 'll, 'ctx> {
     fn build_fptoui(&mut self, val: Self::Value, dest_ty: Self::Type) -> Self::Value {
         self.ll_builder
             .build_float_to_unsigned_int(val.into_float_value(), dest_ty.into_int_type(), "fptoui")
-            .unwrap()
+            .expect("Failed to build fptoui")
             .into()
     }
 
@@ -434,7 +582,7 @@ impl<'a, 'll, 'ctx> BuilderMethods<'a, 'ctx> for CodegenBuilder<'a, 'll, 'ctx> {
                 dest_ty.into_pointer_type(),
                 "inttoptr",
             )
-            .unwrap()
+            .expect("Failed to build inttoptr")
             .into()
     }
 
@@ -446,7 +594,7 @@ impl<'a, 'll, 'ctx> BuilderMethods<'a, 'ctx> for CodegenBuilder<'a, 'll, 'ctx> {
                 dest_ty.into_int_type(),
                 "ptrtoint",
             )
-            .unwrap()
+            .expect("Failed to build ptrtoint")
             .into()
     }
 
@@ -454,7 +602,7 @@ impl<'a, 'll, 'ctx> BuilderMethods<'a, 'ctx> for CodegenBuilder<'a, 'll, 'ctx> {
     fn build_bitcast(&mut self, val: Self::Value, dest_ty: Self::Type) -> Self::Value {
         self.ll_builder
             .build_bit_cast(val, dest_ty, "bitcast")
-            .unwrap()
+            .expect("Failed to build bitcast")
     }
 
     // Float arithmetic operations
@@ -486,7 +634,7 @@ impl<'a, 'll, 'ctx> BuilderMethods<'a, 'ctx> for CodegenBuilder<'a, 'll, 'ctx> {
         ty_layout: TyAndLayout<TirTy<'ctx>>,
     ) -> Self::Value {
         assert!(matches!(ty_layout.backend_repr, BackendRepr::Scalar(_)));
-        let llty = ty_layout.ty.into_basic_type(self.ctx);
+        let llty = self.ctx.backend_type_of(ty_layout.ty);
         let be_repr = ty_layout.backend_repr.to_primitive();
         let bitsize = if ty_layout.is_bool() {
             1
@@ -506,10 +654,9 @@ impl<'a, 'll, 'ctx> BuilderMethods<'a, 'ctx> for CodegenBuilder<'a, 'll, 'ctx> {
                 // because it cannot be cast to a float 32; LLVM rejects such casts because of
                 // "invalid cast opcode". Consequently, the `llval.const_truncate_or_bit_cast(llty.into_int_type()).into()` method
                 // also would fail due to llty being a float type.
-                let base_int = self
-                    .ctx
-                    .ll_context
-                    .custom_width_int_type(NonZeroU32::new(bitsize as u32).unwrap());
+                let base_int = self.ctx.ll_context.custom_width_int_type(
+                    NonZeroU32::new(bitsize as u32).expect("zero-sized scalar"),
+                );
 
                 // Split the 128-bit integer into two 64-bit words for LLVM
                 let words = [(bits & u64::MAX as u128) as u64, (bits >> 64) as u64];
@@ -519,7 +666,9 @@ impl<'a, 'll, 'ctx> BuilderMethods<'a, 'ctx> for CodegenBuilder<'a, 'll, 'ctx> {
                 if let Primitive::Pointer(_) = be_repr {
                     llval.const_to_pointer(llty.into_pointer_type()).into()
                 } else {
-                    self.ll_builder.build_bit_cast(llval, llty, "").unwrap()
+                    self.ll_builder
+                        .build_bit_cast(llval, llty, "")
+                        .expect("Failed to build bitcast")
                 }
             }
         }
@@ -563,6 +712,13 @@ impl<'a, 'll, 'ctx> BuilderMethods<'a, 'ctx> for CodegenBuilder<'a, 'll, 'ctx> {
             .build_call(fn_value, args, name)
             .expect("Failed to build call instruction");
 
+        // Mirror the callee's `sret` return slot on the call site, so that
+        // the hidden leading pointer is treated as the return slot here too.
+        let sret_kind_id = Attribute::get_named_enum_kind_id("sret");
+        if let Some(sret) = fn_value.get_enum_attribute(AttributeLoc::Param(0), sret_kind_id) {
+            call_site.add_attribute(AttributeLoc::Param(0), sret);
+        }
+
         // Try to get the return value. If the function returns void, this will be None.
         // inkwell returns a ValueKind enum with Basic/Instruction variants
         match call_site.try_as_basic_value() {
@@ -571,6 +727,34 @@ impl<'a, 'll, 'ctx> BuilderMethods<'a, 'ctx> for CodegenBuilder<'a, 'll, 'ctx> {
         }
     }
 
+    /// Build a call to inline assembly, e.g. `call void asm sideeffect "nop", ""()`.
+    ///
+    /// Every argument gets an `r` constraint and every clobber a `~{...}`
+    /// one, in that order.
+    fn build_inline_asm(&mut self, template: &str, args: &[Self::Value], clobbers: &[String]) {
+        let param_tys: Vec<_> = args.iter().map(|arg| arg.get_type().into()).collect();
+        let fn_ty = self.ctx.ll_context.void_type().fn_type(&param_tys, false);
+        let constraints = args
+            .iter()
+            .map(|_| "r".to_string())
+            .chain(clobbers.iter().map(|clobber| format!("~{{{clobber}}}")))
+            .collect::<Vec<_>>()
+            .join(",");
+        let asm = self.ctx.ll_context.create_inline_asm(
+            fn_ty,
+            template.to_string(),
+            constraints,
+            true,
+            false,
+            None,
+            false,
+        );
+        let args: Vec<_> = args.iter().map(|&arg| arg.into()).collect();
+        self.ll_builder
+            .build_indirect_call(fn_ty, asm, &args, "")
+            .expect("Failed to build inline assembly call");
+    }
+
     fn build_unconditional_br(&mut self, target: Self::BasicBlock) {
         self.ll_builder
             .build_unconditional_branch(target)
@@ -618,6 +802,27 @@ impl<'a, 'll, 'ctx> BuilderMethods<'a, 'ctx> for CodegenBuilder<'a, 'll, 'ctx> {
             .expect("Failed to build unreachable");
     }
 
+    fn current_block(&self) -> Self::BasicBlock {
+        self.ll_builder
+            .get_insert_block()
+            .expect("Builder is not positioned in a basic block")
+    }
+
+    fn build_phi(&mut self, ty: Self::Type) -> Self::Value {
+        self.ll_builder
+            .build_phi(ty, "phi")
+            .expect("Failed to build phi")
+            .as_basic_value()
+    }
+
+    fn add_phi_incoming(&mut self, phi: Self::Value, value: Self::Value, bb: Self::BasicBlock) {
+        let phi = phi
+            .as_instruction_value()
+            .and_then(|inst| PhiValue::try_from(inst).ok())
+            .expect("add_phi_incoming called with a non-phi value");
+        phi.add_incoming(&[(&value, bb)]);
+    }
+
     fn build_icmp(
         &mut self,
         op: tidec_tir::syntax::BinaryOp,
@@ -667,7 +872,7 @@ impl<'a, 'll, 'ctx> BuilderMethods<'a, 'ctx> for CodegenBuilder<'a, 'll, 'ctx> {
         // whose width matches the target's pointer size.
         let ptr_int_ty_res = self.ctx.ll_context.custom_width_int_type(
             NonZeroU32::new(self.ctx.lir_ctx.target().data_layout.pointer_size.bits() as u32)
-                .unwrap(),
+                .expect("zero-sized pointer"),
         );
         let ptr_int_ty =
             ptr_int_ty_res.expect("Failed to create pointer-width integer type for icmp");
@@ -917,4 +1122,9 @@ impl<'a, 'll, 'ctx> BuilderMethods<'a, 'ctx> for CodegenBuilder<'a, 'll, 'ctx> {
             .const_null()
             .into()
     }
+
+    /// Produce an all-zero constant (`zeroinitializer` for aggregates).
+    fn const_zero(&self, ty_layout: TyAndLayout<'ctx, TirTy<'ctx>>) -> Self::Value {
+        self.ctx.backend_type_of(ty_layout.ty).const_zero()
+    }
 }
@@ -2,27 +2,30 @@ use std::cell::RefCell;
 use std::collections::HashMap;
 use std::num::NonZeroU32;
 use std::ops::Deref;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::process::Command;
 
+use inkwell::attributes::{Attribute, AttributeLoc};
 use inkwell::basic_block::BasicBlock;
 use inkwell::context::Context;
-use inkwell::module::Module;
+use inkwell::module::{FlagBehavior, Module};
 use inkwell::targets::{
-    CodeModel, FileType, InitializationConfig, RelocMode, Target, TargetMachine, TargetTriple,
+    CodeModel, FileType, InitializationConfig, RelocMode, Target, TargetData, TargetMachine,
+    TargetTriple,
 };
-use inkwell::types::{BasicMetadataTypeEnum, BasicTypeEnum, FunctionType};
+use inkwell::types::{AnyType, BasicMetadataTypeEnum, BasicTypeEnum, FunctionType};
 use inkwell::values::{AnyValueEnum, BasicMetadataValueEnum, BasicValueEnum, FunctionValue};
 use inkwell::OptimizationLevel;
-use tidec_abi::calling_convention::function::{ArgAbi, FnAbi, PassMode};
-use tidec_abi::layout::{BackendRepr, TyAndLayout};
+use tidec_abi::calling_convention::function::{classify_arg, ArgAbi, CastTarget, FnAbi, PassMode};
+use tidec_abi::layout::TyAndLayout;
 use tidec_codegen_ssa::tir;
 use tidec_tir::alloc::{AllocId, Allocation, GlobalAlloc};
-use tidec_tir::ctx::{EmitKind, TirCtx};
+use tidec_tir::ctx::{EmitKind, ModuleFlagBehavior, TirCtx};
 use tidec_tir::TirTy;
 use tidec_utils::index_vec::IdxVec;
 use tracing::{debug, info, instrument, trace};
 
+use crate::error::CodegenError;
 use crate::tir::tir_body_metadata::{
     CallConvUtils, LinkageUtils, UnnamedAddressUtils, VisibilityUtils,
 };
@@ -31,7 +34,7 @@ use tidec_codegen_ssa::traits::{
     BackendTypeOf, BuilderMethods, CodegenBackend, CodegenBackendTypes, CodegenMethods,
     DefineCodegenMethods, FnAbiOf, LayoutOf, PreDefineCodegenMethods,
 };
-use tidec_tir::body::{DefId, GlobalId, TirBody, TirBodyMetadata, TirGlobal, TirUnit};
+use tidec_tir::body::{DefId, GlobalId, Linkage, TirBody, TirBodyMetadata, TirGlobal, TirUnit};
 use tidec_tir::syntax::{Local, LocalData, RETURN_LOCAL};
 
 // TODO: Add filelds from rustc/compiler/rustc_codegen_llvm/src/context.rs
@@ -55,8 +58,19 @@ pub struct CodegenCtx<'ctx, 'll> {
     /// so that operands referencing `GlobalAlloc::Static(global_id)` can
     /// be resolved to the backend value.
     pub global_values: RefCell<HashMap<GlobalId, BasicValueEnum<'ll>>>,
+    /// A map from a function to its overflow-panic landing pad.
+    ///
+    /// Populated lazily by `panic_block` the first time a checked arithmetic
+    /// operation is emitted in the function.
+    pub panic_blocks: RefCell<HashMap<FunctionValue<'ll>, BasicBlock<'ll>>>,
 }
 
+/// The runtime symbol called when a checked arithmetic operation overflows.
+const PANIC_OVERFLOW_FN: &str = "tidec_panic_overflow";
+
+/// The runtime symbol called when a bounds check fails.
+const PANIC_BOUNDS_CHECK_FN: &str = "tidec_panic_bounds_check";
+
 impl<'ll, 'ctx> Deref for CodegenCtx<'ctx, 'll> {
     type Target = Context;
 
@@ -86,46 +100,8 @@ impl<'ctx> PreDefineCodegenMethods<'ctx> for CodegenCtx<'ctx, '_> {
         lir_body_metadata: &TirBodyMetadata,
         lir_body_ret_and_args: &IdxVec<Local, LocalData<'ctx>>,
     ) {
-        let name = lir_body_metadata.name.as_str();
-
-        let ret_ty_tir = lir_body_ret_and_args[RETURN_LOCAL].ty;
-        let formal_param_tys = lir_body_ret_and_args.as_slice()[RETURN_LOCAL.next()..]
-            .iter()
-            .map(|local_data| local_data.ty.into_basic_type_metadata(self))
-            .collect::<Vec<_>>();
-
-        // If the return type is Unit (void), use void_type for the LLVM function type.
-        // Otherwise, use the basic type for the return type.
-        let fn_ty = if ret_ty_tir.is_unit() {
-            self.declare_void_fn(formal_param_tys.as_slice(), lir_body_metadata.is_varargs)
-        } else {
-            let ret_ty = ret_ty_tir.into_basic_type(self);
-            self.declare_fn(
-                ret_ty,
-                formal_param_tys.as_slice(),
-                lir_body_metadata.is_varargs,
-            )
-        };
-        let linkage = lir_body_metadata.linkage.into_linkage();
-        let calling_convention = lir_body_metadata.call_conv.into_call_conv();
-        let fn_val = self.ll_module.add_function(name, fn_ty, Some(linkage));
-        fn_val.set_call_conventions(calling_convention);
-
-        let fn_global_value = fn_val.as_global_value();
-        let visibility = lir_body_metadata.visibility.into_visibility();
-        fn_global_value.set_visibility(visibility);
-        let unnamed_addr = lir_body_metadata.unnamed_address.into_unnamed_address();
-        fn_global_value.set_unnamed_address(unnamed_addr);
-
-        debug!(
-            "get_or_declare_fn((name: {}, ret_ty: {:?}, param_tys: {:?}, linkage: {:?}, visibility: {:?}, calling_convention: {:?}, unnamed_addr: {:?})) declared",
-            name, ret_ty_tir, formal_param_tys, linkage, visibility, calling_convention, unnamed_addr
-        );
-
-        self.instances.borrow_mut().insert(
-            lir_body_metadata.def_id,
-            AnyValueEnum::FunctionValue(fn_val),
-        );
+        self.try_predefine_body(lir_body_metadata, lir_body_ret_and_args)
+            .unwrap_or_else(|err| panic!("{err}"));
     }
 }
 
@@ -147,10 +123,30 @@ impl<'ctx, 'll> BackendTypeOf<'ctx> for CodegenCtx<'ctx, 'll> {
     /// Convert a TIR type to the corresponding LLVM `BasicTypeEnum`.
     ///
     /// Delegates to the `BasicTypesUtils::into_basic_type` method defined
-    /// in the `tir_ty` module.
+    /// in the `tir_ty` module, panicking on a `CodegenError`.
     fn backend_type_of(&self, ty: TirTy<'ctx>) -> BasicTypeEnum<'ll> {
         ty.into_basic_type(self)
     }
+
+    /// Coerce to `iN` when the aggregate fits in one register, and to a
+    /// struct of integers (e.g. `{ i64, i32 }`) otherwise.
+    fn cast_backend_type(&self, cast: CastTarget) -> BasicTypeEnum<'ll> {
+        let chunks: Vec<BasicTypeEnum<'ll>> = cast
+            .chunks()
+            .iter()
+            .map(|size| {
+                let bits = NonZeroU32::new(size.bits() as u32).expect("zero-sized cast chunk");
+                self.ll_context
+                    .custom_width_int_type(bits)
+                    .expect("invalid cast chunk width")
+                    .into()
+            })
+            .collect();
+        match chunks.as_slice() {
+            [chunk] => *chunk,
+            _ => self.ll_context.struct_type(&chunks, false).into(),
+        }
+    }
 }
 
 impl<'ctx, 'll> FnAbiOf<'ctx> for CodegenCtx<'ctx, 'll> {
@@ -163,15 +159,8 @@ impl<'ctx, 'll> FnAbiOf<'ctx> for CodegenCtx<'ctx, 'll> {
 
         let argument_of = |ty: TirTy<'ctx>| -> ArgAbi<TirTy<'ctx>> {
             let layout = ty_ctx.layout_of(ty);
-            let pass_mode = match layout.backend_repr {
-                BackendRepr::Scalar(_) => PassMode::Direct,
-                BackendRepr::Memory => PassMode::Indirect,
-            };
-            let mut arg = ArgAbi::new(layout, pass_mode);
-            if arg.layout.is_zst() {
-                arg.mode = PassMode::Ignore;
-            }
-            arg
+            let pass_mode = classify_arg(ty_ctx.target(), &layout);
+            ArgAbi::new(layout, pass_mode)
         };
 
         let ret_arg_abi = argument_of(lir_ret_and_args[RETURN_LOCAL].ty);
@@ -190,8 +179,9 @@ impl<'ctx, 'll> FnAbiOf<'ctx> for CodegenCtx<'ctx, 'll> {
 impl<'ctx, 'll> CodegenCtx<'ctx, 'll> {
     /// Creates a new codegen context for the LLVM backend.
     ///
-    /// Sets the target triple on the module. The data layout is set
-    /// later by [`create_target_machine`] before emitting output.
+    /// Sets the target triple and the data layout of the `TirTarget` on the
+    /// module. When emitting machine code, [`create_target_machine`] replaces
+    /// the data layout with the one of the `TargetMachine`.
     ///
     /// On Windows, LLVM-allocated wrappers are intentionally leaked
     /// with [`std::mem::forget`] to avoid `STATUS_ACCESS_VIOLATION`
@@ -223,15 +213,479 @@ impl<'ctx, 'll> CodegenCtx<'ctx, 'll> {
         ll_module.set_triple(&created_triple);
         std::mem::forget(created_triple);
 
+        // Set the data layout on the module so that LLVM computes the same
+        // sizes and field offsets as our `TargetDataLayout`, instead of
+        // assuming the host's.
+        let data_layout_str = internal_target.data_layout_string();
+        debug!("Using data layout: {:?}", data_layout_str);
+        let target_data = TargetData::create(&data_layout_str);
+        let data_layout = target_data.get_data_layout();
+        ll_module.set_data_layout(&data_layout);
+        // Leak TargetData and DataLayout to avoid cross-heap free crash.
+        std::mem::forget(data_layout);
+        std::mem::forget(target_data);
+
+        for flag in lir_ctx.module_flags() {
+            let value = ll_context.i32_type().const_int(flag.value as u64, true);
+            let behavior = match flag.behavior {
+                ModuleFlagBehavior::Error => FlagBehavior::Error,
+                ModuleFlagBehavior::Warning => FlagBehavior::Warning,
+                ModuleFlagBehavior::Override => FlagBehavior::Override,
+            };
+            ll_module.add_basic_value_flag(&flag.name, behavior, value);
+        }
+
         CodegenCtx {
             ll_context,
             ll_module,
             lir_ctx,
             instances: RefCell::new(HashMap::new()),
             global_values: RefCell::new(HashMap::new()),
+            panic_blocks: RefCell::new(HashMap::new()),
         }
     }
 
+    /// Declare the function of the given body in the module.
+    ///
+    /// This is the fallible counterpart of `predefine_body`: it fails if the
+    /// signature contains a type with no LLVM value representation.
+    pub fn try_predefine_body(
+        &self,
+        lir_body_metadata: &TirBodyMetadata,
+        lir_body_ret_and_args: &IdxVec<Local, LocalData<'ctx>>,
+    ) -> Result<(), CodegenError<'ctx>> {
+        let name = lir_body_metadata.symbol_name();
+
+        let ret_ty_tir = lir_body_ret_and_args[RETURN_LOCAL].ty;
+        let fn_abi = self.fn_abi_of(lir_body_ret_and_args);
+        let ptr_ty =
+            BasicMetadataTypeEnum::from(self.ll_context.ptr_type(inkwell::AddressSpace::default()));
+
+        // An indirect return is lowered to a leading `sret` pointer parameter
+        // and a `void` return.
+        let mut formal_param_tys = Vec::with_capacity(fn_abi.args.len() + 1);
+        if fn_abi.ret.mode == PassMode::Indirect {
+            formal_param_tys.push(ptr_ty);
+        }
+        for (local_data, arg_abi) in lir_body_ret_and_args.as_slice()[RETURN_LOCAL.next()..]
+            .iter()
+            .zip(&fn_abi.args)
+        {
+            match arg_abi.mode {
+                PassMode::Ignore => {}
+                PassMode::Direct => {
+                    formal_param_tys.push(local_data.ty.into_basic_type_metadata(self)?)
+                }
+                PassMode::Indirect => formal_param_tys.push(ptr_ty),
+                PassMode::Cast(cast) => formal_param_tys.push(self.cast_backend_type(cast).into()),
+            }
+        }
+
+        // If the return type is Unit (void) or returned through an `sret`
+        // pointer, use void_type for the LLVM function type. Otherwise, use
+        // the basic type for the return type, or the type it is cast to.
+        let fn_ty = match fn_abi.ret.mode {
+            PassMode::Ignore | PassMode::Indirect => {
+                self.declare_void_fn(formal_param_tys.as_slice(), lir_body_metadata.is_varargs)
+            }
+            PassMode::Direct => {
+                let ret_ty = ret_ty_tir.try_into_basic_type(self)?;
+                self.declare_fn(
+                    ret_ty,
+                    formal_param_tys.as_slice(),
+                    lir_body_metadata.is_varargs,
+                )
+            }
+            PassMode::Cast(cast) => self.declare_fn(
+                self.cast_backend_type(cast),
+                formal_param_tys.as_slice(),
+                lir_body_metadata.is_varargs,
+            ),
+        };
+        let linkage = lir_body_metadata.effective_linkage().into_linkage();
+        let calling_convention = lir_body_metadata.call_conv.into_call_conv();
+        let fn_val = self.ll_module.add_function(name, fn_ty, Some(linkage));
+        fn_val.set_call_conventions(calling_convention);
+        if fn_abi.ret.mode == PassMode::Indirect {
+            let sret_ty = ret_ty_tir.try_into_basic_type(self)?;
+            fn_val.add_attribute(
+                AttributeLoc::Param(0),
+                self.ll_context.create_type_attribute(
+                    Attribute::get_named_enum_kind_id("sret"),
+                    sret_ty.as_any_type_enum(),
+                ),
+            );
+        }
+        let target_features = self.lir_ctx.target_features();
+        if !target_features.is_empty() {
+            fn_val.add_attribute(
+                AttributeLoc::Function,
+                self.ll_context
+                    .create_string_attribute("target-features", &target_features.join(",")),
+            );
+        }
+
+        let fn_global_value = fn_val.as_global_value();
+        let visibility = lir_body_metadata.visibility.into_visibility();
+        fn_global_value.set_visibility(visibility);
+        let unnamed_addr = lir_body_metadata.unnamed_address.into_unnamed_address();
+        fn_global_value.set_unnamed_address(unnamed_addr);
+
+        debug!(
+            "get_or_declare_fn((name: {}, ret_ty: {:?}, param_tys: {:?}, linkage: {:?}, visibility: {:?}, calling_convention: {:?}, unnamed_addr: {:?})) declared",
+            name, ret_ty_tir, formal_param_tys, linkage, visibility, calling_convention, unnamed_addr
+        );
+
+        self.instances.borrow_mut().insert(
+            lir_body_metadata.def_id,
+            AnyValueEnum::FunctionValue(fn_val),
+        );
+
+        Ok(())
+    }
+
+    /// Run the LLVM verifier on the module if `TirArgs::verify_module` is set,
+    /// so that codegen bugs are reported before the module is emitted.
+    pub fn verify_module(&self) -> Result<(), CodegenError<'ctx>> {
+        if !self.lir_ctx.verify_module() {
+            return Ok(());
+        }
+        self.ll_module.verify().map_err(|llvm_string| {
+            let message = llvm_string.to_string();
+            // Leak the LLVMString to avoid cross-heap free crash.
+            std::mem::forget(llvm_string);
+            CodegenError::InvalidModule(message)
+        })
+    }
+
+    /// Compile the given TIR unit, reporting type conversion failures in
+    /// globals and function signatures as a `CodegenError`.
+    ///
+    /// TODO(bruzzone): errors raised while lowering function bodies still
+    /// panic, as the generic SSA codegen is infallible.
+    #[instrument(level = "info", skip(self, lir_unit), fields(unit = %lir_unit.metadata.unit_name, bodies = lir_unit.bodies.len(), globals = lir_unit.globals.len()))]
+    pub fn try_compile_tir_unit(&self, lir_unit: TirUnit<'ctx>) -> Result<(), CodegenError<'ctx>> {
+        info!(
+            "Starting codegen for unit `{}` ({} globals, {} bodies)",
+            lir_unit.metadata.unit_name,
+            lir_unit.globals.len(),
+            lir_unit.bodies.len()
+        );
+
+        // 1. Define global variables first so that function bodies can reference them.
+        for (global_id, global) in lir_unit.globals.iter_enumerated() {
+            trace!(?global_id, name = %global.name, "Defining global");
+            self.try_define_global(global_id, global)?;
+        }
+
+        // 2. Predefine the functions. That is, create the function declarations.
+        for lir_body in &lir_unit.bodies {
+            debug!(
+                "Predefining body `{}` (is_declaration = {}, linkage = {:?})",
+                lir_body.metadata.name, lir_body.metadata.is_declaration, lir_body.metadata.linkage
+            );
+            self.try_predefine_body(&lir_body.metadata, &lir_body.ret_and_args)?;
+            lir_body
+                .check_block_args()
+                .map_err(CodegenError::InvalidBlockArgs)?;
+            lir_body
+                .check_simd_binops(&self.lir_ctx)
+                .map_err(CodegenError::UnsupportedSimdBinOp)?;
+            lir_body
+                .check_inline_asm_operands(&self.lir_ctx)
+                .map_err(CodegenError::UnsupportedInlineAsmOperand)?;
+        }
+
+        // 3. Find the bodies identical to one defined before them. Bodies are
+        // bucketed by `content_hash` and only aliased after a `content_eq`
+        // check, so that a hash collision never merges different code.
+        let mut canonical_bodies: HashMap<u64, Vec<&TirBody<'ctx>>> = HashMap::new();
+        let aliases: Vec<Option<FunctionValue<'ll>>> = lir_unit
+            .bodies
+            .iter()
+            .map(|lir_body| {
+                if lir_body.metadata.is_declaration {
+                    return None;
+                }
+                let candidates = canonical_bodies.entry(lir_body.content_hash()).or_default();
+                match candidates.iter().find(|c| c.content_eq(lir_body)) {
+                    Some(canonical)
+                        if matches!(
+                            lir_body.metadata.effective_linkage(),
+                            Linkage::Private | Linkage::Internal
+                        ) =>
+                    {
+                        self.get_fn(&canonical.metadata)
+                    }
+                    Some(_) => None,
+                    None => {
+                        candidates.push(lir_body);
+                        None
+                    }
+                }
+            })
+            .collect();
+
+        // Destructure the TirUnit to get the bodies
+        let TirUnit { bodies, .. } = lir_unit;
+
+        // 4. Now that all globals and functions are pre-defined, compile the bodies.
+        for (lir_body, alias) in bodies.into_iter().zip(aliases) {
+            // Skip external declarations (like libc functions) that have no body.
+            if lir_body.metadata.is_declaration {
+                debug!(
+                    "Skipping body definition for declaration: {}",
+                    lir_body.metadata.name
+                );
+                continue;
+            }
+            if let Some(canonical) = alias {
+                self.alias_fn(&lir_body.metadata, canonical);
+                continue;
+            }
+            // It corresponds to:
+            // ```rust
+            // for &(mono_item, item_data) in &mono_items {
+            //     mono_item.define::<Builder<'_, '_, '_>>(&mut cx, cgu_name.as_str(), item_data);
+            // }
+            // ```
+            // in rustc_codegen_llvm/src/base.rs
+            // lir::define_lir_body::<B>(ctx, lir_body);
+            self.define_body(lir_body);
+        }
+
+        let llvm_str = self.ll_module.print_to_string();
+        debug!("\n{}", llvm_str.to_string());
+        std::mem::forget(llvm_str);
+
+        Ok(())
+    }
+
+    /// Define a global variable in the module.
+    ///
+    /// This is the fallible counterpart of `define_global`: it fails if the
+    /// type of the global has no LLVM value representation.
+    pub fn try_define_global(
+        &self,
+        global_id: GlobalId,
+        global: &TirGlobal<'ctx>,
+    ) -> Result<(), CodegenError<'ctx>> {
+        use tidec_tir::syntax::{ConstScalar, ConstValue};
+
+        let ll_ty = global.ty.try_into_basic_type(self)?;
+        let ll_global = self.ll_module.add_global(ll_ty, None, &global.name);
+
+        // Set initializer
+        if let Some(ref init) = global.initializer {
+            match init {
+                ConstValue::ZST => {
+                    // Zero-sized: use undef (no real storage needed, but LLVM
+                    // still requires an initializer for definitions).
+                    ll_global.set_initializer(&ll_ty.const_zero());
+                }
+                ConstValue::ZeroInit => {
+                    ll_global.set_initializer(&ll_ty.const_zero());
+                }
+                ConstValue::NullPtr => {
+                    let ptr_ty = self.ll_context.ptr_type(inkwell::AddressSpace::default());
+                    ll_global.set_initializer(&ptr_ty.const_null());
+                }
+                ConstValue::Scalar(scalar) => match scalar {
+                    ConstScalar::Value(raw) => {
+                        let layout = self.layout_of(global.ty);
+                        let val = self.const_scalar_to_backend_value_internal(raw, layout);
+                        ll_global.set_initializer(&val);
+                    }
+                },
+                ConstValue::Slice { data } => {
+                    // The elements live in their own private global; this
+                    // global holds the `{ ptr, len }` pair pointing to them.
+                    let layout = self.layout_of(global.ty);
+                    let elem_layout = self.lir_ctx.slice_elem_layout(layout);
+                    let len = self.lir_ctx.const_slice_len(layout, data);
+                    let i8_type = self.ll_context.i8_type();
+                    let byte_values: Vec<_> = data
+                        .iter()
+                        .map(|&b| i8_type.const_int(b as u64, false))
+                        .collect();
+                    let data_global = self.ll_module.add_global(
+                        i8_type.array_type(data.len() as u32),
+                        None,
+                        &format!("{}.data", global.name),
+                    );
+                    data_global.set_initializer(&i8_type.const_array(&byte_values));
+                    data_global.set_constant(true);
+                    data_global.set_linkage(inkwell::module::Linkage::Private);
+                    data_global.set_unnamed_addr(true);
+                    data_global.set_alignment(elem_layout.align.abi.bytes() as u32);
+
+                    let pair_ty = ll_ty.into_struct_type();
+                    let len_ty = pair_ty
+                        .get_field_type_at_index(1)
+                        .expect("wide pointer has a length field")
+                        .into_int_type();
+                    ll_global.set_initializer(&pair_ty.const_named_struct(&[
+                        data_global.as_pointer_value().into(),
+                        len_ty.const_int(len, false).into(),
+                    ]));
+                }
+                ConstValue::Indirect { alloc_id, .. } => {
+                    let alloc_data = self.global_alloc(*alloc_id);
+                    match alloc_data {
+                        GlobalAlloc::Memory(interned_alloc) => {
+                            // Indirect initializers carry raw bytes. We must
+                            // set the global's type to `[N x i8]` so the
+                            // initializer's type matches the global's type.
+                            let bytes = interned_alloc.bytes();
+                            let i8_type = self.ll_context.i8_type();
+                            let byte_values: Vec<_> = bytes
+                                .iter()
+                                .map(|&b| i8_type.const_int(b as u64, false))
+                                .collect();
+                            let const_array = i8_type.const_array(&byte_values);
+
+                            // Remove the old global (wrong type) and recreate
+                            // with the correct `[N x i8]` type so LLVM accepts
+                            // the initializer.
+                            let byte_array_ty = i8_type.array_type(bytes.len() as u32);
+                            ll_global.as_pointer_value(); // ensure it exists
+                            unsafe { ll_global.delete() };
+                            let ll_global =
+                                self.ll_module.add_global(byte_array_ty, None, &global.name);
+                            ll_global.set_initializer(&const_array);
+
+                            // Replicate mutability/linkage/visibility below
+                            // (the original ll_global is deleted).
+                            ll_global.set_constant(!global.mutable);
+                            ll_global.set_linkage(global.linkage.into_linkage());
+                            ll_global.set_visibility(global.visibility.into_visibility());
+                            ll_global
+                                .set_unnamed_address(global.unnamed_address.into_unnamed_address());
+
+                            // Store the global early and return the normal
+                            // attribute-setting. Code below would fail because
+                            // `ll_global` was deleted and recreated.
+                            debug!(
+                                "define_global(Indirect, name: {}, byte_len: {})",
+                                global.name,
+                                bytes.len()
+                            );
+                            self.global_values
+                                .borrow_mut()
+                                .insert(global_id, ll_global.as_pointer_value().into());
+                            return Ok(());
+                        }
+                        _ => panic!(
+                            "Global {} has Indirect initializer pointing to non-Memory alloc",
+                            global.name
+                        ),
+                    }
+                }
+            }
+        } else {
+            // No initializer — this is a declaration (e.g. `extern int x;`).
+            // Do NOT set an initializer: LLVM treats globals without an
+            // initializer as external declarations, which is exactly the
+            // semantics documented by `TirGlobal::initializer: None`.
+            // Setting `const_zero()` here would turn the declaration into a
+            // definition and cause duplicate-symbol errors at link time.
+            //
+            // Linkage/visibility/mutability are set by the common code below.
+        }
+
+        // Set mutability
+        ll_global.set_constant(!global.mutable);
+
+        // Set linkage
+        let linkage = global.linkage.into_linkage();
+        ll_global.set_linkage(linkage);
+
+        // Set visibility
+        let visibility = global.visibility.into_visibility();
+        ll_global.as_pointer_value(); // ensure global value exists
+        ll_global.set_visibility(visibility);
+
+        // Set unnamed address
+        let unnamed_addr = global.unnamed_address.into_unnamed_address();
+        ll_global.set_unnamed_address(unnamed_addr);
+
+        debug!(
+            "define_global(name: {}, ty: {:?}, mutable: {}, linkage: {:?})",
+            global.name, global.ty, global.mutable, linkage
+        );
+
+        // Store the global's pointer value so function bodies can reference it.
+        self.global_values
+            .borrow_mut()
+            .insert(global_id, ll_global.as_pointer_value().into());
+
+        Ok(())
+    }
+
+    /// Replace the predefined function of a duplicated body with `canonical`.
+    ///
+    /// Every use of the duplicate is redirected to `canonical`, the `DefId`
+    /// of the duplicate is mapped to `canonical`, and the duplicate is removed
+    /// from the module. This is only sound for bodies with private or internal
+    /// linkage, whose symbol is not visible outside the module.
+    fn alias_fn(&self, lir_body_metadata: &TirBodyMetadata, canonical: FunctionValue<'ll>) {
+        let duplicate = self
+            .get_fn(lir_body_metadata)
+            .expect("body should be predefined before being aliased");
+        debug!(
+            "Aliasing body `{}` to structurally identical function `{}`",
+            lir_body_metadata.name,
+            canonical.get_name().to_string_lossy()
+        );
+
+        duplicate
+            .as_global_value()
+            .as_pointer_value()
+            .replace_all_uses_with(canonical.as_global_value().as_pointer_value());
+        self.instances
+            .borrow_mut()
+            .insert(lir_body_metadata.def_id, canonical.into());
+        unsafe { duplicate.delete() };
+    }
+
+    /// Returns the `tidec_panic_overflow` runtime function, declaring it on
+    /// first use.
+    ///
+    /// The function takes no arguments, never returns, and is expected to be
+    /// provided by the runtime at link time.
+    fn get_or_declare_panic_overflow(&self) -> FunctionValue<'ll> {
+        self.get_or_declare_panic_fn(PANIC_OVERFLOW_FN, &[])
+    }
+
+    /// Returns the panicking runtime function `name`, declaring it with the
+    /// given parameters on first use.
+    ///
+    /// The function never returns, and is expected to be provided by the
+    /// runtime at link time.
+    fn get_or_declare_panic_fn(
+        &self,
+        name: &str,
+        param_tys: &[BasicMetadataTypeEnum<'ll>],
+    ) -> FunctionValue<'ll> {
+        if let Some(f) = self.ll_module.get_function(name) {
+            return f;
+        }
+
+        let fn_ty = self.declare_void_fn(param_tys, false);
+        let fn_val =
+            self.ll_module
+                .add_function(name, fn_ty, Some(inkwell::module::Linkage::External));
+        for attr in ["noreturn", "cold", "nounwind"] {
+            let kind_id = Attribute::get_named_enum_kind_id(attr);
+            fn_val.add_attribute(
+                AttributeLoc::Function,
+                self.ll_context.create_enum_attribute(kind_id, 0),
+            );
+        }
+        debug!("Declared runtime function `{}`", name);
+        fn_val
+    }
+
     fn declare_fn(
         &self,
         ret_ty: BasicTypeEnum<'ll>,
@@ -278,7 +732,7 @@ impl<'ctx, 'll> CodegenCtx<'ctx, 'll> {
     ) -> BasicValueEnum<'ll> {
         use tidec_abi::layout::Primitive;
 
-        let llty = ty_layout.ty.into_basic_type(self);
+        let llty = self.backend_type_of(ty_layout.ty);
         let be_repr = ty_layout.backend_repr.to_primitive();
         let bitsize = if ty_layout.is_bool() {
             1
@@ -287,10 +741,22 @@ impl<'ctx, 'll> CodegenCtx<'ctx, 'll> {
         };
 
         let bits = raw.to_bits(ty_layout.size);
+        // Signed integers are sign-extended, so that the arbitrary-precision
+        // words below hold the two's-complement value of the constant (e.g.
+        // `-1i32` becomes all ones rather than `0xFFFF_FFFF`). LLVM truncates
+        // the words to the width of the type.
+        let int_bits = if ty_layout.ty.is_signed_integer() {
+            raw.to_signed(ty_layout.size) as u128
+        } else {
+            bits
+        };
         let base_int = self
             .ll_context
             .custom_width_int_type(NonZeroU32::new(bitsize as u32).unwrap());
-        let words = [(bits & u64::MAX as u128) as u64, (bits >> 64) as u64];
+        let words = [
+            (int_bits & u64::MAX as u128) as u64,
+            (int_bits >> 64) as u64,
+        ];
         let llval_int = base_int.expect("Failed to create custom width integer type");
         let llval = llval_int.const_int_arbitrary_precision(&words);
 
@@ -377,75 +843,65 @@ impl<'ctx, 'll> CodegenCtx<'ctx, 'll> {
         self.ll_module.get_name().to_str().unwrap()
     }
 
-    /// Emits an object file (`.o`) from the LLVM module.
-    fn emit_object(&self) {
-        self.emit_object_to_path(&format!("{}.o", self.module_name()));
-    }
-
     /// Emits an object file to the specified path.
     ///
-    /// This is a helper function used by both `emit_object` and `emit_executable`.
-    fn emit_object_to_path(&self, obj_path: &str) {
+    /// This is used both for object output and for the intermediate object
+    /// file of `emit_executable`.
+    fn emit_object(&self, obj_path: &Path) {
         let target_machine = self.create_target_machine();
         target_machine
-            .write_to_file(&self.ll_module, FileType::Object, Path::new(obj_path))
+            .write_to_file(&self.ll_module, FileType::Object, obj_path)
             .expect("Failed to write object file");
-        debug!("Wrote object file to {}", obj_path);
+        debug!("Wrote object file to {}", obj_path.display());
         // Leak the TargetMachine to avoid cross-heap crash
         std::mem::forget(target_machine);
     }
 
-    /// Emits an assembly file (`.s`) from the LLVM module.
-    fn emit_assembly(&self) {
+    /// Emits an assembly file from the LLVM module.
+    fn emit_assembly(&self, asm_path: &Path) {
         let target_machine = self.create_target_machine();
-        let asm_path = format!("{}.s", self.module_name());
         target_machine
-            .write_to_file(&self.ll_module, FileType::Assembly, Path::new(&asm_path))
+            .write_to_file(&self.ll_module, FileType::Assembly, asm_path)
             .expect("Failed to write assembly file");
-        debug!("Wrote assembly file to {}", asm_path);
+        debug!("Wrote assembly file to {}", asm_path.display());
         // Leak the TargetMachine to avoid cross-heap crash
         std::mem::forget(target_machine);
     }
 
-    /// Emits an LLVM IR file (`.ll`) from the LLVM module.
-    fn emit_llvm_ir(&self) {
-        let ir_path = format!("{}.ll", self.module_name());
-        let llvm_string = self.ll_module.print_to_string();
-        let ir = llvm_string.to_string();
-        std::mem::forget(llvm_string);
-        std::fs::write(&ir_path, ir).expect("Failed to write LLVM IR file");
-        debug!("Wrote LLVM IR file to {}", ir_path);
+    /// Emits an LLVM IR file from the LLVM module.
+    fn emit_llvm_ir(&self, ir_path: &Path) {
+        if let Err(err) = self.ll_module.print_to_file(ir_path) {
+            panic!("Failed to write LLVM IR file: {}", err);
+        }
+        debug!("Wrote LLVM IR file to {}", ir_path.display());
     }
 
-    /// Emits an LLVM bitcode file (`.bc`) from the LLVM module.
-    fn emit_llvm_bitcode(&self) {
-        let bc_path = format!("{}.bc", self.module_name());
-        if !self.ll_module.write_bitcode_to_path(&bc_path) {
+    /// Emits an LLVM bitcode file from the LLVM module.
+    fn emit_llvm_bitcode(&self, bc_path: &Path) {
+        if !self.ll_module.write_bitcode_to_path(bc_path) {
             panic!("Failed to write LLVM bitcode file");
         }
-        debug!("Wrote LLVM bitcode file to {}", bc_path);
+        debug!("Wrote LLVM bitcode file to {}", bc_path.display());
     }
 
     /// Emits an executable by first generating an object file and then linking it.
     ///
-    /// The linker is determined at compile time based on the host OS:
+    /// The intermediate object file is written next to the executable, with
+    /// a `.o` suffix. The linker is determined at compile time based on the
+    /// host OS:
     /// - Windows: `link.exe`
     /// - macOS/Linux: `cc`
-    fn emit_executable(&self) {
-        let module_name = self.module_name();
-        let obj_path = format!("{}.o", module_name);
-
-        #[cfg(target_os = "windows")]
-        let exe_path = format!("{}.exe", module_name);
-        #[cfg(not(target_os = "windows"))]
-        let exe_path = module_name.to_string();
+    fn emit_executable(&self, exe_path: &Path) {
+        let mut obj_path = exe_path.as_os_str().to_owned();
+        obj_path.push(".o");
+        let obj_path = PathBuf::from(obj_path);
 
         // First, generate the object file
-        self.emit_object_to_path(&obj_path);
-        debug!("Wrote intermediate object file to {}", obj_path);
+        self.emit_object(&obj_path);
+        debug!("Wrote intermediate object file to {}", obj_path.display());
 
         // Link the object file into an executable
-        self.link_object_to_executable(&obj_path, &exe_path);
+        self.link_object_to_executable(&obj_path, exe_path);
 
         // Clean up the intermediate object file
         if let Err(e) = std::fs::remove_file(&obj_path) {
@@ -456,11 +912,12 @@ impl<'ctx, 'll> CodegenCtx<'ctx, 'll> {
     /// Links an object file into an executable.
     ///
     /// The linker command is determined at compile time based on the host OS.
-    fn link_object_to_executable(&self, obj_path: &str, exe_path: &str) {
+    fn link_object_to_executable(&self, obj_path: &Path, exe_path: &Path) {
         #[cfg(target_os = "windows")]
         let mut linker_cmd = {
             let mut cmd = Command::new("link.exe");
-            cmd.arg(format!("/OUT:{}", exe_path)).arg(obj_path);
+            cmd.arg(format!("/OUT:{}", exe_path.display()))
+                .arg(obj_path);
             cmd
         };
 
@@ -487,7 +944,7 @@ impl<'ctx, 'll> CodegenCtx<'ctx, 'll> {
             panic!("Linker failed: {}", stderr);
         }
 
-        debug!("Linked executable to {}", exe_path);
+        debug!("Linked executable to {}", exe_path.display());
     }
 }
 
@@ -496,61 +953,12 @@ impl<'ctx, 'll> CodegenMethods<'ctx> for CodegenCtx<'ctx, 'll> {
         self.lir_ctx
     }
 
-    #[instrument(level = "info", skip(self, lir_unit), fields(unit = %lir_unit.metadata.unit_name, bodies = lir_unit.bodies.len(), globals = lir_unit.globals.len()))]
-    // TODO: Move as a method of `CodegenCtx`?
     fn compile_tir_unit<'a, B: BuilderMethods<'a, 'ctx>>(&self, lir_unit: TirUnit<'ctx>) {
-        info!(
-            "Starting codegen for unit `{}` ({} globals, {} bodies)",
-            lir_unit.metadata.unit_name,
-            lir_unit.globals.len(),
-            lir_unit.bodies.len()
-        );
-
-        // 1. Define global variables first so that function bodies can reference them.
-        for (global_id, global) in lir_unit.globals.iter_enumerated() {
-            trace!(?global_id, name = %global.name, "Defining global");
-            self.define_global(global_id, global);
-        }
-
-        // 2. Predefine the functions. That is, create the function declarations.
-        for lir_body in &lir_unit.bodies {
-            debug!(
-                "Predefining body `{}` (is_declaration = {}, linkage = {:?})",
-                lir_body.metadata.name, lir_body.metadata.is_declaration, lir_body.metadata.linkage
-            );
-            self.predefine_body(&lir_body.metadata, &lir_body.ret_and_args);
-        }
-
-        // Destructure the TirUnit to get the bodies
-        let TirUnit { bodies, .. } = lir_unit;
-
-        // 3. Now that all globals and functions are pre-defined, compile the bodies.
-        for lir_body in bodies {
-            // Skip external declarations (like libc functions) that have no body.
-            if lir_body.metadata.is_declaration {
-                debug!(
-                    "Skipping body definition for declaration: {}",
-                    lir_body.metadata.name
-                );
-                continue;
-            }
-            // It corresponds to:
-            // ```rust
-            // for &(mono_item, item_data) in &mono_items {
-            //     mono_item.define::<Builder<'_, '_, '_>>(&mut cx, cgu_name.as_str(), item_data);
-            // }
-            // ```
-            // in rustc_codegen_llvm/src/base.rs
-            // lir::define_lir_body::<B>(ctx, lir_body);
-            self.define_body(lir_body);
-        }
-
-        let llvm_str = self.ll_module.print_to_string();
-        debug!("\n{}", llvm_str.to_string());
-        std::mem::forget(llvm_str);
+        self.try_compile_tir_unit(lir_unit)
+            .unwrap_or_else(|err| panic!("{err}"));
     }
 
-    fn emit_output(&self) {
+    fn emit_output(&self) -> PathBuf {
         let triple = self.ll_module.get_triple();
         let triple_empty = triple.as_str().to_bytes().is_empty();
         std::mem::forget(triple);
@@ -559,17 +967,19 @@ impl<'ctx, 'll> CodegenMethods<'ctx> for CodegenCtx<'ctx, 'll> {
             "Module target triple must be set before emitting output"
         );
 
+        let path = self.tir_ctx().output_path(self.module_name());
         match self.tir_ctx().emit_kind() {
-            EmitKind::Object => self.emit_object(),
-            EmitKind::Assembly => self.emit_assembly(),
-            EmitKind::LlvmIr => self.emit_llvm_ir(),
-            EmitKind::LlvmBitcode => self.emit_llvm_bitcode(),
-            EmitKind::Executable => self.emit_executable(),
+            EmitKind::Object => self.emit_object(&path),
+            EmitKind::Assembly => self.emit_assembly(&path),
+            EmitKind::LlvmIr => self.emit_llvm_ir(&path),
+            EmitKind::LlvmBitcode => self.emit_llvm_bitcode(&path),
+            EmitKind::Executable => self.emit_executable(&path),
         }
+        path
     }
 
     fn get_fn(&self, lir_body_metadata: &TirBodyMetadata) -> Option<FunctionValue<'ll>> {
-        let name = lir_body_metadata.name.as_str();
+        let name = lir_body_metadata.symbol_name();
 
         if let Some(instance) = self.instances.borrow().get(&lir_body_metadata.def_id) {
             debug!("get_fn(name: {}) found in instances", name);
@@ -618,6 +1028,31 @@ impl<'ctx, 'll> CodegenMethods<'ctx> for CodegenCtx<'ctx, 'll> {
         None
     }
 
+    fn panic_block(&self, fn_value: FunctionValue<'ll>) -> BasicBlock<'ll> {
+        if let Some(bb) = self.panic_blocks.borrow().get(&fn_value) {
+            return *bb;
+        }
+
+        let panic_fn = self.get_or_declare_panic_overflow();
+        let bb = self.ll_context.append_basic_block(fn_value, "panic");
+        let builder = self.ll_context.create_builder();
+        builder.position_at_end(bb);
+        builder
+            .build_call(panic_fn, &[], "")
+            .expect("Failed to build overflow panic call");
+        builder
+            .build_unreachable()
+            .expect("Failed to build unreachable");
+
+        self.panic_blocks.borrow_mut().insert(fn_value, bb);
+        bb
+    }
+
+    fn panic_bounds_check_fn(&self) -> FunctionValue<'ll> {
+        let u64_ty = self.ll_context.i64_type().into();
+        self.get_or_declare_panic_fn(PANIC_BOUNDS_CHECK_FN, &[u64_ty, u64_ty])
+    }
+
     fn global_alloc(&self, alloc_id: AllocId) -> GlobalAlloc<'ctx> {
         self.lir_ctx.get_global_alloc_unwrap(alloc_id)
     }
@@ -661,119 +1096,8 @@ impl<'ctx, 'll> CodegenMethods<'ctx> for CodegenCtx<'ctx, 'll> {
     }
 
     fn define_global(&self, global_id: GlobalId, global: &TirGlobal<'ctx>) {
-        use tidec_tir::syntax::{ConstScalar, ConstValue};
-
-        let ll_ty = global.ty.into_basic_type(self);
-        let ll_global = self.ll_module.add_global(ll_ty, None, &global.name);
-
-        // Set initializer
-        if let Some(ref init) = global.initializer {
-            match init {
-                ConstValue::ZST => {
-                    // Zero-sized: use undef (no real storage needed, but LLVM
-                    // still requires an initializer for definitions).
-                    ll_global.set_initializer(&ll_ty.const_zero());
-                }
-                ConstValue::NullPtr => {
-                    let ptr_ty = self.ll_context.ptr_type(inkwell::AddressSpace::default());
-                    ll_global.set_initializer(&ptr_ty.const_null());
-                }
-                ConstValue::Scalar(scalar) => match scalar {
-                    ConstScalar::Value(raw) => {
-                        let layout = self.layout_of(global.ty);
-                        let val = self.const_scalar_to_backend_value_internal(raw, layout);
-                        ll_global.set_initializer(&val);
-                    }
-                },
-                ConstValue::Indirect { alloc_id, .. } => {
-                    let alloc_data = self.global_alloc(*alloc_id);
-                    match alloc_data {
-                        GlobalAlloc::Memory(interned_alloc) => {
-                            // Indirect initializers carry raw bytes. We must
-                            // set the global's type to `[N x i8]` so the
-                            // initializer's type matches the global's type.
-                            let bytes = interned_alloc.bytes();
-                            let i8_type = self.ll_context.i8_type();
-                            let byte_values: Vec<_> = bytes
-                                .iter()
-                                .map(|&b| i8_type.const_int(b as u64, false))
-                                .collect();
-                            let const_array = i8_type.const_array(&byte_values);
-
-                            // Remove the old global (wrong type) and recreate
-                            // with the correct `[N x i8]` type so LLVM accepts
-                            // the initializer.
-                            let byte_array_ty = i8_type.array_type(bytes.len() as u32);
-                            ll_global.as_pointer_value(); // ensure it exists
-                            unsafe { ll_global.delete() };
-                            let ll_global =
-                                self.ll_module.add_global(byte_array_ty, None, &global.name);
-                            ll_global.set_initializer(&const_array);
-
-                            // Replicate mutability/linkage/visibility below
-                            // (the original ll_global is deleted).
-                            ll_global.set_constant(!global.mutable);
-                            ll_global.set_linkage(global.linkage.into_linkage());
-                            ll_global.set_visibility(global.visibility.into_visibility());
-                            ll_global
-                                .set_unnamed_address(global.unnamed_address.into_unnamed_address());
-
-                            // Store the global early and return the normal
-                            // attribute-setting. Code below would fail because
-                            // `ll_global` was deleted and recreated.
-                            debug!(
-                                "define_global(Indirect, name: {}, byte_len: {})",
-                                global.name,
-                                bytes.len()
-                            );
-                            self.global_values
-                                .borrow_mut()
-                                .insert(global_id, ll_global.as_pointer_value().into());
-                            return;
-                        }
-                        _ => panic!(
-                            "Global {} has Indirect initializer pointing to non-Memory alloc",
-                            global.name
-                        ),
-                    }
-                }
-            }
-        } else {
-            // No initializer — this is a declaration (e.g. `extern int x;`).
-            // Do NOT set an initializer: LLVM treats globals without an
-            // initializer as external declarations, which is exactly the
-            // semantics documented by `TirGlobal::initializer: None`.
-            // Setting `const_zero()` here would turn the declaration into a
-            // definition and cause duplicate-symbol errors at link time.
-            //
-            // Linkage/visibility/mutability are set by the common code below.
-        }
-
-        // Set mutability
-        ll_global.set_constant(!global.mutable);
-
-        // Set linkage
-        let linkage = global.linkage.into_linkage();
-        ll_global.set_linkage(linkage);
-
-        // Set visibility
-        let visibility = global.visibility.into_visibility();
-        ll_global.as_pointer_value(); // ensure global value exists
-        ll_global.set_visibility(visibility);
-
-        // Set unnamed address
-        let unnamed_addr = global.unnamed_address.into_unnamed_address();
-        ll_global.set_unnamed_address(unnamed_addr);
-
-        debug!(
-            "define_global(name: {}, ty: {:?}, mutable: {}, linkage: {:?})",
-            global.name, global.ty, global.mutable, linkage
-        );
-
-        // Store the global's pointer value so function bodies can reference it.
-        self.global_values
-            .borrow_mut()
-            .insert(global_id, ll_global.as_pointer_value().into());
+        self.try_define_global(global_id, global)
+            .unwrap_or_else(|err| panic!("{err}"));
     }
 
     fn get_global_value(&self, global_id: GlobalId) -> BasicValueEnum<'ll> {
@@ -1,18 +1,28 @@
-use crate::{builder::CodegenBuilder, context::CodegenCtx};
+use crate::{context::CodegenCtx, error::CodegenError};
 use inkwell::context::Context;
+use std::path::PathBuf;
 use tidec_codegen_ssa::traits::CodegenMethods;
 use tidec_tir::{body::TirUnit, ctx::TirCtx};
 use tracing::instrument;
 
+/// Compile a TIR unit through the full LLVM codegen pipeline and emit it as
+/// configured by the `TirArgs` of `tir_ctx`.
+///
+/// Returns the path the output was written to, see [`TirCtx::output_path`].
 #[instrument(level = "info", skip(tir_ctx, lir_unit), fields(unit = %lir_unit.metadata.unit_name))]
 // TODO(bruzzone): try to move it to `tidec_codegen_ssa`
-pub fn llvm_codegen_lir_unit<'ctx>(tir_ctx: TirCtx<'ctx>, lir_unit: TirUnit<'ctx>) {
+pub fn llvm_codegen_lir_unit<'ctx>(
+    tir_ctx: TirCtx<'ctx>,
+    lir_unit: TirUnit<'ctx>,
+) -> Result<PathBuf, CodegenError<'ctx>> {
     let ll_context = Context::create();
     let ll_module = ll_context.create_module(&lir_unit.metadata.unit_name);
     let ctx = CodegenCtx::new(tir_ctx, &ll_context, ll_module);
 
-    ctx.compile_tir_unit::<CodegenBuilder<'_, '_, 'ctx>>(lir_unit);
-    ctx.emit_output();
+    let result = ctx
+        .try_compile_tir_unit(lir_unit)
+        .and_then(|()| ctx.verify_module())
+        .map(|()| ctx.emit_output());
 
     // On Windows, dropping inkwell LLVM wrappers (`Context`, `Module`)
     // can crash with `STATUS_ACCESS_VIOLATION` due to CRT-heap
@@ -20,6 +30,8 @@ pub fn llvm_codegen_lir_unit<'ctx>(tir_ctx: TirCtx<'ctx>, lir_unit: TirUnit<'ctx
     // intentionally leak them. The OS reclaims the memory on exit.
     std::mem::forget(ctx);
     std::mem::forget(ll_context);
+
+    result
 }
 
 /// Compile a TIR unit through the full LLVM codegen pipeline and return the
@@ -29,12 +41,15 @@ pub fn llvm_codegen_lir_unit<'ctx>(tir_ctx: TirCtx<'ctx>, lir_unit: TirUnit<'ctx
 /// emitting to a file it returns the textual IR. Useful for testing the
 /// codegen output without requiring a linker.
 #[instrument(level = "debug", skip(tir_ctx, lir_unit), fields(unit = %lir_unit.metadata.unit_name))]
-pub fn llvm_codegen_to_ir_string<'ctx>(tir_ctx: TirCtx<'ctx>, lir_unit: TirUnit<'ctx>) -> String {
+pub fn llvm_codegen_to_ir_string<'ctx>(
+    tir_ctx: TirCtx<'ctx>,
+    lir_unit: TirUnit<'ctx>,
+) -> Result<String, CodegenError<'ctx>> {
     let ll_context = Context::create();
     let ll_module = ll_context.create_module(&lir_unit.metadata.unit_name);
     let ctx = CodegenCtx::new(tir_ctx, &ll_context, ll_module);
 
-    ctx.compile_tir_unit::<CodegenBuilder<'_, '_, 'ctx>>(lir_unit);
+    let result = ctx.try_compile_tir_unit(lir_unit);
 
     let llvm_string = ctx.ll_module.print_to_string();
     let ir = llvm_string.to_string();
@@ -47,5 +62,5 @@ pub fn llvm_codegen_to_ir_string<'ctx>(tir_ctx: TirCtx<'ctx>, lir_unit: TirUnit<
     std::mem::forget(ctx);
     std::mem::forget(ll_context);
 
-    ir
+    result.map(|()| ir)
 }
@@ -0,0 +1,44 @@
+use std::fmt;
+
+use tidec_tir::body::{BlockArgsError, InlineAsmOperandError, SimdBinOpError};
+use tidec_tir::TirTy;
+
+/// Errors that can occur while lowering TIR to LLVM IR.
+#[derive(Debug)]
+pub enum CodegenError<'ctx> {
+    /// The TIR type has no LLVM value type representation
+    /// (e.g., `TirTy::Unit` or `TirTy::Metadata`).
+    InvalidTypeConversion(TirTy<'ctx>),
+    /// The floating point type is not supported by the target
+    /// (see `TargetDataLayout::supported_float_types`).
+    UnsupportedFloatType(TirTy<'ctx>),
+    /// The generated LLVM module failed verification; carries LLVM's message.
+    InvalidModule(String),
+    /// A terminator passes block arguments that do not match the parameters
+    /// of one of its successors.
+    InvalidBlockArgs(BlockArgsError),
+    /// A binary operation applies an operator that has no lane-wise form
+    /// to SIMD vectors.
+    UnsupportedSimdBinOp(SimdBinOpError),
+    /// An inline assembly operand is not an immediate.
+    UnsupportedInlineAsmOperand(InlineAsmOperandError<'ctx>),
+}
+
+impl fmt::Display for CodegenError<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CodegenError::InvalidTypeConversion(ty) => {
+                write!(f, "type {ty:?} cannot be converted to an LLVM basic type")
+            }
+            CodegenError::UnsupportedFloatType(ty) => {
+                write!(f, "float type {ty:?} is not supported by the target")
+            }
+            CodegenError::InvalidModule(msg) => write!(f, "invalid LLVM module: {msg}"),
+            CodegenError::InvalidBlockArgs(err) => write!(f, "invalid block arguments: {err}"),
+            CodegenError::UnsupportedSimdBinOp(err) => write!(f, "{err}"),
+            CodegenError::UnsupportedInlineAsmOperand(err) => write!(f, "{err}"),
+        }
+    }
+}
+
+impl std::error::Error for CodegenError<'_> {}
@@ -1,4 +1,5 @@
 pub mod builder;
 pub mod context;
 pub mod entry;
+pub mod error;
 pub mod tir;
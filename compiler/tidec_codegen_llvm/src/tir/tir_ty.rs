@@ -1,103 +1,128 @@
+use std::num::NonZeroU32;
+
 use inkwell::types::{BasicMetadataTypeEnum, BasicTypeEnum};
+use inkwell::AddressSpace;
+use tidec_abi::layout::Primitive;
 use tidec_tir::{ty, TirTy};
 
 use crate::context::CodegenCtx;
+use crate::error::CodegenError;
 
 /// A trait to convert TirTy into LLVM BasicTypeEnum and BasicMetadataTypeEnum.
 ///
 /// We need to do this due to the orphan rule in Rust. This could cause the
 /// stop of the compilation process of an external crate.
 pub trait BasicTypesUtils<'ctx, 'll> {
-    fn into_basic_type_metadata(self, ctx: &CodegenCtx<'ctx, 'll>) -> BasicMetadataTypeEnum<'ll>;
-    fn into_basic_type(self, ctx: &CodegenCtx<'ctx, 'll>) -> BasicTypeEnum<'ll>;
+    /// Convert into an LLVM type that may appear in a function signature.
+    ///
+    /// This is [`Self::try_into_basic_type`], extended to map `Metadata` to
+    /// the LLVM metadata type.
+    fn into_basic_type_metadata(
+        self,
+        ctx: &CodegenCtx<'ctx, 'll>,
+    ) -> Result<BasicMetadataTypeEnum<'ll>, CodegenError<'ctx>>;
+
+    /// Convert into an LLVM value type.
+    ///
+    /// Returns [`CodegenError::InvalidTypeConversion`] for types without a
+    /// value representation (`Unit` and `Metadata`).
+    fn try_into_basic_type(
+        self,
+        ctx: &CodegenCtx<'ctx, 'll>,
+    ) -> Result<BasicTypeEnum<'ll>, CodegenError<'ctx>>;
+
+    /// Like [`Self::try_into_basic_type`], but panics if the conversion fails.
+    fn into_basic_type(self, ctx: &CodegenCtx<'ctx, 'll>) -> BasicTypeEnum<'ll>
+    where
+        Self: Sized,
+    {
+        self.try_into_basic_type(ctx)
+            .unwrap_or_else(|err| panic!("into_basic_type: {err}"))
+    }
 }
 
 impl<'ctx, 'll> BasicTypesUtils<'ctx, 'll> for TirTy<'ctx> {
-    fn into_basic_type_metadata(self, ctx: &CodegenCtx<'ctx, 'll>) -> BasicMetadataTypeEnum<'ll> {
+    fn into_basic_type_metadata(
+        self,
+        ctx: &CodegenCtx<'ctx, 'll>,
+    ) -> Result<BasicMetadataTypeEnum<'ll>, CodegenError<'ctx>> {
         match &**self {
-            ty::TirTy::Unit => panic!("Unit/void type cannot be converted to BasicMetadataTypeEnum; handle void returns separately"),
-            ty::TirTy::Bool => BasicTypeEnum::IntType(ctx.ll_context.bool_type()).into(),
-            ty::TirTy::I8 => BasicTypeEnum::IntType(ctx.ll_context.i8_type()).into(),
-            ty::TirTy::I16 => BasicTypeEnum::IntType(ctx.ll_context.i16_type()).into(),
-            ty::TirTy::I32 => BasicTypeEnum::IntType(ctx.ll_context.i32_type()).into(),
-            ty::TirTy::I64 => BasicTypeEnum::IntType(ctx.ll_context.i64_type()).into(),
-            ty::TirTy::I128 => BasicTypeEnum::IntType(ctx.ll_context.i128_type()).into(),
-            ty::TirTy::U8 => BasicTypeEnum::IntType(ctx.ll_context.i8_type()).into(),
-            ty::TirTy::U16 => BasicTypeEnum::IntType(ctx.ll_context.i16_type()).into(),
-            ty::TirTy::U32 => BasicTypeEnum::IntType(ctx.ll_context.i32_type()).into(),
-            ty::TirTy::U64 => BasicTypeEnum::IntType(ctx.ll_context.i64_type()).into(),
-            ty::TirTy::U128 => BasicTypeEnum::IntType(ctx.ll_context.i128_type()).into(),
-            ty::TirTy::F16 => BasicTypeEnum::FloatType(ctx.ll_context.f16_type()).into(),
-            ty::TirTy::F32 => BasicTypeEnum::FloatType(ctx.ll_context.f32_type()).into(),
-            ty::TirTy::F64 => BasicTypeEnum::FloatType(ctx.ll_context.f64_type()).into(),
-            ty::TirTy::F128 => BasicTypeEnum::FloatType(ctx.ll_context.f128_type()).into(),
-            ty::TirTy::RawPtr(_, _) => {
-                // In LLVM's opaque pointer model, all pointers are just `ptr`
-                BasicTypeEnum::PointerType(ctx.ll_context.ptr_type(Default::default())).into()
-            }
-            ty::TirTy::Struct { fields, packed } => {
-                let basic_fields: Vec<BasicTypeEnum<'ll>> = fields
-                    .as_slice()
-                    .iter()
-                    .map(|f| f.into_basic_type(ctx))
-                    .collect();
-                BasicTypeEnum::StructType(
-                    ctx.ll_context.struct_type(&basic_fields, *packed),
-                )
-                .into()
-            }
-            ty::TirTy::Array(element_ty, count) => {
-                assert!(
-                    *count <= u32::MAX as u64,
-                    "Array count {count} exceeds u32::MAX; inkwell's array_type only supports u32 sizes"
-                );
-                let len = *count as u32;
-                let elem_llty = element_ty.into_basic_type(ctx);
-                match elem_llty {
-                    BasicTypeEnum::IntType(t) => BasicTypeEnum::ArrayType(t.array_type(len)).into(),
-                    BasicTypeEnum::FloatType(t) => BasicTypeEnum::ArrayType(t.array_type(len)).into(),
-                    BasicTypeEnum::PointerType(t) => BasicTypeEnum::ArrayType(t.array_type(len)).into(),
-                    BasicTypeEnum::StructType(t) => BasicTypeEnum::ArrayType(t.array_type(len)).into(),
-                    BasicTypeEnum::ArrayType(t) => BasicTypeEnum::ArrayType(t.array_type(len)).into(),
-                    BasicTypeEnum::VectorType(t) => BasicTypeEnum::ArrayType(t.array_type(len)).into(),
-                    #[allow(unreachable_patterns)]
-                    _ => panic!("Unsupported array element type: {:?}", elem_llty),
-                }
-            }
-            ty::TirTy::Metadata => {
-                BasicMetadataTypeEnum::MetadataType(ctx.ll_context.metadata_type())
-            }
+            ty::TirTy::Metadata => Ok(BasicMetadataTypeEnum::MetadataType(
+                ctx.ll_context.metadata_type(),
+            )),
+            _ => self.try_into_basic_type(ctx).map(Into::into),
         }
     }
 
-    fn into_basic_type(self, ctx: &CodegenCtx<'ctx, 'll>) -> BasicTypeEnum<'ll> {
-        match &**self {
-            ty::TirTy::Unit => panic!("Unit/void type cannot be converted to BasicTypeEnum; handle void returns separately"),
+    fn try_into_basic_type(
+        self,
+        ctx: &CodegenCtx<'ctx, 'll>,
+    ) -> Result<BasicTypeEnum<'ll>, CodegenError<'ctx>> {
+        let float_ty = |float: Primitive| {
+            if ctx.lir_ctx.target().data_layout.supports_float(float) {
+                Ok(())
+            } else {
+                Err(CodegenError::UnsupportedFloatType(self))
+            }
+        };
+        // `Isize`/`Usize` lower to an integer as wide as a pointer in the
+        // default address space of the target.
+        let pointer_sized_int = || {
+            let bits = ctx.lir_ctx.target().data_layout.pointer_size.bits() as u32;
+            ctx.ll_context
+                .custom_width_int_type(NonZeroU32::new(bits).expect("zero-sized pointer"))
+                .expect("Failed to create pointer-sized integer type")
+        };
+
+        let llty = match &**self {
+            // Unit/void is not a value type; void returns are handled separately.
+            ty::TirTy::Unit | ty::TirTy::Metadata => {
+                return Err(CodegenError::InvalidTypeConversion(self))
+            }
             ty::TirTy::Bool => BasicTypeEnum::IntType(ctx.ll_context.bool_type()),
             ty::TirTy::I8 => BasicTypeEnum::IntType(ctx.ll_context.i8_type()),
             ty::TirTy::I16 => BasicTypeEnum::IntType(ctx.ll_context.i16_type()),
             ty::TirTy::I32 => BasicTypeEnum::IntType(ctx.ll_context.i32_type()),
             ty::TirTy::I64 => BasicTypeEnum::IntType(ctx.ll_context.i64_type()),
             ty::TirTy::I128 => BasicTypeEnum::IntType(ctx.ll_context.i128_type()),
+            ty::TirTy::Isize => BasicTypeEnum::IntType(pointer_sized_int()),
             ty::TirTy::U8 => BasicTypeEnum::IntType(ctx.ll_context.i8_type()),
             ty::TirTy::U16 => BasicTypeEnum::IntType(ctx.ll_context.i16_type()),
             ty::TirTy::U32 => BasicTypeEnum::IntType(ctx.ll_context.i32_type()),
             ty::TirTy::U64 => BasicTypeEnum::IntType(ctx.ll_context.i64_type()),
             ty::TirTy::U128 => BasicTypeEnum::IntType(ctx.ll_context.i128_type()),
-            ty::TirTy::F16 => BasicTypeEnum::FloatType(ctx.ll_context.f16_type()),
-            ty::TirTy::F32 => BasicTypeEnum::FloatType(ctx.ll_context.f32_type()),
-            ty::TirTy::F64 => BasicTypeEnum::FloatType(ctx.ll_context.f64_type()),
-            ty::TirTy::F128 => BasicTypeEnum::FloatType(ctx.ll_context.f128_type()),
-            ty::TirTy::RawPtr(_, _) => {
-                // In LLVM's opaque pointer model, all pointers are just `ptr`
-                BasicTypeEnum::PointerType(ctx.ll_context.ptr_type(Default::default()))
+            ty::TirTy::Usize => BasicTypeEnum::IntType(pointer_sized_int()),
+            ty::TirTy::F16 => {
+                float_ty(Primitive::F16)?;
+                BasicTypeEnum::FloatType(ctx.ll_context.f16_type())
+            }
+            ty::TirTy::F32 => {
+                float_ty(Primitive::F32)?;
+                BasicTypeEnum::FloatType(ctx.ll_context.f32_type())
+            }
+            ty::TirTy::F64 => {
+                float_ty(Primitive::F64)?;
+                BasicTypeEnum::FloatType(ctx.ll_context.f64_type())
+            }
+            ty::TirTy::F128 => {
+                float_ty(Primitive::F128)?;
+                BasicTypeEnum::FloatType(ctx.ll_context.f128_type())
+            }
+            ty::TirTy::RawPtr(_, _, address_space) => {
+                // In LLVM's opaque pointer model, all pointers are just `ptr`,
+                // optionally qualified with an address space (`ptr addrspace(N)`).
+                let address_space = AddressSpace::try_from(u32::from(address_space))
+                    .unwrap_or_else(|_| {
+                        panic!("Address space {address_space:?} is not supported by LLVM")
+                    });
+                BasicTypeEnum::PointerType(ctx.ll_context.ptr_type(address_space))
             }
             ty::TirTy::Struct { fields, packed } => {
-                let basic_fields: Vec<BasicTypeEnum<'ll>> = fields
+                let basic_fields = fields
                     .as_slice()
                     .iter()
-                    .map(|f| f.into_basic_type(ctx))
-                    .collect();
+                    .map(|f| f.try_into_basic_type(ctx))
+                    .collect::<Result<Vec<_>, _>>()?;
                 BasicTypeEnum::StructType(ctx.ll_context.struct_type(&basic_fields, *packed))
             }
             ty::TirTy::Array(element_ty, count) => {
@@ -106,7 +131,7 @@ impl<'ctx, 'll> BasicTypesUtils<'ctx, 'll> for TirTy<'ctx> {
                     "Array count {count} exceeds u32::MAX; inkwell's array_type only supports u32 sizes"
                 );
                 let len = *count as u32;
-                let elem_llty = element_ty.into_basic_type(ctx);
+                let elem_llty = element_ty.try_into_basic_type(ctx)?;
                 match elem_llty {
                     BasicTypeEnum::IntType(t) => BasicTypeEnum::ArrayType(t.array_type(len)),
                     BasicTypeEnum::FloatType(t) => BasicTypeEnum::ArrayType(t.array_type(len)),
@@ -118,7 +143,61 @@ impl<'ctx, 'll> BasicTypesUtils<'ctx, 'll> for TirTy<'ctx> {
                     _ => panic!("Unsupported array element type: {:?}", elem_llty),
                 }
             }
-            ty::TirTy::Metadata => panic!("Metadata type cannot be converted to BasicTypeEnum"),
-        }
+            ty::TirTy::Enum { variants } => {
+                // The tag, followed by enough bytes for the largest payload
+                // and the tail padding. Payloads are reached by byte offset,
+                // so only the tag and the total size need to match the layout.
+                let mut fields = Vec::new();
+                if !variants.as_slice().is_empty() {
+                    let tag_ty = ctx.lir_ctx.discriminant_ty(self);
+                    let rest = ctx.lir_ctx.layout_of(self).size.bytes()
+                        - ctx.lir_ctx.layout_of(tag_ty).size.bytes();
+                    assert!(
+                        rest <= u32::MAX as u64,
+                        "Enum payload of {rest} bytes exceeds u32::MAX; inkwell's array_type only supports u32 sizes"
+                    );
+                    fields.push(tag_ty.try_into_basic_type(ctx)?);
+                    fields.push(BasicTypeEnum::ArrayType(
+                        ctx.ll_context.i8_type().array_type(rest as u32),
+                    ));
+                }
+                BasicTypeEnum::StructType(ctx.ll_context.struct_type(&fields, false))
+            }
+            ty::TirTy::Simd { elem, lanes } => {
+                let lanes = *lanes;
+                let vector = match elem {
+                    Primitive::I8 | Primitive::U8 => ctx.ll_context.i8_type().vec_type(lanes),
+                    Primitive::I16 | Primitive::U16 => ctx.ll_context.i16_type().vec_type(lanes),
+                    Primitive::I32 | Primitive::U32 => ctx.ll_context.i32_type().vec_type(lanes),
+                    Primitive::I64 | Primitive::U64 => ctx.ll_context.i64_type().vec_type(lanes),
+                    Primitive::I128 | Primitive::U128 => ctx.ll_context.i128_type().vec_type(lanes),
+                    Primitive::F16 => {
+                        float_ty(Primitive::F16)?;
+                        ctx.ll_context.f16_type().vec_type(lanes)
+                    }
+                    Primitive::F32 => {
+                        float_ty(Primitive::F32)?;
+                        ctx.ll_context.f32_type().vec_type(lanes)
+                    }
+                    Primitive::F64 => {
+                        float_ty(Primitive::F64)?;
+                        ctx.ll_context.f64_type().vec_type(lanes)
+                    }
+                    Primitive::F128 => {
+                        float_ty(Primitive::F128)?;
+                        ctx.ll_context.f128_type().vec_type(lanes)
+                    }
+                    Primitive::Pointer(address_space) => {
+                        let address_space = AddressSpace::try_from(u32::from(address_space))
+                            .unwrap_or_else(|_| {
+                                panic!("Address space {address_space:?} is not supported by LLVM")
+                            });
+                        ctx.ll_context.ptr_type(address_space).vec_type(lanes)
+                    }
+                };
+                BasicTypeEnum::VectorType(vector)
+            }
+        };
+        Ok(llty)
     }
 }
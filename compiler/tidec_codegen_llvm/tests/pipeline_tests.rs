@@ -8,18 +8,25 @@
 //! `LLVM_SYS_201_PREFIX` or have `llvm-config` on `PATH`).
 use std::num::NonZero;
 
-use tidec_abi::size_and_align::Size;
-use tidec_abi::target::{BackendKind, TirTarget};
-use tidec_codegen_llvm::entry::llvm_codegen_to_ir_string;
+use inkwell::context::Context;
+use tidec_abi::layout::Primitive;
+use tidec_abi::size_and_align::{AbiAndPrefAlign, Size};
+use tidec_abi::target::{AddressSpace, BackendKind, TargetTriple, TirTarget};
+use tidec_codegen_llvm::context::CodegenCtx;
+use tidec_codegen_llvm::entry::{llvm_codegen_lir_unit, llvm_codegen_to_ir_string};
+use tidec_codegen_llvm::error::CodegenError;
+use tidec_codegen_llvm::tir::tir_ty::BasicTypesUtils;
 use tidec_tir::body::{
-    CallConv, DefId, GlobalId, Linkage, TirBody, TirBodyKind, TirBodyMetadata, TirGlobal,
-    TirItemKind, TirUnit, TirUnitMetadata, UnnamedAddress, Visibility,
+    CallConv, DefId, EntryKind, GlobalId, Linkage, TirBody, TirBodyKind, TirBodyMetadata,
+    TirGlobal, TirItemKind, TirUnit, TirUnitMetadata, UnnamedAddress, Visibility,
+};
+use tidec_tir::ctx::{
+    EmitKind, InternCtx, ModuleFlag, ModuleFlagBehavior, TirArena, TirArgs, TirCtx,
 };
-use tidec_tir::ctx::{EmitKind, InternCtx, TirArena, TirArgs, TirCtx};
 use tidec_tir::syntax::{
-    AggregateKind, BasicBlock, BasicBlockData, BinaryOp, CastKind, ConstOperand, ConstScalar,
-    ConstValue, Local, LocalData, Operand, Place, Projection, RValue, RawScalarValue, Statement,
-    SwitchTargets, Terminator, UnaryOp, RETURN_LOCAL,
+    AggregateKind, AssertMessage, BasicBlock, BasicBlockData, BinaryOp, CastKind, ConstOperand,
+    ConstScalar, ConstValue, FieldIdx, Local, LocalData, Operand, Place, Projection, RValue,
+    RawScalarValue, Statement, SwitchTargets, Terminator, UnaryOp, RETURN_LOCAL,
 };
 use tidec_tir::ty::{Mutability, TirTy};
 use tidec_utils::idx::Idx;
@@ -41,6 +48,7 @@ fn main_metadata(def_id: DefId) -> TirBodyMetadata {
         call_conv: CallConv::C,
         is_varargs: false,
         is_declaration: false,
+        entry_point: None,
     }
 }
 
@@ -76,12 +84,17 @@ where
     let target = TirTarget::new(BackendKind::Llvm);
     let args = TirArgs {
         emit_kind: EmitKind::Object, // not used by ir-string path
+        checked_div: false,
+        verify_module: cfg!(debug_assertions),
+        output_path: None,
+        target_features: Vec::new(),
+        module_flags: Vec::new(),
     };
     let arena = TirArena::default();
     let intern_ctx = InternCtx::new(&arena);
     let tir_ctx = TirCtx::new(&target, &args, &intern_ctx);
     let unit = build_fn(&tir_ctx);
-    llvm_codegen_to_ir_string(tir_ctx, unit)
+    llvm_codegen_to_ir_string(tir_ctx, unit).expect("codegen failed")
 }
 
 /// Build a `TirBody` for testing a binary operation using **mutable locals**
@@ -110,6 +123,7 @@ fn binop_body_with_locals<'ctx>(
             ty: result_ty,
             mutable: false,
         }]),
+        arg_count: 0,
         locals: IdxVec::from_raw(vec![
             LocalData {
                 ty: operand_ty,
@@ -121,6 +135,7 @@ fn binop_body_with_locals<'ctx>(
             },
         ]),
         basic_blocks: IdxVec::from_raw(vec![BasicBlockData {
+            params: vec![],
             statements: vec![
                 Statement::Assign(Box::new((Place::from(Local::new(1)), RValue::Operand(lhs)))),
                 Statement::Assign(Box::new((Place::from(Local::new(2)), RValue::Operand(rhs)))),
@@ -156,8 +171,10 @@ fn unop_body_with_local<'ctx>(
     TirBody {
         metadata: main_metadata(DefId(0)),
         ret_and_args: IdxVec::from_raw(vec![LocalData { ty, mutable: false }]),
+        arg_count: 0,
         locals: IdxVec::from_raw(vec![LocalData { ty, mutable: true }]),
         basic_blocks: IdxVec::from_raw(vec![BasicBlockData {
+            params: vec![],
             statements: vec![
                 Statement::Assign(Box::new((
                     Place::from(Local::new(1)),
@@ -189,8 +206,10 @@ fn pipeline_return_zero() {
                 ty: i32_ty,
                 mutable: false,
             }]),
+            arg_count: 0,
             locals: IdxVec::new(),
             basic_blocks: IdxVec::from_raw(vec![BasicBlockData {
+                params: vec![],
                 statements: vec![Statement::Assign(Box::new((
                     Place::from(RETURN_LOCAL),
                     RValue::Operand(const_i32(ctx, 0)),
@@ -229,8 +248,10 @@ fn pipeline_return_42() {
                 ty: i32_ty,
                 mutable: false,
             }]),
+            arg_count: 0,
             locals: IdxVec::new(),
             basic_blocks: IdxVec::from_raw(vec![BasicBlockData {
+                params: vec![],
                 statements: vec![Statement::Assign(Box::new((
                     Place::from(RETURN_LOCAL),
                     RValue::Operand(const_i32(ctx, 42)),
@@ -251,6 +272,55 @@ fn pipeline_return_42() {
     assert!(ir.contains("ret i32 42"), "main should return 42");
 }
 
+/// Return a negative constant built from its signed value:
+/// `main() -> i32 { return -1; }`
+#[test]
+fn pipeline_return_negative_one() {
+    let ir = compile_to_ir(|ctx| {
+        let i32_ty = ctx.intern_ty(TirTy::<TirCtx>::I32);
+        let minus_one = Operand::Const(ConstOperand::Value(
+            ConstValue::Scalar(ConstScalar::Value(RawScalarValue::from_signed(
+                -1,
+                Size::from_bytes(4),
+            ))),
+            i32_ty,
+        ));
+
+        let body = TirBody {
+            metadata: main_metadata(DefId(0)),
+            ret_and_args: IdxVec::from_raw(vec![LocalData {
+                ty: i32_ty,
+                mutable: false,
+            }]),
+            arg_count: 0,
+            locals: IdxVec::new(),
+            basic_blocks: IdxVec::from_raw(vec![BasicBlockData {
+                params: vec![],
+                statements: vec![Statement::Assign(Box::new((
+                    Place::from(RETURN_LOCAL),
+                    RValue::Operand(minus_one),
+                )))],
+                terminator: Terminator::Return,
+            }]),
+        };
+
+        TirUnit {
+            metadata: TirUnitMetadata {
+                unit_name: "test".to_string(),
+            },
+            globals: IdxVec::new(),
+            bodies: IdxVec::from_raw(vec![body]),
+        }
+    });
+
+    println!("--- negative constant IR ---\n{}", ir);
+    assert!(
+        ir.contains("ret i32 -1"),
+        "main should return -1, got:\n{}",
+        ir
+    );
+}
+
 /// Void function: `void_fn() { return; }`
 #[test]
 fn pipeline_void_return() {
@@ -269,13 +339,16 @@ fn pipeline_void_return() {
                 call_conv: CallConv::C,
                 is_varargs: false,
                 is_declaration: false,
+                entry_point: None,
             },
             ret_and_args: IdxVec::from_raw(vec![LocalData {
                 ty: unit_ty,
                 mutable: false,
             }]),
+            arg_count: 0,
             locals: IdxVec::new(),
             basic_blocks: IdxVec::from_raw(vec![BasicBlockData {
+                params: vec![],
                 statements: vec![],
                 terminator: Terminator::Return,
             }]),
@@ -310,8 +383,10 @@ fn pipeline_unary_neg() {
                 ty: i32_ty,
                 mutable: false,
             }]),
+            arg_count: 0,
             locals: IdxVec::new(),
             basic_blocks: IdxVec::from_raw(vec![BasicBlockData {
+                params: vec![],
                 statements: vec![Statement::Assign(Box::new((
                     Place::from(RETURN_LOCAL),
                     RValue::UnaryOp(UnaryOp::Neg, const_i32(ctx, 42)),
@@ -455,7 +530,7 @@ fn pipeline_binary_div_signed() {
 fn pipeline_function_call_printf() {
     let ir = compile_to_ir(|ctx| {
         let i8_ty = ctx.intern_ty(TirTy::<TirCtx>::I8);
-        let ptr_i8_ty = ctx.intern_ty(TirTy::RawPtr(i8_ty, Mutability::Imm));
+        let ptr_i8_ty = ctx.intern_ty(TirTy::RawPtr(i8_ty, Mutability::Imm, AddressSpace::DATA));
         let i32_ty = ctx.intern_ty(TirTy::<TirCtx>::I32);
 
         // Declare printf
@@ -472,6 +547,7 @@ fn pipeline_function_call_printf() {
                 call_conv: CallConv::C,
                 is_varargs: true,
                 is_declaration: true,
+                entry_point: None,
             },
             ret_and_args: IdxVec::from_raw(vec![
                 LocalData {
@@ -483,6 +559,7 @@ fn pipeline_function_call_printf() {
                     mutable: false,
                 },
             ]),
+            arg_count: 1,
             locals: IdxVec::new(),
             basic_blocks: IdxVec::new(),
         };
@@ -492,6 +569,7 @@ fn pipeline_function_call_printf() {
 
         // main calls printf then returns 0
         let bb0 = BasicBlockData {
+            params: vec![],
             statements: vec![],
             terminator: Terminator::Call {
                 func: Operand::Const(ConstOperand::Value(
@@ -510,13 +588,15 @@ fn pipeline_function_call_printf() {
                 ))],
                 destination: Place {
                     local: Local::new(1),
-                    projection: vec![],
+                    projection: &[],
                 },
                 target: BasicBlock::new(1),
+                block_args: vec![],
             },
         };
 
         let bb1 = BasicBlockData {
+            params: vec![],
             statements: vec![Statement::Assign(Box::new((
                 Place::from(RETURN_LOCAL),
                 RValue::Operand(const_i32(ctx, 0)),
@@ -530,6 +610,7 @@ fn pipeline_function_call_printf() {
                 ty: i32_ty,
                 mutable: false,
             }]),
+            arg_count: 0,
             locals: IdxVec::from_raw(vec![LocalData {
                 ty: i32_ty,
                 mutable: false,
@@ -579,13 +660,16 @@ fn pipeline_goto() {
         let i32_ty = ctx.intern_ty(TirTy::<TirCtx>::I32);
 
         let bb0 = BasicBlockData {
+            params: vec![],
             statements: vec![],
             terminator: Terminator::Goto {
                 target: BasicBlock::new(1),
+                block_args: vec![],
             },
         };
 
         let bb1 = BasicBlockData {
+            params: vec![],
             statements: vec![Statement::Assign(Box::new((
                 Place::from(RETURN_LOCAL),
                 RValue::Operand(const_i32(ctx, 7)),
@@ -599,6 +683,7 @@ fn pipeline_goto() {
                 ty: i32_ty,
                 mutable: false,
             }]),
+            arg_count: 0,
             locals: IdxVec::new(),
             basic_blocks: IdxVec::from_raw(vec![bb0, bb1]),
         };
@@ -636,8 +721,10 @@ fn pipeline_unreachable() {
                 ty: i32_ty,
                 mutable: false,
             }]),
+            arg_count: 0,
             locals: IdxVec::new(),
             basic_blocks: IdxVec::from_raw(vec![BasicBlockData {
+                params: vec![],
                 statements: vec![],
                 terminator: Terminator::Unreachable,
             }]),
@@ -682,6 +769,7 @@ fn pipeline_icmp_eq() {
                 ty: i32_ty,
                 mutable: false,
             }]),
+            arg_count: 0,
             locals: IdxVec::from_raw(vec![
                 LocalData {
                     ty: i32_ty,
@@ -697,6 +785,7 @@ fn pipeline_icmp_eq() {
                 }, // _3
             ]),
             basic_blocks: IdxVec::from_raw(vec![BasicBlockData {
+                params: vec![],
                 statements: vec![
                     Statement::Assign(Box::new((
                         Place::from(Local::new(1)),
@@ -754,6 +843,7 @@ fn pipeline_icmp_lt_signed() {
                 ty: i32_ty,
                 mutable: false,
             }]),
+            arg_count: 0,
             locals: IdxVec::from_raw(vec![
                 LocalData {
                     ty: i32_ty,
@@ -769,6 +859,7 @@ fn pipeline_icmp_lt_signed() {
                 }, // _3
             ]),
             basic_blocks: IdxVec::from_raw(vec![BasicBlockData {
+                params: vec![],
                 statements: vec![
                     Statement::Assign(Box::new((
                         Place::from(Local::new(1)),
@@ -829,6 +920,7 @@ fn pipeline_switch_int_bool() {
 
         // bb0: compare and branch
         let bb0 = BasicBlockData {
+            params: vec![],
             statements: vec![
                 Statement::Assign(Box::new((
                     Place::from(Local::new(1)),
@@ -850,11 +942,13 @@ fn pipeline_switch_int_bool() {
             terminator: Terminator::SwitchInt {
                 discr: Operand::Use(Place::from(Local::new(3))),
                 targets: SwitchTargets::if_then(BasicBlock::new(1), BasicBlock::new(2)),
+                block_args: vec![],
             },
         };
 
         // bb1: then branch → return 1
         let bb1 = BasicBlockData {
+            params: vec![],
             statements: vec![Statement::Assign(Box::new((
                 Place::from(RETURN_LOCAL),
                 RValue::Operand(const_i32(ctx, 1)),
@@ -864,6 +958,7 @@ fn pipeline_switch_int_bool() {
 
         // bb2: else branch → return 0
         let bb2 = BasicBlockData {
+            params: vec![],
             statements: vec![Statement::Assign(Box::new((
                 Place::from(RETURN_LOCAL),
                 RValue::Operand(const_i32(ctx, 0)),
@@ -879,6 +974,7 @@ fn pipeline_switch_int_bool() {
                 ty: i32_ty,
                 mutable: true,
             }]),
+            arg_count: 0,
             locals: IdxVec::from_raw(vec![
                 LocalData {
                     ty: i32_ty,
@@ -934,6 +1030,7 @@ fn pipeline_switch_int_multi() {
         let i32_ty = ctx.intern_ty(TirTy::<TirCtx>::I32);
 
         let bb0 = BasicBlockData {
+            params: vec![],
             statements: vec![Statement::Assign(Box::new((
                 Place::from(Local::new(1)),
                 RValue::Operand(const_i32(ctx, 2)),
@@ -944,10 +1041,12 @@ fn pipeline_switch_int_multi() {
                     vec![(0, BasicBlock::new(1)), (1, BasicBlock::new(2))],
                     BasicBlock::new(3),
                 ),
+                block_args: vec![],
             },
         };
 
         let make_ret_bb = |val: i32| BasicBlockData {
+            params: vec![],
             statements: vec![Statement::Assign(Box::new((
                 Place::from(RETURN_LOCAL),
                 RValue::Operand(const_i32(ctx, val)),
@@ -963,6 +1062,7 @@ fn pipeline_switch_int_multi() {
                 ty: i32_ty,
                 mutable: true,
             }]),
+            arg_count: 0,
             locals: IdxVec::from_raw(vec![LocalData {
                 ty: i32_ty,
                 mutable: true,
@@ -1008,17 +1108,20 @@ fn pipeline_loop_pattern() {
 
         // bb0: initialise counter, goto header
         let bb0 = BasicBlockData {
+            params: vec![],
             statements: vec![Statement::Assign(Box::new((
                 Place::from(Local::new(1)),
                 RValue::Operand(const_i32(ctx, 0)),
             )))],
             terminator: Terminator::Goto {
                 target: BasicBlock::new(1),
+                block_args: vec![],
             },
         };
 
         // bb1 (header): compare counter < 10, branch
         let bb1 = BasicBlockData {
+            params: vec![],
             statements: vec![Statement::Assign(Box::new((
                 Place::from(Local::new(2)),
                 RValue::BinaryOp(
@@ -1030,11 +1133,13 @@ fn pipeline_loop_pattern() {
             terminator: Terminator::SwitchInt {
                 discr: Operand::Use(Place::from(Local::new(2))),
                 targets: SwitchTargets::if_then(BasicBlock::new(2), BasicBlock::new(3)),
+                block_args: vec![],
             },
         };
 
         // bb2 (body): increment counter, goto header
         let bb2 = BasicBlockData {
+            params: vec![],
             statements: vec![Statement::Assign(Box::new((
                 Place::from(Local::new(1)),
                 RValue::BinaryOp(
@@ -1045,11 +1150,13 @@ fn pipeline_loop_pattern() {
             )))],
             terminator: Terminator::Goto {
                 target: BasicBlock::new(1),
+                block_args: vec![],
             },
         };
 
         // bb3 (exit): return counter value
         let bb3 = BasicBlockData {
+            params: vec![],
             statements: vec![Statement::Assign(Box::new((
                 Place::from(RETURN_LOCAL),
                 RValue::Operand(Operand::Use(Place::from(Local::new(1)))),
@@ -1063,6 +1170,7 @@ fn pipeline_loop_pattern() {
                 ty: i32_ty,
                 mutable: false,
             }]),
+            arg_count: 0,
             locals: IdxVec::from_raw(vec![
                 // _1: counter (i32)
                 LocalData {
@@ -1175,8 +1283,10 @@ fn pipeline_all_icmp_operators() {
                 ty: i32_ty,
                 mutable: false,
             }]),
+            arg_count: 0,
             locals: IdxVec::from_raw(locals),
             basic_blocks: IdxVec::from_raw(vec![BasicBlockData {
+                params: vec![],
                 statements: stmts,
                 terminator: Terminator::Return,
             }]),
@@ -1541,8 +1651,10 @@ fn pipeline_all_aritlogic_ops() {
         let body = TirBody {
             metadata: main_metadata(DefId(0)),
             ret_and_args,
+            arg_count: 0,
             locals,
             basic_blocks: IdxVec::from_raw(vec![BasicBlockData {
+                params: vec![],
                 statements: stmts,
                 terminator: Terminator::Return,
             }]),
@@ -1837,17 +1949,20 @@ fn pipeline_mutable_local_alloca() {
 
         // bb0: _1 = 10; goto bb1
         let bb0 = BasicBlockData {
+            params: vec![],
             statements: vec![Statement::Assign(Box::new((
                 Place::from(Local::new(1)),
                 RValue::Operand(const_i32(ctx, 10)),
             )))],
             terminator: Terminator::Goto {
                 target: BasicBlock::new(1),
+                block_args: vec![],
             },
         };
 
         // bb1: _1 = 20; _0 = _1; return
         let bb1 = BasicBlockData {
+            params: vec![],
             statements: vec![
                 Statement::Assign(Box::new((
                     Place::from(Local::new(1)),
@@ -1867,6 +1982,7 @@ fn pipeline_mutable_local_alloca() {
                 ty: i32_ty,
                 mutable: false,
             }]),
+            arg_count: 0,
             locals: IdxVec::from_raw(vec![LocalData {
                 ty: i32_ty,
                 mutable: true,
@@ -1923,11 +2039,13 @@ fn cast_body_with_local<'ctx>(
             ty: dest_ty,
             mutable: false,
         }]),
+        arg_count: 0,
         locals: IdxVec::from_raw(vec![LocalData {
             ty: src_ty,
             mutable: true,
         }]),
         basic_blocks: IdxVec::from_raw(vec![BasicBlockData {
+            params: vec![],
             statements: vec![
                 Statement::Assign(Box::new((
                     Place::from(Local::new(1)),
@@ -2209,7 +2327,11 @@ fn pipeline_cast_inttoptr() {
     let ir = compile_to_ir(|ctx| {
         let u64_ty = ctx.intern_ty(TirTy::<TirCtx>::U64);
         let i32_ty = ctx.intern_ty(TirTy::<TirCtx>::I32);
-        let ptr_ty = ctx.intern_ty(TirTy::<TirCtx>::RawPtr(i32_ty, Mutability::Mut));
+        let ptr_ty = ctx.intern_ty(TirTy::<TirCtx>::RawPtr(
+            i32_ty,
+            Mutability::Mut,
+            AddressSpace::DATA,
+        ));
         let src = Operand::Const(ConstOperand::Value(
             ConstValue::Scalar(ConstScalar::Value(RawScalarValue {
                 data: 0xDEAD_BEEF,
@@ -2236,13 +2358,55 @@ fn pipeline_cast_inttoptr() {
     );
 }
 
+/// IntToPtr into a non-default address space: u64 → *mut i32 in addrspace(1)
+#[test]
+fn pipeline_cast_inttoptr_addrspace() {
+    let ir = compile_to_ir(|ctx| {
+        let u64_ty = ctx.intern_ty(TirTy::<TirCtx>::U64);
+        let i32_ty = ctx.intern_ty(TirTy::<TirCtx>::I32);
+        let ptr_ty = ctx.intern_ty(TirTy::<TirCtx>::RawPtr(
+            i32_ty,
+            Mutability::Mut,
+            AddressSpace(1),
+        ));
+        let src = Operand::Const(ConstOperand::Value(
+            ConstValue::Scalar(ConstScalar::Value(RawScalarValue {
+                data: 0xDEAD_BEEF,
+                size: NonZero::new(8).unwrap(),
+            })),
+            u64_ty,
+        ));
+
+        let body = cast_body_with_local(CastKind::IntToPtr, src, u64_ty, ptr_ty);
+
+        TirUnit {
+            metadata: TirUnitMetadata {
+                unit_name: "test".to_string(),
+            },
+            globals: IdxVec::new(),
+            bodies: IdxVec::from_raw(vec![body]),
+        }
+    });
+
+    println!("--- inttoptr addrspace IR ---\n{}", ir);
+    assert!(
+        ir.contains("to ptr addrspace(1)"),
+        "pointer in address space 1 should lower to `ptr addrspace(1)`, got:\n{}",
+        ir
+    );
+}
+
 /// PtrToInt: *mut i32 → u64 (ptrtoint)
 #[test]
 fn pipeline_cast_ptrtoint() {
     let ir = compile_to_ir(|ctx| {
         let i32_ty = ctx.intern_ty(TirTy::<TirCtx>::I32);
         let u64_ty = ctx.intern_ty(TirTy::<TirCtx>::U64);
-        let ptr_ty = ctx.intern_ty(TirTy::<TirCtx>::RawPtr(i32_ty, Mutability::Mut));
+        let ptr_ty = ctx.intern_ty(TirTy::<TirCtx>::RawPtr(
+            i32_ty,
+            Mutability::Mut,
+            AddressSpace::DATA,
+        ));
         // Represent the pointer as a scalar with 8-byte size (64-bit pointer)
         let src = Operand::Const(ConstOperand::Value(
             ConstValue::Scalar(ConstScalar::Value(RawScalarValue {
@@ -2301,8 +2465,16 @@ fn pipeline_cast_ptr_to_ptr() {
     let ir = compile_to_ir(|ctx| {
         let i32_ty = ctx.intern_ty(TirTy::<TirCtx>::I32);
         let i64_ty = ctx.intern_ty(TirTy::<TirCtx>::I64);
-        let ptr_i32 = ctx.intern_ty(TirTy::<TirCtx>::RawPtr(i32_ty, Mutability::Imm));
-        let ptr_i64 = ctx.intern_ty(TirTy::<TirCtx>::RawPtr(i64_ty, Mutability::Mut));
+        let ptr_i32 = ctx.intern_ty(TirTy::<TirCtx>::RawPtr(
+            i32_ty,
+            Mutability::Imm,
+            AddressSpace::DATA,
+        ));
+        let ptr_i64 = ctx.intern_ty(TirTy::<TirCtx>::RawPtr(
+            i64_ty,
+            Mutability::Mut,
+            AddressSpace::DATA,
+        ));
         let src = Operand::Const(ConstOperand::Value(
             ConstValue::Scalar(ConstScalar::Value(RawScalarValue {
                 data: 0x1000,
@@ -2394,11 +2566,13 @@ fn pipeline_struct_aggregate_and_field_access() {
                 ty: i32_ty,
                 mutable: false,
             }]),
+            arg_count: 0,
             locals: IdxVec::from_raw(vec![LocalData {
                 ty: struct_ty,
                 mutable: true,
             }]),
             basic_blocks: IdxVec::from_raw(vec![BasicBlockData {
+                params: vec![],
                 statements: vec![
                     // _1 = Aggregate::Struct { 10, 20 }
                     Statement::Assign(Box::new((
@@ -2413,7 +2587,8 @@ fn pipeline_struct_aggregate_and_field_access() {
                         Place::from(RETURN_LOCAL),
                         RValue::Operand(Operand::Use(Place {
                             local: Local::new(1),
-                            projection: vec![Projection::Field(0, i32_ty)],
+                            projection: ctx
+                                .intern_projection(&[Projection::Field(FieldIdx::new(0), i32_ty)]),
                         })),
                     ))),
                 ],
@@ -2452,6 +2627,86 @@ fn pipeline_struct_aggregate_and_field_access() {
     );
 }
 
+/// Construct a struct { i32, i32 } from two constants.
+///
+/// Each field is stored at its byte offset within the struct, as computed
+/// by `TirCtx::offset_of`.
+///
+/// ```text
+/// fn main() -> i32 {
+///     _1: { i32, i32 } = Aggregate::Struct(1, 2);
+///     _0 = 0;
+///     return;
+/// }
+/// ```
+#[test]
+fn pipeline_struct_aggregate_stores_at_field_offsets() {
+    let ir = compile_to_ir(|ctx| {
+        let i32_ty = ctx.intern_ty(TirTy::<TirCtx>::I32);
+        let fields = ctx.intern_type_list(&[i32_ty, i32_ty]);
+        let struct_ty = ctx.intern_ty(TirTy::<TirCtx>::Struct {
+            fields,
+            packed: false,
+        });
+
+        let body = TirBody {
+            metadata: main_metadata(DefId(0)),
+            ret_and_args: IdxVec::from_raw(vec![LocalData {
+                ty: i32_ty,
+                mutable: false,
+            }]),
+            arg_count: 0,
+            locals: IdxVec::from_raw(vec![LocalData {
+                ty: struct_ty,
+                mutable: true,
+            }]),
+            basic_blocks: IdxVec::from_raw(vec![BasicBlockData {
+                params: vec![],
+                statements: vec![
+                    Statement::Assign(Box::new((
+                        Place::from(Local::new(1)),
+                        RValue::Aggregate(
+                            AggregateKind::Struct(struct_ty),
+                            vec![const_i32(ctx, 1), const_i32(ctx, 2)],
+                        ),
+                    ))),
+                    Statement::Assign(Box::new((
+                        Place::from(RETURN_LOCAL),
+                        RValue::Operand(const_i32(ctx, 0)),
+                    ))),
+                ],
+                terminator: Terminator::Return,
+            }]),
+        };
+
+        TirUnit {
+            metadata: TirUnitMetadata {
+                unit_name: "test".to_string(),
+            },
+            globals: IdxVec::new(),
+            bodies: IdxVec::from_raw(vec![body]),
+        }
+    });
+
+    println!("--- struct aggregate offsets IR ---\n{}", ir);
+
+    let field_geps: Vec<&str> = ir
+        .lines()
+        .filter(|line| line.contains("getelementptr") && line.contains(" i8, "))
+        .collect();
+    assert_eq!(field_geps.len(), 2, "Expected two field GEPs, got:\n{}", ir);
+    assert!(
+        field_geps[0].ends_with("i64 0") && field_geps[1].ends_with("i64 4"),
+        "Expected fields at offsets 0 and 4, got:\n{}",
+        ir
+    );
+    assert!(
+        ir.contains("store i32 1") && ir.contains("store i32 2"),
+        "Expected both fields to be stored, got:\n{}",
+        ir
+    );
+}
+
 /// Construct a struct { i32, i32 } and read the *second* field.
 ///
 /// ```text
@@ -2477,11 +2732,13 @@ fn pipeline_struct_read_second_field() {
                 ty: i32_ty,
                 mutable: false,
             }]),
+            arg_count: 0,
             locals: IdxVec::from_raw(vec![LocalData {
                 ty: struct_ty,
                 mutable: true,
             }]),
             basic_blocks: IdxVec::from_raw(vec![BasicBlockData {
+                params: vec![],
                 statements: vec![
                     Statement::Assign(Box::new((
                         Place::from(Local::new(1)),
@@ -2495,7 +2752,8 @@ fn pipeline_struct_read_second_field() {
                         Place::from(RETURN_LOCAL),
                         RValue::Operand(Operand::Use(Place {
                             local: Local::new(1),
-                            projection: vec![Projection::Field(1, i32_ty)],
+                            projection: ctx
+                                .intern_projection(&[Projection::Field(FieldIdx::new(1), i32_ty)]),
                         })),
                     ))),
                 ],
@@ -2555,11 +2813,13 @@ fn pipeline_packed_struct() {
                 ty: i32_ty,
                 mutable: false,
             }]),
+            arg_count: 0,
             locals: IdxVec::from_raw(vec![LocalData {
                 ty: struct_ty,
                 mutable: true,
             }]),
             basic_blocks: IdxVec::from_raw(vec![BasicBlockData {
+                params: vec![],
                 statements: vec![
                     Statement::Assign(Box::new((
                         Place::from(Local::new(1)),
@@ -2572,7 +2832,8 @@ fn pipeline_packed_struct() {
                         Place::from(RETURN_LOCAL),
                         RValue::Operand(Operand::Use(Place {
                             local: Local::new(1),
-                            projection: vec![Projection::Field(1, i32_ty)],
+                            projection: ctx
+                                .intern_projection(&[Projection::Field(FieldIdx::new(1), i32_ty)]),
                         })),
                     ))),
                 ],
@@ -2638,11 +2899,13 @@ fn pipeline_struct_mixed_types() {
                 ty: f64_ty,
                 mutable: false,
             }]),
+            arg_count: 0,
             locals: IdxVec::from_raw(vec![LocalData {
                 ty: struct_ty,
                 mutable: true,
             }]),
             basic_blocks: IdxVec::from_raw(vec![BasicBlockData {
+                params: vec![],
                 statements: vec![
                     Statement::Assign(Box::new((
                         Place::from(Local::new(1)),
@@ -2656,7 +2919,8 @@ fn pipeline_struct_mixed_types() {
                         Place::from(RETURN_LOCAL),
                         RValue::Operand(Operand::Use(Place {
                             local: Local::new(1),
-                            projection: vec![Projection::Field(1, f64_ty)],
+                            projection: ctx
+                                .intern_projection(&[Projection::Field(FieldIdx::new(1), f64_ty)]),
                         })),
                     ))),
                 ],
@@ -2718,6 +2982,7 @@ fn pipeline_array_aggregate_and_index() {
                 ty: i32_ty,
                 mutable: false,
             }]),
+            arg_count: 0,
             locals: IdxVec::from_raw(vec![
                 // _1: [i32; 3]
                 LocalData {
@@ -2731,6 +2996,7 @@ fn pipeline_array_aggregate_and_index() {
                 },
             ]),
             basic_blocks: IdxVec::from_raw(vec![BasicBlockData {
+                params: vec![],
                 statements: vec![
                     // _1 = [100, 200, 300]
                     Statement::Assign(Box::new((
@@ -2754,7 +3020,7 @@ fn pipeline_array_aggregate_and_index() {
                         Place::from(RETURN_LOCAL),
                         RValue::Operand(Operand::Use(Place {
                             local: Local::new(1),
-                            projection: vec![Projection::Index(Local::new(2))],
+                            projection: ctx.intern_projection(&[Projection::Index(Local::new(2))]),
                         })),
                     ))),
                 ],
@@ -2831,6 +3097,7 @@ fn pipeline_array_single_element() {
                 ty: f64_ty,
                 mutable: false,
             }]),
+            arg_count: 0,
             locals: IdxVec::from_raw(vec![
                 LocalData {
                     ty: array_ty,
@@ -2842,6 +3109,7 @@ fn pipeline_array_single_element() {
                 },
             ]),
             basic_blocks: IdxVec::from_raw(vec![BasicBlockData {
+                params: vec![],
                 statements: vec![
                     Statement::Assign(Box::new((
                         Place::from(Local::new(1)),
@@ -2855,7 +3123,7 @@ fn pipeline_array_single_element() {
                         Place::from(RETURN_LOCAL),
                         RValue::Operand(Operand::Use(Place {
                             local: Local::new(1),
-                            projection: vec![Projection::Index(Local::new(2))],
+                            projection: ctx.intern_projection(&[Projection::Index(Local::new(2))]),
                         })),
                     ))),
                 ],
@@ -2912,11 +3180,13 @@ fn pipeline_struct_field_write() {
                 ty: i32_ty,
                 mutable: false,
             }]),
+            arg_count: 0,
             locals: IdxVec::from_raw(vec![LocalData {
                 ty: struct_ty,
                 mutable: true,
             }]),
             basic_blocks: IdxVec::from_raw(vec![BasicBlockData {
+                params: vec![],
                 statements: vec![
                     // _1 = Aggregate::Struct(0, 0)
                     Statement::Assign(Box::new((
@@ -2930,7 +3200,8 @@ fn pipeline_struct_field_write() {
                     Statement::Assign(Box::new((
                         Place {
                             local: Local::new(1),
-                            projection: vec![Projection::Field(0, i32_ty)],
+                            projection: ctx
+                                .intern_projection(&[Projection::Field(FieldIdx::new(0), i32_ty)]),
                         },
                         RValue::Operand(const_i32(ctx, 99)),
                     ))),
@@ -2939,7 +3210,8 @@ fn pipeline_struct_field_write() {
                         Place::from(RETURN_LOCAL),
                         RValue::Operand(Operand::Use(Place {
                             local: Local::new(1),
-                            projection: vec![Projection::Field(0, i32_ty)],
+                            projection: ctx
+                                .intern_projection(&[Projection::Field(FieldIdx::new(0), i32_ty)]),
                         })),
                     ))),
                 ],
@@ -3004,6 +3276,7 @@ fn pipeline_array_element_write() {
                 ty: i32_ty,
                 mutable: false,
             }]),
+            arg_count: 0,
             locals: IdxVec::from_raw(vec![
                 // _1: [i32; 2]
                 LocalData {
@@ -3017,6 +3290,7 @@ fn pipeline_array_element_write() {
                 },
             ]),
             basic_blocks: IdxVec::from_raw(vec![BasicBlockData {
+                params: vec![],
                 statements: vec![
                     // _1 = [0, 0]
                     Statement::Assign(Box::new((
@@ -3035,7 +3309,7 @@ fn pipeline_array_element_write() {
                     Statement::Assign(Box::new((
                         Place {
                             local: Local::new(1),
-                            projection: vec![Projection::Index(Local::new(2))],
+                            projection: ctx.intern_projection(&[Projection::Index(Local::new(2))]),
                         },
                         RValue::Operand(const_i32(ctx, 77)),
                     ))),
@@ -3044,7 +3318,7 @@ fn pipeline_array_element_write() {
                         Place::from(RETURN_LOCAL),
                         RValue::Operand(Operand::Use(Place {
                             local: Local::new(1),
-                            projection: vec![Projection::Index(Local::new(2))],
+                            projection: ctx.intern_projection(&[Projection::Index(Local::new(2))]),
                         })),
                     ))),
                 ],
@@ -3107,6 +3381,7 @@ fn pipeline_struct_with_array_field() {
                 ty: i32_ty,
                 mutable: false,
             }]),
+            arg_count: 0,
             locals: IdxVec::from_raw(vec![
                 // _1: [i32; 2] (inner array)
                 LocalData {
@@ -3120,6 +3395,7 @@ fn pipeline_struct_with_array_field() {
                 },
             ]),
             basic_blocks: IdxVec::from_raw(vec![BasicBlockData {
+                params: vec![],
                 statements: vec![
                     // _1 = [10, 20]
                     Statement::Assign(Box::new((
@@ -3137,7 +3413,8 @@ fn pipeline_struct_with_array_field() {
                     Statement::Assign(Box::new((
                         Place {
                             local: Local::new(2),
-                            projection: vec![Projection::Field(0, i32_ty)],
+                            projection: ctx
+                                .intern_projection(&[Projection::Field(FieldIdx::new(0), i32_ty)]),
                         },
                         RValue::Operand(const_i32(ctx, 99)),
                     ))),
@@ -3146,7 +3423,8 @@ fn pipeline_struct_with_array_field() {
                         Place::from(RETURN_LOCAL),
                         RValue::Operand(Operand::Use(Place {
                             local: Local::new(2),
-                            projection: vec![Projection::Field(0, i32_ty)],
+                            projection: ctx
+                                .intern_projection(&[Projection::Field(FieldIdx::new(0), i32_ty)]),
                         })),
                     ))),
                 ],
@@ -3194,7 +3472,11 @@ fn pipeline_struct_with_array_field() {
 fn pipeline_address_of_local() {
     let ir = compile_to_ir(|ctx| {
         let i32_ty = ctx.intern_ty(TirTy::<TirCtx>::I32);
-        let ptr_ty = ctx.intern_ty(TirTy::<TirCtx>::RawPtr(i32_ty, Mutability::Mut));
+        let ptr_ty = ctx.intern_ty(TirTy::<TirCtx>::RawPtr(
+            i32_ty,
+            Mutability::Mut,
+            AddressSpace::DATA,
+        ));
 
         let body = TirBody {
             metadata: main_metadata(DefId(0)),
@@ -3202,6 +3484,7 @@ fn pipeline_address_of_local() {
                 ty: ptr_ty,
                 mutable: false,
             }]),
+            arg_count: 0,
             locals: IdxVec::from_raw(vec![
                 // _1: i32 (mutable → alloca)
                 LocalData {
@@ -3210,6 +3493,7 @@ fn pipeline_address_of_local() {
                 },
             ]),
             basic_blocks: IdxVec::from_raw(vec![BasicBlockData {
+                params: vec![],
                 statements: vec![
                     // _1 = 42
                     Statement::Assign(Box::new((
@@ -3268,7 +3552,11 @@ fn pipeline_address_of_struct_field() {
             fields,
             packed: false,
         });
-        let ptr_ty = ctx.intern_ty(TirTy::<TirCtx>::RawPtr(i32_ty, Mutability::Mut));
+        let ptr_ty = ctx.intern_ty(TirTy::<TirCtx>::RawPtr(
+            i32_ty,
+            Mutability::Mut,
+            AddressSpace::DATA,
+        ));
 
         let body = TirBody {
             metadata: main_metadata(DefId(0)),
@@ -3276,11 +3564,13 @@ fn pipeline_address_of_struct_field() {
                 ty: ptr_ty,
                 mutable: false,
             }]),
+            arg_count: 0,
             locals: IdxVec::from_raw(vec![LocalData {
                 ty: struct_ty,
                 mutable: true,
             }]),
             basic_blocks: IdxVec::from_raw(vec![BasicBlockData {
+                params: vec![],
                 statements: vec![
                     // _1 = { 10, 20 }
                     Statement::Assign(Box::new((
@@ -3297,7 +3587,10 @@ fn pipeline_address_of_struct_field() {
                             Mutability::Mut,
                             Place {
                                 local: Local::new(1),
-                                projection: vec![Projection::Field(0, i32_ty)],
+                                projection: ctx.intern_projection(&[Projection::Field(
+                                    FieldIdx::new(0),
+                                    i32_ty,
+                                )]),
                             },
                         ),
                     ))),
@@ -3346,7 +3639,11 @@ fn pipeline_address_of_array_element() {
         let i32_ty = ctx.intern_ty(TirTy::<TirCtx>::I32);
         let u64_ty = ctx.intern_ty(TirTy::<TirCtx>::U64);
         let array_ty = ctx.intern_ty(TirTy::<TirCtx>::Array(i32_ty, 3));
-        let ptr_ty = ctx.intern_ty(TirTy::<TirCtx>::RawPtr(i32_ty, Mutability::Imm));
+        let ptr_ty = ctx.intern_ty(TirTy::<TirCtx>::RawPtr(
+            i32_ty,
+            Mutability::Imm,
+            AddressSpace::DATA,
+        ));
 
         let const_u64_one = Operand::Const(ConstOperand::Value(
             ConstValue::Scalar(ConstScalar::Value(RawScalarValue {
@@ -3362,6 +3659,7 @@ fn pipeline_address_of_array_element() {
                 ty: ptr_ty,
                 mutable: false,
             }]),
+            arg_count: 0,
             locals: IdxVec::from_raw(vec![
                 LocalData {
                     ty: array_ty,
@@ -3373,6 +3671,7 @@ fn pipeline_address_of_array_element() {
                 },
             ]),
             basic_blocks: IdxVec::from_raw(vec![BasicBlockData {
+                params: vec![],
                 statements: vec![
                     // _1 = [1, 2, 3]
                     Statement::Assign(Box::new((
@@ -3394,7 +3693,8 @@ fn pipeline_address_of_array_element() {
                             Mutability::Imm,
                             Place {
                                 local: Local::new(1),
-                                projection: vec![Projection::Index(Local::new(2))],
+                                projection: ctx
+                                    .intern_projection(&[Projection::Index(Local::new(2))]),
                             },
                         ),
                     ))),
@@ -3438,7 +3738,11 @@ fn pipeline_address_of_array_element() {
 fn pipeline_null_ptr_constant() {
     let ir = compile_to_ir(|ctx| {
         let i32_ty = ctx.intern_ty(TirTy::<TirCtx>::I32);
-        let ptr_ty = ctx.intern_ty(TirTy::<TirCtx>::RawPtr(i32_ty, Mutability::Mut));
+        let ptr_ty = ctx.intern_ty(TirTy::<TirCtx>::RawPtr(
+            i32_ty,
+            Mutability::Mut,
+            AddressSpace::DATA,
+        ));
 
         let body = TirBody {
             metadata: main_metadata(DefId(0)),
@@ -3446,8 +3750,10 @@ fn pipeline_null_ptr_constant() {
                 ty: ptr_ty,
                 mutable: false,
             }]),
+            arg_count: 0,
             locals: IdxVec::new(),
             basic_blocks: IdxVec::from_raw(vec![BasicBlockData {
+                params: vec![],
                 statements: vec![Statement::Assign(Box::new((
                     Place::from(RETURN_LOCAL),
                     RValue::Operand(Operand::Const(ConstOperand::Value(
@@ -3490,7 +3796,11 @@ fn pipeline_null_ptr_constant() {
 fn pipeline_null_ptr_stored_and_loaded() {
     let ir = compile_to_ir(|ctx| {
         let i32_ty = ctx.intern_ty(TirTy::<TirCtx>::I32);
-        let ptr_ty = ctx.intern_ty(TirTy::<TirCtx>::RawPtr(i32_ty, Mutability::Mut));
+        let ptr_ty = ctx.intern_ty(TirTy::<TirCtx>::RawPtr(
+            i32_ty,
+            Mutability::Mut,
+            AddressSpace::DATA,
+        ));
 
         let body = TirBody {
             metadata: main_metadata(DefId(0)),
@@ -3498,11 +3808,13 @@ fn pipeline_null_ptr_stored_and_loaded() {
                 ty: ptr_ty,
                 mutable: false,
             }]),
+            arg_count: 0,
             locals: IdxVec::from_raw(vec![LocalData {
                 ty: ptr_ty,
                 mutable: true,
             }]),
             basic_blocks: IdxVec::from_raw(vec![BasicBlockData {
+                params: vec![],
                 statements: vec![
                     // _1 = NULL
                     Statement::Assign(Box::new((
@@ -3573,6 +3885,7 @@ fn pipeline_struct_copy_via_memcpy() {
                 ty: i32_ty,
                 mutable: false,
             }]),
+            arg_count: 0,
             locals: IdxVec::from_raw(vec![
                 // _1: { i32, i32 } (source struct)
                 LocalData {
@@ -3586,6 +3899,7 @@ fn pipeline_struct_copy_via_memcpy() {
                 },
             ]),
             basic_blocks: IdxVec::from_raw(vec![BasicBlockData {
+                params: vec![],
                 statements: vec![
                     // _1 = { 10, 20 }
                     Statement::Assign(Box::new((
@@ -3605,7 +3919,8 @@ fn pipeline_struct_copy_via_memcpy() {
                         Place::from(RETURN_LOCAL),
                         RValue::Operand(Operand::Use(Place {
                             local: Local::new(2),
-                            projection: vec![Projection::Field(0, i32_ty)],
+                            projection: ctx
+                                .intern_projection(&[Projection::Field(FieldIdx::new(0), i32_ty)]),
                         })),
                     ))),
                 ],
@@ -3654,6 +3969,7 @@ fn pipeline_array_copy_via_memcpy() {
                 ty: i32_ty,
                 mutable: false,
             }]),
+            arg_count: 0,
             locals: IdxVec::from_raw(vec![
                 LocalData {
                     ty: array_ty,
@@ -3665,6 +3981,7 @@ fn pipeline_array_copy_via_memcpy() {
                 },
             ]),
             basic_blocks: IdxVec::from_raw(vec![BasicBlockData {
+                params: vec![],
                 statements: vec![
                     // _1 = [1, 2, 3]
                     Statement::Assign(Box::new((
@@ -3723,7 +4040,11 @@ fn pipeline_array_copy_via_memcpy() {
 fn pipeline_address_of_deref_write_read() {
     let ir = compile_to_ir(|ctx| {
         let i32_ty = ctx.intern_ty(TirTy::<TirCtx>::I32);
-        let ptr_ty = ctx.intern_ty(TirTy::<TirCtx>::RawPtr(i32_ty, Mutability::Mut));
+        let ptr_ty = ctx.intern_ty(TirTy::<TirCtx>::RawPtr(
+            i32_ty,
+            Mutability::Mut,
+            AddressSpace::DATA,
+        ));
 
         let body = TirBody {
             metadata: main_metadata(DefId(0)),
@@ -3731,6 +4052,7 @@ fn pipeline_address_of_deref_write_read() {
                 ty: i32_ty,
                 mutable: false,
             }]),
+            arg_count: 0,
             locals: IdxVec::from_raw(vec![
                 // _1: i32
                 LocalData {
@@ -3744,6 +4066,7 @@ fn pipeline_address_of_deref_write_read() {
                 },
             ]),
             basic_blocks: IdxVec::from_raw(vec![BasicBlockData {
+                params: vec![],
                 statements: vec![
                     // _1 = 42
                     Statement::Assign(Box::new((
@@ -3759,7 +4082,7 @@ fn pipeline_address_of_deref_write_read() {
                     Statement::Assign(Box::new((
                         Place {
                             local: Local::new(2),
-                            projection: vec![Projection::Deref],
+                            projection: ctx.intern_projection(&[Projection::Deref]),
                         },
                         RValue::Operand(const_i32(ctx, 99)),
                     ))),
@@ -3768,7 +4091,7 @@ fn pipeline_address_of_deref_write_read() {
                         Place::from(RETURN_LOCAL),
                         RValue::Operand(Operand::Use(Place {
                             local: Local::new(2),
-                            projection: vec![Projection::Deref],
+                            projection: ctx.intern_projection(&[Projection::Deref]),
                         })),
                     ))),
                 ],
@@ -3835,6 +4158,7 @@ fn pipeline_ternary_via_switch_int() {
         // bb2: _0 = 0;  Goto(bb3)
         // bb3: return
         let bb0 = BasicBlockData {
+            params: vec![],
             statements: vec![Statement::Assign(Box::new((
                 Place::from(Local::new(1)),
                 RValue::Operand(const_true),
@@ -3842,27 +4166,33 @@ fn pipeline_ternary_via_switch_int() {
             terminator: Terminator::SwitchInt {
                 discr: Operand::Use(Place::from(Local::new(1))),
                 targets: SwitchTargets::if_then(BasicBlock::new(1), BasicBlock::new(2)),
+                block_args: vec![],
             },
         };
         let bb1 = BasicBlockData {
+            params: vec![],
             statements: vec![Statement::Assign(Box::new((
                 Place::from(RETURN_LOCAL),
                 RValue::Operand(const_i32(ctx, 42)),
             )))],
             terminator: Terminator::Goto {
                 target: BasicBlock::new(3),
+                block_args: vec![],
             },
         };
         let bb2 = BasicBlockData {
+            params: vec![],
             statements: vec![Statement::Assign(Box::new((
                 Place::from(RETURN_LOCAL),
                 RValue::Operand(const_i32(ctx, 0)),
             )))],
             terminator: Terminator::Goto {
                 target: BasicBlock::new(3),
+                block_args: vec![],
             },
         };
         let bb3 = BasicBlockData {
+            params: vec![],
             statements: vec![],
             terminator: Terminator::Return,
         };
@@ -3873,6 +4203,7 @@ fn pipeline_ternary_via_switch_int() {
                 ty: i32_ty,
                 mutable: true, // must be mutable: assigned from two branches (bb1 and bb2)
             }]),
+            arg_count: 0,
             locals: IdxVec::from_raw(vec![LocalData {
                 ty: bool_ty,
                 mutable: true,
@@ -3923,11 +4254,16 @@ fn pipeline_null_check_pattern() {
     let ir = compile_to_ir(|ctx| {
         let i32_ty = ctx.intern_ty(TirTy::<TirCtx>::I32);
         let bool_ty = ctx.intern_ty(TirTy::<TirCtx>::Bool);
-        let ptr_ty = ctx.intern_ty(TirTy::<TirCtx>::RawPtr(i32_ty, Mutability::Mut));
+        let ptr_ty = ctx.intern_ty(TirTy::<TirCtx>::RawPtr(
+            i32_ty,
+            Mutability::Mut,
+            AddressSpace::DATA,
+        ));
 
         let null_op = Operand::Const(ConstOperand::Value(ConstValue::NullPtr, ptr_ty));
 
         let bb0 = BasicBlockData {
+            params: vec![],
             statements: vec![
                 // _1 = NULL
                 Statement::Assign(Box::new((
@@ -3955,27 +4291,33 @@ fn pipeline_null_check_pattern() {
             terminator: Terminator::SwitchInt {
                 discr: Operand::Use(Place::from(Local::new(3))),
                 targets: SwitchTargets::if_then(BasicBlock::new(1), BasicBlock::new(2)),
+                block_args: vec![],
             },
         };
         let bb1 = BasicBlockData {
+            params: vec![],
             statements: vec![Statement::Assign(Box::new((
                 Place::from(RETURN_LOCAL),
                 RValue::Operand(const_i32(ctx, 1)),
             )))],
             terminator: Terminator::Goto {
                 target: BasicBlock::new(3),
+                block_args: vec![],
             },
         };
         let bb2 = BasicBlockData {
+            params: vec![],
             statements: vec![Statement::Assign(Box::new((
                 Place::from(RETURN_LOCAL),
                 RValue::Operand(const_i32(ctx, 0)),
             )))],
             terminator: Terminator::Goto {
                 target: BasicBlock::new(3),
+                block_args: vec![],
             },
         };
         let bb3 = BasicBlockData {
+            params: vec![],
             statements: vec![],
             terminator: Terminator::Return,
         };
@@ -3986,6 +4328,7 @@ fn pipeline_null_check_pattern() {
                 ty: i32_ty,
                 mutable: true, // must be mutable: assigned from two branches (bb1 and bb2)
             }]),
+            arg_count: 0,
             locals: IdxVec::from_raw(vec![
                 LocalData {
                     ty: ptr_ty,
@@ -4065,8 +4408,10 @@ fn global_constant_i32_zero() {
                 ty: i32_ty,
                 mutable: false,
             }]),
+            arg_count: 0,
             locals: IdxVec::new(),
             basic_blocks: IdxVec::from_raw(vec![BasicBlockData {
+                params: vec![],
                 statements: vec![Statement::Assign(Box::new((
                     Place::from(RETURN_LOCAL),
                     RValue::Operand(const_i32(ctx, 0)),
@@ -4114,8 +4459,10 @@ fn global_mutable_i32_scalar() {
                 ty: i32_ty,
                 mutable: false,
             }]),
+            arg_count: 0,
             locals: IdxVec::new(),
             basic_blocks: IdxVec::from_raw(vec![BasicBlockData {
+                params: vec![],
                 statements: vec![Statement::Assign(Box::new((
                     Place::from(RETURN_LOCAL),
                     RValue::Operand(const_i32(ctx, 0)),
@@ -4163,8 +4510,10 @@ fn global_private_linkage() {
                 ty: i32_ty,
                 mutable: false,
             }]),
+            arg_count: 0,
             locals: IdxVec::new(),
             basic_blocks: IdxVec::from_raw(vec![BasicBlockData {
+                params: vec![],
                 statements: vec![Statement::Assign(Box::new((
                     Place::from(RETURN_LOCAL),
                     RValue::Operand(const_i32(ctx, 0)),
@@ -4190,12 +4539,88 @@ fn global_private_linkage() {
     );
 }
 
+/// A global initialized with a `ConstValue::Slice` points to a private data
+/// array aligned for its elements, and stores the element count as length.
+#[test]
+fn global_const_slice_is_aligned_for_its_elements() {
+    let ir = compile_to_ir(|ctx| {
+        let i32_ty = ctx.intern_ty(TirTy::I32);
+        let u32_ty = ctx.intern_ty(TirTy::<TirCtx>::U32);
+        let usize_ty = ctx.intern_ty(TirTy::<TirCtx>::Usize);
+        let ptr_ty = ctx.intern_ty(TirTy::<TirCtx>::RawPtr(
+            u32_ty,
+            Mutability::Imm,
+            AddressSpace::DATA,
+        ));
+        let slice_ty = ctx.intern_ty(TirTy::<TirCtx>::Struct {
+            fields: ctx.intern_type_list(&[ptr_ty, usize_ty]),
+            packed: false,
+        });
+
+        let global = TirGlobal {
+            name: "WORDS".to_string(),
+            ty: slice_ty,
+            initializer: Some(ConstValue::Slice {
+                data: [1u32, 2u32].iter().flat_map(|w| w.to_le_bytes()).collect(),
+            }),
+            mutable: false,
+            linkage: Linkage::External,
+            visibility: Visibility::Default,
+            unnamed_address: UnnamedAddress::None,
+        };
+
+        let body = TirBody {
+            metadata: main_metadata(DefId(0)),
+            ret_and_args: IdxVec::from_raw(vec![LocalData {
+                ty: i32_ty,
+                mutable: false,
+            }]),
+            arg_count: 0,
+            locals: IdxVec::new(),
+            basic_blocks: IdxVec::from_raw(vec![BasicBlockData {
+                params: vec![],
+                statements: vec![Statement::Assign(Box::new((
+                    Place::from(RETURN_LOCAL),
+                    RValue::Operand(const_i32(ctx, 0)),
+                )))],
+                terminator: Terminator::Return,
+            }]),
+        };
+
+        TirUnit {
+            metadata: TirUnitMetadata {
+                unit_name: "test".to_string(),
+            },
+            globals: IdxVec::from_raw(vec![global]),
+            bodies: IdxVec::from_raw(vec![body]),
+        }
+    });
+
+    println!("--- global const slice IR ---\n{}", ir);
+    assert!(
+        ir.contains("@WORDS.data = private unnamed_addr constant [8 x i8]"),
+        "Expected a private data array for the slice, got:\n{}",
+        ir
+    );
+    assert!(
+        ir.lines()
+            .any(|line| line.starts_with("@WORDS.data") && line.ends_with("align 4")),
+        "Expected the data array to be aligned for `u32`, got:\n{}",
+        ir
+    );
+    assert!(
+        ir.contains("@WORDS = constant { ptr, i64 } { ptr @WORDS.data, i64 2 }"),
+        "Expected the slice to hold two elements, got:\n{}",
+        ir
+    );
+}
+
 /// 6.4 — Global null pointer
 #[test]
 fn global_null_pointer() {
     let ir = compile_to_ir(|ctx| {
         let i32_ty = ctx.intern_ty(TirTy::I32);
-        let ptr_ty = ctx.intern_ty(TirTy::RawPtr(i32_ty, Mutability::Mut));
+        let ptr_ty = ctx.intern_ty(TirTy::RawPtr(i32_ty, Mutability::Mut, AddressSpace::DATA));
 
         let global = TirGlobal {
             name: "null_global".to_string(),
@@ -4213,8 +4638,10 @@ fn global_null_pointer() {
                 ty: i32_ty,
                 mutable: false,
             }]),
+            arg_count: 0,
             locals: IdxVec::new(),
             basic_blocks: IdxVec::from_raw(vec![BasicBlockData {
+                params: vec![],
                 statements: vec![Statement::Assign(Box::new((
                     Place::from(RETURN_LOCAL),
                     RValue::Operand(const_i32(ctx, 0)),
@@ -4266,8 +4693,10 @@ fn global_no_initializer_extern_decl() {
                 ty: i32_ty,
                 mutable: false,
             }]),
+            arg_count: 0,
             locals: IdxVec::new(),
             basic_blocks: IdxVec::from_raw(vec![BasicBlockData {
+                params: vec![],
                 statements: vec![Statement::Assign(Box::new((
                     Place::from(RETURN_LOCAL),
                     RValue::Operand(const_i32(ctx, 0)),
@@ -4330,8 +4759,10 @@ fn global_multiple_globals() {
                 ty: i32_ty,
                 mutable: false,
             }]),
+            arg_count: 0,
             locals: IdxVec::new(),
             basic_blocks: IdxVec::from_raw(vec![BasicBlockData {
+                params: vec![],
                 statements: vec![Statement::Assign(Box::new((
                     Place::from(RETURN_LOCAL),
                     RValue::Operand(const_i32(ctx, 0)),
@@ -4367,7 +4798,7 @@ fn global_multiple_globals() {
 fn global_load_from_body() {
     let ir = compile_to_ir(|ctx| {
         let i32_ty = ctx.intern_ty(TirTy::I32);
-        let ptr_ty = ctx.intern_ty(TirTy::RawPtr(i32_ty, Mutability::Imm));
+        let ptr_ty = ctx.intern_ty(TirTy::RawPtr(i32_ty, Mutability::Imm, AddressSpace::DATA));
 
         // Define a global variable
         let global = TirGlobal {
@@ -4394,6 +4825,7 @@ fn global_load_from_body() {
                 ty: i32_ty,
                 mutable: true,
             }]),
+            arg_count: 0,
             locals: IdxVec::from_raw(vec![
                 // _1: pointer to the global
                 LocalData {
@@ -4407,6 +4839,7 @@ fn global_load_from_body() {
                 },
             ]),
             basic_blocks: IdxVec::from_raw(vec![BasicBlockData {
+                params: vec![],
                 statements: vec![
                     // _1 = &the_global (via Indirect with Static alloc_id)
                     Statement::Assign(Box::new((
@@ -4424,7 +4857,7 @@ fn global_load_from_body() {
                         Place::from(Local::new(2)),
                         RValue::Operand(Operand::Use(Place {
                             local: Local::new(1),
-                            projection: vec![Projection::Deref],
+                            projection: ctx.intern_projection(&[Projection::Deref]),
                         })),
                     ))),
                     // _0 = _2
@@ -4483,8 +4916,10 @@ fn global_internal_linkage() {
                 ty: i32_ty,
                 mutable: false,
             }]),
+            arg_count: 0,
             locals: IdxVec::new(),
             basic_blocks: IdxVec::from_raw(vec![BasicBlockData {
+                params: vec![],
                 statements: vec![Statement::Assign(Box::new((
                     Place::from(RETURN_LOCAL),
                     RValue::Operand(const_i32(ctx, 0)),
@@ -4536,8 +4971,10 @@ fn global_f64_scalar() {
                 ty: i32_ty,
                 mutable: false,
             }]),
+            arg_count: 0,
             locals: IdxVec::new(),
             basic_blocks: IdxVec::from_raw(vec![BasicBlockData {
+                params: vec![],
                 statements: vec![Statement::Assign(Box::new((
                     Place::from(RETURN_LOCAL),
                     RValue::Operand(const_i32(ctx, 0)),
@@ -4586,8 +5023,10 @@ fn global_unnamed_addr() {
                 ty: i32_ty,
                 mutable: false,
             }]),
+            arg_count: 0,
             locals: IdxVec::new(),
             basic_blocks: IdxVec::from_raw(vec![BasicBlockData {
+                params: vec![],
                 statements: vec![Statement::Assign(Box::new((
                     Place::from(RETURN_LOCAL),
                     RValue::Operand(const_i32(ctx, 0)),
@@ -4617,3 +5056,2542 @@ fn global_unnamed_addr() {
         ir
     );
 }
+
+// ====================================================================
+// Body deduplication
+// ====================================================================
+
+/// Two internal bodies with identical contents are emitted only once; the
+/// second one is aliased to the first.
+#[test]
+fn identical_internal_bodies_are_emitted_once() {
+    let ir = compile_to_ir(|ctx| {
+        let i32_ty = ctx.intern_ty(TirTy::I32);
+
+        let helper = |def_id: usize, name: &str| TirBody {
+            metadata: TirBodyMetadata {
+                name: name.to_string(),
+                linkage: Linkage::Internal,
+                ..main_metadata(DefId(def_id))
+            },
+            ret_and_args: IdxVec::from_raw(vec![LocalData {
+                ty: i32_ty,
+                mutable: false,
+            }]),
+            arg_count: 0,
+            locals: IdxVec::new(),
+            basic_blocks: IdxVec::from_raw(vec![BasicBlockData {
+                params: vec![],
+                statements: vec![Statement::Assign(Box::new((
+                    Place::from(RETURN_LOCAL),
+                    RValue::Operand(const_i32(ctx, 7)),
+                )))],
+                terminator: Terminator::Return,
+            }]),
+        };
+
+        TirUnit {
+            metadata: TirUnitMetadata {
+                unit_name: "test".to_string(),
+            },
+            globals: IdxVec::new(),
+            bodies: IdxVec::from_raw(vec![helper(0, "helper_a"), helper(1, "helper_b")]),
+        }
+    });
+
+    println!("--- identical bodies IR ---\n{}", ir);
+    assert_eq!(
+        ir.matches("define ").count(),
+        1,
+        "Expected a single function definition, got:\n{}",
+        ir
+    );
+    assert!(
+        ir.contains("@helper_a"),
+        "Expected the first body to be emitted, got:\n{}",
+        ir
+    );
+    assert!(
+        !ir.contains("@helper_b"),
+        "Expected the duplicate body to be aliased away, got:\n{}",
+        ir
+    );
+}
+
+// ====================================================================
+// Data layout
+// ====================================================================
+
+/// The module carries the data layout of the `TirTarget`, not LLVM's default.
+#[test]
+fn pipeline_module_uses_target_data_layout() {
+    let mut target = TirTarget::new(BackendKind::Llvm);
+    target.data_layout.int64_align = AbiAndPrefAlign::new(8, 8);
+    let expected = format!("target datalayout = \"{}\"", target.data_layout_string());
+
+    let args = TirArgs::default();
+    let arena = TirArena::default();
+    let intern_ctx = InternCtx::new(&arena);
+    let tir_ctx = TirCtx::new(&target, &args, &intern_ctx);
+    let i32_ty = tir_ctx.intern_ty(TirTy::<TirCtx>::I32);
+    let body = TirBody {
+        metadata: main_metadata(DefId(0)),
+        ret_and_args: IdxVec::from_raw(vec![LocalData {
+            ty: i32_ty,
+            mutable: false,
+        }]),
+        arg_count: 0,
+        locals: IdxVec::new(),
+        basic_blocks: IdxVec::from_raw(vec![BasicBlockData {
+            params: vec![],
+            statements: vec![Statement::Assign(Box::new((
+                Place::from(RETURN_LOCAL),
+                RValue::Operand(const_i32(&tir_ctx, 0)),
+            )))],
+            terminator: Terminator::Return,
+        }]),
+    };
+    let unit = TirUnit {
+        metadata: TirUnitMetadata {
+            unit_name: "test".to_string(),
+        },
+        globals: IdxVec::new(),
+        bodies: IdxVec::from_raw(vec![body]),
+    };
+    let ir = llvm_codegen_to_ir_string(tir_ctx, unit).expect("codegen failed");
+
+    println!("--- data layout IR ---\n{}", ir);
+    assert!(
+        ir.contains(&expected),
+        "Expected `{}` in the module, got:\n{}",
+        expected,
+        ir
+    );
+}
+
+// ====================================================================
+// Checked arithmetic
+// ====================================================================
+
+/// Two checked additions in the same function branch to a single shared
+/// overflow-panic landing pad.
+///
+/// ```text
+/// fn main() -> i32 {
+///     _1: i32 = 1;            // mutable
+///     _2: i32 = 2;            // mutable
+///     _3: i32 = _1 +? _2;     // mutable
+///     _0 = _3 +? _2;
+///     return;
+/// }
+/// ```
+#[test]
+fn checked_adds_share_panic_block() {
+    let ir = compile_to_ir(|ctx| {
+        let i32_ty = ctx.intern_ty(TirTy::<TirCtx>::I32);
+        let local = |ty| LocalData { ty, mutable: true };
+
+        let body = TirBody {
+            metadata: main_metadata(DefId(0)),
+            ret_and_args: IdxVec::from_raw(vec![LocalData {
+                ty: i32_ty,
+                mutable: false,
+            }]),
+            arg_count: 0,
+            locals: IdxVec::from_raw(vec![local(i32_ty), local(i32_ty), local(i32_ty)]),
+            basic_blocks: IdxVec::from_raw(vec![BasicBlockData {
+                params: vec![],
+                statements: vec![
+                    Statement::Assign(Box::new((
+                        Place::from(Local::new(1)),
+                        RValue::Operand(const_i32(ctx, 1)),
+                    ))),
+                    Statement::Assign(Box::new((
+                        Place::from(Local::new(2)),
+                        RValue::Operand(const_i32(ctx, 2)),
+                    ))),
+                    Statement::Assign(Box::new((
+                        Place::from(Local::new(3)),
+                        RValue::BinaryOp(
+                            BinaryOp::AddChecked,
+                            Operand::Use(Place::from(Local::new(1))),
+                            Operand::Use(Place::from(Local::new(2))),
+                        ),
+                    ))),
+                    Statement::Assign(Box::new((
+                        Place::from(RETURN_LOCAL),
+                        RValue::BinaryOp(
+                            BinaryOp::AddChecked,
+                            Operand::Use(Place::from(Local::new(3))),
+                            Operand::Use(Place::from(Local::new(2))),
+                        ),
+                    ))),
+                ],
+                terminator: Terminator::Return,
+            }]),
+        };
+
+        TirUnit {
+            metadata: TirUnitMetadata {
+                unit_name: "test".to_string(),
+            },
+            globals: IdxVec::new(),
+            bodies: IdxVec::from_raw(vec![body]),
+        }
+    });
+
+    println!("--- checked add IR ---\n{}", ir);
+    assert_eq!(
+        ir.matches("call { i32, i1 } @llvm.sadd.with.overflow")
+            .count(),
+        2,
+        "Expected two overflow-checked additions, got:\n{}",
+        ir
+    );
+    assert!(
+        ir.contains("declare void @tidec_panic_overflow()"),
+        "Expected the panic runtime function to be declared, got:\n{}",
+        ir
+    );
+    assert_eq!(
+        ir.matches("call void @tidec_panic_overflow()").count(),
+        1,
+        "Expected a single shared panic call, got:\n{}",
+        ir
+    );
+    assert_eq!(
+        ir.matches("label %panic,").count(),
+        2,
+        "Expected both checked additions to branch to the same panic block, got:\n{}",
+        ir
+    );
+}
+
+/// With `checked_div` set, a signed division first checks for a zero
+/// divisor and for `i32::MIN / -1`, branching to the panic landing pad.
+///
+/// ```text
+/// fn main() -> i32 {
+///     _1: i32 = 100;          // mutable
+///     _2: i32 = 3;            // mutable
+///     _0 = _1 / _2;
+///     return;
+/// }
+/// ```
+#[test]
+fn checked_div_guards_the_divisor() {
+    let target = TirTarget::new(BackendKind::Llvm);
+    let args = TirArgs {
+        checked_div: true,
+        ..Default::default()
+    };
+    let arena = TirArena::default();
+    let intern_ctx = InternCtx::new(&arena);
+    let tir_ctx = TirCtx::new(&target, &args, &intern_ctx);
+
+    let i32_ty = tir_ctx.intern_ty(TirTy::<TirCtx>::I32);
+    let body = binop_body_with_locals(
+        BinaryOp::Div,
+        const_i32(&tir_ctx, 100),
+        const_i32(&tir_ctx, 3),
+        i32_ty,
+        i32_ty,
+    );
+    let ir = llvm_codegen_to_ir_string(tir_ctx, single_body_unit(body)).expect("codegen failed");
+
+    println!("--- checked div IR ---\n{}", ir);
+    assert!(
+        ir.contains("icmp eq i32") && ir.contains("-2147483648"),
+        "Expected the divisor and `i32::MIN / -1` checks, got:\n{}",
+        ir
+    );
+    assert!(
+        ir.contains("label %panic, label %div_ok"),
+        "Expected the guard to branch to the panic block, got:\n{}",
+        ir
+    );
+    let div_ok = ir.find("div_ok:").expect("missing `div_ok` block");
+    let sdiv = ir.find("sdiv i32").expect("missing `sdiv` instruction");
+    assert!(
+        div_ok < sdiv,
+        "Expected the division after the guard, got:\n{}",
+        ir
+    );
+}
+
+// ====================================================================
+// Saturating arithmetic
+// ====================================================================
+
+/// Build a one-byte integer constant operand of type `ty`.
+fn const_byte<'ctx>(ty: tidec_tir::TirTy<'ctx>, value: u8) -> Operand<'ctx> {
+    Operand::Const(ConstOperand::Value(
+        ConstValue::Scalar(ConstScalar::Value(RawScalarValue {
+            data: value as u128,
+            size: NonZero::new(1).unwrap(),
+        })),
+        ty,
+    ))
+}
+
+/// Wrap a single `main` body into a unit.
+fn single_body_unit(body: TirBody<'_>) -> TirUnit<'_> {
+    TirUnit {
+        metadata: TirUnitMetadata {
+            unit_name: "test".to_string(),
+        },
+        globals: IdxVec::new(),
+        bodies: IdxVec::from_raw(vec![body]),
+    }
+}
+
+/// A saturating addition on `i8` lowers to the signed `llvm.sadd.sat`
+/// intrinsic.
+///
+/// ```text
+/// fn main() -> i8 {
+///     _1: i8 = 100;           // mutable
+///     _2: i8 = 100;           // mutable
+///     _0 = _1 +| _2;
+///     return;
+/// }
+/// ```
+#[test]
+fn pipeline_saturating_add_i8() {
+    let ir = compile_to_ir(|ctx| {
+        let i8_ty = ctx.intern_ty(TirTy::<TirCtx>::I8);
+        single_body_unit(binop_body_with_locals(
+            BinaryOp::SaturatingAdd,
+            const_byte(i8_ty, 100),
+            const_byte(i8_ty, 100),
+            i8_ty,
+            i8_ty,
+        ))
+    });
+
+    println!("--- saturating add IR ---\n{}", ir);
+    assert!(
+        ir.contains("call i8 @llvm.sadd.sat.i8"),
+        "Expected a signed saturating add on i8, got:\n{}",
+        ir
+    );
+    assert!(
+        !ir.contains("with.overflow"),
+        "Saturating add must not use the overflow-checked intrinsic, got:\n{}",
+        ir
+    );
+}
+
+/// A saturating subtraction on `u8` lowers to the unsigned `llvm.usub.sat`
+/// intrinsic.
+///
+/// ```text
+/// fn main() -> u8 {
+///     _1: u8 = 1;             // mutable
+///     _2: u8 = 200;           // mutable
+///     _0 = _1 -| _2;
+///     return;
+/// }
+/// ```
+#[test]
+fn pipeline_saturating_sub_u8() {
+    let ir = compile_to_ir(|ctx| {
+        let u8_ty = ctx.intern_ty(TirTy::<TirCtx>::U8);
+        single_body_unit(binop_body_with_locals(
+            BinaryOp::SaturatingSub,
+            const_byte(u8_ty, 1),
+            const_byte(u8_ty, 200),
+            u8_ty,
+            u8_ty,
+        ))
+    });
+
+    println!("--- saturating sub IR ---\n{}", ir);
+    assert!(
+        ir.contains("call i8 @llvm.usub.sat.i8"),
+        "Expected an unsigned saturating sub on u8, got:\n{}",
+        ir
+    );
+}
+
+// ====================================================================
+// Codegen errors
+// ====================================================================
+
+/// `TirTy::Metadata` has no LLVM value type, so converting it is an error
+/// rather than a panic.
+#[test]
+fn try_into_basic_type_rejects_metadata() {
+    let target = TirTarget::new(BackendKind::Llvm);
+    let args = TirArgs::default();
+    let arena = TirArena::default();
+    let intern_ctx = InternCtx::new(&arena);
+    let tir_ctx = TirCtx::new(&target, &args, &intern_ctx);
+    let ll_context = Context::create();
+    let ll_module = ll_context.create_module("test");
+    let ctx = CodegenCtx::new(tir_ctx, &ll_context, ll_module);
+
+    let metadata_ty = tir_ctx.intern_ty(TirTy::<TirCtx>::Metadata);
+    let result = metadata_ty.try_into_basic_type(&ctx);
+    assert!(
+        matches!(result, Err(CodegenError::InvalidTypeConversion(ty)) if ty == metadata_ty),
+        "Expected an invalid type conversion error, got: {:?}",
+        result
+    );
+
+    // The signature conversion still accepts metadata.
+    let metadata_llty = metadata_ty.into_basic_type_metadata(&ctx).unwrap();
+    assert!(metadata_llty.is_metadata_type());
+
+    std::mem::forget(ctx);
+    std::mem::forget(ll_context);
+}
+
+/// A global whose type has no LLVM value type makes the whole unit fail to
+/// compile with a `CodegenError`.
+#[test]
+fn pipeline_reports_invalid_global_type() {
+    let target = TirTarget::new(BackendKind::Llvm);
+    let args = TirArgs::default();
+    let arena = TirArena::default();
+    let intern_ctx = InternCtx::new(&arena);
+    let tir_ctx = TirCtx::new(&target, &args, &intern_ctx);
+
+    let global = TirGlobal {
+        name: "BAD".to_string(),
+        ty: tir_ctx.intern_ty(TirTy::<TirCtx>::Metadata),
+        initializer: None,
+        mutable: false,
+        linkage: Linkage::External,
+        visibility: Visibility::Default,
+        unnamed_address: UnnamedAddress::None,
+    };
+    let unit = TirUnit {
+        metadata: TirUnitMetadata {
+            unit_name: "test".to_string(),
+        },
+        globals: IdxVec::from_raw(vec![global]),
+        bodies: IdxVec::new(),
+    };
+
+    let result = llvm_codegen_to_ir_string(tir_ctx, unit);
+    assert!(
+        matches!(result, Err(CodegenError::InvalidTypeConversion(_))),
+        "Expected an invalid type conversion error, got: {:?}",
+        result
+    );
+}
+
+/// An `f128` addition on a target without `f128` support fails with a
+/// `CodegenError` instead of emitting IR the target cannot lower.
+///
+/// ```text
+/// fn main() -> f128 {
+///     _1: f128 = 1.5;         // mutable
+///     _2: f128 = 2.5;         // mutable
+///     _0 = _1 + _2;
+///     return;
+/// }
+/// ```
+#[test]
+fn pipeline_reports_unsupported_float_type() {
+    let target = TirTarget::with_triple(
+        BackendKind::Llvm,
+        TargetTriple::new("armv7", "unknown", "linux", "gnu", "eabihf"),
+    );
+    let args = TirArgs::default();
+    let arena = TirArena::default();
+    let intern_ctx = InternCtx::new(&arena);
+    let tir_ctx = TirCtx::new(&target, &args, &intern_ctx);
+
+    let f128_ty = tir_ctx.intern_ty(TirTy::<TirCtx>::F128);
+    let f128_const = |val: f64| -> Operand<'_> {
+        Operand::Const(ConstOperand::Value(
+            ConstValue::Scalar(ConstScalar::Value(RawScalarValue {
+                data: val.to_bits() as u128,
+                size: NonZero::new(16).unwrap(),
+            })),
+            f128_ty,
+        ))
+    };
+    let body = binop_body_with_locals(
+        BinaryOp::Add,
+        f128_const(1.5),
+        f128_const(2.5),
+        f128_ty,
+        f128_ty,
+    );
+
+    let result = llvm_codegen_to_ir_string(tir_ctx, single_body_unit(body));
+    assert!(
+        matches!(result, Err(CodegenError::UnsupportedFloatType(ty)) if ty == f128_ty),
+        "Expected an unsupported float type error, got: {:?}",
+        result
+    );
+}
+
+/// A body whose SSA value is used in a block it does not dominate lowers to
+/// a malformed module, which the verifier rejects before anything is emitted.
+///
+/// ```text
+/// fn main(_1: i32) -> i32 {
+///     bb0: switchInt(_1) -> [0: bb1, otherwise: bb2];
+///     bb1: _2 = _1 + _1; goto bb2;
+///     bb2: _0 = _2; return;    // `_2` is not defined along bb0 -> bb2
+/// }
+/// ```
+#[test]
+fn pipeline_reports_invalid_module() {
+    let target = TirTarget::new(BackendKind::Llvm);
+    let args = TirArgs {
+        verify_module: true,
+        ..Default::default()
+    };
+    let arena = TirArena::default();
+    let intern_ctx = InternCtx::new(&arena);
+    let tir_ctx = TirCtx::new(&target, &args, &intern_ctx);
+
+    let i32_ty = tir_ctx.intern_ty(TirTy::<TirCtx>::I32);
+    let local = |ty| LocalData { ty, mutable: false };
+    let body = TirBody {
+        metadata: main_metadata(DefId(0)),
+        ret_and_args: IdxVec::from_raw(vec![local(i32_ty), local(i32_ty)]),
+        arg_count: 1,
+        locals: IdxVec::from_raw(vec![local(i32_ty)]),
+        basic_blocks: IdxVec::from_raw(vec![
+            BasicBlockData {
+                params: vec![],
+                statements: vec![],
+                terminator: Terminator::SwitchInt {
+                    discr: Operand::Use(Place::from(Local::new(1))),
+                    targets: SwitchTargets::new(vec![(0, BasicBlock::new(1))], BasicBlock::new(2)),
+                    block_args: vec![],
+                },
+            },
+            BasicBlockData {
+                params: vec![],
+                statements: vec![Statement::Assign(Box::new((
+                    Place::from(Local::new(2)),
+                    RValue::BinaryOp(
+                        BinaryOp::Add,
+                        Operand::Use(Place::from(Local::new(1))),
+                        Operand::Use(Place::from(Local::new(1))),
+                    ),
+                )))],
+                terminator: Terminator::Goto {
+                    target: BasicBlock::new(2),
+                    block_args: vec![],
+                },
+            },
+            BasicBlockData {
+                params: vec![],
+                statements: vec![Statement::Assign(Box::new((
+                    Place::from(RETURN_LOCAL),
+                    RValue::Operand(Operand::Use(Place::from(Local::new(2)))),
+                )))],
+                terminator: Terminator::Return,
+            },
+        ]),
+    };
+
+    let result = llvm_codegen_lir_unit(tir_ctx, single_body_unit(body));
+    assert!(
+        matches!(&result, Err(CodegenError::InvalidModule(msg)) if msg.contains("dominate")),
+        "Expected an invalid module error, got: {:?}",
+        result
+    );
+}
+
+// ====================================================================
+// Argument passing
+// ====================================================================
+
+/// Build the metadata of an external C function declaration.
+fn extern_fn_metadata(def_id: DefId, name: &str) -> TirBodyMetadata {
+    TirBodyMetadata {
+        def_id,
+        name: name.to_string(),
+        kind: TirBodyKind::Item(TirItemKind::Function),
+        inlined: false,
+        linkage: Linkage::External,
+        visibility: Visibility::Default,
+        unnamed_address: UnnamedAddress::None,
+        call_conv: CallConv::C,
+        is_varargs: false,
+        is_declaration: true,
+        entry_point: None,
+    }
+}
+
+/// Large aggregates travel through pointers (an `sret` pointer for the
+/// return value), small ones are passed by value.
+///
+/// ```text
+/// extern fn make_big() -> [i64; 8];
+/// extern fn take_big(_1: [i64; 8]);
+/// extern fn take_pair(_1: { i32, i32 });
+///
+/// fn main() -> i32 {
+///     _1: [i64; 8] = make_big();
+///     _2: () = take_big(_1);
+///     _3: { i32, i32 } = Aggregate::Struct(1, 2);
+///     _2 = take_pair(_3);
+///     _0 = 0;
+///     return;
+/// }
+/// ```
+#[test]
+fn pipeline_aggregate_pass_modes() {
+    let ir = compile_to_ir(|ctx| {
+        let i32_ty = ctx.intern_ty(TirTy::<TirCtx>::I32);
+        let i64_ty = ctx.intern_ty(TirTy::<TirCtx>::I64);
+        let unit_ty = ctx.intern_ty(TirTy::<TirCtx>::Unit);
+        let ptr_ty = ctx.intern_ty(TirTy::RawPtr(i32_ty, Mutability::Imm, AddressSpace::DATA));
+        let big_ty = ctx.intern_ty(TirTy::<TirCtx>::Array(i64_ty, 8));
+        let fields = ctx.intern_type_list(&[i32_ty, i32_ty]);
+        let pair_ty = ctx.intern_ty(TirTy::<TirCtx>::Struct {
+            fields,
+            packed: false,
+        });
+
+        let declare = |def_id: DefId, name: &str, ret_and_args: Vec<_>| TirBody {
+            metadata: extern_fn_metadata(def_id, name),
+            arg_count: ret_and_args.len() - 1,
+            ret_and_args: IdxVec::from_raw(
+                ret_and_args
+                    .into_iter()
+                    .map(|ty| LocalData { ty, mutable: false })
+                    .collect(),
+            ),
+            locals: IdxVec::new(),
+            basic_blocks: IdxVec::new(),
+        };
+        let make_big = declare(DefId(0), "make_big", vec![big_ty]);
+        let take_big = declare(DefId(1), "take_big", vec![unit_ty, big_ty]);
+        let take_pair = declare(DefId(2), "take_pair", vec![unit_ty, pair_ty]);
+
+        let fn_operand = |def_id: DefId| {
+            Operand::Const(ConstOperand::Value(
+                ConstValue::Indirect {
+                    alloc_id: ctx.intern_fn(def_id),
+                    offset: Size::ZERO,
+                },
+                ptr_ty,
+            ))
+        };
+
+        let basic_blocks = vec![
+            // bb0: _1 = make_big() -> bb1
+            BasicBlockData {
+                params: vec![],
+                statements: vec![],
+                terminator: Terminator::Call {
+                    func: fn_operand(DefId(0)),
+                    args: vec![],
+                    destination: Place::from(Local::new(1)),
+                    target: BasicBlock::new(1),
+                    block_args: vec![],
+                },
+            },
+            // bb1: _2 = take_big(_1) -> bb2
+            BasicBlockData {
+                params: vec![],
+                statements: vec![],
+                terminator: Terminator::Call {
+                    func: fn_operand(DefId(1)),
+                    args: vec![Operand::Use(Place::from(Local::new(1)))],
+                    destination: Place::from(Local::new(2)),
+                    target: BasicBlock::new(2),
+                    block_args: vec![],
+                },
+            },
+            // bb2: _3 = { 1, 2 }; _2 = take_pair(_3) -> bb3
+            BasicBlockData {
+                params: vec![],
+                statements: vec![Statement::Assign(Box::new((
+                    Place::from(Local::new(3)),
+                    RValue::Aggregate(
+                        AggregateKind::Struct(pair_ty),
+                        vec![const_i32(ctx, 1), const_i32(ctx, 2)],
+                    ),
+                )))],
+                terminator: Terminator::Call {
+                    func: fn_operand(DefId(2)),
+                    args: vec![Operand::Use(Place::from(Local::new(3)))],
+                    destination: Place::from(Local::new(2)),
+                    target: BasicBlock::new(3),
+                    block_args: vec![],
+                },
+            },
+            // bb3: _0 = 0; return
+            BasicBlockData {
+                params: vec![],
+                statements: vec![Statement::Assign(Box::new((
+                    Place::from(RETURN_LOCAL),
+                    RValue::Operand(const_i32(ctx, 0)),
+                )))],
+                terminator: Terminator::Return,
+            },
+        ];
+
+        let main_body = TirBody {
+            metadata: main_metadata(DefId(3)),
+            ret_and_args: IdxVec::from_raw(vec![LocalData {
+                ty: i32_ty,
+                mutable: false,
+            }]),
+            arg_count: 0,
+            locals: IdxVec::from_raw(vec![
+                LocalData {
+                    ty: big_ty,
+                    mutable: true,
+                },
+                LocalData {
+                    ty: unit_ty,
+                    mutable: false,
+                },
+                LocalData {
+                    ty: pair_ty,
+                    mutable: true,
+                },
+            ]),
+            basic_blocks: IdxVec::from_raw(basic_blocks),
+        };
+
+        TirUnit {
+            metadata: TirUnitMetadata {
+                unit_name: "test".to_string(),
+            },
+            globals: IdxVec::new(),
+            bodies: IdxVec::from_raw(vec![make_big, take_big, take_pair, main_body]),
+        }
+    });
+
+    println!("--- aggregate pass modes IR ---\n{}", ir);
+    assert!(
+        ir.contains("declare void @make_big(ptr sret([8 x i64]))"),
+        "Expected `[i64; 8]` to be returned through an `sret` pointer, got:\n{}",
+        ir
+    );
+    assert!(
+        ir.contains("declare void @take_big(ptr)"),
+        "Expected `[i64; 8]` to be passed indirectly, got:\n{}",
+        ir
+    );
+    assert!(
+        ir.contains("declare void @take_pair({ i32, i32 })"),
+        "Expected `{{ i32, i32 }}` to be passed directly, got:\n{}",
+        ir
+    );
+    assert!(
+        ir.contains("call void @llvm.memcpy"),
+        "Expected the indirect argument to be copied to a temporary, got:\n{}",
+        ir
+    );
+}
+
+/// A call whose destination has a projection still gets its return value:
+/// an indirect one through an `sret` pointer to the projected place, a
+/// direct one by a store to it.
+///
+/// ```text
+/// extern fn make_big() -> [i64; 8];
+/// extern fn make_i32() -> i32;
+///
+/// fn main() -> i32 {
+///     _1: { [i64; 8], i32 };
+///     _1.0 = make_big();
+///     _1.1 = make_i32();
+///     _0 = _1.1;
+///     return;
+/// }
+/// ```
+#[test]
+fn pipeline_call_into_projected_destination() {
+    let ir = compile_to_ir(|ctx| {
+        let i32_ty = ctx.intern_ty(TirTy::<TirCtx>::I32);
+        let i64_ty = ctx.intern_ty(TirTy::<TirCtx>::I64);
+        let ptr_ty = ctx.intern_ty(TirTy::RawPtr(i32_ty, Mutability::Imm, AddressSpace::DATA));
+        let big_ty = ctx.intern_ty(TirTy::<TirCtx>::Array(i64_ty, 8));
+        let fields = ctx.intern_type_list(&[big_ty, i32_ty]);
+        let outer_ty = ctx.intern_ty(TirTy::<TirCtx>::Struct {
+            fields,
+            packed: false,
+        });
+
+        let declare = |def_id: DefId, name: &str, ret_ty| TirBody {
+            metadata: extern_fn_metadata(def_id, name),
+            ret_and_args: IdxVec::from_raw(vec![LocalData {
+                ty: ret_ty,
+                mutable: false,
+            }]),
+            arg_count: 0,
+            locals: IdxVec::new(),
+            basic_blocks: IdxVec::new(),
+        };
+        let fn_operand = |def_id: DefId| {
+            Operand::Const(ConstOperand::Value(
+                ConstValue::Indirect {
+                    alloc_id: ctx.intern_fn(def_id),
+                    offset: Size::ZERO,
+                },
+                ptr_ty,
+            ))
+        };
+        let field = |idx: usize, ty| Place {
+            local: Local::new(1),
+            projection: ctx.intern_projection(&[Projection::Field(FieldIdx::new(idx), ty)]),
+        };
+
+        let basic_blocks = vec![
+            // bb0: _1.0 = make_big() -> bb1
+            BasicBlockData {
+                params: vec![],
+                statements: vec![],
+                terminator: Terminator::Call {
+                    func: fn_operand(DefId(0)),
+                    args: vec![],
+                    destination: field(0, big_ty),
+                    target: BasicBlock::new(1),
+                    block_args: vec![],
+                },
+            },
+            // bb1: _1.1 = make_i32() -> bb2
+            BasicBlockData {
+                params: vec![],
+                statements: vec![],
+                terminator: Terminator::Call {
+                    func: fn_operand(DefId(1)),
+                    args: vec![],
+                    destination: field(1, i32_ty),
+                    target: BasicBlock::new(2),
+                    block_args: vec![],
+                },
+            },
+            // bb2: _0 = _1.1; return
+            BasicBlockData {
+                params: vec![],
+                statements: vec![Statement::Assign(Box::new((
+                    Place::from(RETURN_LOCAL),
+                    RValue::Operand(Operand::Use(field(1, i32_ty))),
+                )))],
+                terminator: Terminator::Return,
+            },
+        ];
+
+        let main_body = TirBody {
+            metadata: main_metadata(DefId(2)),
+            ret_and_args: IdxVec::from_raw(vec![LocalData {
+                ty: i32_ty,
+                mutable: false,
+            }]),
+            arg_count: 0,
+            locals: IdxVec::from_raw(vec![LocalData {
+                ty: outer_ty,
+                mutable: true,
+            }]),
+            basic_blocks: IdxVec::from_raw(basic_blocks),
+        };
+
+        TirUnit {
+            metadata: TirUnitMetadata {
+                unit_name: "test".to_string(),
+            },
+            globals: IdxVec::new(),
+            bodies: IdxVec::from_raw(vec![
+                declare(DefId(0), "make_big", big_ty),
+                declare(DefId(1), "make_i32", i32_ty),
+                main_body,
+            ]),
+        }
+    });
+
+    println!("--- projected call destination IR ---\n{}", ir);
+    assert!(
+        ir.contains("call void @make_big(ptr"),
+        "Expected the projected place to be passed as the `sret` pointer, got:\n{}",
+        ir
+    );
+    assert!(
+        ir.contains("store i32 %call"),
+        "Expected the direct result to be stored into the projected place, got:\n{}",
+        ir
+    );
+}
+
+/// A function returning a large struct writes its result through a leading
+/// `sret` pointer, and its caller passes the address of the destination.
+///
+/// ```text
+/// fn make_big() -> { i64, i64, i64, i64 } {
+///     _0 = Aggregate::Struct(1, 2, 3, 4);
+///     return;
+/// }
+///
+/// fn main() -> i64 {
+///     _1: { i64, i64, i64, i64 } = make_big();
+///     _0 = _1.3;
+///     return;
+/// }
+/// ```
+#[test]
+fn pipeline_large_struct_return_uses_sret() {
+    let ir = compile_to_ir(|ctx| {
+        let i64_ty = ctx.intern_ty(TirTy::<TirCtx>::I64);
+        let ptr_ty = ctx.intern_ty(TirTy::RawPtr(i64_ty, Mutability::Imm, AddressSpace::DATA));
+        let fields = ctx.intern_type_list(&[i64_ty, i64_ty, i64_ty, i64_ty]);
+        let big_ty = ctx.intern_ty(TirTy::<TirCtx>::Struct {
+            fields,
+            packed: false,
+        });
+        let const_i64 = |value: u128| {
+            Operand::Const(ConstOperand::Value(
+                ConstValue::Scalar(ConstScalar::Value(RawScalarValue {
+                    data: value,
+                    size: NonZero::new(8).unwrap(),
+                })),
+                i64_ty,
+            ))
+        };
+
+        let make_big = TirBody {
+            metadata: TirBodyMetadata {
+                name: "make_big".to_string(),
+                ..main_metadata(DefId(0))
+            },
+            ret_and_args: IdxVec::from_raw(vec![LocalData {
+                ty: big_ty,
+                mutable: false,
+            }]),
+            arg_count: 0,
+            locals: IdxVec::new(),
+            basic_blocks: IdxVec::from_raw(vec![BasicBlockData {
+                params: vec![],
+                statements: vec![Statement::Assign(Box::new((
+                    Place::from(RETURN_LOCAL),
+                    RValue::Aggregate(
+                        AggregateKind::Struct(big_ty),
+                        vec![const_i64(1), const_i64(2), const_i64(3), const_i64(4)],
+                    ),
+                )))],
+                terminator: Terminator::Return,
+            }]),
+        };
+
+        let main_body = TirBody {
+            metadata: main_metadata(DefId(1)),
+            ret_and_args: IdxVec::from_raw(vec![LocalData {
+                ty: i64_ty,
+                mutable: false,
+            }]),
+            arg_count: 0,
+            locals: IdxVec::from_raw(vec![LocalData {
+                ty: big_ty,
+                mutable: true,
+            }]),
+            basic_blocks: IdxVec::from_raw(vec![
+                // bb0: _1 = make_big() -> bb1
+                BasicBlockData {
+                    params: vec![],
+                    statements: vec![],
+                    terminator: Terminator::Call {
+                        func: Operand::Const(ConstOperand::Value(
+                            ConstValue::Indirect {
+                                alloc_id: ctx.intern_fn(DefId(0)),
+                                offset: Size::ZERO,
+                            },
+                            ptr_ty,
+                        )),
+                        args: vec![],
+                        destination: Place::from(Local::new(1)),
+                        target: BasicBlock::new(1),
+                        block_args: vec![],
+                    },
+                },
+                // bb1: _0 = _1.3; return
+                BasicBlockData {
+                    params: vec![],
+                    statements: vec![Statement::Assign(Box::new((
+                        Place::from(RETURN_LOCAL),
+                        RValue::Operand(Operand::Use(Place {
+                            local: Local::new(1),
+                            projection: ctx
+                                .intern_projection(&[Projection::Field(FieldIdx::new(3), i64_ty)]),
+                        })),
+                    )))],
+                    terminator: Terminator::Return,
+                },
+            ]),
+        };
+
+        TirUnit {
+            metadata: TirUnitMetadata {
+                unit_name: "test".to_string(),
+            },
+            globals: IdxVec::new(),
+            bodies: IdxVec::from_raw(vec![make_big, main_body]),
+        }
+    });
+
+    println!("--- sret return IR ---\n{}", ir);
+    assert!(
+        ir.contains("define void @make_big(ptr sret({ i64, i64, i64, i64 })"),
+        "Expected the first parameter of `make_big` to be an `sret` slot, got:\n{}",
+        ir
+    );
+    assert!(
+        ir.contains("call void @make_big(ptr sret({ i64, i64, i64, i64 })"),
+        "Expected the caller to pass its destination as the `sret` slot, got:\n{}",
+        ir
+    );
+    assert!(
+        !ir.contains("ret { i64, i64, i64, i64 }"),
+        "Large structs must not be returned by value, got:\n{}",
+        ir
+    );
+}
+
+/// A two-field struct of scalars is a scalar pair: it is returned by value
+/// (in two registers) rather than through an `sret` pointer.
+///
+/// ```text
+/// fn make_pair() -> { i64, i64 } {
+///     _0 = Aggregate::Struct(1, 2);
+///     return;
+/// }
+///
+/// fn main() -> i64 {
+///     _1: { i64, i64 } = make_pair();
+///     _0 = _1.1;
+///     return;
+/// }
+/// ```
+#[test]
+fn pipeline_scalar_pair_return_is_direct() {
+    let ir = compile_to_ir(|ctx| {
+        let i64_ty = ctx.intern_ty(TirTy::<TirCtx>::I64);
+        let ptr_ty = ctx.intern_ty(TirTy::RawPtr(i64_ty, Mutability::Imm, AddressSpace::DATA));
+        let fields = ctx.intern_type_list(&[i64_ty, i64_ty]);
+        let pair_ty = ctx.intern_ty(TirTy::<TirCtx>::Struct {
+            fields,
+            packed: false,
+        });
+        let const_i64 = |value: u128| {
+            Operand::Const(ConstOperand::Value(
+                ConstValue::Scalar(ConstScalar::Value(RawScalarValue {
+                    data: value,
+                    size: NonZero::new(8).unwrap(),
+                })),
+                i64_ty,
+            ))
+        };
+
+        let make_pair = TirBody {
+            metadata: TirBodyMetadata {
+                name: "make_pair".to_string(),
+                ..main_metadata(DefId(0))
+            },
+            ret_and_args: IdxVec::from_raw(vec![LocalData {
+                ty: pair_ty,
+                mutable: false,
+            }]),
+            arg_count: 0,
+            locals: IdxVec::new(),
+            basic_blocks: IdxVec::from_raw(vec![BasicBlockData {
+                params: vec![],
+                statements: vec![Statement::Assign(Box::new((
+                    Place::from(RETURN_LOCAL),
+                    RValue::Aggregate(
+                        AggregateKind::Struct(pair_ty),
+                        vec![const_i64(1), const_i64(2)],
+                    ),
+                )))],
+                terminator: Terminator::Return,
+            }]),
+        };
+
+        let main_body = TirBody {
+            metadata: main_metadata(DefId(1)),
+            ret_and_args: IdxVec::from_raw(vec![LocalData {
+                ty: i64_ty,
+                mutable: false,
+            }]),
+            arg_count: 0,
+            locals: IdxVec::from_raw(vec![LocalData {
+                ty: pair_ty,
+                mutable: true,
+            }]),
+            basic_blocks: IdxVec::from_raw(vec![
+                // bb0: _1 = make_pair() -> bb1
+                BasicBlockData {
+                    params: vec![],
+                    statements: vec![],
+                    terminator: Terminator::Call {
+                        func: Operand::Const(ConstOperand::Value(
+                            ConstValue::Indirect {
+                                alloc_id: ctx.intern_fn(DefId(0)),
+                                offset: Size::ZERO,
+                            },
+                            ptr_ty,
+                        )),
+                        args: vec![],
+                        destination: Place::from(Local::new(1)),
+                        target: BasicBlock::new(1),
+                        block_args: vec![],
+                    },
+                },
+                // bb1: _0 = _1.1; return
+                BasicBlockData {
+                    params: vec![],
+                    statements: vec![Statement::Assign(Box::new((
+                        Place::from(RETURN_LOCAL),
+                        RValue::Operand(Operand::Use(Place {
+                            local: Local::new(1),
+                            projection: ctx
+                                .intern_projection(&[Projection::Field(FieldIdx::new(1), i64_ty)]),
+                        })),
+                    )))],
+                    terminator: Terminator::Return,
+                },
+            ]),
+        };
+
+        TirUnit {
+            metadata: TirUnitMetadata {
+                unit_name: "test".to_string(),
+            },
+            globals: IdxVec::new(),
+            bodies: IdxVec::from_raw(vec![make_pair, main_body]),
+        }
+    });
+
+    println!("--- scalar pair return IR ---\n{}", ir);
+    assert!(
+        ir.contains("define { i64, i64 } @make_pair()"),
+        "Expected `make_pair` to return `{{ i64, i64 }}` by value, got:\n{}",
+        ir
+    );
+    assert!(
+        ir.contains("ret { i64, i64 }"),
+        "Expected the pair to be returned directly, got:\n{}",
+        ir
+    );
+    assert!(
+        !ir.contains("sret"),
+        "Scalar pairs must not be returned through an `sret` pointer, got:\n{}",
+        ir
+    );
+}
+
+/// A three-field struct of `i32`s lives in memory but fits in two registers,
+/// so it is coerced to `{ i64, i32 }` both as an argument and as a return
+/// value.
+///
+/// ```text
+/// fn make_triple() -> { i32, i32, i32 } {
+///     _0 = Aggregate::Struct(1, 2, 3);
+///     return;
+/// }
+///
+/// fn second(_1: { i32, i32, i32 }) -> i32 {
+///     _0 = _1.1;
+///     return;
+/// }
+///
+/// fn main() -> i32 {
+///     _1: { i32, i32, i32 } = make_triple();
+///     _0 = second(_1);
+///     return;
+/// }
+/// ```
+#[test]
+fn pipeline_small_aggregate_is_cast_to_integers() {
+    let ir = compile_to_ir(|ctx| {
+        let i32_ty = ctx.intern_ty(TirTy::<TirCtx>::I32);
+        let ptr_ty = ctx.intern_ty(TirTy::RawPtr(i32_ty, Mutability::Imm, AddressSpace::DATA));
+        let fields = ctx.intern_type_list(&[i32_ty, i32_ty, i32_ty]);
+        let triple_ty = ctx.intern_ty(TirTy::<TirCtx>::Struct {
+            fields,
+            packed: false,
+        });
+        let const_i32 = |value: u128| {
+            Operand::Const(ConstOperand::Value(
+                ConstValue::Scalar(ConstScalar::Value(RawScalarValue {
+                    data: value,
+                    size: NonZero::new(4).unwrap(),
+                })),
+                i32_ty,
+            ))
+        };
+        let fn_operand = |def_id: DefId| {
+            Operand::Const(ConstOperand::Value(
+                ConstValue::Indirect {
+                    alloc_id: ctx.intern_fn(def_id),
+                    offset: Size::ZERO,
+                },
+                ptr_ty,
+            ))
+        };
+
+        let make_triple = TirBody {
+            metadata: TirBodyMetadata {
+                name: "make_triple".to_string(),
+                ..main_metadata(DefId(0))
+            },
+            ret_and_args: IdxVec::from_raw(vec![LocalData {
+                ty: triple_ty,
+                mutable: false,
+            }]),
+            arg_count: 0,
+            locals: IdxVec::new(),
+            basic_blocks: IdxVec::from_raw(vec![BasicBlockData {
+                params: vec![],
+                statements: vec![Statement::Assign(Box::new((
+                    Place::from(RETURN_LOCAL),
+                    RValue::Aggregate(
+                        AggregateKind::Struct(triple_ty),
+                        vec![const_i32(1), const_i32(2), const_i32(3)],
+                    ),
+                )))],
+                terminator: Terminator::Return,
+            }]),
+        };
+
+        let second = TirBody {
+            metadata: TirBodyMetadata {
+                name: "second".to_string(),
+                ..main_metadata(DefId(1))
+            },
+            ret_and_args: IdxVec::from_raw(vec![
+                LocalData {
+                    ty: i32_ty,
+                    mutable: false,
+                },
+                LocalData {
+                    ty: triple_ty,
+                    mutable: false,
+                },
+            ]),
+            arg_count: 1,
+            locals: IdxVec::new(),
+            basic_blocks: IdxVec::from_raw(vec![BasicBlockData {
+                params: vec![],
+                statements: vec![Statement::Assign(Box::new((
+                    Place::from(RETURN_LOCAL),
+                    RValue::Operand(Operand::Use(Place {
+                        local: Local::new(1),
+                        projection: ctx
+                            .intern_projection(&[Projection::Field(FieldIdx::new(1), i32_ty)]),
+                    })),
+                )))],
+                terminator: Terminator::Return,
+            }]),
+        };
+
+        let main_body = TirBody {
+            metadata: main_metadata(DefId(2)),
+            ret_and_args: IdxVec::from_raw(vec![LocalData {
+                ty: i32_ty,
+                mutable: false,
+            }]),
+            arg_count: 0,
+            locals: IdxVec::from_raw(vec![LocalData {
+                ty: triple_ty,
+                mutable: true,
+            }]),
+            basic_blocks: IdxVec::from_raw(vec![
+                // bb0: _1 = make_triple() -> bb1
+                BasicBlockData {
+                    params: vec![],
+                    statements: vec![],
+                    terminator: Terminator::Call {
+                        func: fn_operand(DefId(0)),
+                        args: vec![],
+                        destination: Place::from(Local::new(1)),
+                        target: BasicBlock::new(1),
+                        block_args: vec![],
+                    },
+                },
+                // bb1: _0 = second(_1) -> bb2
+                BasicBlockData {
+                    params: vec![],
+                    statements: vec![],
+                    terminator: Terminator::Call {
+                        func: fn_operand(DefId(1)),
+                        args: vec![Operand::Use(Place::from(Local::new(1)))],
+                        destination: Place::from(RETURN_LOCAL),
+                        target: BasicBlock::new(2),
+                        block_args: vec![],
+                    },
+                },
+                // bb2: return
+                BasicBlockData {
+                    params: vec![],
+                    statements: vec![],
+                    terminator: Terminator::Return,
+                },
+            ]),
+        };
+
+        TirUnit {
+            metadata: TirUnitMetadata {
+                unit_name: "test".to_string(),
+            },
+            globals: IdxVec::new(),
+            bodies: IdxVec::from_raw(vec![make_triple, second, main_body]),
+        }
+    });
+
+    println!("--- small aggregate cast IR ---\n{}", ir);
+    assert!(
+        ir.contains("define { i64, i32 } @make_triple()"),
+        "Expected `make_triple` to return `{{ i64, i32 }}`, got:\n{}",
+        ir
+    );
+    assert!(
+        ir.contains("define i32 @second({ i64, i32 }"),
+        "Expected `second` to take `{{ i64, i32 }}`, got:\n{}",
+        ir
+    );
+    assert!(
+        ir.contains("call { i64, i32 } @make_triple()"),
+        "Expected the call to return `{{ i64, i32 }}`, got:\n{}",
+        ir
+    );
+    assert!(
+        !ir.contains("sret"),
+        "Small aggregates must not be returned through an `sret` pointer, got:\n{}",
+        ir
+    );
+}
+
+// ====================================================================
+// Block parameters
+// ====================================================================
+
+/// Block parameters are lowered to a phi node in the target block, with one
+/// incoming value per predecessor.
+///
+/// ```text
+/// fn main() -> i32 {
+///     _1: bool = true;        // mutable
+///     SwitchInt(_1, [1 → bb1, otherwise → bb2])
+/// bb1:
+///     goto bb3(1);
+/// bb2:
+///     goto bb3(2);
+/// bb3(_2: i32):
+///     _0 = _2;
+///     return;
+/// }
+/// ```
+#[test]
+fn pipeline_block_params_lower_to_phi() {
+    let ir = compile_to_ir(|ctx| {
+        let i32_ty = ctx.intern_ty(TirTy::<TirCtx>::I32);
+        let bool_ty = ctx.intern_ty(TirTy::<TirCtx>::Bool);
+
+        let bb0 = BasicBlockData {
+            params: vec![],
+            statements: vec![Statement::Assign(Box::new((
+                Place::from(Local::new(1)),
+                RValue::Operand(const_byte(bool_ty, 1)),
+            )))],
+            terminator: Terminator::SwitchInt {
+                discr: Operand::Use(Place::from(Local::new(1))),
+                targets: SwitchTargets::if_then(BasicBlock::new(1), BasicBlock::new(2)),
+                block_args: vec![],
+            },
+        };
+        let goto_join = |value| BasicBlockData {
+            params: vec![],
+            statements: vec![],
+            terminator: Terminator::Goto {
+                target: BasicBlock::new(3),
+                block_args: vec![const_i32(ctx, value)],
+            },
+        };
+        let join = BasicBlockData {
+            params: vec![(Local::new(2), i32_ty)],
+            statements: vec![Statement::Assign(Box::new((
+                Place::from(RETURN_LOCAL),
+                RValue::Operand(Operand::Use(Place::from(Local::new(2)))),
+            )))],
+            terminator: Terminator::Return,
+        };
+
+        single_body_unit(TirBody {
+            metadata: main_metadata(DefId(0)),
+            ret_and_args: IdxVec::from_raw(vec![LocalData {
+                ty: i32_ty,
+                mutable: true,
+            }]),
+            arg_count: 0,
+            locals: IdxVec::from_raw(vec![
+                LocalData {
+                    ty: bool_ty,
+                    mutable: true,
+                }, // _1
+                LocalData {
+                    ty: i32_ty,
+                    mutable: false,
+                }, // _2
+            ]),
+            basic_blocks: IdxVec::from_raw(vec![bb0, goto_join(1), goto_join(2), join]),
+        })
+    });
+
+    println!("--- block params IR ---\n{}", ir);
+    assert!(
+        ir.contains("phi i32 [ 1, ") && ir.contains("], [ 2, "),
+        "Expected a phi merging the two block arguments, got:\n{}",
+        ir
+    );
+}
+
+/// A pair block argument, such as a wide pointer, is passed through a single
+/// aggregate phi.
+///
+/// ```text
+/// fn main() -> i32 {
+///     goto bb1(const b"hi");
+/// bb1(_1: { *const u8, usize }):
+///     _2 = _1;                // mutable
+///     _0 = 0;
+///     return;
+/// }
+/// ```
+#[test]
+fn pipeline_pair_block_param_lowers_to_aggregate_phi() {
+    let ir = compile_to_ir(|ctx| {
+        let i32_ty = ctx.intern_ty(TirTy::<TirCtx>::I32);
+        let u8_ty = ctx.intern_ty(TirTy::<TirCtx>::U8);
+        let usize_ty = ctx.intern_ty(TirTy::<TirCtx>::Usize);
+        let ptr_ty = ctx.intern_ty(TirTy::<TirCtx>::RawPtr(
+            u8_ty,
+            Mutability::Imm,
+            AddressSpace::DATA,
+        ));
+        let slice_ty = ctx.intern_ty(TirTy::<TirCtx>::Struct {
+            fields: ctx.intern_type_list(&[ptr_ty, usize_ty]),
+            packed: false,
+        });
+
+        let bb0 = BasicBlockData {
+            params: vec![],
+            statements: vec![],
+            terminator: Terminator::Goto {
+                target: BasicBlock::new(1),
+                block_args: vec![Operand::Const(ConstOperand::Value(
+                    ConstValue::Slice {
+                        data: b"hi".to_vec(),
+                    },
+                    slice_ty,
+                ))],
+            },
+        };
+        let bb1 = BasicBlockData {
+            params: vec![(Local::new(1), slice_ty)],
+            statements: vec![
+                Statement::Assign(Box::new((
+                    Place::from(Local::new(2)),
+                    RValue::Operand(Operand::Use(Place::from(Local::new(1)))),
+                ))),
+                Statement::Assign(Box::new((
+                    Place::from(RETURN_LOCAL),
+                    RValue::Operand(const_i32(ctx, 0)),
+                ))),
+            ],
+            terminator: Terminator::Return,
+        };
+
+        single_body_unit(TirBody {
+            metadata: main_metadata(DefId(0)),
+            ret_and_args: IdxVec::from_raw(vec![LocalData {
+                ty: i32_ty,
+                mutable: false,
+            }]),
+            arg_count: 0,
+            locals: IdxVec::from_raw(vec![
+                LocalData {
+                    ty: slice_ty,
+                    mutable: false,
+                }, // _1
+                LocalData {
+                    ty: slice_ty,
+                    mutable: true,
+                }, // _2
+            ]),
+            basic_blocks: IdxVec::from_raw(vec![bb0, bb1]),
+        })
+    });
+
+    println!("--- pair block param IR ---\n{}", ir);
+    assert!(
+        ir.contains("phi { ptr, i64 }"),
+        "Expected an aggregate phi for the wide pointer, got:\n{}",
+        ir
+    );
+}
+
+/// A `SwitchInt` passes the same block arguments to all its successors, so
+/// successors declaring different parameters are reported as an error
+/// instead of reaching codegen.
+#[test]
+fn pipeline_reports_mismatched_switch_block_args() {
+    let target = TirTarget::new(BackendKind::Llvm);
+    let args = TirArgs::default();
+    let arena = TirArena::default();
+    let intern_ctx = InternCtx::new(&arena);
+    let tir_ctx = TirCtx::new(&target, &args, &intern_ctx);
+
+    let i32_ty = tir_ctx.intern_ty(TirTy::<TirCtx>::I32);
+    let bool_ty = tir_ctx.intern_ty(TirTy::<TirCtx>::Bool);
+    let ret = |params| BasicBlockData {
+        params,
+        statements: vec![],
+        terminator: Terminator::Return,
+    };
+    // bb0: switch _1 -> [1: bb1, otherwise: bb2](7)
+    // bb1(_2: i32): return
+    // bb2: return
+    let body = TirBody {
+        metadata: main_metadata(DefId(0)),
+        ret_and_args: IdxVec::from_raw(vec![LocalData {
+            ty: i32_ty,
+            mutable: true,
+        }]),
+        arg_count: 0,
+        locals: IdxVec::from_raw(vec![
+            LocalData {
+                ty: bool_ty,
+                mutable: true,
+            },
+            LocalData {
+                ty: i32_ty,
+                mutable: false,
+            },
+        ]),
+        basic_blocks: IdxVec::from_raw(vec![
+            BasicBlockData {
+                params: vec![],
+                statements: vec![],
+                terminator: Terminator::SwitchInt {
+                    discr: Operand::Use(Place::from(Local::new(1))),
+                    targets: SwitchTargets::if_then(BasicBlock::new(1), BasicBlock::new(2)),
+                    block_args: vec![const_i32(&tir_ctx, 7)],
+                },
+            },
+            ret(vec![(Local::new(2), i32_ty)]),
+            ret(vec![]),
+        ]),
+    };
+
+    let result = llvm_codegen_to_ir_string(tir_ctx, single_body_unit(body));
+    assert!(
+        matches!(
+            result,
+            Err(CodegenError::InvalidBlockArgs(err)) if err.successor == BasicBlock::new(2)
+        ),
+        "Expected an invalid block arguments error, got: {:?}",
+        result
+    );
+}
+
+// ====================================================================
+// SIMD vectors
+// ====================================================================
+
+/// Binary operations on SIMD vectors lower to a single lane-wise vector
+/// instruction.
+///
+/// ```text
+/// fn main(_1: <4 x i32>, _2: <4 x i32>) -> <4 x i32> {
+///     _0 = _1 + _2;
+///     return;
+/// }
+/// ```
+#[test]
+fn pipeline_simd_add_4_x_i32() {
+    let ir = compile_to_ir(|ctx| {
+        let simd_ty = ctx.intern_ty(TirTy::<TirCtx>::Simd {
+            elem: Primitive::I32,
+            lanes: 4,
+        });
+        let arg = LocalData {
+            ty: simd_ty,
+            mutable: false,
+        };
+
+        single_body_unit(TirBody {
+            metadata: main_metadata(DefId(0)),
+            ret_and_args: IdxVec::from_raw(vec![arg.clone(), arg.clone(), arg]),
+            arg_count: 2,
+            locals: IdxVec::new(),
+            basic_blocks: IdxVec::from_raw(vec![BasicBlockData {
+                params: vec![],
+                statements: vec![Statement::Assign(Box::new((
+                    Place::from(RETURN_LOCAL),
+                    RValue::BinaryOp(
+                        BinaryOp::Add,
+                        Operand::Use(Place::from(Local::new(1))),
+                        Operand::Use(Place::from(Local::new(2))),
+                    ),
+                )))],
+                terminator: Terminator::Return,
+            }]),
+        })
+    });
+
+    println!("--- simd add IR ---\n{}", ir);
+    assert!(
+        ir.contains("add <4 x i32>"),
+        "Expected a vector `add <4 x i32>` instruction, got:\n{}",
+        ir
+    );
+}
+
+/// Build `fn main(_1: <4 x i32>, _2: <4 x i32>) -> ret { _0 = op(_1, _2) }`.
+fn simd_binop_body<'ctx>(
+    ctx: &TirCtx<'ctx>,
+    op: BinaryOp,
+    ret_ty: tidec_tir::TirTy<'ctx>,
+) -> TirUnit<'ctx> {
+    let simd_ty = ctx.intern_ty(TirTy::<TirCtx>::Simd {
+        elem: Primitive::I32,
+        lanes: 4,
+    });
+    let arg = LocalData {
+        ty: simd_ty,
+        mutable: false,
+    };
+    let ret = LocalData {
+        ty: ret_ty,
+        mutable: false,
+    };
+
+    single_body_unit(TirBody {
+        metadata: main_metadata(DefId(0)),
+        ret_and_args: IdxVec::from_raw(vec![ret, arg.clone(), arg]),
+        arg_count: 2,
+        locals: IdxVec::new(),
+        basic_blocks: IdxVec::from_raw(vec![BasicBlockData {
+            params: vec![],
+            statements: vec![Statement::Assign(Box::new((
+                Place::from(RETURN_LOCAL),
+                RValue::BinaryOp(
+                    op,
+                    Operand::Use(Place::from(Local::new(1))),
+                    Operand::Use(Place::from(Local::new(2))),
+                ),
+            )))],
+            terminator: Terminator::Return,
+        }]),
+    })
+}
+
+/// Saturating operations on SIMD vectors call the vector overload of the
+/// saturating intrinsic, which clamps each lane.
+#[test]
+fn pipeline_simd_saturating_add_4_x_i32() {
+    let ir = compile_to_ir(|ctx| {
+        let simd_ty = ctx.intern_ty(TirTy::<TirCtx>::Simd {
+            elem: Primitive::I32,
+            lanes: 4,
+        });
+        simd_binop_body(ctx, BinaryOp::SaturatingAdd, simd_ty)
+    });
+
+    println!("--- simd saturating add IR ---\n{}", ir);
+    assert!(
+        ir.contains("@llvm.sadd.sat.v4i32"),
+        "Expected a call to `llvm.sadd.sat.v4i32`, got:\n{}",
+        ir
+    );
+}
+
+/// Comparisons have no lane-wise form, so comparing SIMD vectors is
+/// reported as an error instead of reaching codegen.
+#[test]
+fn pipeline_reports_simd_comparison() {
+    let target = TirTarget::new(BackendKind::Llvm);
+    let args = TirArgs::default();
+    let arena = TirArena::default();
+    let intern_ctx = InternCtx::new(&arena);
+    let tir_ctx = TirCtx::new(&target, &args, &intern_ctx);
+
+    let bool_ty = tir_ctx.intern_ty(TirTy::<TirCtx>::Bool);
+    let unit = simd_binop_body(&tir_ctx, BinaryOp::Lt, bool_ty);
+
+    let result = llvm_codegen_to_ir_string(tir_ctx, unit);
+    assert!(
+        matches!(
+            result,
+            Err(CodegenError::UnsupportedSimdBinOp(ref err)) if err.op == BinaryOp::Lt
+        ),
+        "Expected an unsupported SIMD operation error, got: {:?}",
+        result
+    );
+}
+
+// ====================================================================
+// Assertions
+// ====================================================================
+
+/// A bounds check branches on the condition, and calls the bounds-check
+/// panic function with the index and the length when it fails.
+///
+/// ```text
+/// fn main(_1: u64) -> i32 {
+///     bb0: { _2 = Lt(_1, 4); assert(_2, true, bounds_check(len: 4, index: _1)) -> bb1; }
+///     bb1: { _0 = 7; return; }
+/// }
+/// ```
+#[test]
+fn pipeline_assert_bounds_check() {
+    let ir = compile_to_ir(|ctx| {
+        let i32_ty = ctx.intern_ty(TirTy::<TirCtx>::I32);
+        let u64_ty = ctx.intern_ty(TirTy::<TirCtx>::U64);
+        let bool_ty = ctx.intern_ty(TirTy::<TirCtx>::Bool);
+        let len = || {
+            Operand::Const(ConstOperand::Value(
+                ConstValue::Scalar(ConstScalar::Value(RawScalarValue {
+                    data: 4,
+                    size: NonZero::new(8).unwrap(),
+                })),
+                u64_ty,
+            ))
+        };
+
+        single_body_unit(TirBody {
+            metadata: main_metadata(DefId(0)),
+            ret_and_args: IdxVec::from_raw(vec![
+                LocalData {
+                    ty: i32_ty,
+                    mutable: false,
+                },
+                LocalData {
+                    ty: u64_ty,
+                    mutable: false,
+                },
+            ]),
+            arg_count: 1,
+            locals: IdxVec::from_raw(vec![LocalData {
+                ty: bool_ty,
+                mutable: false,
+            }]),
+            basic_blocks: IdxVec::from_raw(vec![
+                BasicBlockData {
+                    params: vec![],
+                    statements: vec![Statement::Assign(Box::new((
+                        Place::from(Local::new(2)),
+                        RValue::BinaryOp(
+                            BinaryOp::Lt,
+                            Operand::Use(Place::from(Local::new(1))),
+                            len(),
+                        ),
+                    )))],
+                    terminator: Terminator::Assert {
+                        cond: Operand::Use(Place::from(Local::new(2))),
+                        expected: true,
+                        target: BasicBlock::new(1),
+                        msg: AssertMessage::BoundsCheck {
+                            len: len(),
+                            index: Operand::Use(Place::from(Local::new(1))),
+                        },
+                        block_args: vec![],
+                    },
+                },
+                BasicBlockData {
+                    params: vec![],
+                    statements: vec![Statement::Assign(Box::new((
+                        Place::from(RETURN_LOCAL),
+                        RValue::Operand(const_i32(ctx, 7)),
+                    )))],
+                    terminator: Terminator::Return,
+                },
+            ]),
+        })
+    });
+
+    println!("--- assert bounds check IR ---\n{}", ir);
+    assert!(
+        ir.contains("br i1"),
+        "Expected a conditional branch on the check, got:\n{}",
+        ir
+    );
+    assert!(
+        ir.contains("call void @tidec_panic_bounds_check(i64 %"),
+        "Expected a call to the bounds-check panic function, got:\n{}",
+        ir
+    );
+    assert!(
+        ir.contains("declare void @tidec_panic_bounds_check(i64, i64)"),
+        "Expected the bounds-check panic function to be declared, got:\n{}",
+        ir
+    );
+}
+
+/// The bounds-check panic function takes `u64`s, so a `u32` index and
+/// length are zero-extended before the call.
+///
+/// ```text
+/// fn main(_1: u32) -> i32 {
+///     bb0: { _2 = Lt(_1, 4); assert(_2, true, bounds_check(len: 4, index: _1)) -> bb1; }
+///     bb1: { _0 = 7; return; }
+/// }
+/// ```
+#[test]
+fn pipeline_assert_bounds_check_zero_extends_u32_operands() {
+    let ir = compile_to_ir(|ctx| {
+        let i32_ty = ctx.intern_ty(TirTy::<TirCtx>::I32);
+        let u32_ty = ctx.intern_ty(TirTy::<TirCtx>::U32);
+        let bool_ty = ctx.intern_ty(TirTy::<TirCtx>::Bool);
+        let len = || {
+            Operand::Const(ConstOperand::Value(
+                ConstValue::Scalar(ConstScalar::Value(RawScalarValue {
+                    data: 4,
+                    size: NonZero::new(4).unwrap(),
+                })),
+                u32_ty,
+            ))
+        };
+
+        single_body_unit(TirBody {
+            metadata: main_metadata(DefId(0)),
+            ret_and_args: IdxVec::from_raw(vec![
+                LocalData {
+                    ty: i32_ty,
+                    mutable: false,
+                },
+                LocalData {
+                    ty: u32_ty,
+                    mutable: false,
+                },
+            ]),
+            arg_count: 1,
+            locals: IdxVec::from_raw(vec![LocalData {
+                ty: bool_ty,
+                mutable: false,
+            }]),
+            basic_blocks: IdxVec::from_raw(vec![
+                BasicBlockData {
+                    params: vec![],
+                    statements: vec![Statement::Assign(Box::new((
+                        Place::from(Local::new(2)),
+                        RValue::BinaryOp(
+                            BinaryOp::Lt,
+                            Operand::Use(Place::from(Local::new(1))),
+                            len(),
+                        ),
+                    )))],
+                    terminator: Terminator::Assert {
+                        cond: Operand::Use(Place::from(Local::new(2))),
+                        expected: true,
+                        target: BasicBlock::new(1),
+                        msg: AssertMessage::BoundsCheck {
+                            len: len(),
+                            index: Operand::Use(Place::from(Local::new(1))),
+                        },
+                        block_args: vec![],
+                    },
+                },
+                BasicBlockData {
+                    params: vec![],
+                    statements: vec![Statement::Assign(Box::new((
+                        Place::from(RETURN_LOCAL),
+                        RValue::Operand(const_i32(ctx, 7)),
+                    )))],
+                    terminator: Terminator::Return,
+                },
+            ]),
+        })
+    });
+
+    println!("--- assert bounds check u32 IR ---\n{}", ir);
+    assert!(
+        ir.contains("zext i32 %"),
+        "Expected the u32 index to be zero-extended, got:\n{}",
+        ir
+    );
+    assert!(
+        ir.contains("call void @tidec_panic_bounds_check(i64 %zext, i64 4)"),
+        "Expected the bounds-check panic function to receive i64s, got:\n{}",
+        ir
+    );
+    assert!(
+        !ir.contains("@tidec_panic_bounds_check(i32"),
+        "The bounds-check panic function must not receive i32s, got:\n{}",
+        ir
+    );
+}
+
+/// An overflow assertion that expects `false` branches to the shared
+/// overflow-panic block when the condition holds.
+#[test]
+fn pipeline_assert_overflow() {
+    let ir = compile_to_ir(|ctx| {
+        let i32_ty = ctx.intern_ty(TirTy::<TirCtx>::I32);
+        let bool_ty = ctx.intern_ty(TirTy::<TirCtx>::Bool);
+
+        single_body_unit(TirBody {
+            metadata: main_metadata(DefId(0)),
+            ret_and_args: IdxVec::from_raw(vec![
+                LocalData {
+                    ty: i32_ty,
+                    mutable: false,
+                },
+                LocalData {
+                    ty: bool_ty,
+                    mutable: false,
+                },
+            ]),
+            arg_count: 1,
+            locals: IdxVec::new(),
+            basic_blocks: IdxVec::from_raw(vec![
+                BasicBlockData {
+                    params: vec![],
+                    statements: vec![],
+                    terminator: Terminator::Assert {
+                        cond: Operand::Use(Place::from(Local::new(1))),
+                        expected: false,
+                        target: BasicBlock::new(1),
+                        msg: AssertMessage::Overflow,
+                        block_args: vec![],
+                    },
+                },
+                BasicBlockData {
+                    params: vec![],
+                    statements: vec![Statement::Assign(Box::new((
+                        Place::from(RETURN_LOCAL),
+                        RValue::Operand(const_i32(ctx, 7)),
+                    )))],
+                    terminator: Terminator::Return,
+                },
+            ]),
+        })
+    });
+
+    println!("--- assert overflow IR ---\n{}", ir);
+    assert!(
+        ir.contains("label %panic, label %"),
+        "Expected a branch to the panic block when the condition holds, got:\n{}",
+        ir
+    );
+    assert!(
+        ir.contains("call void @tidec_panic_overflow()"),
+        "Expected a call to the overflow panic function, got:\n{}",
+        ir
+    );
+}
+
+/// Zero-initialize a struct local with `ConstValue::ZeroInit`.
+///
+/// ```text
+/// fn main() -> i32 {
+///     _1: { i32, i64, i32 } = const ZeroInit;
+///     _0 = _1.0;
+///     return;
+/// }
+/// ```
+#[test]
+fn pipeline_zero_init_struct() {
+    let ir = compile_to_ir(|ctx| {
+        let i32_ty = ctx.intern_ty(TirTy::<TirCtx>::I32);
+        let i64_ty = ctx.intern_ty(TirTy::<TirCtx>::I64);
+        let fields = ctx.intern_type_list(&[i32_ty, i64_ty, i32_ty]);
+        let struct_ty = ctx.intern_ty(TirTy::<TirCtx>::Struct {
+            fields,
+            packed: false,
+        });
+
+        single_body_unit(TirBody {
+            metadata: main_metadata(DefId(0)),
+            ret_and_args: IdxVec::from_raw(vec![LocalData {
+                ty: i32_ty,
+                mutable: false,
+            }]),
+            arg_count: 0,
+            locals: IdxVec::from_raw(vec![LocalData {
+                ty: struct_ty,
+                mutable: true,
+            }]),
+            basic_blocks: IdxVec::from_raw(vec![BasicBlockData {
+                params: vec![],
+                statements: vec![
+                    Statement::Assign(Box::new((
+                        Place::from(Local::new(1)),
+                        RValue::Operand(Operand::Const(ConstOperand::Value(
+                            ConstValue::ZeroInit,
+                            struct_ty,
+                        ))),
+                    ))),
+                    Statement::Assign(Box::new((
+                        Place::from(RETURN_LOCAL),
+                        RValue::Operand(Operand::Use(Place {
+                            local: Local::new(1),
+                            projection: ctx
+                                .intern_projection(&[Projection::Field(FieldIdx::new(0), i32_ty)]),
+                        })),
+                    ))),
+                ],
+                terminator: Terminator::Return,
+            }]),
+        })
+    });
+
+    println!("--- zero-init struct IR ---\n{}", ir);
+    assert!(
+        ir.contains("zeroinitializer"),
+        "Expected the struct local to be zero-initialized, got:\n{}",
+        ir
+    );
+}
+
+/// Emit a trivial `nop` through `RValue::InlineAsm`.
+///
+/// ```text
+/// fn main() -> i32 {
+///     _1: () = asm!("nop", clobbers: ["memory"]);
+///     _0 = 0;
+///     return;
+/// }
+/// ```
+#[test]
+fn pipeline_inline_asm_nop() {
+    let ir = compile_to_ir(|ctx| {
+        let i32_ty = ctx.intern_ty(TirTy::<TirCtx>::I32);
+        let unit_ty = ctx.intern_ty(TirTy::<TirCtx>::Unit);
+
+        single_body_unit(TirBody {
+            metadata: main_metadata(DefId(0)),
+            ret_and_args: IdxVec::from_raw(vec![LocalData {
+                ty: i32_ty,
+                mutable: false,
+            }]),
+            arg_count: 0,
+            locals: IdxVec::from_raw(vec![LocalData {
+                ty: unit_ty,
+                mutable: false,
+            }]),
+            basic_blocks: IdxVec::from_raw(vec![BasicBlockData {
+                params: vec![],
+                statements: vec![
+                    Statement::Assign(Box::new((
+                        Place::from(Local::new(1)),
+                        RValue::InlineAsm {
+                            template: "nop".to_string(),
+                            operands: vec![],
+                            clobbers: vec!["memory".to_string()],
+                        },
+                    ))),
+                    Statement::Assign(Box::new((
+                        Place::from(RETURN_LOCAL),
+                        RValue::Operand(const_i32(ctx, 0)),
+                    ))),
+                ],
+                terminator: Terminator::Return,
+            }]),
+        })
+    });
+
+    println!("--- inline asm IR ---\n{}", ir);
+    assert!(
+        ir.contains(r#"call void asm sideeffect "nop", "~{memory}"()"#),
+        "Expected a call to the `nop` inline assembly, got:\n{}",
+        ir
+    );
+}
+
+/// Inline assembly operands are bound to registers, so a scalar pair operand
+/// is reported as an error instead of reaching codegen.
+///
+/// ```text
+/// fn main(_1: { i64, i64 }) -> i32 {
+///     _2: () = asm!("", _1);
+///     _0 = 0;
+///     return;
+/// }
+/// ```
+#[test]
+fn pipeline_reports_non_immediate_inline_asm_operand() {
+    let target = TirTarget::new(BackendKind::Llvm);
+    let args = TirArgs::default();
+    let arena = TirArena::default();
+    let intern_ctx = InternCtx::new(&arena);
+    let tir_ctx = TirCtx::new(&target, &args, &intern_ctx);
+
+    let i32_ty = tir_ctx.intern_ty(TirTy::<TirCtx>::I32);
+    let i64_ty = tir_ctx.intern_ty(TirTy::<TirCtx>::I64);
+    let unit_ty = tir_ctx.intern_ty(TirTy::<TirCtx>::Unit);
+    let fields = tir_ctx.intern_type_list(&[i64_ty, i64_ty]);
+    let pair_ty = tir_ctx.intern_ty(TirTy::<TirCtx>::Struct {
+        fields,
+        packed: false,
+    });
+
+    let unit = single_body_unit(TirBody {
+        metadata: main_metadata(DefId(0)),
+        ret_and_args: IdxVec::from_raw(vec![
+            LocalData {
+                ty: i32_ty,
+                mutable: false,
+            },
+            LocalData {
+                ty: pair_ty,
+                mutable: false,
+            },
+        ]),
+        arg_count: 1,
+        locals: IdxVec::from_raw(vec![LocalData {
+            ty: unit_ty,
+            mutable: false,
+        }]),
+        basic_blocks: IdxVec::from_raw(vec![BasicBlockData {
+            params: vec![],
+            statements: vec![
+                Statement::Assign(Box::new((
+                    Place::from(Local::new(2)),
+                    RValue::InlineAsm {
+                        template: "".to_string(),
+                        operands: vec![Operand::Use(Place::from(Local::new(1)))],
+                        clobbers: vec![],
+                    },
+                ))),
+                Statement::Assign(Box::new((
+                    Place::from(RETURN_LOCAL),
+                    RValue::Operand(const_i32(&tir_ctx, 0)),
+                ))),
+            ],
+            terminator: Terminator::Return,
+        }]),
+    });
+
+    let result = llvm_codegen_to_ir_string(tir_ctx, unit);
+    assert!(
+        matches!(
+            result,
+            Err(CodegenError::UnsupportedInlineAsmOperand(ref err))
+                if err.operand_index == 0 && err.ty == pair_ty
+        ),
+        "Expected an unsupported inline assembly operand error, got: {:?}",
+        result
+    );
+}
+
+/// A private function marked as the `main` entry point is emitted as an
+/// externally visible `main`, whatever its TIR name and linkage.
+#[test]
+fn pipeline_entry_point_symbol_and_linkage() {
+    let ir = compile_to_ir(|ctx| {
+        let i32_ty = ctx.intern_ty(TirTy::<TirCtx>::I32);
+        let mut metadata = TirBodyMetadata::function(DefId(0), "tide_entry");
+        metadata.linkage = Linkage::Private;
+        metadata.entry_point = Some(EntryKind::Main);
+
+        single_body_unit(TirBody {
+            metadata,
+            ret_and_args: IdxVec::from_raw(vec![LocalData {
+                ty: i32_ty,
+                mutable: false,
+            }]),
+            arg_count: 0,
+            locals: IdxVec::new(),
+            basic_blocks: IdxVec::from_raw(vec![BasicBlockData {
+                params: vec![],
+                statements: vec![Statement::Assign(Box::new((
+                    Place::from(RETURN_LOCAL),
+                    RValue::Operand(const_i32(ctx, 0)),
+                )))],
+                terminator: Terminator::Return,
+            }]),
+        })
+    });
+
+    println!("--- entry point IR ---\n{}", ir);
+    assert!(
+        ir.contains("define i32 @main()"),
+        "Expected the entry point to be emitted as an external `main`, got:\n{}",
+        ir
+    );
+    assert!(
+        !ir.contains("tide_entry"),
+        "The TIR name of the entry point should not be emitted, got:\n{}",
+        ir
+    );
+}
+
+/// `TirArgs::output_path` overrides the path derived from the unit name, and
+/// `llvm_codegen_lir_unit` reports where the output landed.
+#[test]
+fn pipeline_emits_to_output_path_override() {
+    let path = std::env::temp_dir().join(format!("tidec_output_path_{}.ll", std::process::id()));
+    let target = TirTarget::new(BackendKind::Llvm);
+    let args = TirArgs {
+        emit_kind: EmitKind::LlvmIr,
+        verify_module: true,
+        output_path: Some(path.clone()),
+        ..Default::default()
+    };
+    let arena = TirArena::default();
+    let intern_ctx = InternCtx::new(&arena);
+    let tir_ctx = TirCtx::new(&target, &args, &intern_ctx);
+
+    let i32_ty = tir_ctx.intern_ty(TirTy::<TirCtx>::I32);
+    let body = TirBody {
+        metadata: main_metadata(DefId(0)),
+        ret_and_args: IdxVec::from_raw(vec![LocalData {
+            ty: i32_ty,
+            mutable: false,
+        }]),
+        arg_count: 0,
+        locals: IdxVec::new(),
+        basic_blocks: IdxVec::from_raw(vec![BasicBlockData {
+            params: vec![],
+            statements: vec![Statement::Assign(Box::new((
+                Place::from(RETURN_LOCAL),
+                RValue::Operand(const_i32(&tir_ctx, 0)),
+            )))],
+            terminator: Terminator::Return,
+        }]),
+    };
+
+    let emitted = llvm_codegen_lir_unit(tir_ctx, single_body_unit(body)).expect("codegen failed");
+    let ir = std::fs::read_to_string(&emitted).unwrap();
+    std::fs::remove_file(&emitted).unwrap();
+
+    assert_eq!(emitted, path);
+    assert!(ir.contains("define i32 @main()"), "IR:\n{}", ir);
+}
+
+/// `TirArgs::target_features` become a `"target-features"` attribute on the
+/// emitted functions, and `TirArgs::module_flags` become module flags.
+#[test]
+fn pipeline_applies_target_features_and_module_flags() {
+    let target = TirTarget::new(BackendKind::Llvm);
+    let args = TirArgs {
+        target_features: vec!["+sse4.2".to_string(), "+popcnt".to_string()],
+        module_flags: vec![
+            ModuleFlag::new("PIC Level", 2),
+            ModuleFlag {
+                behavior: ModuleFlagBehavior::Override,
+                ..ModuleFlag::new("PIE Level", 2)
+            },
+        ],
+        ..Default::default()
+    };
+    let arena = TirArena::default();
+    let intern_ctx = InternCtx::new(&arena);
+    let tir_ctx = TirCtx::new(&target, &args, &intern_ctx);
+
+    let i32_ty = tir_ctx.intern_ty(TirTy::<TirCtx>::I32);
+    let body = TirBody {
+        metadata: main_metadata(DefId(0)),
+        ret_and_args: IdxVec::from_raw(vec![LocalData {
+            ty: i32_ty,
+            mutable: false,
+        }]),
+        arg_count: 0,
+        locals: IdxVec::new(),
+        basic_blocks: IdxVec::from_raw(vec![BasicBlockData {
+            params: vec![],
+            statements: vec![Statement::Assign(Box::new((
+                Place::from(RETURN_LOCAL),
+                RValue::Operand(const_i32(&tir_ctx, 0)),
+            )))],
+            terminator: Terminator::Return,
+        }]),
+    };
+
+    let ir = llvm_codegen_to_ir_string(tir_ctx, single_body_unit(body)).expect("codegen failed");
+    println!("--- target features IR ---\n{}", ir);
+
+    assert!(
+        ir.contains("\"target-features\"=\"+sse4.2,+popcnt\""),
+        "IR:\n{}",
+        ir
+    );
+    assert!(
+        ir.contains("!{i32 1, !\"PIC Level\", i32 2}"),
+        "IR:\n{}",
+        ir
+    );
+    // `Override` is behavior 4 in the module flag metadata.
+    assert!(
+        ir.contains("!{i32 4, !\"PIE Level\", i32 2}"),
+        "IR:\n{}",
+        ir
+    );
+}
+
+/// A `ConstValue::Slice` puts its bytes in a private global array and
+/// materializes the `{ ptr, len }` wide pointer to them.
+#[test]
+fn pipeline_const_slice_builds_wide_pointer() {
+    let ir = compile_to_ir(|ctx| {
+        let i32_ty = ctx.intern_ty(TirTy::<TirCtx>::I32);
+        let u8_ty = ctx.intern_ty(TirTy::<TirCtx>::U8);
+        let usize_ty = ctx.intern_ty(TirTy::<TirCtx>::Usize);
+        let ptr_ty = ctx.intern_ty(TirTy::<TirCtx>::RawPtr(
+            u8_ty,
+            Mutability::Imm,
+            AddressSpace::DATA,
+        ));
+        let slice_ty = ctx.intern_ty(TirTy::<TirCtx>::Struct {
+            fields: ctx.intern_type_list(&[ptr_ty, usize_ty]),
+            packed: false,
+        });
+
+        single_body_unit(TirBody {
+            metadata: main_metadata(DefId(0)),
+            ret_and_args: IdxVec::from_raw(vec![LocalData {
+                ty: i32_ty,
+                mutable: false,
+            }]),
+            arg_count: 0,
+            locals: IdxVec::from_raw(vec![LocalData {
+                ty: slice_ty,
+                mutable: false,
+            }]),
+            basic_blocks: IdxVec::from_raw(vec![BasicBlockData {
+                params: vec![],
+                statements: vec![
+                    // _1 = const b"hi!"
+                    Statement::Assign(Box::new((
+                        Place::from(Local::new(1)),
+                        RValue::Operand(Operand::Const(ConstOperand::Value(
+                            ConstValue::Slice {
+                                data: b"hi!".to_vec(),
+                            },
+                            slice_ty,
+                        ))),
+                    ))),
+                    Statement::Assign(Box::new((
+                        Place::from(RETURN_LOCAL),
+                        RValue::Operand(const_i32(ctx, 0)),
+                    ))),
+                ],
+                terminator: Terminator::Return,
+            }]),
+        })
+    });
+
+    println!("--- const slice IR ---\n{}", ir);
+    assert!(
+        ir.contains("private unnamed_addr constant [3 x i8] c\"hi!\""),
+        "IR:\n{}",
+        ir
+    );
+    assert!(ir.contains("alloca [16 x i8]"), "IR:\n{}", ir);
+    assert!(ir.contains("store ptr @const_data"), "IR:\n{}", ir);
+    assert!(ir.contains("store i64 3"), "IR:\n{}", ir);
+}
+
+// ====================================================================
+// Enums
+// ====================================================================
+
+/// An enum lowers to its tag followed by bytes for the largest payload, so
+/// that its LLVM type has the size of its layout.
+#[test]
+fn enum_lowers_to_its_tag_and_payload_bytes() {
+    let target = TirTarget::new(BackendKind::Llvm);
+    let args = TirArgs::default();
+    let arena = TirArena::default();
+    let intern_ctx = InternCtx::new(&arena);
+    let tir_ctx = TirCtx::new(&target, &args, &intern_ctx);
+    let ll_context = Context::create();
+    let ll_module = ll_context.create_module("test");
+    let ctx = CodegenCtx::new(tir_ctx, &ll_context, ll_module);
+
+    // enum { None, Some(i32) }: a `u8` tag, then the `i32` at offset 4.
+    let unit_ty = tir_ctx.intern_ty(TirTy::<TirCtx>::Unit);
+    let i32_ty = tir_ctx.intern_ty(TirTy::<TirCtx>::I32);
+    let enum_ty = tir_ctx.intern_ty(TirTy::<TirCtx>::Enum {
+        variants: tir_ctx.intern_type_list(&[unit_ty, i32_ty]),
+    });
+    let llty = enum_ty.into_basic_type(&ctx);
+    assert_eq!(llty.print_to_string().to_string(), "{ i8, [7 x i8] }");
+
+    std::mem::forget(ctx);
+    std::mem::forget(ll_context);
+}
+
+/// Read the discriminant of a two-variant enum behind a pointer and switch
+/// on it, returning the payload of the `Some` variant.
+///
+/// The single arm on the `u8` discriminant must lower to a `switch`: only a
+/// `Bool` discriminant takes the conditional branch fast path, even though
+/// `u8` shares its layout.
+///
+/// ```text
+/// fn main(_1: *imm enum { None, Some(i32) }) -> i32 {
+/// bb0:
+///     _2 = discriminant((*_1));
+///     switchInt(_2) -> [1: bb1, otherwise: bb2];
+/// bb1:
+///     _0 = ((*_1) as Some);
+///     return;
+/// bb2:
+///     _0 = 0;
+///     return;
+/// }
+/// ```
+#[test]
+fn pipeline_switch_on_enum_discriminant() {
+    let ir = compile_to_ir(|ctx| {
+        let unit_ty = ctx.intern_ty(TirTy::<TirCtx>::Unit);
+        let i32_ty = ctx.intern_ty(TirTy::<TirCtx>::I32);
+        let u8_ty = ctx.intern_ty(TirTy::<TirCtx>::U8);
+        let enum_ty = ctx.intern_ty(TirTy::<TirCtx>::Enum {
+            variants: ctx.intern_type_list(&[unit_ty, i32_ty]),
+        });
+        let ptr_ty = ctx.intern_ty(TirTy::<TirCtx>::RawPtr(
+            enum_ty,
+            Mutability::Imm,
+            AddressSpace::DATA,
+        ));
+
+        let ret = |value| BasicBlockData {
+            params: vec![],
+            statements: vec![Statement::Assign(Box::new((
+                Place::from(RETURN_LOCAL),
+                value,
+            )))],
+            terminator: Terminator::Return,
+        };
+
+        single_body_unit(TirBody {
+            metadata: main_metadata(DefId(0)),
+            ret_and_args: IdxVec::from_raw(vec![
+                LocalData {
+                    ty: i32_ty,
+                    mutable: true,
+                },
+                LocalData {
+                    ty: ptr_ty,
+                    mutable: true,
+                },
+            ]),
+            arg_count: 1,
+            locals: IdxVec::from_raw(vec![LocalData {
+                ty: u8_ty,
+                mutable: false,
+            }]),
+            basic_blocks: IdxVec::from_raw(vec![
+                BasicBlockData {
+                    params: vec![],
+                    statements: vec![Statement::Assign(Box::new((
+                        Place::from(Local::new(2)),
+                        RValue::Discriminant(Place {
+                            local: Local::new(1),
+                            projection: ctx.intern_projection(&[Projection::Deref]),
+                        }),
+                    )))],
+                    terminator: Terminator::SwitchInt {
+                        discr: Operand::Use(Place::from(Local::new(2))),
+                        targets: SwitchTargets::new(
+                            vec![(1, BasicBlock::new(1))],
+                            BasicBlock::new(2),
+                        ),
+                        block_args: vec![],
+                    },
+                },
+                ret(RValue::Operand(Operand::Use(Place {
+                    local: Local::new(1),
+                    projection: ctx
+                        .intern_projection(&[Projection::Deref, Projection::Downcast(1)]),
+                }))),
+                ret(RValue::Operand(const_i32(ctx, 0))),
+            ]),
+        })
+    });
+
+    println!("--- enum discriminant switch IR ---\n{}", ir);
+    assert!(
+        ir.contains("load i8, ptr"),
+        "Expected the tag to be loaded as an i8, got:\n{}",
+        ir
+    );
+    assert!(
+        ir.contains("switch i8 %"),
+        "Expected a switch on the loaded discriminant, got:\n{}",
+        ir
+    );
+    assert!(
+        ir.contains("i8 1, label"),
+        "Expected a case for the `Some` variant, got:\n{}",
+        ir
+    );
+    assert!(
+        !ir.contains("br i1"),
+        "A u8 discriminant must not take the Bool fast path, got:\n{}",
+        ir
+    );
+    assert!(
+        ir.contains("%variant1 = getelementptr inbounds i8, ptr") && ir.contains("i64 4"),
+        "Expected the payload to be reached past the tag, got:\n{}",
+        ir
+    );
+    assert!(
+        ir.contains("load i32, ptr %variant1"),
+        "Expected the payload to be loaded as an i32, got:\n{}",
+        ir
+    );
+}
+
+#[test]
+fn pipeline_set_discriminant_stores_the_tag() {
+    let ir = compile_to_ir(|ctx| {
+        let unit_ty = ctx.intern_ty(TirTy::<TirCtx>::Unit);
+        let i32_ty = ctx.intern_ty(TirTy::<TirCtx>::I32);
+        let enum_ty = ctx.intern_ty(TirTy::<TirCtx>::Enum {
+            variants: ctx.intern_type_list(&[unit_ty, i32_ty]),
+        });
+        let payload = Place {
+            local: Local::new(1),
+            projection: ctx.intern_projection(&[Projection::Downcast(1)]),
+        };
+
+        // _1 = Some(7); _0 = (_1 as Some)
+        single_body_unit(TirBody {
+            metadata: main_metadata(DefId(0)),
+            ret_and_args: IdxVec::from_raw(vec![LocalData {
+                ty: i32_ty,
+                mutable: true,
+            }]),
+            arg_count: 0,
+            locals: IdxVec::from_raw(vec![LocalData {
+                ty: enum_ty,
+                mutable: true,
+            }]),
+            basic_blocks: IdxVec::from_raw(vec![BasicBlockData {
+                params: vec![],
+                statements: vec![
+                    Statement::Assign(Box::new((payload, RValue::Operand(const_i32(ctx, 7))))),
+                    Statement::SetDiscriminant {
+                        place: Place::from(Local::new(1)),
+                        variant: 1,
+                    },
+                    Statement::Assign(Box::new((
+                        Place::from(RETURN_LOCAL),
+                        RValue::Operand(Operand::Use(payload)),
+                    ))),
+                ],
+                terminator: Terminator::Return,
+            }]),
+        })
+    });
+
+    println!("--- enum set discriminant IR ---\n{}", ir);
+    assert!(
+        ir.contains("store i8 1, ptr"),
+        "Expected the `Some` tag to be stored as an i8, got:\n{}",
+        ir
+    );
+    assert!(
+        ir.contains("store i32 7, ptr %variant1"),
+        "Expected the payload to be stored past the tag, got:\n{}",
+        ir
+    );
+}
@@ -1,14 +1,22 @@
+use std::num::NonZero;
+
 use crate::{
     tir::{OperandVal, PlaceRef},
     traits::{BackendTypeOf, CodegenMethods, FnAbiOf, LayoutOf},
 };
-use tidec_abi::{calling_convention::function::PassMode, layout::TyAndLayout};
+use tidec_abi::{
+    calling_convention::function::{PassMode, classify_arg},
+    layout::{BackendRepr, TyAndLayout, Variants},
+    size_and_align::Size,
+    target::AddressSpace,
+};
 use tidec_tir::{
     TirTy,
     body::TirBody,
     syntax::{
-        AggregateKind, BasicBlock, BasicBlockData, BinaryOp, CastKind, Local, Operand, Place,
-        Projection, RETURN_LOCAL, RValue, Statement, SwitchTargets, Terminator, UnaryOp,
+        AggregateKind, AssertMessage, BasicBlock, BasicBlockData, BinaryOp, CastKind, ConstScalar,
+        ENTRY_BLOCK, FieldIdx, Local, Operand, Place, Projection, RETURN_LOCAL, RValue,
+        RawScalarValue, Statement, SwitchTargets, Terminator, UnaryOp,
     },
 };
 use tidec_utils::idx::Idx;
@@ -39,21 +47,19 @@ pub struct FnCtx<'be, 'ctx, B: BuilderMethods<'be, 'ctx>> {
     /// A cache of the basic blocks in the function.
     /// This is also used to avoid creating multiple basic blocks for the same TIR basic block.
     pub cached_bbs: IdxVec<BasicBlock, Option<B::BasicBlock>>,
+
+    /// The phi nodes lowering the parameters of each basic block, created on
+    /// the first jump to (or codegen of) the block.
+    ///
+    /// There is one entry per parameter; zero-sized parameters have no phi.
+    pub block_phis: IdxVec<BasicBlock, Option<Vec<Option<B::Value>>>>,
 }
 
 impl<'ll, 'ctx, B: BuilderMethods<'ll, 'ctx>> FnCtx<'ll, 'ctx, B> {
     /// Get the type of a local variable by its index.
     /// This handles locals that are in either `ret_and_args` or `locals`.
     fn local_ty(&self, local: Local) -> tidec_tir::TirTy<'ctx> {
-        let ret_and_args_len = self.lir_body.ret_and_args.len();
-        let local_idx = local.idx();
-        if local_idx < ret_and_args_len {
-            self.lir_body.ret_and_args[local].ty
-        } else {
-            // Adjust index to account for ret_and_args
-            let adjusted_idx = local_idx - ret_and_args_len;
-            self.lir_body.locals[Local::new(adjusted_idx)].ty
-        }
+        self.lir_body.local_ty(local)
     }
 
     /// Codegen the given TIR basic block.
@@ -65,6 +71,7 @@ impl<'ll, 'ctx, B: BuilderMethods<'ll, 'ctx>> FnCtx<'ll, 'ctx, B> {
         let builder = &mut B::build(self.ctx, be_bb);
         let bb_data: BasicBlockData<'ctx> = self.lir_body.basic_blocks[bb].clone();
         debug!("Codegen basic block {:?}: {:?}", bb, bb_data);
+        self.codegen_block_params(builder, bb, &bb_data);
         for stmt in &bb_data.statements {
             self.codegen_statement(builder, stmt);
         }
@@ -84,6 +91,121 @@ impl<'ll, 'ctx, B: BuilderMethods<'ll, 'ctx>> FnCtx<'ll, 'ctx, B> {
         be_bb
     }
 
+    /// Get the phi nodes of the parameters of the given TIR basic block.
+    /// If they do not exist, create them at the start of the (still empty)
+    /// backend basic block and cache them.
+    fn get_or_insert_block_phis(&mut self, bb: BasicBlock) -> Vec<Option<B::Value>> {
+        if let Some(phis) = &self.block_phis[bb] {
+            return phis.clone();
+        }
+
+        let be_bb = self.get_or_insert_bb(bb);
+        let mut builder = B::build(self.ctx, be_bb);
+        let phis: Vec<_> = self.lir_body.basic_blocks[bb]
+            .params
+            .iter()
+            .map(|(_, ty)| {
+                let layout = self.ctx.layout_of(*ty);
+                (!layout.is_zst()).then(|| builder.build_phi(self.ctx.backend_type_of(*ty)))
+            })
+            .collect();
+        self.block_phis[bb] = Some(phis.clone());
+        phis
+    }
+
+    /// Bind the parameters of the given TIR basic block to their phi nodes.
+    /// Parameters living in memory get the phi stored into their place; the
+    /// others become the phi value itself, split back into its two halves
+    /// for pairs.
+    fn codegen_block_params(
+        &mut self,
+        builder: &mut B,
+        bb: BasicBlock,
+        bb_data: &BasicBlockData<'ctx>,
+    ) {
+        if bb_data.params.is_empty() {
+            return;
+        }
+        assert_ne!(bb, ENTRY_BLOCK, "The entry block cannot have parameters");
+
+        let phis = self.get_or_insert_block_phis(bb);
+        for ((local, ty), phi) in bb_data.params.iter().zip(phis) {
+            let Some(phi) = phi else {
+                // Zero-sized parameters carry no value.
+                continue;
+            };
+            match &self.locals[*local] {
+                LocalRef::PlaceRef(place_ref) => {
+                    builder.build_store(phi, place_ref.place_val.value, place_ref.place_val.align);
+                }
+                _ => {
+                    let layout = self.ctx.layout_of(*ty);
+                    let operand_ref = if let BackendRepr::ScalarPair(_, _) = layout.backend_repr {
+                        let a = builder.build_extract_value(phi, 0, "pair0");
+                        let b = builder.build_extract_value(phi, 1, "pair1");
+                        OperandRef {
+                            operand_val: OperandVal::Pair(a, b),
+                            ty_layout: layout,
+                        }
+                    } else {
+                        OperandRef::new_immediate(phi, layout)
+                    };
+                    self.overwrite_local(*local, LocalRef::OperandRef(operand_ref));
+                }
+            }
+        }
+    }
+
+    /// Codegen the block arguments of a terminator, adding them as incoming
+    /// values to the phi nodes of every successor.
+    ///
+    /// The incoming edge is taken from the block the builder is currently in,
+    /// so this must be called right before the branch is emitted. A successor
+    /// reached along several edges gets one incoming value per edge.
+    fn codegen_block_args(
+        &mut self,
+        builder: &mut B,
+        block_args: &[Operand<'ctx>],
+        successors: &[BasicBlock],
+    ) {
+        let arg_vals: Vec<Option<B::Value>> = block_args
+            .iter()
+            .map(|arg| {
+                let arg_ref = self.codegen_operand(builder, arg);
+                match arg_ref.operand_val {
+                    OperandVal::Zst => None,
+                    OperandVal::Immediate(val) => Some(val),
+                    OperandVal::Ref(place_val) => {
+                        let llty = builder.ctx().backend_type_of(arg_ref.ty_layout.ty);
+                        Some(builder.build_load(llty, place_val.value, place_val.align))
+                    }
+                    // Pairs are passed as a single aggregate phi.
+                    OperandVal::Pair(a, b) => {
+                        Some(self.pair_to_aggregate(builder, a, b, arg_ref.ty_layout))
+                    }
+                }
+            })
+            .collect();
+        let from_bb = builder.current_block();
+
+        for &succ in successors {
+            let phis = self.get_or_insert_block_phis(succ);
+            assert_eq!(
+                phis.len(),
+                arg_vals.len(),
+                "Block {:?} expects {} arguments, but {} were passed",
+                succ,
+                phis.len(),
+                arg_vals.len()
+            );
+            for (phi, val) in phis.into_iter().zip(&arg_vals) {
+                if let (Some(phi), Some(val)) = (phi, val) {
+                    builder.add_phi_incoming(phi, *val, from_bb);
+                }
+            }
+        }
+    }
+
     #[instrument(level = "debug", skip(self, builder))]
     /// Codegen the given TIR statement.
     /// This function is called by `codegen_basic_block` for each statement in the basic block.
@@ -150,11 +272,39 @@ impl<'ll, 'ctx, B: BuilderMethods<'ll, 'ctx>> FnCtx<'ll, 'ctx, B> {
                     None => {
                         // The place has projections — we need to compute the
                         // effective address and store the rvalue there.
-                        let place_ref = self.codegen_place(builder, place);
+                        let place_ref = self.codegen_place(builder, *place);
                         self.codegen_rvalue(builder, place_ref, rvalue);
                     }
                 }
             }
+            Statement::SetDiscriminant { place, variant } => {
+                // The tag sits at offset 0 of the enum and holds the index of
+                // the active variant, so setting it is a store of the index.
+                debug!("SetDiscriminant of {:?} to variant {}", place, variant);
+                let place_ref = self.codegen_place(builder, *place);
+                let enum_ty = place_ref.ty_layout.ty;
+                let variant_count = match &*enum_ty.0 {
+                    tidec_tir::ty::TirTy::Enum { variants } => variants.as_slice().len(),
+                    _ => panic!(
+                        "SetDiscriminant of {place:?}, whose type {enum_ty:?} is not an enum"
+                    ),
+                };
+                assert!(
+                    (*variant as usize) < variant_count,
+                    "SetDiscriminant of {place:?} to variant {variant}, but {enum_ty:?} has {variant_count} variants"
+                );
+
+                let ctx = builder.ctx();
+                let tag_layout = ctx.layout_of(ctx.tir_ctx().discriminant_ty(enum_ty));
+                let tag = builder.const_scalar_to_backend_value(
+                    ConstScalar::Value(RawScalarValue {
+                        data: u128::from(*variant),
+                        size: NonZero::new(tag_layout.size.bytes() as u8).unwrap(),
+                    }),
+                    tag_layout,
+                );
+                builder.build_store(tag, place_ref.place_val.value, place_ref.place_val.align);
+            }
         }
     }
 
@@ -187,9 +337,7 @@ impl<'ll, 'ctx, B: BuilderMethods<'ll, 'ctx>> FnCtx<'ll, 'ctx, B> {
             OperandVal::Zst => {
                 // Zero-sized types have no bytes to store — nothing to do.
             }
-            OperandVal::Pair(_a, _b) => {
-                todo!("Handle storing scalar pair into a place (e.g., fat pointers)")
-            }
+            OperandVal::Pair(a, b) => self.store_pair(builder, a, b, place_ref),
             OperandVal::Ref(src_place_val) => {
                 // The source is a memory-backed value. We need to copy
                 // `size` bytes from the source location to the destination.
@@ -205,10 +353,93 @@ impl<'ll, 'ctx, B: BuilderMethods<'ll, 'ctx>> FnCtx<'ll, 'ctx, B> {
         }
     }
 
+    /// Store the two halves of a pair operand (e.g. the data pointer and the
+    /// length of a wide pointer) into `place_ref`, each at the offset of its
+    /// field.
+    fn store_pair(
+        &self,
+        builder: &mut B,
+        a: B::Value,
+        b: B::Value,
+        place_ref: PlaceRef<'ctx, B::Value>,
+    ) {
+        let tir_ctx = builder.ctx().tir_ctx();
+        let b_layout = tir_ctx.field_layout_of(place_ref.ty_layout, FieldIdx::new(1));
+        let b_offset = tir_ctx.offset_of(place_ref.ty_layout, FieldIdx::new(1));
+        let b_ptr = self.inbounds_byte_gep(builder, place_ref.place_val.value, b_offset, "pair1");
+        builder.build_store(a, place_ref.place_val.value, place_ref.place_val.align);
+        builder.build_store(b, b_ptr, b_layout.layout.align.abi);
+    }
+
+    /// Compute the address `offset` bytes past `ptr`, as a GEP over `u8`.
+    fn inbounds_byte_gep(
+        &self,
+        builder: &mut B,
+        ptr: B::Value,
+        offset: Size,
+        name: &str,
+    ) -> B::Value {
+        let tir_ctx = builder.ctx().tir_ctx();
+        let u8_llty = builder
+            .ctx()
+            .backend_type_of(tir_ctx.intern_ty(tidec_tir::ty::TirTy::U8));
+        let u64_layout = builder
+            .ctx()
+            .layout_of(tir_ctx.intern_ty(tidec_tir::ty::TirTy::U64));
+        let offset_val = builder.const_scalar_to_backend_value(
+            ConstScalar::Value(RawScalarValue {
+                data: offset.bytes() as u128,
+                size: NonZero::new(8).unwrap(),
+            }),
+            u64_layout,
+        );
+        builder.build_inbounds_gep(u8_llty, ptr, &[offset_val], name)
+    }
+
+    /// Copy `operand` into a fresh stack slot of its layout and return the
+    /// slot, for arguments that must be passed through memory.
+    fn spill_operand(
+        &self,
+        builder: &mut B,
+        operand: OperandRef<'ctx, B::Value>,
+    ) -> PlaceRef<'ctx, B::Value> {
+        let tmp = PlaceRef::alloca(builder, operand.ty_layout);
+        match operand.operand_val {
+            OperandVal::Ref(place_val) => builder.build_memcpy(
+                tmp.place_val.value,
+                tmp.place_val.align,
+                place_val.value,
+                place_val.align,
+                operand.ty_layout.size,
+            ),
+            OperandVal::Immediate(val) => {
+                builder.build_store(val, tmp.place_val.value, tmp.place_val.align)
+            }
+            OperandVal::Pair(a, b) => self.store_pair(builder, a, b, tmp),
+            OperandVal::Zst => unreachable!("ZST arguments are ignored"),
+        }
+        tmp
+    }
+
+    /// Pack the two halves of a pair operand into a single first-class
+    /// aggregate of the backend type of `layout`, for the uses that need one
+    /// value: call arguments, block arguments and return values.
+    fn pair_to_aggregate(
+        &self,
+        builder: &mut B,
+        a: B::Value,
+        b: B::Value,
+        layout: TyAndLayout<'ctx, TirTy<'ctx>>,
+    ) -> B::Value {
+        let agg = builder.const_zero(layout);
+        let agg = builder.build_insert_value(agg, a, 0, "pair0");
+        builder.build_insert_value(agg, b, 1, "pair1")
+    }
+
     /// Codegen an aggregate construction (`RValue::Aggregate`) into a place.
     ///
-    /// For structs: each operand is stored into the corresponding struct field
-    /// via a GEP + store.
+    /// For structs: each operand is stored at the byte offset of the
+    /// corresponding field (see `TirCtx::offset_of`) via a GEP + store.
     /// For arrays: each operand is stored into the corresponding array element
     /// via a GEP + store.
     fn codegen_aggregate(
@@ -218,7 +449,17 @@ impl<'ll, 'ctx, B: BuilderMethods<'ll, 'ctx>> FnCtx<'ll, 'ctx, B> {
         agg_kind: &AggregateKind<'ctx>,
         operands: &[Operand<'ctx>],
     ) {
-        let aggregate_llty = builder.ctx().backend_type_of(place_ref.ty_layout.ty);
+        let i64_type_val = |builder: &mut B, idx: u64| -> B::Value {
+            let ctx = builder.ctx();
+            let i64_ty = ctx.layout_of(ctx.tir_ctx().intern_ty(tidec_tir::ty::TirTy::U64));
+            builder.const_scalar_to_backend_value(
+                ConstScalar::Value(RawScalarValue {
+                    data: idx as u128,
+                    size: NonZero::new(8).unwrap(),
+                }),
+                i64_ty,
+            )
+        };
 
         match agg_kind {
             AggregateKind::Struct(struct_ty) => {
@@ -227,12 +468,17 @@ impl<'ll, 'ctx, B: BuilderMethods<'ll, 'ctx>> FnCtx<'ll, 'ctx, B> {
                     struct_ty,
                     operands.len()
                 );
+                let ctx = builder.ctx();
+                let tir_ctx = ctx.tir_ctx();
+                let i8_llty = ctx.backend_type_of(tir_ctx.intern_ty(tidec_tir::ty::TirTy::U8));
                 for (i, operand) in operands.iter().enumerate() {
                     let field_ref = self.codegen_operand(builder, operand);
-                    let field_ptr = builder.build_struct_gep(
-                        aggregate_llty,
+                    let offset = tir_ctx.offset_of(place_ref.ty_layout, FieldIdx::new(i));
+                    let offset_val = i64_type_val(builder, offset.bytes());
+                    let field_ptr = builder.build_inbounds_gep(
+                        i8_llty,
                         place_ref.place_val.value,
-                        i as u32,
+                        &[offset_val],
                         &format!("field{}", i),
                     );
                     match field_ref.operand_val {
@@ -258,17 +504,6 @@ impl<'ll, 'ctx, B: BuilderMethods<'ll, 'ctx>> FnCtx<'ll, 'ctx, B> {
                 );
                 let elem_llty = builder.ctx().backend_type_of(*elem_ty);
                 let elem_layout = builder.ctx().layout_of(*elem_ty);
-                let i64_type_val = |builder: &mut B, idx: u64| -> B::Value {
-                    let ctx = builder.ctx();
-                    let i64_ty = ctx.layout_of(ctx.tir_ctx().intern_ty(tidec_tir::ty::TirTy::U64));
-                    builder.const_scalar_to_backend_value(
-                        tidec_tir::syntax::ConstScalar::Value(tidec_tir::syntax::RawScalarValue {
-                            data: idx as u128,
-                            size: std::num::NonZero::new(8).unwrap(),
-                        }),
-                        i64_ty,
-                    )
-                };
                 for (i, operand) in operands.iter().enumerate() {
                     let elem_ref = self.codegen_operand(builder, operand);
                     let index_val = i64_type_val(builder, i as u64);
@@ -377,18 +612,59 @@ impl<'ll, 'ctx, B: BuilderMethods<'ll, 'ctx>> FnCtx<'ll, 'ctx, B> {
             RValue::AddressOf(mutability, place) => {
                 // Evaluate the place to get its memory address, then return
                 // the pointer as an immediate scalar value.
-                let place_ref = self.codegen_place(builder, place);
+                let place_ref = self.codegen_place(builder, *place);
 
                 // The result type is a raw pointer to the place's type.
                 let pointee_ty = place_ref.ty_layout.ty;
                 let ptr_ty = builder
                     .ctx()
                     .tir_ctx()
-                    .intern_ty(tidec_tir::ty::TirTy::RawPtr(pointee_ty, *mutability));
+                    .intern_ty(tidec_tir::ty::TirTy::RawPtr(
+                        pointee_ty,
+                        *mutability,
+                        AddressSpace::DATA,
+                    ));
                 let ptr_layout = builder.ctx().layout_of(ptr_ty);
 
                 OperandRef::new_immediate(place_ref.place_val.value, ptr_layout)
             }
+            RValue::Discriminant(place) => {
+                // The tag sits at offset 0 of the enum and holds the index of
+                // the active variant, so the discriminant is a load of it.
+                let place_ref = self.codegen_place(builder, *place);
+                let enum_ty = place_ref.ty_layout.ty;
+                let Variants::Tagged { .. } = place_ref.ty_layout.variants else {
+                    panic!("RValue::Discriminant of {place:?}, whose type {enum_ty:?} has no tag");
+                };
+                let ctx = builder.ctx();
+                let tag_layout = ctx.layout_of(ctx.tir_ctx().discriminant_ty(enum_ty));
+                let tag_place = PlaceRef {
+                    place_val: place_ref.place_val,
+                    ty_layout: tag_layout,
+                };
+                builder.load_operand(&tag_place)
+            }
+            RValue::InlineAsm {
+                template,
+                operands,
+                clobbers,
+            } => {
+                let args: Vec<_> = operands
+                    .iter()
+                    .map(|operand| {
+                        // `TirBody::check_inline_asm_operands` rejects
+                        // non-immediate operands before codegen.
+                        let operand_ref = self.codegen_operand(builder, operand);
+                        operand_ref.operand_val.immediate()
+                    })
+                    .collect();
+                builder.build_inline_asm(template, &args, clobbers);
+
+                // The assembly produces no value.
+                let ctx = builder.ctx();
+                let unit_ty = ctx.tir_ctx().intern_ty(tidec_tir::ty::TirTy::Unit);
+                OperandRef::new_zst(ctx.layout_of(unit_ty))
+            }
         }
     }
 
@@ -465,110 +741,171 @@ impl<'ll, 'ctx, B: BuilderMethods<'ll, 'ctx>> FnCtx<'ll, 'ctx, B> {
         OperandRef::new_immediate(cast_val, dest_layout)
     }
 
-    /// Codegen a scalar binary operation.
+    /// Codegen a scalar or SIMD vector binary operation.
     /// This function generates the code for the binary operation and returns the resulting value.
     ///
     /// Note that we only need the type layout of the lhs because
     /// in the TIR, both operands of a binary operation must have the same type.
+    ///
+    /// On SIMD vectors, each lane is computed by the instruction the scalar
+    /// operation would use on the element type, e.g. `add <4 x i32>` or
+    /// `fmul <2 x f64>`, and integer division is not guarded by
+    /// `checked_div`. Checked and comparison operators have no lane-wise
+    /// form; `TirBody::check_simd_binops` rejects them before codegen.
     fn codegen_scalar_binary_op(
         &mut self,
         builder: &mut B,
         bin_op: &BinaryOp,
         lhs: B::Value,
         rhs: B::Value,
-        lhs_ty_layout: TyAndLayout<TirTy>,
+        lhs_ty_layout: TyAndLayout<TirTy<'ctx>>,
     ) -> B::Value {
-        let is_float = lhs_ty_layout.ty.is_floating_point();
-        let is_signed = lhs_ty_layout.ty.is_signed_integer();
+        let (is_float, is_signed, is_vector) = match lhs_ty_layout.backend_repr {
+            BackendRepr::Vector { element, .. } => (
+                element.is_floating_point(),
+                element.is_signed_integer(),
+                true,
+            ),
+            _ => (
+                lhs_ty_layout.ty.is_floating_point(),
+                lhs_ty_layout.ty.is_signed_integer(),
+                false,
+            ),
+        };
 
         match bin_op {
-            BinaryOp::Add => {
-                if is_float {
-                    builder.build_fadd(lhs, rhs)
-                } else {
-                    builder.build_add(lhs, rhs)
-                }
-            }
-            BinaryOp::AddUnchecked => {
-                if is_signed {
-                    builder.build_sadd_unchecked(lhs, rhs)
-                } else {
-                    builder.build_uadd_unchecked(lhs, rhs)
-                }
+            BinaryOp::AddChecked | BinaryOp::SubChecked | BinaryOp::MulChecked => {
+                assert!(!is_vector, "{:?} is not supported on SIMD vectors", bin_op);
+                let (result, overflow) =
+                    builder.build_checked_binop(bin_op.clone(), lhs, rhs, is_signed);
+                // All checked operations of the function share one landing pad;
+                // the rest of the TIR block continues in a fresh backend block.
+                let panic_bb = self.ctx.panic_block(self.fn_value);
+                let cont_bb = B::append_basic_block(self.ctx, self.fn_value, "checked_ok");
+                builder.build_conditional_br(overflow, panic_bb, cont_bb);
+                builder.position_at_end(cont_bb);
+                result
             }
-            BinaryOp::Sub => {
-                if is_float {
-                    builder.build_fsub(lhs, rhs)
-                } else {
-                    builder.build_sub(lhs, rhs)
-                }
-            }
-            BinaryOp::SubUnchecked => {
-                if is_signed {
-                    builder.build_ssub_unchecked(lhs, rhs)
-                } else {
-                    builder.build_usub_unchecked(lhs, rhs)
-                }
-            }
-            BinaryOp::Mul => {
+            // Comparison operators
+            BinaryOp::Eq
+            | BinaryOp::Ne
+            | BinaryOp::Lt
+            | BinaryOp::Le
+            | BinaryOp::Gt
+            | BinaryOp::Ge => {
+                assert!(!is_vector, "{:?} is not supported on SIMD vectors", bin_op);
                 if is_float {
-                    builder.build_fmul(lhs, rhs)
-                } else {
-                    builder.build_mul(lhs, rhs)
-                }
-            }
-            BinaryOp::MulUnchecked => {
-                if is_signed {
-                    builder.build_smul_unchecked(lhs, rhs)
+                    builder.build_fcmp(bin_op.clone(), lhs, rhs)
                 } else {
-                    builder.build_umul_unchecked(lhs, rhs)
+                    builder.build_icmp(bin_op.clone(), lhs, rhs, is_signed)
                 }
             }
-            BinaryOp::Div => {
-                if is_float {
-                    builder.build_fdiv(lhs, rhs)
-                } else if is_signed {
-                    builder.build_sdiv(lhs, rhs)
-                } else {
-                    builder.build_udiv(lhs, rhs)
+            _ => {
+                if matches!(bin_op, BinaryOp::Div | BinaryOp::Rem) && !is_float && !is_vector {
+                    self.codegen_div_guard(builder, lhs, rhs, lhs_ty_layout, is_signed);
                 }
+                Self::codegen_lanewise_binary_op(builder, bin_op, lhs, rhs, is_float, is_signed)
             }
-            BinaryOp::Rem => {
-                if is_float {
-                    builder.build_frem(lhs, rhs)
-                } else if is_signed {
-                    builder.build_srem(lhs, rhs)
-                } else {
-                    builder.build_urem(lhs, rhs)
-                }
+        }
+    }
+
+    /// Codegen a binary operation that has the same instruction on scalars
+    /// and on SIMD vectors, selected by whether the (element) type is a
+    /// float and whether it is signed.
+    ///
+    /// Checked and comparison operators are handled by
+    /// [`Self::codegen_scalar_binary_op`].
+    fn codegen_lanewise_binary_op(
+        builder: &mut B,
+        bin_op: &BinaryOp,
+        lhs: B::Value,
+        rhs: B::Value,
+        is_float: bool,
+        is_signed: bool,
+    ) -> B::Value {
+        match bin_op {
+            BinaryOp::Add if is_float => builder.build_fadd(lhs, rhs),
+            BinaryOp::Add => builder.build_add(lhs, rhs),
+            BinaryOp::AddUnchecked if is_signed => builder.build_sadd_unchecked(lhs, rhs),
+            BinaryOp::AddUnchecked => builder.build_uadd_unchecked(lhs, rhs),
+            BinaryOp::Sub if is_float => builder.build_fsub(lhs, rhs),
+            BinaryOp::Sub => builder.build_sub(lhs, rhs),
+            BinaryOp::SubUnchecked if is_signed => builder.build_ssub_unchecked(lhs, rhs),
+            BinaryOp::SubUnchecked => builder.build_usub_unchecked(lhs, rhs),
+            BinaryOp::Mul if is_float => builder.build_fmul(lhs, rhs),
+            BinaryOp::Mul => builder.build_mul(lhs, rhs),
+            BinaryOp::MulUnchecked if is_signed => builder.build_smul_unchecked(lhs, rhs),
+            BinaryOp::MulUnchecked => builder.build_umul_unchecked(lhs, rhs),
+            BinaryOp::SaturatingAdd | BinaryOp::SaturatingSub => {
+                builder.build_saturating_binop(bin_op.clone(), lhs, rhs, is_signed)
             }
+            BinaryOp::Div if is_float => builder.build_fdiv(lhs, rhs),
+            BinaryOp::Div if is_signed => builder.build_sdiv(lhs, rhs),
+            BinaryOp::Div => builder.build_udiv(lhs, rhs),
+            BinaryOp::Rem if is_float => builder.build_frem(lhs, rhs),
+            BinaryOp::Rem if is_signed => builder.build_srem(lhs, rhs),
+            BinaryOp::Rem => builder.build_urem(lhs, rhs),
             BinaryOp::BitAnd => builder.build_and(lhs, rhs),
             BinaryOp::BitOr => builder.build_or(lhs, rhs),
             BinaryOp::BitXor => builder.build_xor(lhs, rhs),
             BinaryOp::Shl => builder.build_shl(lhs, rhs),
-            BinaryOp::Shr => {
-                if is_signed {
-                    builder.build_ashr(lhs, rhs)
-                } else {
-                    builder.build_lshr(lhs, rhs)
-                }
-            }
-            // Comparison operators
-            BinaryOp::Eq
+            BinaryOp::Shr if is_signed => builder.build_ashr(lhs, rhs),
+            BinaryOp::Shr => builder.build_lshr(lhs, rhs),
+            BinaryOp::AddChecked
+            | BinaryOp::SubChecked
+            | BinaryOp::MulChecked
+            | BinaryOp::Eq
             | BinaryOp::Ne
             | BinaryOp::Lt
             | BinaryOp::Le
             | BinaryOp::Gt
-            | BinaryOp::Ge => {
-                if is_float {
-                    builder.build_fcmp(bin_op.clone(), lhs, rhs)
-                } else {
-                    builder.build_icmp(bin_op.clone(), lhs, rhs, is_signed)
-                }
-            }
+            | BinaryOp::Ge => unreachable!("{:?} has no lane-wise instruction", bin_op),
         }
     }
 
+    /// Guard an integer division or remainder when `TirArgs::checked_div` is
+    /// set.
+    ///
+    /// A zero divisor, or a signed `MIN / -1` that overflows, branches to
+    /// the overflow-panic landing pad of the function; the division itself
+    /// is emitted in a fresh `div_ok` block.
+    fn codegen_div_guard(
+        &mut self,
+        builder: &mut B,
+        lhs: B::Value,
+        rhs: B::Value,
+        ty_layout: TyAndLayout<TirTy<'ctx>>,
+        is_signed: bool,
+    ) {
+        if !self.ctx.tir_ctx().checked_div() {
+            return;
+        }
+
+        let size = ty_layout.size;
+        let const_int = |builder: &mut B, value: i128| {
+            builder.const_scalar_to_backend_value(
+                ConstScalar::Value(RawScalarValue::from_signed(value, size)),
+                ty_layout,
+            )
+        };
+        let zero = const_int(builder, 0);
+        let mut panics = builder.build_icmp(BinaryOp::Eq, rhs, zero, is_signed);
+        if is_signed {
+            // The minimum value of the type is its sign bit alone.
+            let min = const_int(builder, i128::MIN >> (128 - size.bits()));
+            let minus_one = const_int(builder, -1);
+            let is_min = builder.build_icmp(BinaryOp::Eq, lhs, min, is_signed);
+            let is_minus_one = builder.build_icmp(BinaryOp::Eq, rhs, minus_one, is_signed);
+            let overflows = builder.build_and(is_min, is_minus_one);
+            panics = builder.build_or(panics, overflows);
+        }
+
+        let panic_bb = self.ctx.panic_block(self.fn_value);
+        let cont_bb = B::append_basic_block(self.ctx, self.fn_value, "div_ok");
+        builder.build_conditional_br(panics, panic_bb, cont_bb);
+        builder.position_at_end(cont_bb);
+    }
+
     fn codegen_operand(
         &mut self,
         builder: &mut B,
@@ -599,11 +936,17 @@ impl<'ll, 'ctx, B: BuilderMethods<'ll, 'ctx>> FnCtx<'ll, 'ctx, B> {
         debug!("Codegen terminator: {:?}", term);
         match term {
             Terminator::Return => self.codegen_return_terminator(builder),
-            Terminator::Goto { target } => {
+            Terminator::Goto { target, block_args } => {
+                self.codegen_block_args(builder, block_args, &[*target]);
                 let be_bb = self.get_or_insert_bb(*target);
                 builder.build_unconditional_br(be_bb);
             }
-            Terminator::SwitchInt { discr, targets } => {
+            Terminator::SwitchInt {
+                discr,
+                targets,
+                block_args,
+            } => {
+                self.codegen_block_args(builder, block_args, &term.successors());
                 self.codegen_switch_int_terminator(builder, discr, targets);
             }
             Terminator::Unreachable => {
@@ -614,7 +957,85 @@ impl<'ll, 'ctx, B: BuilderMethods<'ll, 'ctx>> FnCtx<'ll, 'ctx, B> {
                 args,
                 destination,
                 target,
-            } => self.codegen_call_terminator(builder, func, args, destination, *target),
+                block_args,
+            } => {
+                self.codegen_call_terminator(builder, func, args, destination, *target, block_args)
+            }
+            Terminator::Assert {
+                cond,
+                expected,
+                target,
+                msg,
+                block_args,
+            } => self.codegen_assert_terminator(builder, cond, *expected, *target, msg, block_args),
+        }
+    }
+
+    /// Codegen an `Assert` terminator.
+    ///
+    /// The check is a conditional branch to `target` or to a panic block.
+    /// Overflow checks share the overflow-panic landing pad of the function,
+    /// while each bounds check gets its own block that reports the index and
+    /// the length to the runtime.
+    fn codegen_assert_terminator(
+        &mut self,
+        builder: &mut B,
+        cond: &Operand<'ctx>,
+        expected: bool,
+        target: BasicBlock,
+        msg: &AssertMessage<'ctx>,
+        block_args: &[Operand<'ctx>],
+    ) {
+        let cond_val = self.codegen_operand(builder, cond).operand_val.immediate();
+        // The panic arguments are computed before branching, as the panic
+        // block has no access to the values of the current block otherwise.
+        // The runtime takes the index and the length as `u64`s, so narrower
+        // integers are zero-extended (and wider ones truncated) to 64 bits.
+        let panic_args: Vec<B::MetadataValue> = match msg {
+            AssertMessage::Overflow => vec![],
+            AssertMessage::BoundsCheck { len, index } => {
+                let u64_ty = builder.ctx().tir_ctx().intern_ty(tidec_tir::ty::TirTy::U64);
+                let u64_llty = builder.ctx().backend_type_of(u64_ty);
+                let u64_size = builder.ctx().layout_of(u64_ty).size;
+                [index, len]
+                    .into_iter()
+                    .map(|operand| {
+                        let operand_ref = self.codegen_operand(builder, operand);
+                        let val = operand_ref.operand_val.immediate();
+                        let size = operand_ref.ty_layout.size;
+                        let val = if size < u64_size {
+                            builder.build_zext(val, u64_llty)
+                        } else if size > u64_size {
+                            builder.build_trunc(val, u64_llty)
+                        } else {
+                            val
+                        };
+                        val.into()
+                    })
+                    .collect()
+            }
+        };
+
+        self.codegen_block_args(builder, block_args, &[target]);
+        let target_bb = self.get_or_insert_bb(target);
+        let panic_bb = match msg {
+            AssertMessage::Overflow => self.ctx.panic_block(self.fn_value),
+            AssertMessage::BoundsCheck { .. } => {
+                B::append_basic_block(self.ctx, self.fn_value, "bounds_check_failed")
+            }
+        };
+
+        if expected {
+            builder.build_conditional_br(cond_val, target_bb, panic_bb);
+        } else {
+            builder.build_conditional_br(cond_val, panic_bb, target_bb);
+        }
+
+        if let AssertMessage::BoundsCheck { .. } = msg {
+            builder.position_at_end(panic_bb);
+            let panic_fn = self.ctx.panic_bounds_check_fn();
+            builder.build_call(panic_fn, &panic_args, "");
+            builder.build_unreachable();
         }
     }
 
@@ -625,6 +1046,7 @@ impl<'ll, 'ctx, B: BuilderMethods<'ll, 'ctx>> FnCtx<'ll, 'ctx, B> {
         args: &[Operand<'ctx>],
         destination: &Place<'ctx>,
         target: BasicBlock,
+        block_args: &[Operand<'ctx>],
     ) {
         // This is the callee function reference. `func` is either a function pointer or a direct function.
         let func_ref = self.codegen_operand(builder, func);
@@ -675,39 +1097,94 @@ impl<'ll, 'ctx, B: BuilderMethods<'ll, 'ctx>> FnCtx<'ll, 'ctx, B> {
             }
         };
 
+        let tir_ctx = self.ctx.tir_ctx();
+        let target_info = tir_ctx.target();
+        // The destination has the return type of the callee, so its layout
+        // gives the pass mode of the return value, whatever its projections.
+        let dest_layout = builder
+            .ctx()
+            .layout_of(self.lir_body.place_ty(destination, &tir_ctx));
+        let ret_mode = classify_arg(target_info, &dest_layout);
+
+        let mut arg_vals: Vec<B::MetadataValue> = Vec::with_capacity(args.len() + 1);
+
+        // An indirect return is written by the callee through a hidden
+        // leading pointer to the destination place.
+        if ret_mode == PassMode::Indirect {
+            let dest_place = self.codegen_place(builder, *destination);
+            arg_vals.push(dest_place.place_val.value.into());
+        }
+
         // Codegen the arguments
-        let arg_vals: Vec<B::MetadataValue> = args
-            .iter()
-            .map(|arg| {
-                let arg_ref = self.codegen_operand(builder, arg);
-                match arg_ref.operand_val {
-                    OperandVal::Immediate(val) => val.into(),
-                    OperandVal::Pair(_, _) => {
-                        todo!("Handle wide pointer arguments in function calls")
+        for arg in args {
+            let arg_ref = self.codegen_operand(builder, arg);
+            match classify_arg(target_info, &arg_ref.ty_layout) {
+                PassMode::Ignore => continue,
+                PassMode::Direct => match arg_ref.operand_val {
+                    OperandVal::Immediate(val) => arg_vals.push(val.into()),
+                    OperandVal::Ref(place_val) => {
+                        let llty = builder.ctx().backend_type_of(arg_ref.ty_layout.ty);
+                        let val = builder.build_load(llty, place_val.value, place_val.align);
+                        arg_vals.push(val.into());
                     }
-                    OperandVal::Ref(_) => {
-                        panic!("Cannot pass argument by reference in function call")
-                    }
-                    OperandVal::Zst => {
-                        panic!("Cannot pass ZST argument in function call")
+                    OperandVal::Pair(a, b) => {
+                        let val = self.pair_to_aggregate(builder, a, b, arg_ref.ty_layout);
+                        arg_vals.push(val.into());
                     }
+                    OperandVal::Zst => unreachable!("ZST arguments are ignored"),
+                },
+                PassMode::Indirect => {
+                    // Pass a pointer to a caller-owned copy so that the
+                    // callee cannot observe or clobber the original place.
+                    let tmp = self.spill_operand(builder, arg_ref);
+                    arg_vals.push(tmp.place_val.value.into());
                 }
-            })
-            .collect();
+                PassMode::Cast(cast) => {
+                    // Reload the aggregate from memory as the integers it
+                    // is coerced to.
+                    let place_val = match arg_ref.operand_val {
+                        OperandVal::Ref(place_val) => place_val,
+                        _ => self.spill_operand(builder, arg_ref).place_val,
+                    };
+                    let llty = builder.ctx().cast_backend_type(cast);
+                    let val = builder.build_load(llty, place_val.value, place_val.align);
+                    arg_vals.push(val.into());
+                }
+            }
+        }
 
         // Build the call instruction
         let ret_val = builder.build_call(fn_value, &arg_vals, "call");
 
-        // Handle the return value - store it in the destination if not void
-        if let (Some(ret), Some(local)) = (ret_val, destination.try_local()) {
-            let layout = builder.ctx().layout_of(self.local_ty(local));
-            self.overwrite_local(
-                local,
-                LocalRef::OperandRef(OperandRef::new_immediate(ret, layout)),
-            );
+        // Handle the return value - store it in the destination if not void.
+        // Indirect returns have already been written through the `sret` pointer.
+        if let Some(ret) = ret_val {
+            match destination.try_local() {
+                Some(local)
+                    if !matches!(ret_mode, PassMode::Cast(_))
+                        && !matches!(self.locals[local], LocalRef::PlaceRef(_)) =>
+                {
+                    self.overwrite_local(
+                        local,
+                        LocalRef::OperandRef(OperandRef::new_immediate(ret, dest_layout)),
+                    );
+                }
+                _ => {
+                    // Aggregates returned directly or cast to integers,
+                    // and destinations with projections, are stored into
+                    // their place.
+                    let dest_place = self.codegen_place(builder, *destination);
+                    builder.build_store(
+                        ret,
+                        dest_place.place_val.value,
+                        dest_place.place_val.align,
+                    );
+                }
+            }
         }
 
         // Jump to the target basic block
+        self.codegen_block_args(builder, block_args, &[target]);
         let be_target_bb = self.get_or_insert_bb(target);
         builder.build_unconditional_br(be_target_bb);
     }
@@ -728,13 +1205,15 @@ impl<'ll, 'ctx, B: BuilderMethods<'ll, 'ctx>> FnCtx<'ll, 'ctx, B> {
 
         let otherwise_bb = self.get_or_insert_bb(targets.otherwise);
 
-        if targets.len() == 1 && discr_ref.ty_layout.is_bool() {
+        if targets.len() == 1 && discr_ref.ty_layout.ty.is_bool() {
             // Boolean single-arm switch → conditional branch optimisation.
             //
             // This applies only when the discriminant is already `i1` (Bool)
             // and the single arm tests for `1` (true). For non-boolean
             // single-arm switches we fall through to `build_switch` to avoid
-            // passing a non-`i1` value to `build_conditional_br`.
+            // passing a non-`i1` value to `build_conditional_br`. The type is
+            // checked rather than the layout, because `U8` shares the layout
+            // of `Bool`.
             trace!("Optimizing single-arm switch to conditional branch");
             let (_, then_bb_idx) = targets.values[0];
             let then_bb = self.get_or_insert_bb(then_bb_idx);
@@ -765,13 +1244,28 @@ impl<'ll, 'ctx, B: BuilderMethods<'ll, 'ctx>> FnCtx<'ll, 'ctx, B> {
                 let operand_ref = self.codegen_consume(builder, &RETURN_LOCAL.into());
                 match operand_ref.operand_val {
                     OperandVal::Zst => todo!("Handle return of ZST. Should be unreachable?"),
-                    OperandVal::Ref(_) => todo!("Handle return by reference — load from place"),
-                    OperandVal::Pair(_, _) => {
-                        todo!("Handle return of pair. That is, create an LLVM pair and return it")
+                    OperandVal::Ref(place_val) => {
+                        // An aggregate small enough to be returned directly
+                        // lives in memory; load it as a first-class value.
+                        let llty = builder.ctx().backend_type_of(operand_ref.ty_layout.ty);
+                        builder.build_load(llty, place_val.value, place_val.align)
+                    }
+                    OperandVal::Pair(a, b) => {
+                        self.pair_to_aggregate(builder, a, b, operand_ref.ty_layout)
                     }
                     OperandVal::Immediate(val) => val,
                 }
             }
+            PassMode::Cast(cast) => {
+                info!("Handling cast return");
+                let operand_ref = self.codegen_consume(builder, &RETURN_LOCAL.into());
+                let place_val = match operand_ref.operand_val {
+                    OperandVal::Ref(place_val) => place_val,
+                    _ => self.spill_operand(builder, operand_ref).place_val,
+                };
+                let llty = builder.ctx().cast_backend_type(cast);
+                builder.build_load(llty, place_val.value, place_val.align)
+            }
         };
 
         builder.build_return(Some(be_val));
@@ -799,7 +1293,7 @@ impl<'ll, 'ctx, B: BuilderMethods<'ll, 'ctx>> FnCtx<'ll, 'ctx, B> {
             return operand_ref;
         }
 
-        let place_ref = self.codegen_place(builder, place);
+        let place_ref = self.codegen_place(builder, *place);
         builder.load_operand(&place_ref)
     }
 
@@ -827,8 +1321,10 @@ impl<'ll, 'ctx, B: BuilderMethods<'ll, 'ctx>> FnCtx<'ll, 'ctx, B> {
     ///   the new base. The resulting type is the pointee type.
     /// - `Field(idx, ty)` — emits a GEP to compute the address of a struct
     ///   field. Requires the current place to have a memory layout.
+    /// - `Downcast(variant)` — emits a GEP past the tag of an enum to the
+    ///   payload of the variant.
     /// - Other projections are not yet implemented and will panic.
-    fn codegen_place(&mut self, builder: &mut B, place: &Place<'ctx>) -> PlaceRef<'ctx, B::Value> {
+    fn codegen_place(&mut self, builder: &mut B, place: Place<'ctx>) -> PlaceRef<'ctx, B::Value> {
         let local = place.local;
         let mut place_ref = match &self.locals[local] {
             LocalRef::PlaceRef(place_ref) => *place_ref,
@@ -847,7 +1343,7 @@ impl<'ll, 'ctx, B: BuilderMethods<'ll, 'ctx>> FnCtx<'ll, 'ctx, B> {
         };
 
         // Apply each projection in sequence, adjusting the place reference.
-        for proj in &place.projection {
+        for proj in place.projection {
             match proj {
                 Projection::Deref => {
                     // The current place holds a pointer value. Load it, then
@@ -863,7 +1359,7 @@ impl<'ll, 'ctx, B: BuilderMethods<'ll, 'ctx>> FnCtx<'ll, 'ctx, B> {
                     // so that the result retains the `'ctx` lifetime rather than
                     // being tied to a short-lived borrow.
                     let pointee_ty = match &*place_ref.ty_layout.ty.0 {
-                        tidec_tir::ty::TirTy::RawPtr(pointee, _) => *pointee,
+                        tidec_tir::ty::TirTy::RawPtr(pointee, _, _) => *pointee,
                         _ => panic!(
                             "Deref projection on non-pointer type: {:?}",
                             place_ref.ty_layout.ty
@@ -889,7 +1385,7 @@ impl<'ll, 'ctx, B: BuilderMethods<'ll, 'ctx>> FnCtx<'ll, 'ctx, B> {
                     // The current place must be in memory (BackendRepr::Memory).
                     // We emit a `getelementptr` to compute the field pointer.
                     debug!(
-                        "Field projection: index={}, field_ty={:?}",
+                        "Field projection: index={:?}, field_ty={:?}",
                         field_idx, field_ty
                     );
 
@@ -904,8 +1400,8 @@ impl<'ll, 'ctx, B: BuilderMethods<'ll, 'ctx>> FnCtx<'ll, 'ctx, B> {
                     let field_ptr = builder.build_struct_gep(
                         aggregate_llty,
                         place_ref.place_val.value,
-                        *field_idx as u32,
-                        &format!("field{}", field_idx),
+                        field_idx.idx() as u32,
+                        &format!("field{}", field_idx.idx()),
                     );
 
                     place_ref = PlaceRef {
@@ -956,8 +1452,36 @@ impl<'ll, 'ctx, B: BuilderMethods<'ll, 'ctx>> FnCtx<'ll, 'ctx, B> {
                 Projection::Subslice { .. } => {
                     todo!("Subslice projection requires slice type support")
                 }
-                Projection::Downcast(_variant_idx) => {
-                    todo!("Downcast projection requires enum type support")
+                Projection::Downcast(variant_idx) => {
+                    // Step over the tag to the payload, which all the
+                    // variants share.
+                    debug!("Downcast projection to variant {}", variant_idx);
+
+                    let Variants::Tagged { payload_offset, .. } = place_ref.ty_layout.variants
+                    else {
+                        panic!(
+                            "Downcast projection on non-enum type: {:?}",
+                            place_ref.ty_layout.ty
+                        );
+                    };
+                    let variant_layout = builder
+                        .ctx()
+                        .tir_ctx()
+                        .variant_layout_of(place_ref.ty_layout, *variant_idx);
+                    let payload_ptr = self.inbounds_byte_gep(
+                        builder,
+                        place_ref.place_val.value,
+                        payload_offset,
+                        &format!("variant{}", variant_idx),
+                    );
+
+                    place_ref = PlaceRef {
+                        place_val: crate::tir::PlaceVal {
+                            value: payload_ptr,
+                            align: variant_layout.layout.align.abi,
+                        },
+                        ty_layout: variant_layout,
+                    };
                 }
             }
         }
@@ -1,16 +1,18 @@
-use crate::traits::LayoutOf;
+use crate::traits::{FnAbiOf, LayoutOf};
 use crate::{
     entry::FnCtx,
     traits::{BuilderMethods, CodegenMethods},
 };
+use tidec_abi::calling_convention::function::PassMode;
 use tidec_abi::layout::BackendRepr;
 use tidec_abi::{
     layout::TyAndLayout,
     size_and_align::{Align, Size},
 };
 use tidec_tir::TirTy;
-use tidec_tir::syntax::ConstValue;
-use tidec_tir::syntax::ENTRY_BLOCK;
+use tidec_tir::alloc::Allocation;
+use tidec_tir::syntax::{ConstScalar, ConstValue, FieldIdx, RawScalarValue};
+use tidec_tir::syntax::{ENTRY_BLOCK, RETURN_LOCAL};
 use tidec_tir::{
     body::TirBody,
     syntax::{Local, LocalData},
@@ -103,6 +105,30 @@ impl<'be, 'ctx, V: std::fmt::Debug> OperandRef<'ctx, V> {
                 let be_val = builder.const_null_ptr();
                 OperandVal::Immediate(be_val)
             }
+            ConstValue::ZeroInit if ty_layout.is_zst() => OperandVal::Zst,
+            ConstValue::ZeroInit => OperandVal::Immediate(builder.const_zero(ty_layout)),
+            ConstValue::Slice { data } => {
+                assert!(
+                    matches!(ty_layout.backend_repr, BackendRepr::ScalarPair(_, _)),
+                    "ConstValue::Slice used with non-pair type: {:?}",
+                    lir_ty
+                );
+                let tir_ctx = builder.ctx().tir_ctx();
+                let elem_layout = tir_ctx.slice_elem_layout(ty_layout);
+                let len_layout = tir_ctx.field_layout_of(ty_layout, FieldIdx::new(1));
+                let len = tir_ctx.const_slice_len(ty_layout, &data) as u128;
+
+                let alloc = Allocation::new(data, elem_layout.align.abi);
+                let ptr_val = builder.const_data_from_alloc(&alloc);
+                let len_val = builder.const_scalar_to_backend_value(
+                    ConstScalar::Value(RawScalarValue {
+                        data: len,
+                        size: std::num::NonZero::new(len_layout.size.bytes() as u8).unwrap(),
+                    }),
+                    len_layout,
+                );
+                OperandVal::Pair(ptr_val, len_val)
+            }
             ConstValue::Indirect { alloc_id, offset } => {
                 return Self::from_const_alloc(builder, ty_layout, alloc_id, offset);
             }
@@ -312,6 +338,7 @@ pub fn codegen_tir_body<'a, 'ctx: 'a, B: BuilderMethods<'a, 'ctx>>(
         ctx,
         locals: IdxVec::new(),
         cached_bbs,
+        block_phis: IdxVec::from_elem_n(None, bbs.len()),
     };
 
     let mut allocate_locals =
@@ -329,8 +356,11 @@ pub fn codegen_tir_body<'a, 'ctx: 'a, B: BuilderMethods<'a, 'ctx>>(
                 let local_ref = if layout.is_zst() {
                     // ZSTs do not need to be allocated.
                     LocalRef::OperandRef(OperandRef::new_zst(layout))
-                } else if layout.is_memory() || local_data.mutable {
-                    // Memory types need stack allocation (alloca).
+                } else if !layout.is_immediate() || local_data.mutable {
+                    // Memory types need stack allocation (alloca). So do
+                    // scalar pairs: their fields are accessed through
+                    // projections of the place, and they are loaded as a
+                    // first-class aggregate when passed or returned.
                     //
                     // Mutable locals also require alloca: in our codegen model
                     // an `OperandRef` is a single SSA value that is bound once
@@ -368,20 +398,69 @@ pub fn codegen_tir_body<'a, 'ctx: 'a, B: BuilderMethods<'a, 'ctx>>(
     // can load the correct values.
     //
     // Arguments occupy locals _1, _2, … (skipping _0 which is the return
-    // place).  The LLVM parameter index is 0-based.
+    // place).  The backend parameter index follows the function ABI: an
+    // indirect return takes the first parameter (the `sret` pointer), and
+    // ignored (zero-sized) arguments have no parameter at all.
     {
-        let num_args = fn_ctx.lir_body.ret_and_args.len().saturating_sub(1);
-        for arg_idx in 0..num_args {
+        let fn_abi = ctx.fn_abi_of(&fn_ctx.lir_body.ret_and_args);
+        let mut param_idx = 0u32;
+
+        if fn_abi.ret.mode == PassMode::Indirect {
+            // The caller owns the return slot; write the result through
+            // the `sret` pointer instead of a local alloca.
+            if let Some(sret_ptr) = start_builder.get_fn_param(fn_value, param_idx) {
+                fn_ctx.locals[RETURN_LOCAL] = LocalRef::PlaceRef(
+                    PlaceVal {
+                        value: sret_ptr,
+                        align: fn_abi.ret.layout.align.abi,
+                    }
+                    .with_layout(fn_abi.ret.layout),
+                );
+            }
+            param_idx += 1;
+        }
+
+        for (arg_idx, arg_abi) in fn_abi.args.iter().enumerate() {
             let local = Local::new(arg_idx + 1); // skip _0 (return)
-            if let LocalRef::PlaceRef(place_ref) = &fn_ctx.locals[local]
-                && let Some(param_val) = start_builder.get_fn_param(fn_value, arg_idx as u32)
-            {
-                let ptr = place_ref.place_val.value;
-                let align = place_ref.place_val.align;
-                start_builder.build_store(param_val, ptr, align);
+            match arg_abi.mode {
+                PassMode::Ignore => continue,
+                PassMode::Indirect => {
+                    // The parameter points to a caller-owned copy of the
+                    // argument, which we use directly as the local's place.
+                    if let Some(arg_ptr) = start_builder.get_fn_param(fn_value, param_idx) {
+                        fn_ctx.locals[local] = LocalRef::PlaceRef(
+                            PlaceVal {
+                                value: arg_ptr,
+                                align: arg_abi.layout.align.abi,
+                            }
+                            .with_layout(arg_abi.layout),
+                        );
+                    }
+                }
+                PassMode::Direct => {
+                    if let LocalRef::PlaceRef(place_ref) = &fn_ctx.locals[local]
+                        && let Some(param_val) = start_builder.get_fn_param(fn_value, param_idx)
+                    {
+                        let ptr = place_ref.place_val.value;
+                        let align = place_ref.place_val.align;
+                        start_builder.build_store(param_val, ptr, align);
+                    }
+                    // If the local is PendingOperandRef or OperandRef we skip it —
+                    // immutable scalar args are handled as SSA values directly.
+                }
+                PassMode::Cast(_) => {
+                    // The aggregate arrives coerced to integers of its own
+                    // size; store them into the local's stack slot as-is.
+                    if let LocalRef::PlaceRef(place_ref) = &fn_ctx.locals[local]
+                        && let Some(param_val) = start_builder.get_fn_param(fn_value, param_idx)
+                    {
+                        let ptr = place_ref.place_val.value;
+                        let align = place_ref.place_val.align;
+                        start_builder.build_store(param_val, ptr, align);
+                    }
+                }
             }
-            // If the local is PendingOperandRef or OperandRef we skip it —
-            // immutable scalar args are handled as SSA values directly.
+            param_idx += 1;
         }
     }
 
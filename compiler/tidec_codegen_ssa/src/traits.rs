@@ -1,5 +1,7 @@
+use std::path::PathBuf;
+
 use tidec_abi::{
-    calling_convention::function::FnAbi,
+    calling_convention::function::{CastTarget, FnAbi},
     layout::TyAndLayout,
     size_and_align::{Align, Size},
 };
@@ -34,6 +36,11 @@ pub trait BackendTypeOf<'ctx>: CodegenBackendTypes {
     /// Panics if the type cannot be represented as a backend value type
     /// (e.g., `TirTy::Unit` / void, which is not a value type).
     fn backend_type_of(&self, ty: TirTy<'ctx>) -> Self::Type;
+
+    /// Returns the backend type that an aggregate passed with
+    /// [`PassMode::Cast`](tidec_abi::calling_convention::function::PassMode::Cast)
+    /// is coerced to: a single integer, or a struct of integers.
+    fn cast_backend_type(&self, cast: CastTarget) -> Self::Type;
 }
 
 pub trait FnAbiOf<'ctx> {
@@ -113,7 +120,10 @@ pub trait CodegenMethods<'ctx>:
     /// Emit the output of the codegen backend.
     /// This could be writing to a file ASM, object file, or JIT execution.
     /// The output format is backend-specific.
-    fn emit_output(&self);
+    ///
+    /// Returns the path the output was written to, see
+    /// [`TirCtx::output_path`].
+    fn emit_output(&self) -> PathBuf;
 
     /// Returns the function value for the given TIR body if it exists.
     fn get_fn(&self, lir_body_metadata: &TirBodyMetadata) -> Option<Self::FunctionValue>;
@@ -128,6 +138,18 @@ pub trait CodegenMethods<'ctx>:
     /// Returns the function value for the given function name if it exists.
     fn get_fn_by_name(&self, name: &str) -> Option<Self::FunctionValue>;
 
+    /// Returns the overflow-panic landing pad of the given function.
+    ///
+    /// The block is created on first use and shared by every checked
+    /// arithmetic operation of the function, so the panic code is emitted
+    /// once per function rather than once per call site.
+    fn panic_block(&self, fn_value: Self::FunctionValue) -> Self::BasicBlock;
+
+    /// Returns the runtime function called when an index is out of bounds.
+    ///
+    /// It takes the index and the length, both `u64`, and never returns.
+    fn panic_bounds_check_fn(&self) -> Self::FunctionValue;
+
     /// Get a global allocation by its ID.
     fn global_alloc(&self, alloc_id: AllocId) -> GlobalAlloc<'ctx>;
 
@@ -183,6 +205,9 @@ pub trait BuilderMethods<'a, 'ctx>: Sized + CodegenBackendTypes {
     /// The builder is positioned at the end of the basic block.
     fn build(ctx: &'a Self::CodegenCtx, bb: Self::BasicBlock) -> Self;
 
+    /// Move the builder to the end of the given basic block.
+    fn position_at_end(&mut self, bb: Self::BasicBlock);
+
     /// Append a new basic block to the given function value with the given name.
     /// The name can be empty, in which case a unique name will be generated.
     /// The function value is assumed to be valid and belong to the same context as the codegen context.
@@ -237,6 +262,29 @@ pub trait BuilderMethods<'a, 'ctx>: Sized + CodegenBackendTypes {
     fn build_smul_unchecked(&mut self, lhs: Self::Value, rhs: Self::Value) -> Self::Value;
     /// Build an unsigned integer multiplication instruction for the given values, with undefined behavior on overflow.
     fn build_umul_unchecked(&mut self, lhs: Self::Value, rhs: Self::Value) -> Self::Value;
+    /// Build an integer arithmetic instruction that also reports overflow.
+    ///
+    /// `op` must be one of `AddChecked`, `SubChecked` or `MulChecked`. Returns
+    /// the (wrapped) result together with an `i1` overflow bit.
+    fn build_checked_binop(
+        &mut self,
+        op: tidec_tir::syntax::BinaryOp,
+        lhs: Self::Value,
+        rhs: Self::Value,
+        signed: bool,
+    ) -> (Self::Value, Self::Value);
+    /// Build an integer arithmetic instruction that saturates on overflow.
+    ///
+    /// `op` must be one of `SaturatingAdd` or `SaturatingSub`. The result is
+    /// clamped to the signed or unsigned bounds of the type depending on `signed`.
+    /// On integer vectors, each lane is clamped independently.
+    fn build_saturating_binop(
+        &mut self,
+        op: tidec_tir::syntax::BinaryOp,
+        lhs: Self::Value,
+        rhs: Self::Value,
+        signed: bool,
+    ) -> Self::Value;
     /// Build a floating-point division instruction for the given values.
     fn build_fdiv(&mut self, lhs: Self::Value, rhs: Self::Value) -> Self::Value;
     /// Build a signed integer division instruction for the given values.
@@ -367,6 +415,13 @@ pub trait BuilderMethods<'a, 'ctx>: Sized + CodegenBackendTypes {
     /// This creates a global constant and returns a pointer to it.
     fn const_data_from_alloc(&mut self, alloc: &Allocation) -> Self::Value;
 
+    /// Build a call to raw inline assembly that produces no value.
+    ///
+    /// Each of `args` is passed in a register, and the assembly is assumed
+    /// to have side effects. `clobbers` lists the registers and resources
+    /// (e.g. `"memory"`) the assembly modifies.
+    fn build_inline_asm(&mut self, template: &str, args: &[Self::Value], clobbers: &[String]);
+
     /// Build a function call instruction.
     /// Returns the return value of the call (or a placeholder for void returns).
     fn build_call(
@@ -410,6 +465,21 @@ pub trait BuilderMethods<'a, 'ctx>: Sized + CodegenBackendTypes {
     /// point. If it does at runtime, the behaviour is undefined.
     fn build_unreachable(&mut self);
 
+    /// Returns the basic block the builder is currently positioned in.
+    fn current_block(&self) -> Self::BasicBlock;
+
+    /// Build an empty phi node of type `ty`.
+    ///
+    /// Phi nodes must be grouped at the start of their block, so the builder
+    /// must not have emitted any other instruction in it yet. Incoming values
+    /// are added with [`add_phi_incoming`](Self::add_phi_incoming).
+    fn build_phi(&mut self, ty: Self::Type) -> Self::Value;
+
+    /// Add the incoming `value` flowing into `phi` along the edge from `bb`.
+    ///
+    /// `phi` must have been built with [`build_phi`](Self::build_phi).
+    fn add_phi_incoming(&mut self, phi: Self::Value, value: Self::Value, bb: Self::BasicBlock);
+
     /// Build an integer comparison instruction.
     ///
     /// Compares `lhs` and `rhs` using the given predicate string and returns
@@ -548,4 +618,10 @@ pub trait BuilderMethods<'a, 'ctx>: Sized + CodegenBackendTypes {
     /// Returns `ptr null` in LLVM's opaque-pointer model. This does not
     /// require the caller to know the target pointer size.
     fn const_null_ptr(&self) -> Self::Value;
+
+    /// Produce an all-zero constant of the given type.
+    ///
+    /// Used for `ConstValue::ZeroInit`. Unlike `const_null_ptr`, this works
+    /// for any non-ZST type, including aggregates.
+    fn const_zero(&self, ty_layout: TyAndLayout<'ctx, TirTy<'ctx>>) -> Self::Value;
 }
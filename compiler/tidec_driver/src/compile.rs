@@ -15,6 +15,7 @@
 //!   `BuilderCtx::with_default` closure and the arena is still live.
 
 use std::fmt;
+use std::path::PathBuf;
 
 use tidec_abi::target::{BackendKind, TirTarget};
 use tidec_codegen_llvm::entry::{llvm_codegen_lir_unit, llvm_codegen_to_ir_string};
@@ -97,6 +98,10 @@ pub struct CompileOutput {
     /// For `EmitKind::LlvmIr` when using [`compile_unit_to_ir_string`], this
     /// contains the textual LLVM IR. `None` for file-based outputs.
     pub ir_string: Option<String>,
+
+    /// For file-based outputs, the path the output was written to. `None`
+    /// for in-memory outputs.
+    pub output_path: Option<PathBuf>,
 }
 
 // =============================================================================
@@ -164,6 +169,7 @@ where
     let target = TirTarget::new(config.backend);
     let arguments = TirArgs {
         emit_kind: config.emit,
+        ..Default::default()
     };
     let tir_arena = TirArena::default();
     let intern_ctx = InternCtx::new(&tir_arena);
@@ -200,10 +206,12 @@ pub fn compile_unit_with_ctx<'ctx>(
     match tir_ctx.backend_kind() {
         BackendKind::Llvm => {
             debug!("Using LLVM backend");
-            llvm_codegen_lir_unit(tir_ctx, tir_unit);
+            let output_path = llvm_codegen_lir_unit(tir_ctx, tir_unit)
+                .map_err(|err| CompileError::CodegenError(err.to_string()))?;
             Ok(CompileOutput {
                 emit_kind: config.emit,
                 ir_string: None,
+                output_path: Some(output_path),
             })
         }
         BackendKind::Cranelift => Err(CompileError::UnsupportedBackend("cranelift".to_string())),
@@ -224,10 +232,12 @@ pub fn compile_unit_to_ir_string<'ctx>(
 
     match tir_ctx.backend_kind() {
         BackendKind::Llvm => {
-            let ir = llvm_codegen_to_ir_string(tir_ctx, tir_unit);
+            let ir = llvm_codegen_to_ir_string(tir_ctx, tir_unit)
+                .map_err(|err| CompileError::CodegenError(err.to_string()))?;
             Ok(CompileOutput {
                 emit_kind: EmitKind::LlvmIr,
                 ir_string: Some(ir),
+                output_path: None,
             })
         }
         BackendKind::Cranelift => Err(CompileError::UnsupportedBackend("cranelift".to_string())),
@@ -244,10 +254,10 @@ pub fn compile_unit_to_ir_string<'ctx>(
 /// This is a convenience function for CLI entry points. Library callers
 /// (e.g. nlgc) should initialize their own logger.
 pub fn init_tidec_logger() {
-    if let Err(err) = tidec_log::Logger::init_logger(
-        tidec_log::LoggerConfig::from_prefix("TIDEC").unwrap(),
-        tidec_log::FallbackDefaultEnv::No,
-    ) {
+    let result = tidec_log::LoggerConfig::from_prefix("TIDEC").and_then(|config| {
+        tidec_log::Logger::init_logger(config, tidec_log::FallbackDefaultEnv::No)
+    });
+    if let Err(err) = result {
         eprintln!("Error initializing tidec logger: {:?}", err);
         std::process::exit(1);
     }
@@ -2,9 +2,12 @@
 //!
 //! The allowed environment variables are:
 //! - `<PREFIX>_LOG`: The log level. This can be "debug", "info", "warn", "error", or "trace".
-//! - `<PREFIX>_LOG_COLOR`: The color setting. This can be "always", "never", or "auto".
-//! - `<PREFIX>_LOG_WRITER`: The log writer. This can be "stdout", "stderr", or a file path. If the file path does not exist, it will be created.
+//! - `<PREFIX>_LOG_COLOR`: The color setting. This can be "always", "never", or "auto" (the default). With "auto", color is used only when `stderr` is a terminal; on Windows, virtual terminal processing must also be enabled successfully. The variable is ignored, and color is never used, when the crate is built with the `no-color` feature or without the `ansi` feature.
+//! - `<PREFIX>_LOG_WRITER`: The log writers, separated by commas (e.g. "stderr,syslog:daemon:mytool"); every event is written to each of them. A writer can be "stdout", "stderr", "syslog:<facility>:<ident>" (Unix only, e.g. "syslog:daemon:mytool"), "journald" (Linux only, with the `journald` feature; the level of each event becomes the priority of the journal entry), or a file path, which thus cannot contain a comma. If the file path does not exist, it will be created.
 //! - `<PREFIX>_LOG_LINE_NUMBERS`: Whether to show line numbers in the log. This can be "1" or "0".
+//! - `<PREFIX>_LOG_SYNC`: Whether to sync the log file to disk after every event, so that no line is lost if the process crashes. This can be "1" or "0" and defaults to "0", as syncing on every event is slow. It only applies to file writers.
+//! - `<PREFIX>_LOG_TIME`: The timestamp of each log line. This can be "none", "uptime" (time since the logger was installed), "rfc3339" (RFC 3339 in UTC, the default) or "local" (RFC 3339 in the local time zone).
+//! - `<PREFIX>_LOG_FLATTEN_FIELDS`: Whether the fields of an event are rendered inline after its message (`assigning local=_1`) or grouped in braces (`assigning {local=_1}`). This can be "1" (the default) or "0". It only affects this human-readable text output: a structured format such as JSON always keeps the fields apart from the message, so it has no flattened form to toggle.
 //!
 //! The `<PREFIX>` is a prefix that can be set to any string. It is used to customize the log configuration for different tools. For example, `tidec` uses `TIDEC` as the prefix.
 //!
@@ -42,6 +45,47 @@
 //!
 //! This allows you to simply run `cargo run` and still see debug output.
 //!
+//! To send the logs to your own sink instead of stdout, stderr or a file, pass
+//! any `MakeWriter` (e.g. a closure returning an `io::Write`) to
+//! [`Logger::init_with_make_writer`].
+//!
+//! A process embedding several tidec-based tools can give each of them its
+//! own `<PREFIX>_LOG` without fighting over the single global subscriber:
+//! build one layer per prefix with [`Logger::build_layer`] and compose them
+//! into a registry the host installs itself. [`Logger::init_logger`] is the
+//! convenience wrapper for the common single-tool case.
+//!
+//! To mute noisy dependencies by default, call [`LoggerConfig::scoped_to`]
+//! with the name of the tool crate: unless `<PREFIX>_LOG` says otherwise,
+//! only its events at `info` and above are shown.
+//!
+//! ---
+//!
+//! When several compilation units are processed (possibly concurrently), wrap
+//! the work for each unit in a [`unit_span`] so that every log line emitted
+//! while the span is entered is prefixed with the unit name:
+//!
+//! ```rust
+//! let span = tidec_log::unit_span("main");
+//! let _enter = span.enter();
+//! tracing::info!("lowering unit");
+//! // Logs: `unit{name=main}: lowering unit`
+//! ```
+//!
+//! Entering a span only affects the current thread, so each worker thread
+//! should enter the span of the unit it is processing.
+//!
+//! ---
+//!
+//! Log levels can also be filtered at compile time by enabling one of the
+//! `max_level_off`, `max_level_error`, `max_level_warn`, `max_level_info`,
+//! `max_level_debug` or `max_level_trace` cargo features. They forward to
+//! `tracing`'s static max level features, so macros below the chosen level
+//! (e.g. `debug!` and `trace!` with `max_level_info`) are removed entirely at
+//! compile time and cannot be re-enabled through `<PREFIX>_LOG`. Since cargo
+//! features are unified, enabling one affects every crate in the build that
+//! uses `tracing`.
+//!
 //! ---
 //!
 //! The `tidec_log` crate exists as a minimal, self-contained logger setup,
@@ -50,11 +94,31 @@
 //! components like `tidec_tir`, without requiring full rebuilds of the entire
 //! compiler stack.
 
-use std::{env::VarError, fmt::Debug, fs::File, io::IsTerminal, path::PathBuf};
+#[cfg(unix)]
+mod syslog;
+
+#[cfg(unix)]
+pub use syslog::{SyslogFacility, SyslogGuard, SyslogWriter};
+
+use std::{
+    env::VarError,
+    fmt::Debug,
+    fs::File,
+    io::{IsTerminal, Write},
+    path::PathBuf,
+    str::FromStr,
+};
 use tracing::Subscriber;
 use tracing_subscriber::{
-    EnvFilter, Layer,
-    fmt::{format::FmtSpan, layer},
+    EnvFilter, Layer, Registry,
+    field::RecordFields,
+    fmt::{
+        self, FormatFields, MakeWriter,
+        format::{DefaultFields, FmtSpan, Writer},
+        layer,
+        time::{LocalTime, SystemTime, Uptime},
+    },
+    layer::Layered,
     prelude::*,
     registry::LookupSpan,
     util::TryInitError,
@@ -73,12 +137,118 @@ pub enum LogWriter {
     Stderr,
     /// Write to a file.
     File(PathBuf),
+    /// Write to the local syslog daemon.
+    #[cfg(unix)]
+    Syslog {
+        /// The facility the messages are logged under.
+        facility: SyslogFacility,
+        /// The name prefixed to every message, usually the program name.
+        ident: String,
+    },
+    /// Write to the systemd journal.
+    #[cfg(all(target_os = "linux", feature = "journald"))]
+    Journald,
+}
+
+impl FromStr for LogWriter {
+    type Err = LogError;
+
+    /// Parses a `<PREFIX>_LOG_WRITER` value. Anything that is not `stdout`,
+    /// `stderr`, `journald` or a `syslog:<facility>:<ident>` spec is taken as
+    /// a file path.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "stdout" => Ok(LogWriter::Stdout),
+            "stderr" => Ok(LogWriter::Stderr),
+            #[cfg(all(target_os = "linux", feature = "journald"))]
+            "journald" => Ok(LogWriter::Journald),
+            #[cfg(not(all(target_os = "linux", feature = "journald")))]
+            "journald" => Err(LogError::JournaldUnsupported),
+            _ => match s.strip_prefix("syslog:") {
+                Some(spec) => Self::parse_syslog(spec),
+                None => Ok(LogWriter::File(s.into())),
+            },
+        }
+    }
+}
+
+impl TryFrom<&str> for LogWriter {
+    type Error = LogError;
+
+    /// Same as [`LogWriter::from_str`].
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl LogWriter {
+    /// Parses a comma-separated list of writers, as accepted by
+    /// `<PREFIX>_LOG_WRITER`. Each item is parsed by [`LogWriter::from_str`].
+    ///
+    /// Fails with [`LogError::WriterNotValid`] if an item is empty.
+    pub fn parse_list(s: &str) -> Result<Vec<Self>, LogError> {
+        s.split(',')
+            .map(|writer| match writer.trim() {
+                "" => Err(LogError::WriterNotValid(s.to_string())),
+                writer => writer.parse(),
+            })
+            .collect()
+    }
+
+    #[cfg(unix)]
+    fn parse_syslog(spec: &str) -> Result<Self, LogError> {
+        let (facility, ident) = spec
+            .split_once(':')
+            .filter(|(_, ident)| !ident.is_empty())
+            .ok_or_else(|| LogError::WriterNotValid(format!("syslog:{}", spec)))?;
+        let facility = facility
+            .parse()
+            .map_err(|()| LogError::WriterNotValid(format!("syslog:{}", spec)))?;
+        Ok(LogWriter::Syslog {
+            facility,
+            ident: ident.to_string(),
+        })
+    }
+
+    #[cfg(not(unix))]
+    fn parse_syslog(_spec: &str) -> Result<Self, LogError> {
+        Err(LogError::SyslogUnsupported)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+/// The timestamp printed at the start of each log line.
+pub enum LogTime {
+    /// No timestamp.
+    None,
+    /// The time elapsed since the logger was installed.
+    Uptime,
+    /// The wall-clock time in UTC, formatted as RFC 3339.
+    #[default]
+    Rfc3339,
+    /// The wall-clock time in the local time zone, formatted as RFC 3339.
+    Local,
+}
+
+impl FromStr for LogTime {
+    type Err = LogError;
+
+    /// Parses a `<PREFIX>_LOG_TIME` value.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "none" => Ok(LogTime::None),
+            "uptime" => Ok(LogTime::Uptime),
+            "rfc3339" => Ok(LogTime::Rfc3339),
+            "local" => Ok(LogTime::Local),
+            _ => Err(LogError::TimeFormatNotValid(s.to_string())),
+        }
+    }
 }
 
 /// The configuration for the logger.
 pub struct LoggerConfig {
-    /// The writer for the logger.
-    pub log_writer: LogWriter,
+    /// The writers for the logger. Every event is written to each of them.
+    pub log_writers: Vec<LogWriter>,
     /// The filter for the logger.
     /// This is a string that can be "debug", "info", "warn", "error", or "trace".
     pub filter: Result<String, VarError>,
@@ -91,6 +261,18 @@ pub struct LoggerConfig {
     /// Whether to show file names in the logger.
     /// If this is set to "1", file names will be shown otherwise they will not.
     pub file_names: Result<String, VarError>,
+    /// Whether to sync the log file after every event.
+    /// If this is set to "1", each event is synced to disk before logging continues.
+    pub sync: Result<String, VarError>,
+    /// The timestamp printed at the start of each log line.
+    pub time: LogTime,
+    /// Whether to render the fields of an event inline after its message.
+    /// If this is set to "0", they are grouped in braces instead.
+    pub flatten_fields: Result<String, VarError>,
+    /// Filter directives (e.g. `"my_tool=info"`) applied before `filter`,
+    /// which can override them. When there are any, they replace the `INFO`
+    /// level used when `<PREFIX>_LOG` is not set.
+    pub default_directives: Vec<String>,
 }
 
 #[derive(Debug)]
@@ -104,6 +286,15 @@ pub enum LogError {
     IoError(std::io::Error),
     /// Wrapping a TryInitError.
     TryInitError(TryInitError),
+    /// The log writer value is not valid.
+    WriterNotValid(String),
+    /// Logging to syslog was requested on a platform without syslog.
+    SyslogUnsupported,
+    /// Logging to the systemd journal was requested on a platform other
+    /// than Linux, or without the `journald` feature.
+    JournaldUnsupported,
+    /// The time format value is not valid.
+    TimeFormatNotValid(String),
 }
 
 /// The fallback default environment variable for the logger.
@@ -118,119 +309,532 @@ pub enum FallbackDefaultEnv {
 
 impl LoggerConfig {
     /// Create a new logger configuration from the given environment variable.
-    pub fn from_prefix(prefix_env_var: &str) -> Result<Self, VarError> {
+    ///
+    /// Fails if `<PREFIX>_LOG_WRITER` is not a valid list of writers, see
+    /// [`LogWriter::parse_list`], or if `<PREFIX>_LOG_TIME` is not a valid
+    /// time format, see [`LogTime::from_str`].
+    pub fn from_prefix(prefix_env_var: &str) -> Result<Self, LogError> {
         let filter = std::env::var(format!("{}_LOG", prefix_env_var));
         let color = std::env::var(format!("{}_LOG_COLOR", prefix_env_var));
-        let log_writer = std::env::var(format!("{}_LOG_WRITER", prefix_env_var))
-            .map(|s| match s.as_str() {
-                "stdout" => LogWriter::Stdout,
-                "stderr" => LogWriter::Stderr,
-                _ => LogWriter::File(s.into()),
-            })
-            .unwrap_or(LogWriter::Stderr);
+        let log_writers = match std::env::var(format!("{}_LOG_WRITER", prefix_env_var)) {
+            Ok(s) => LogWriter::parse_list(&s)?,
+            Err(_) => vec![LogWriter::Stderr],
+        };
         let line_numbers = std::env::var(format!("{}_LOG_LINE_NUMBERS", prefix_env_var));
         let file_names = std::env::var(format!("{}_LOG_FILE_NAMES", prefix_env_var));
+        let sync = std::env::var(format!("{}_LOG_SYNC", prefix_env_var));
+        let time = match std::env::var(format!("{}_LOG_TIME", prefix_env_var)) {
+            Ok(s) => s.parse()?,
+            Err(_) => LogTime::default(),
+        };
+        let flatten_fields = std::env::var(format!("{}_LOG_FLATTEN_FIELDS", prefix_env_var));
 
         Ok(LoggerConfig {
             filter,
             color,
-            log_writer,
+            log_writers,
             line_numbers,
             file_names,
+            sync,
+            time,
+            flatten_fields,
+            default_directives: Vec::new(),
         })
     }
+
+    /// Only show the events of `crate_name`, at `info` and above, unless
+    /// `<PREFIX>_LOG` says otherwise.
+    ///
+    /// This adds `crate_name=info` to the default directives, so that the
+    /// noisy dependencies of a tool are muted by default while the user can
+    /// still enable (or further restrict) any target.
+    pub fn scoped_to(&mut self, crate_name: &str) -> &mut Self {
+        self.default_directives.push(format!("{}=info", crate_name));
+        self
+    }
 }
 
-impl Logger {
-    pub fn init_logger(
-        cfg: LoggerConfig,
-        fallback_default_env: FallbackDefaultEnv,
-    ) -> Result<(), LogError> {
-        let filter = match cfg.filter {
-            Ok(filter) => EnvFilter::new(filter),
-            Err(_) => {
-                if let FallbackDefaultEnv::Yes = fallback_default_env {
-                    EnvFilter::from_default_env()
-                } else {
-                    EnvFilter::default().add_directive(tracing::Level::INFO.into())
+/// The fmt layer installed on top of the filtered registry.
+type FmtLayer = Box<dyn Layer<Layered<EnvFilter, Registry>> + Send + Sync + 'static>;
+
+/// The options of the fmt layer, parsed from a [`LoggerConfig`].
+///
+/// All the format settings are validated here, so that building and
+/// installing the layer cannot fail on a bad setting.
+#[derive(Debug)]
+pub struct LayerOptions {
+    /// Whether to color the output with ANSI escape codes.
+    pub color_log: bool,
+    /// Whether to print the line number of each event.
+    pub line_numbers: bool,
+    /// Whether to print the source file of each event.
+    pub file_names: bool,
+    /// Whether to sync file writers after every event.
+    pub sync: bool,
+    /// The timestamp printed at the start of each line.
+    pub time: LogTime,
+    /// Whether the fields of an event are rendered inline after its message.
+    pub flatten_fields: bool,
+    /// The span lifecycle events that are logged: the creation and the
+    /// closing of each span.
+    pub span_events: FmtSpan,
+}
+
+impl LayerOptions {
+    /// Parses and validates the format settings of `cfg`.
+    ///
+    /// Fails if `color` is not one of "always", "never" or "auto", or is not
+    /// valid unicode. The filter is not part of the options, as its fallback
+    /// depends on how the logger is installed.
+    ///
+    /// With the `no-color` feature, or without the `ansi` feature, `color` is
+    /// ignored and `color_log` is always `false`.
+    pub fn from_config(cfg: &LoggerConfig) -> Result<Self, LogError> {
+        let color_log = if cfg!(any(feature = "no-color", not(feature = "ansi"))) {
+            false
+        } else {
+            match &cfg.color {
+                Ok(color) => match color.as_str() {
+                    "always" => true,
+                    "never" => false,
+                    "auto" => auto_color(),
+                    e => return Err(LogError::ColorNotValid(e.to_string())),
+                },
+                Err(VarError::NotPresent) => auto_color(),
+                Err(VarError::NotUnicode(os_string)) => {
+                    return Err(LogError::NotUnicode(
+                        os_string.to_string_lossy().to_string(),
+                    ));
                 }
             }
         };
 
-        let color_log = match cfg.color {
-            Ok(color) => match color.as_str() {
-                "always" => true,
-                "never" => false,
-                "auto" => std::io::stderr().is_terminal(),
-                e => return Err(LogError::ColorNotValid(e.to_string())),
-            },
-            Err(VarError::NotPresent) => std::io::stderr().is_terminal(),
-            Err(VarError::NotUnicode(os_string)) => {
-                return Err(LogError::NotUnicode(
-                    os_string.to_string_lossy().to_string(),
-                ));
-            }
+        let line_numbers = match &cfg.line_numbers {
+            Ok(line_numbers) => line_numbers == "1",
+            Err(_) => false,
         };
 
-        let line_numbers = match cfg.line_numbers {
-            Ok(line_numbers) => &line_numbers == "1",
+        let file_names = match &cfg.file_names {
+            Ok(file_names) => file_names == "1",
             Err(_) => false,
         };
 
-        let file_names = match cfg.file_names {
-            Ok(file_names) => &file_names == "1",
+        let sync = match &cfg.sync {
+            Ok(sync) => sync == "1",
             Err(_) => false,
         };
 
-        let layer = Self::create_layer(cfg.log_writer, color_log, line_numbers, file_names);
-        // Here we can add other layers
+        let flatten_fields = match &cfg.flatten_fields {
+            Ok(flatten_fields) => flatten_fields != "0",
+            Err(_) => true,
+        };
+
+        Ok(LayerOptions {
+            color_log,
+            line_numbers,
+            file_names,
+            sync,
+            time: cfg.time,
+            flatten_fields,
+            span_events: FmtSpan::NEW | FmtSpan::CLOSE,
+        })
+    }
+}
 
-        let subscriber = tracing_subscriber::Registry::default()
-            .with(filter)
-            .with(layer);
+/// Builds the filter of `cfg`, falling back to `RUST_LOG` or to `INFO` when
+/// `<PREFIX>_LOG` is not set. The default directives of `cfg` come first, so
+/// that the user filter overrides them.
+fn env_filter(cfg: &LoggerConfig, fallback_default_env: FallbackDefaultEnv) -> EnvFilter {
+    let filter = match (&cfg.filter, fallback_default_env) {
+        (Ok(filter), _) => filter.clone(),
+        (Err(_), FallbackDefaultEnv::Yes) => {
+            std::env::var(EnvFilter::DEFAULT_ENV).unwrap_or_default()
+        }
+        (Err(_), FallbackDefaultEnv::No) if cfg.default_directives.is_empty() => {
+            tracing::Level::INFO.to_string()
+        }
+        (Err(_), FallbackDefaultEnv::No) => String::new(),
+    };
+    let directives: Vec<&str> = cfg
+        .default_directives
+        .iter()
+        .map(String::as_str)
+        .chain(Some(filter.as_str()).filter(|filter| !filter.is_empty()))
+        .collect();
+    EnvFilter::new(directives.join(","))
+}
 
-        let _ = subscriber.try_init().map_err(LogError::TryInitError);
+impl Logger {
+    /// Install the global subscriber, logging as configured by `cfg`.
+    ///
+    /// This is a convenience wrapper that installs the layer of
+    /// [`Logger::build_layer`] on a fresh [`Registry`]. If `<PREFIX>_LOG` is
+    /// not set, the filter falls back as described by `fallback_default_env`.
+    pub fn init_logger(
+        cfg: LoggerConfig,
+        fallback_default_env: FallbackDefaultEnv,
+    ) -> Result<(), LogError> {
+        let layer = Self::filtered_layer(cfg, fallback_default_env)?;
+        Registry::default()
+            .with(layer)
+            .try_init()
+            .map_err(LogError::TryInitError)
+    }
 
-        Ok(())
+    /// Build the fmt layer of `cfg`, with its own `<PREFIX>_LOG` filter,
+    /// without installing anything.
+    ///
+    /// Only one global subscriber can exist per process. A host embedding
+    /// several tidec-based tools can build one layer per tool prefix and
+    /// compose them into a registry it controls; each layer only sees the
+    /// events its own filter enables. If `<PREFIX>_LOG` is not set, the
+    /// filter defaults to `INFO`: `RUST_LOG` is not consulted, as it would be
+    /// shared by every tool.
+    ///
+    /// ```rust
+    /// use tidec_log::{Logger, LoggerConfig};
+    /// use tracing_subscriber::prelude::*;
+    ///
+    /// let subscriber = tracing_subscriber::registry()
+    ///     .with(Logger::build_layer(LoggerConfig::from_prefix("TOOL_A").unwrap()).unwrap())
+    ///     .with(Logger::build_layer(LoggerConfig::from_prefix("TOOL_B").unwrap()).unwrap());
+    /// tracing::subscriber::with_default(subscriber, || tracing::info!("hello"));
+    /// ```
+    pub fn build_layer<S>(
+        cfg: LoggerConfig,
+    ) -> Result<Box<dyn Layer<S> + Send + Sync + 'static>, LogError>
+    where
+        S: Subscriber,
+        for<'a> S: LookupSpan<'a>,
+    {
+        Self::filtered_layer(cfg, FallbackDefaultEnv::No)
     }
 
+    fn filtered_layer<S>(
+        cfg: LoggerConfig,
+        fallback_default_env: FallbackDefaultEnv,
+    ) -> Result<Box<dyn Layer<S> + Send + Sync + 'static>, LogError>
+    where
+        S: Subscriber,
+        for<'a> S: LookupSpan<'a>,
+    {
+        let options = LayerOptions::from_config(&cfg)?;
+        let filter = env_filter(&cfg, fallback_default_env);
+        let layers = cfg
+            .log_writers
+            .into_iter()
+            .map(|log_writer| Self::create_layer(log_writer, &options))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Box::new(layers.with_filter(filter)))
+    }
+
+    /// Initialize the logger with a custom writer factory.
+    ///
+    /// This lets embedders route logs into their own sink (a channel, a ring
+    /// buffer, a socket, ...). The `log_writers` and `sync` settings of `cfg`
+    /// are ignored, while the filter and the format options (color, line
+    /// numbers, file names) are applied as in [`Logger::init_logger`]. If
+    /// `<PREFIX>_LOG` is not set, `RUST_LOG` is used as the filter.
+    pub fn init_with_make_writer<W>(cfg: LoggerConfig, make_writer: W) -> Result<(), LogError>
+    where
+        W: for<'a> MakeWriter<'a> + Send + Sync + 'static,
+    {
+        let options = LayerOptions::from_config(&cfg)?;
+        let filter = env_filter(&cfg, FallbackDefaultEnv::Yes);
+        let layer = Self::finish_layer(Self::fmt_layer(&options), options.time, make_writer);
+        Self::init_subscriber(filter, layer)
+    }
+
+    fn init_subscriber(filter: EnvFilter, layer: FmtLayer) -> Result<(), LogError> {
+        // Here we can add other layers
+        let subscriber = Registry::default().with(filter).with(layer);
+
+        subscriber.try_init().map_err(LogError::TryInitError)
+    }
+
+    fn fmt_layer<S>(options: &LayerOptions) -> fmt::Layer<S, EventFields>
+    where
+        S: Subscriber,
+        for<'a> S: LookupSpan<'a>,
+    {
+        layer()
+            .fmt_fields(EventFields {
+                flatten: options.flatten_fields,
+            })
+            .with_span_events(options.span_events.clone())
+            .with_target(true)
+            .with_file(options.file_names)
+            .with_ansi(options.color_log)
+            .with_line_number(options.line_numbers)
+    }
+
+    /// Build the layer writing to `log_writer`.
+    ///
+    /// Fails with [`LogError::IoError`] if the writer cannot be opened: the
+    /// log file cannot be created, or syslog or the journal cannot be reached.
     fn create_layer<S>(
         log_writer: LogWriter,
-        color_log: bool,
-        line_numbers: bool,
-        file_names: bool,
-    ) -> Box<dyn Layer<S> + Send + Sync + 'static>
+        options: &LayerOptions,
+    ) -> Result<Box<dyn Layer<S> + Send + Sync + 'static>, LogError>
     where
         S: Subscriber,
         for<'a> S: LookupSpan<'a>,
     {
-        let layer = layer()
-            .with_span_events(FmtSpan::NEW | FmtSpan::CLOSE) // FmtSpan::FULL
-            .with_target(true)
-            .with_file(file_names)
-            .with_ansi(color_log)
-            .with_line_number(line_numbers);
+        let layer = Self::fmt_layer(options);
 
-        match log_writer {
-            LogWriter::Stdout => Box::new(layer.with_writer(std::io::stdout)),
-            LogWriter::Stderr => Box::new(layer.with_writer(std::io::stderr)),
+        let layer = match log_writer {
+            LogWriter::Stdout => Self::finish_layer(layer, options.time, std::io::stdout),
+            LogWriter::Stderr => Self::finish_layer(layer, options.time, std::io::stderr),
             LogWriter::File(path) => {
-                let file = File::create(path).expect("Failed to create log file");
-                Box::new(layer.with_writer(file))
+                let file = File::create(path).map_err(LogError::IoError)?;
+                if options.sync {
+                    Self::finish_layer(layer, options.time, SyncFileWriter::new(file))
+                } else {
+                    Self::finish_layer(layer, options.time, file)
+                }
+            }
+            #[cfg(unix)]
+            LogWriter::Syslog { facility, ident } => {
+                let writer = SyslogWriter::connect(facility, ident).map_err(LogError::IoError)?;
+                // The syslog daemon does not render terminal escape codes.
+                Self::finish_layer(layer.with_ansi(false), options.time, writer)
             }
+            #[cfg(all(target_os = "linux", feature = "journald"))]
+            LogWriter::Journald => {
+                // `tracing-journald` sends structured entries: the level
+                // becomes the `PRIORITY` and the fields of the event and of its
+                // spans become journal fields, so the fmt options do not apply.
+                Box::new(tracing_journald::layer().map_err(LogError::IoError)?)
+            }
+        };
+        Ok(layer)
+    }
+
+    /// Install the timer selected by `time` and the writer on the fmt layer.
+    ///
+    /// Both change the type of the layer, so the result is boxed.
+    fn finish_layer<S, W>(
+        layer: fmt::Layer<S, EventFields>,
+        time: LogTime,
+        make_writer: W,
+    ) -> Box<dyn Layer<S> + Send + Sync + 'static>
+    where
+        S: Subscriber,
+        for<'a> S: LookupSpan<'a>,
+        W: for<'a> MakeWriter<'a> + Send + Sync + 'static,
+    {
+        match time {
+            LogTime::None => Box::new(layer.without_time().with_writer(make_writer)),
+            LogTime::Uptime => {
+                Box::new(layer.with_timer(Uptime::default()).with_writer(make_writer))
+            }
+            LogTime::Rfc3339 => Box::new(layer.with_timer(SystemTime).with_writer(make_writer)),
+            LogTime::Local => Box::new(
+                layer
+                    .with_timer(LocalTime::rfc_3339())
+                    .with_writer(make_writer),
+            ),
+        }
+    }
+}
+
+/// The field formatter of the fmt layer, see `<PREFIX>_LOG_FLATTEN_FIELDS`.
+///
+/// When `flatten` is set, the fields are formatted by [`DefaultFields`]:
+/// every field follows the message inline. Otherwise the fields other than
+/// the message are grouped in braces after it. Fields are only grouped when
+/// there is a message to set them apart from, so the fields of a span, which
+/// are already grouped after its name (`unit{name=main}`), stay inline.
+#[derive(Debug, Clone, Copy)]
+struct EventFields {
+    flatten: bool,
+}
+
+impl<'writer> FormatFields<'writer> for EventFields {
+    fn format_fields<R: RecordFields>(
+        &self,
+        mut writer: Writer<'writer>,
+        fields: R,
+    ) -> std::fmt::Result {
+        if self.flatten {
+            return DefaultFields::new().format_fields(writer, fields);
+        }
+
+        let mut visitor = GroupedFields::default();
+        fields.record(&mut visitor);
+        match visitor.message {
+            Some(message) if visitor.fields.is_empty() => write!(writer, "{}", message),
+            Some(message) => write!(writer, "{} {{{}}}", message, visitor.fields.join(" ")),
+            None => write!(writer, "{}", visitor.fields.join(" ")),
+        }
+    }
+}
+
+/// Collects the message and the `name=value` pairs of the other fields.
+#[derive(Default)]
+struct GroupedFields {
+    message: Option<String>,
+    fields: Vec<String>,
+}
+
+impl tracing::field::Visit for GroupedFields {
+    fn record_str(&mut self, field: &tracing::field::Field, value: &str) {
+        if field.name() == "message" {
+            self.message = Some(value.to_string());
+        } else {
+            self.record_debug(field, &value);
+        }
+    }
+
+    fn record_debug(&mut self, field: &tracing::field::Field, value: &dyn Debug) {
+        match field.name() {
+            "message" => self.message = Some(format!("{:?}", value)),
+            // The metadata of `log` records, as skipped by `DefaultFields`.
+            name if name.starts_with("log.") => {}
+            name => self.fields.push(format!("{}={:?}", name, value)),
+        }
+    }
+}
+
+/// A file writer that syncs the file to disk after every event.
+///
+/// The fmt layer asks for a new writer for each event, so syncing when the
+/// writer is dropped makes every event durable before logging continues.
+/// This is what `<PREFIX>_LOG_SYNC=1` installs for file writers.
+#[derive(Debug)]
+pub struct SyncFileWriter {
+    file: File,
+}
+
+impl SyncFileWriter {
+    /// Create a new syncing writer for the given file.
+    pub fn new(file: File) -> Self {
+        SyncFileWriter { file }
+    }
+}
+
+impl<'a> MakeWriter<'a> for SyncFileWriter {
+    type Writer = SyncFileGuard<'a>;
+
+    fn make_writer(&'a self) -> Self::Writer {
+        SyncFileGuard { file: &self.file }
+    }
+}
+
+/// The writer of a single event for a [`SyncFileWriter`].
+/// The file is flushed and synced when the guard is dropped.
+#[derive(Debug)]
+pub struct SyncFileGuard<'a> {
+    file: &'a File,
+}
+
+impl Write for SyncFileGuard<'_> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.file.write(buf)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.file.flush()
+    }
+}
+
+impl Drop for SyncFileGuard<'_> {
+    fn drop(&mut self) {
+        // Errors cannot be reported from a log writer; the event has already
+        // been handed to the OS by `write`.
+        let _ = self.file.flush();
+        let _ = self.file.sync_data();
+    }
+}
+
+/// Decides whether to color the log when the color setting is "auto".
+///
+/// Color is used only if `stderr` is a terminal that understands ANSI escape
+/// codes. On Windows this also requires enabling virtual terminal processing
+/// (see [`enable_virtual_terminal`]); when that fails, e.g. on consoles older
+/// than Windows 10, color is disabled.
+pub fn auto_color() -> bool {
+    if !std::io::stderr().is_terminal() {
+        return false;
+    }
+    #[cfg(windows)]
+    {
+        enable_virtual_terminal()
+    }
+    #[cfg(not(windows))]
+    {
+        true
+    }
+}
+
+/// Enables virtual terminal processing on the console attached to `stderr`,
+/// so that it interprets ANSI escape codes instead of printing them.
+///
+/// Returns `true` if the mode is enabled, including when it already was.
+#[cfg(windows)]
+pub fn enable_virtual_terminal() -> bool {
+    use windows_sys::Win32::Foundation::INVALID_HANDLE_VALUE;
+    use windows_sys::Win32::System::Console::{
+        ENABLE_VIRTUAL_TERMINAL_PROCESSING, GetConsoleMode, GetStdHandle, STD_ERROR_HANDLE,
+        SetConsoleMode,
+    };
+
+    // SAFETY: `GetStdHandle` has no preconditions, and the console mode
+    // functions only fail (returning zero) on a handle that is not a console.
+    unsafe {
+        let handle = GetStdHandle(STD_ERROR_HANDLE);
+        if handle.is_null() || handle == INVALID_HANDLE_VALUE {
+            return false;
+        }
+        let mut mode = 0;
+        if GetConsoleMode(handle, &mut mode) == 0 {
+            return false;
         }
+        mode & ENABLE_VIRTUAL_TERMINAL_PROCESSING != 0
+            || SetConsoleMode(handle, mode | ENABLE_VIRTUAL_TERMINAL_PROCESSING) != 0
     }
 }
 
+/// Create an `INFO` span carrying the name of a compilation unit.
+///
+/// Events emitted while the returned span is entered are tagged with the unit
+/// name by the fmt layer, and its creation and closing are logged according to
+/// the span-event configuration of [`Logger::init_logger`].
+pub fn unit_span(name: &str) -> tracing::Span {
+    tracing::info_span!("unit", name = %name)
+}
+
 impl std::error::Error for LogError {}
 
+/// Returns `true` if `err` was caused by a global subscriber (or a `log`
+/// logger) having already been installed.
+///
+/// `TryInitError` does not expose its cause, so we inspect its message.
+fn is_already_initialized(err: &TryInitError) -> bool {
+    let message = err.to_string();
+    message.contains("already been set") || message.contains("already initialized")
+}
+
 impl std::fmt::Display for LogError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             LogError::ColorNotValid(s) => write!(f, "Color not valid: {}", s),
             LogError::NotUnicode(s) => write!(f, "Not unicode: {}", s),
             LogError::IoError(e) => write!(f, "IO error: {}", e),
-            LogError::TryInitError(e) => write!(f, "TryInit error: {:?}", e),
+            LogError::TryInitError(e) if is_already_initialized(e) => write!(
+                f,
+                "failed to set global logger: another subscriber is already installed"
+            ),
+            LogError::TryInitError(e) => write!(f, "failed to set global logger: {}", e),
+            LogError::WriterNotValid(s) => write!(f, "Log writer not valid: {}", s),
+            LogError::SyslogUnsupported => write!(f, "Syslog is only supported on Unix"),
+            LogError::JournaldUnsupported => {
+                write!(
+                    f,
+                    "The systemd journal is only supported on Linux, with the `journald` feature"
+                )
+            }
+            LogError::TimeFormatNotValid(s) => write!(f, "Time format not valid: {}", s),
         }
     }
 }
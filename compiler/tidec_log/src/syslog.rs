@@ -0,0 +1,160 @@
+//! A log writer sending events to the local syslog daemon.
+//!
+//! Events are sent as datagrams to the `/dev/log` socket in the BSD syslog
+//! format (`<PRI>ident[pid]: message`), so no dependency on `libc` is needed.
+
+use std::{io::Write, os::unix::net::UnixDatagram, str::FromStr};
+use tracing::{Level, Metadata};
+use tracing_subscriber::fmt::MakeWriter;
+
+/// The path of the socket the syslog daemon listens on.
+const SYSLOG_SOCKET: &str = "/dev/log";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// The syslog facility, i.e. the kind of program that logs the message.
+pub enum SyslogFacility {
+    Kern = 0,
+    User = 1,
+    Mail = 2,
+    Daemon = 3,
+    Auth = 4,
+    Syslog = 5,
+    Lpr = 6,
+    News = 7,
+    Uucp = 8,
+    Cron = 9,
+    AuthPriv = 10,
+    Ftp = 11,
+    Local0 = 16,
+    Local1 = 17,
+    Local2 = 18,
+    Local3 = 19,
+    Local4 = 20,
+    Local5 = 21,
+    Local6 = 22,
+    Local7 = 23,
+}
+
+impl FromStr for SyslogFacility {
+    type Err = ();
+
+    /// Parses a facility from its lowercase syslog name, e.g. `daemon`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            "kern" => SyslogFacility::Kern,
+            "user" => SyslogFacility::User,
+            "mail" => SyslogFacility::Mail,
+            "daemon" => SyslogFacility::Daemon,
+            "auth" => SyslogFacility::Auth,
+            "syslog" => SyslogFacility::Syslog,
+            "lpr" => SyslogFacility::Lpr,
+            "news" => SyslogFacility::News,
+            "uucp" => SyslogFacility::Uucp,
+            "cron" => SyslogFacility::Cron,
+            "authpriv" => SyslogFacility::AuthPriv,
+            "ftp" => SyslogFacility::Ftp,
+            "local0" => SyslogFacility::Local0,
+            "local1" => SyslogFacility::Local1,
+            "local2" => SyslogFacility::Local2,
+            "local3" => SyslogFacility::Local3,
+            "local4" => SyslogFacility::Local4,
+            "local5" => SyslogFacility::Local5,
+            "local6" => SyslogFacility::Local6,
+            "local7" => SyslogFacility::Local7,
+            _ => return Err(()),
+        })
+    }
+}
+
+/// A writer factory sending every event to syslog as a single message.
+///
+/// This is what `<PREFIX>_LOG_WRITER=syslog:<facility>:<ident>` installs.
+#[derive(Debug)]
+pub struct SyslogWriter {
+    socket: UnixDatagram,
+    facility: SyslogFacility,
+    ident: String,
+    pid: u32,
+}
+
+impl SyslogWriter {
+    /// Connect to the local syslog daemon, tagging messages with `ident`.
+    pub fn connect(facility: SyslogFacility, ident: String) -> std::io::Result<Self> {
+        let socket = UnixDatagram::unbound()?;
+        socket.connect(SYSLOG_SOCKET)?;
+        Ok(SyslogWriter {
+            socket,
+            facility,
+            ident,
+            pid: std::process::id(),
+        })
+    }
+
+    fn guard(&self, severity: u8) -> SyslogGuard<'_> {
+        SyslogGuard {
+            writer: self,
+            severity,
+            buf: Vec::new(),
+        }
+    }
+}
+
+/// Maps a `tracing` level to a syslog severity.
+fn severity_of(level: &Level) -> u8 {
+    match *level {
+        Level::ERROR => 3,
+        Level::WARN => 4,
+        Level::INFO => 6,
+        Level::DEBUG | Level::TRACE => 7,
+    }
+}
+
+impl<'a> MakeWriter<'a> for SyslogWriter {
+    type Writer = SyslogGuard<'a>;
+
+    fn make_writer(&'a self) -> Self::Writer {
+        self.guard(severity_of(&Level::INFO))
+    }
+
+    fn make_writer_for(&'a self, meta: &Metadata<'_>) -> Self::Writer {
+        self.guard(severity_of(meta.level()))
+    }
+}
+
+/// The writer of a single event for a [`SyslogWriter`].
+/// The event is buffered and sent as one message when the guard is dropped.
+#[derive(Debug)]
+pub struct SyslogGuard<'a> {
+    writer: &'a SyslogWriter,
+    severity: u8,
+    buf: Vec<u8>,
+}
+
+impl Write for SyslogGuard<'_> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.buf.extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+impl Drop for SyslogGuard<'_> {
+    fn drop(&mut self) {
+        let message = String::from_utf8_lossy(&self.buf);
+        let message = message.trim_end_matches('\n');
+        if message.is_empty() {
+            return;
+        }
+        let priority = (self.writer.facility as u8) * 8 + self.severity;
+        let datagram = format!(
+            "<{}>{}[{}]: {}",
+            priority, self.writer.ident, self.writer.pid, message
+        );
+        // Errors cannot be reported from a log writer; a missing or busy
+        // daemon drops the message.
+        let _ = self.writer.socket.send(datagram.as_bytes());
+    }
+}
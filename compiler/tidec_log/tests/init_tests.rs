@@ -0,0 +1,18 @@
+//! Tests for initializing the global logger.
+//! They live in their own test binary, as they install the global subscriber.
+
+use tidec_log::{FallbackDefaultEnv, LogError, Logger, LoggerConfig};
+
+#[test]
+fn test_double_init_reports_existing_subscriber() {
+    let config = || LoggerConfig::from_prefix("TEST_DOUBLE_INIT").unwrap();
+
+    Logger::init_logger(config(), FallbackDefaultEnv::No).unwrap();
+    let err = Logger::init_logger(config(), FallbackDefaultEnv::No).unwrap_err();
+
+    assert!(matches!(err, LogError::TryInitError(_)), "error: {:?}", err);
+    assert_eq!(
+        err.to_string(),
+        "failed to set global logger: another subscriber is already installed"
+    );
+}
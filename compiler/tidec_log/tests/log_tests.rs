@@ -1,5 +1,14 @@
 use std::env;
-use tidec_log::{FallbackDefaultEnv, LogError, LogWriter, Logger, LoggerConfig};
+use tidec_log::{
+    FallbackDefaultEnv, LayerOptions, LogError, LogTime, LogWriter, Logger, LoggerConfig,
+    SyncFileWriter,
+};
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::prelude::*;
+
+mod common;
+
+use common::CapturingWriter;
 
 #[test]
 fn test_log_writer_variants() {
@@ -31,8 +40,9 @@ fn test_logger_config_from_prefix() {
     assert!(config.color.is_err());
     assert!(config.line_numbers.is_err());
     assert!(config.file_names.is_err());
+    assert!(config.sync.is_err());
 
-    matches!(config.log_writer, LogWriter::Stderr);
+    assert!(matches!(config.log_writers.as_slice(), [LogWriter::Stderr]));
 }
 
 #[test]
@@ -43,6 +53,7 @@ fn test_logger_config_from_prefix_with_env_vars() {
         env::set_var("TEST_PREFIX_LOG_WRITER", "stdout");
         env::set_var("TEST_PREFIX_LOG_LINE_NUMBERS", "1");
         env::set_var("TEST_PREFIX_LOG_FILE_NAMES", "1");
+        env::set_var("TEST_PREFIX_LOG_SYNC", "1");
     }
 
     let config = LoggerConfig::from_prefix("TEST_PREFIX").unwrap();
@@ -51,8 +62,9 @@ fn test_logger_config_from_prefix_with_env_vars() {
     assert_eq!(config.color.unwrap(), "always");
     assert_eq!(config.line_numbers.unwrap(), "1");
     assert_eq!(config.file_names.unwrap(), "1");
+    assert_eq!(config.sync.unwrap(), "1");
 
-    matches!(config.log_writer, LogWriter::Stdout);
+    assert!(matches!(config.log_writers.as_slice(), [LogWriter::Stdout]));
 
     unsafe {
         env::remove_var("TEST_PREFIX_LOG");
@@ -60,6 +72,7 @@ fn test_logger_config_from_prefix_with_env_vars() {
         env::remove_var("TEST_PREFIX_LOG_WRITER");
         env::remove_var("TEST_PREFIX_LOG_LINE_NUMBERS");
         env::remove_var("TEST_PREFIX_LOG_FILE_NAMES");
+        env::remove_var("TEST_PREFIX_LOG_SYNC");
     }
 }
 
@@ -69,7 +82,7 @@ fn test_logger_config_writer_variants() {
         env::set_var("TEST_WRITER_LOG_WRITER", "stdout");
     }
     let config = LoggerConfig::from_prefix("TEST_WRITER").unwrap();
-    matches!(config.log_writer, LogWriter::Stdout);
+    assert!(matches!(config.log_writers.as_slice(), [LogWriter::Stdout]));
     unsafe {
         env::remove_var("TEST_WRITER_LOG_WRITER");
     }
@@ -78,7 +91,7 @@ fn test_logger_config_writer_variants() {
         env::set_var("TEST_WRITER2_LOG_WRITER", "stderr");
     }
     let config = LoggerConfig::from_prefix("TEST_WRITER2").unwrap();
-    matches!(config.log_writer, LogWriter::Stderr);
+    assert!(matches!(config.log_writers.as_slice(), [LogWriter::Stderr]));
     unsafe {
         env::remove_var("TEST_WRITER2_LOG_WRITER");
     }
@@ -87,7 +100,7 @@ fn test_logger_config_writer_variants() {
         env::set_var("TEST_WRITER3_LOG_WRITER", "/tmp/test.log");
     }
     let config = LoggerConfig::from_prefix("TEST_WRITER3").unwrap();
-    if let LogWriter::File(path) = config.log_writer {
+    if let [LogWriter::File(path)] = config.log_writers.as_slice() {
         assert_eq!(path.to_str().unwrap(), "/tmp/test.log");
     } else {
         panic!("Expected File writer");
@@ -97,6 +110,240 @@ fn test_logger_config_writer_variants() {
     }
 }
 
+#[test]
+fn test_log_writer_from_str() {
+    assert!(matches!(
+        "stdout".parse::<LogWriter>(),
+        Ok(LogWriter::Stdout)
+    ));
+    assert!(matches!(
+        "stderr".parse::<LogWriter>(),
+        Ok(LogWriter::Stderr)
+    ));
+    for path in ["out.log", "/tmp/tidec/out.log", "STDOUT", "stdout2"] {
+        match path.parse::<LogWriter>() {
+            Ok(LogWriter::File(p)) => assert_eq!(p.to_str().unwrap(), path),
+            other => panic!("Expected File writer for {:?}, got {:?}", path, other),
+        }
+    }
+}
+
+#[test]
+fn test_log_writer_try_from_matches_from_str() {
+    assert!(matches!(
+        LogWriter::try_from("stdout"),
+        Ok(LogWriter::Stdout)
+    ));
+    assert!(matches!(
+        LogWriter::try_from("stderr"),
+        Ok(LogWriter::Stderr)
+    ));
+    assert!(matches!(
+        LogWriter::try_from("test.log"),
+        Ok(LogWriter::File(p)) if p.to_str() == Some("test.log")
+    ));
+}
+
+#[test]
+fn test_log_writer_parse_list() {
+    match LogWriter::parse_list("stderr, out.log,stdout") {
+        Ok(writers) => assert!(
+            matches!(
+                writers.as_slice(),
+                [LogWriter::Stderr, LogWriter::File(p), LogWriter::Stdout] if p.to_str() == Some("out.log")
+            ),
+            "{:?}",
+            writers
+        ),
+        Err(err) => panic!("Expected a list of writers, got {:?}", err),
+    }
+    for list in ["", "stderr,", "stderr,,stdout"] {
+        assert!(
+            matches!(
+                LogWriter::parse_list(list),
+                Err(LogError::WriterNotValid(_))
+            ),
+            "{:?} should not be a valid list",
+            list
+        );
+    }
+}
+
+#[test]
+fn test_build_layer_writes_to_every_writer() {
+    let path =
+        |n: u32| env::temp_dir().join(format!("tidec_log_multi_{}_{}.log", n, std::process::id()));
+    let config = LoggerConfig {
+        log_writers: vec![LogWriter::File(path(1)), LogWriter::File(path(2))],
+        filter: Ok("info".to_string()),
+        color: Ok("never".to_string()),
+        line_numbers: Err(env::VarError::NotPresent),
+        file_names: Err(env::VarError::NotPresent),
+        sync: Ok("1".to_string()),
+        time: LogTime::None,
+        flatten_fields: Err(env::VarError::NotPresent),
+        default_directives: Vec::new(),
+    };
+
+    let subscriber = tracing_subscriber::registry().with(Logger::build_layer(config).unwrap());
+    tracing::subscriber::with_default(subscriber, || tracing::info!("shared line"));
+
+    for n in [1, 2] {
+        let output = std::fs::read_to_string(path(n)).unwrap();
+        std::fs::remove_file(path(n)).unwrap();
+        assert!(output.contains("shared line"), "output: {}", output);
+    }
+}
+
+#[cfg(unix)]
+#[test]
+fn test_logger_config_syslog_writer() {
+    unsafe {
+        env::set_var("TEST_SYSLOG_LOG_WRITER", "syslog:daemon:mytool");
+    }
+    let config = LoggerConfig::from_prefix("TEST_SYSLOG").unwrap();
+    match config.log_writers.as_slice() {
+        [LogWriter::Syslog { facility, ident }] => {
+            assert_eq!(*facility, tidec_log::SyslogFacility::Daemon);
+            assert_eq!(ident, "mytool");
+        }
+        other => panic!("Expected Syslog writer, got {:?}", other),
+    }
+    unsafe {
+        env::remove_var("TEST_SYSLOG_LOG_WRITER");
+    }
+}
+
+#[cfg(unix)]
+#[test]
+fn test_syslog_writer_spec_errors() {
+    for spec in ["syslog:nope:mytool", "syslog:daemon", "syslog:daemon:"] {
+        assert!(
+            matches!(spec.parse::<LogWriter>(), Err(LogError::WriterNotValid(s)) if s == spec),
+            "Expected {} to be rejected",
+            spec
+        );
+    }
+    assert!(matches!(
+        "syslog:local3:a:b".parse::<LogWriter>(),
+        Ok(LogWriter::Syslog { facility: tidec_log::SyslogFacility::Local3, ident }) if ident == "a:b"
+    ));
+}
+
+#[cfg(not(unix))]
+#[test]
+fn test_syslog_writer_is_unsupported() {
+    assert!(matches!(
+        "syslog:daemon:mytool".parse::<LogWriter>(),
+        Err(LogError::SyslogUnsupported)
+    ));
+}
+
+#[cfg(all(target_os = "linux", feature = "journald"))]
+#[test]
+fn test_journald_writer_is_parsed() {
+    assert!(matches!(
+        "journald".parse::<LogWriter>(),
+        Ok(LogWriter::Journald)
+    ));
+}
+
+#[cfg(not(all(target_os = "linux", feature = "journald")))]
+#[test]
+fn test_journald_writer_is_unsupported() {
+    assert!(matches!(
+        "journald".parse::<LogWriter>(),
+        Err(LogError::JournaldUnsupported)
+    ));
+}
+
+#[test]
+fn test_logger_config_time_formats() {
+    let cases = [
+        ("none", LogTime::None),
+        ("uptime", LogTime::Uptime),
+        ("rfc3339", LogTime::Rfc3339),
+        ("local", LogTime::Local),
+    ];
+    for (value, time) in cases {
+        unsafe {
+            env::set_var("TEST_TIME_LOG_TIME", value);
+        }
+        let config = LoggerConfig::from_prefix("TEST_TIME").unwrap();
+        assert_eq!(config.time, time, "parsing `{}`", value);
+    }
+    unsafe {
+        env::remove_var("TEST_TIME_LOG_TIME");
+    }
+
+    let config = LoggerConfig::from_prefix("TEST_TIME_UNSET").unwrap();
+    assert_eq!(config.time, LogTime::Rfc3339);
+}
+
+#[test]
+fn test_logger_config_invalid_time_format() {
+    unsafe {
+        env::set_var("TEST_BAD_TIME_LOG_TIME", "yesterday");
+    }
+    let result = LoggerConfig::from_prefix("TEST_BAD_TIME");
+    assert!(
+        matches!(result, Err(LogError::TimeFormatNotValid(ref s)) if s == "yesterday"),
+        "Expected an invalid time format error"
+    );
+    unsafe {
+        env::remove_var("TEST_BAD_TIME_LOG_TIME");
+    }
+}
+
+#[test]
+fn test_layer_options_from_full_config() {
+    let config = LoggerConfig {
+        log_writers: vec![LogWriter::Stderr],
+        filter: Ok("debug".to_string()),
+        color: Ok("always".to_string()),
+        line_numbers: Ok("1".to_string()),
+        file_names: Ok("1".to_string()),
+        sync: Ok("1".to_string()),
+        time: LogTime::Uptime,
+        flatten_fields: Ok("0".to_string()),
+        default_directives: Vec::new(),
+    };
+
+    let options = LayerOptions::from_config(&config).unwrap();
+    assert_eq!(
+        options.color_log,
+        !cfg!(any(feature = "no-color", not(feature = "ansi")))
+    );
+    assert!(options.line_numbers);
+    assert!(options.file_names);
+    assert!(options.sync);
+    assert_eq!(options.time, LogTime::Uptime);
+    assert!(!options.flatten_fields);
+    assert_eq!(options.span_events, FmtSpan::NEW | FmtSpan::CLOSE);
+}
+
+#[cfg(all(feature = "ansi", not(feature = "no-color")))]
+#[test]
+fn test_layer_options_invalid_color() {
+    let config = LoggerConfig {
+        log_writers: vec![LogWriter::Stderr],
+        filter: Err(env::VarError::NotPresent),
+        color: Ok("sometimes".to_string()),
+        line_numbers: Ok("0".to_string()),
+        file_names: Err(env::VarError::NotPresent),
+        sync: Err(env::VarError::NotPresent),
+        time: LogTime::default(),
+        flatten_fields: Err(env::VarError::NotPresent),
+        default_directives: Vec::new(),
+    };
+
+    let result = LayerOptions::from_config(&config);
+    assert!(
+        matches!(result, Err(LogError::ColorNotValid(ref s)) if s == "sometimes"),
+        "Expected an invalid color error"
+    );
+}
+
 #[test]
 fn test_fallback_default_env() {
     let yes = FallbackDefaultEnv::Yes;
@@ -144,3 +391,263 @@ fn test_config_is_send_sync() {
     // Commented out as LogWriter contains PathBuf which should be Send + Sync
     // assert_send_sync::<LoggerConfig>();
 }
+
+#[test]
+fn test_unit_span_tags_events_with_unit_name() {
+    let writer = CapturingWriter::default();
+    let subscriber = tracing_subscriber::fmt()
+        .with_writer(writer.clone())
+        .with_ansi(false)
+        .finish();
+
+    tracing::subscriber::with_default(subscriber, || {
+        let span = tidec_log::unit_span("my_unit");
+        let _enter = span.enter();
+        tracing::info!("processing");
+    });
+
+    let output = writer.contents();
+    assert!(output.contains("processing"), "output: {}", output);
+    assert!(output.contains("unit{name=my_unit}"), "output: {}", output);
+}
+
+#[test]
+fn test_sync_file_writer_persists_each_event() {
+    let path = env::temp_dir().join(format!("tidec_log_sync_{}.log", std::process::id()));
+    let file = std::fs::File::create(&path).unwrap();
+    let subscriber = tracing_subscriber::fmt()
+        .with_writer(SyncFileWriter::new(file))
+        .with_ansi(false)
+        .finish();
+
+    tracing::subscriber::with_default(subscriber, || {
+        tracing::info!("synced line");
+        // Read the file back while the subscriber (and its file) is still alive.
+        let output = std::fs::read_to_string(&path).unwrap();
+        assert!(output.contains("synced line"), "output: {}", output);
+    });
+
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn test_build_layer_composes_independent_filters() {
+    let path = |tool: &str| {
+        env::temp_dir().join(format!(
+            "tidec_log_layer_{}_{}.log",
+            tool,
+            std::process::id()
+        ))
+    };
+    let config = |tool: &str, filter: &str| LoggerConfig {
+        log_writers: vec![LogWriter::File(path(tool))],
+        filter: Ok(filter.to_string()),
+        color: Ok("never".to_string()),
+        line_numbers: Err(env::VarError::NotPresent),
+        file_names: Err(env::VarError::NotPresent),
+        sync: Ok("1".to_string()),
+        time: LogTime::None,
+        flatten_fields: Err(env::VarError::NotPresent),
+        default_directives: Vec::new(),
+    };
+
+    let subscriber = tracing_subscriber::registry()
+        .with(Logger::build_layer(config("a", "debug")).unwrap())
+        .with(Logger::build_layer(config("b", "warn")).unwrap());
+
+    tracing::subscriber::with_default(subscriber, || {
+        tracing::debug!("debug line");
+        tracing::warn!("warn line");
+    });
+
+    let output_a = std::fs::read_to_string(path("a")).unwrap();
+    let output_b = std::fs::read_to_string(path("b")).unwrap();
+    std::fs::remove_file(path("a")).unwrap();
+    std::fs::remove_file(path("b")).unwrap();
+
+    assert!(output_a.contains("debug line"), "output: {}", output_a);
+    assert!(output_a.contains("warn line"), "output: {}", output_a);
+    assert!(!output_b.contains("debug line"), "output: {}", output_b);
+    assert!(output_b.contains("warn line"), "output: {}", output_b);
+}
+
+#[test]
+fn test_build_layer_reports_unopenable_writer() {
+    let config = LoggerConfig {
+        log_writers: vec![LogWriter::File(
+            env::temp_dir().join("tidec_log_missing_dir/test.log"),
+        )],
+        filter: Ok("info".to_string()),
+        color: Ok("never".to_string()),
+        line_numbers: Err(env::VarError::NotPresent),
+        file_names: Err(env::VarError::NotPresent),
+        sync: Err(env::VarError::NotPresent),
+        time: LogTime::None,
+        flatten_fields: Err(env::VarError::NotPresent),
+        default_directives: Vec::new(),
+    };
+
+    let layer = Logger::build_layer::<tracing_subscriber::Registry>(config);
+    assert!(matches!(layer, Err(LogError::IoError(_))));
+}
+
+#[cfg(windows)]
+#[test]
+fn test_auto_color_matches_virtual_terminal_support() {
+    use std::io::IsTerminal;
+
+    // Enabling virtual terminal processing is idempotent, so probing it again
+    // yields the same answer `auto_color` got.
+    let expected = std::io::stderr().is_terminal() && tidec_log::enable_virtual_terminal();
+    assert_eq!(tidec_log::auto_color(), expected);
+}
+
+#[cfg(feature = "no-color")]
+#[test]
+fn test_no_color_ignores_color_setting() {
+    for color in ["always", "auto", "sometimes"] {
+        let config = LoggerConfig {
+            log_writers: vec![LogWriter::Stderr],
+            filter: Err(env::VarError::NotPresent),
+            color: Ok(color.to_string()),
+            line_numbers: Err(env::VarError::NotPresent),
+            file_names: Err(env::VarError::NotPresent),
+            sync: Err(env::VarError::NotPresent),
+            time: LogTime::default(),
+            flatten_fields: Err(env::VarError::NotPresent),
+            default_directives: Vec::new(),
+        };
+
+        let options = LayerOptions::from_config(&config).unwrap();
+        assert!(!options.color_log, "color {color:?} enabled color");
+    }
+}
+
+#[test]
+fn test_scoped_to_only_shows_the_named_crate() {
+    unsafe {
+        env::remove_var("TEST_SCOPED_LOG");
+    }
+    let path = env::temp_dir().join(format!("tidec_log_scoped_{}.log", std::process::id()));
+    let mut config = LoggerConfig::from_prefix("TEST_SCOPED").unwrap();
+    config.log_writers = vec![LogWriter::File(path.clone())];
+    config.color = Ok("never".to_string());
+    config.sync = Ok("1".to_string());
+    config.scoped_to("my_tool");
+    assert_eq!(config.default_directives, vec!["my_tool=info".to_string()]);
+
+    let subscriber = tracing_subscriber::registry().with(Logger::build_layer(config).unwrap());
+    tracing::subscriber::with_default(subscriber, || {
+        tracing::info!(target: "my_tool", "own info line");
+        tracing::debug!(target: "my_tool", "own debug line");
+        tracing::info!(target: "noisy_dep", "dependency info line");
+        tracing::error!(target: "noisy_dep", "dependency error line");
+    });
+
+    let output = std::fs::read_to_string(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    assert!(output.contains("own info line"), "output: {}", output);
+    assert!(!output.contains("own debug line"), "output: {}", output);
+    assert!(!output.contains("dependency"), "output: {}", output);
+}
+
+#[test]
+fn test_scoped_to_is_overridden_by_prefix_log() {
+    let path = env::temp_dir().join(format!(
+        "tidec_log_scoped_override_{}.log",
+        std::process::id()
+    ));
+    let mut config = LoggerConfig::from_prefix("TEST_SCOPED_OVERRIDE").unwrap();
+    config.log_writers = vec![LogWriter::File(path.clone())];
+    config.filter = Ok("my_tool=debug,noisy_dep=warn".to_string());
+    config.color = Ok("never".to_string());
+    config.sync = Ok("1".to_string());
+    config.scoped_to("my_tool");
+
+    let subscriber = tracing_subscriber::registry().with(Logger::build_layer(config).unwrap());
+    tracing::subscriber::with_default(subscriber, || {
+        tracing::debug!(target: "my_tool", "own debug line");
+        tracing::info!(target: "noisy_dep", "dependency info line");
+        tracing::warn!(target: "noisy_dep", "dependency warn line");
+    });
+
+    let output = std::fs::read_to_string(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    assert!(output.contains("own debug line"), "output: {}", output);
+    assert!(
+        !output.contains("dependency info line"),
+        "output: {}",
+        output
+    );
+    assert!(
+        output.contains("dependency warn line"),
+        "output: {}",
+        output
+    );
+}
+
+#[test]
+fn test_flatten_fields_parses_the_flag() {
+    unsafe {
+        env::set_var("TEST_FLATTEN_LOG_FLATTEN_FIELDS", "0");
+        env::remove_var("TEST_UNFLATTENED_LOG_FLATTEN_FIELDS");
+    }
+    let grouped = LoggerConfig::from_prefix("TEST_FLATTEN").unwrap();
+    let default = LoggerConfig::from_prefix("TEST_UNFLATTENED").unwrap();
+    unsafe {
+        env::remove_var("TEST_FLATTEN_LOG_FLATTEN_FIELDS");
+    }
+
+    assert_eq!(grouped.flatten_fields.as_deref(), Ok("0"));
+    assert!(!LayerOptions::from_config(&grouped).unwrap().flatten_fields);
+    assert!(default.flatten_fields.is_err());
+    // Fields are flattened unless the flag is "0".
+    assert!(LayerOptions::from_config(&default).unwrap().flatten_fields);
+}
+
+#[test]
+fn test_grouped_fields_are_bracketed() {
+    let path = |mode: &str| {
+        env::temp_dir().join(format!(
+            "tidec_log_fields_{}_{}.log",
+            mode,
+            std::process::id()
+        ))
+    };
+    let config = |mode: &str, flatten: &str| LoggerConfig {
+        log_writers: vec![LogWriter::File(path(mode))],
+        filter: Ok("debug".to_string()),
+        color: Ok("never".to_string()),
+        line_numbers: Err(env::VarError::NotPresent),
+        file_names: Err(env::VarError::NotPresent),
+        sync: Ok("1".to_string()),
+        time: LogTime::None,
+        flatten_fields: Ok(flatten.to_string()),
+        default_directives: Vec::new(),
+    };
+
+    let subscriber = tracing_subscriber::registry()
+        .with(Logger::build_layer(config("flat", "1")).unwrap())
+        .with(Logger::build_layer(config("grouped", "0")).unwrap());
+    tracing::subscriber::with_default(subscriber, || {
+        tracing::debug!(local = 1, kind = "copy", "assigning");
+    });
+
+    let flat = std::fs::read_to_string(path("flat")).unwrap();
+    let grouped = std::fs::read_to_string(path("grouped")).unwrap();
+    std::fs::remove_file(path("flat")).unwrap();
+    std::fs::remove_file(path("grouped")).unwrap();
+
+    assert!(
+        flat.contains("assigning local=1 kind=\"copy\""),
+        "output: {}",
+        flat
+    );
+    assert!(
+        grouped.contains("assigning {local=1 kind=\"copy\"}"),
+        "output: {}",
+        grouped
+    );
+}
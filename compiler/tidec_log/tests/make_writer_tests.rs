@@ -0,0 +1,34 @@
+//! Tests for `Logger::init_with_make_writer`.
+//! They live in their own test binary, as they install the global subscriber.
+
+use tidec_log::{Logger, LoggerConfig};
+
+mod common;
+
+use common::CapturingWriter;
+
+#[test]
+fn test_init_with_make_writer_routes_logs_to_custom_sink() {
+    unsafe {
+        std::env::set_var("TEST_SINK_LOG", "info");
+        std::env::set_var("TEST_SINK_LOG_COLOR", "never");
+    }
+    let config = LoggerConfig::from_prefix("TEST_SINK").unwrap();
+
+    let writer = CapturingWriter::default();
+    Logger::init_with_make_writer(config, writer.clone()).unwrap();
+
+    tracing::info!("hello from the sink");
+    tracing::debug!("filtered out");
+
+    let output = writer.contents();
+    assert!(output.contains("hello from the sink"), "output: {}", output);
+    assert!(!output.contains("filtered out"), "output: {}", output);
+    // Color is disabled, so no ANSI escape codes are emitted.
+    assert!(!output.contains('\u{1b}'), "output: {}", output);
+
+    unsafe {
+        std::env::remove_var("TEST_SINK_LOG");
+        std::env::remove_var("TEST_SINK_LOG_COLOR");
+    }
+}
@@ -0,0 +1,35 @@
+//! Tests for the static max level features.
+//! Run with `cargo test -p tidec_log --features max_level_info`.
+#![cfg(feature = "max_level_info")]
+
+use tracing::level_filters::{LevelFilter, STATIC_MAX_LEVEL};
+
+mod common;
+
+use common::CapturingWriter;
+
+#[test]
+fn test_static_max_level_is_info() {
+    assert_eq!(STATIC_MAX_LEVEL, LevelFilter::INFO);
+}
+
+#[test]
+fn test_debug_is_compiled_out() {
+    let writer = CapturingWriter::default();
+    // The subscriber accepts everything, so only the static filter can drop
+    // the `debug!` event.
+    let subscriber = tracing_subscriber::fmt()
+        .with_writer(writer.clone())
+        .with_max_level(tracing::Level::TRACE)
+        .with_ansi(false)
+        .finish();
+
+    tracing::subscriber::with_default(subscriber, || {
+        tracing::debug!("debug message");
+        tracing::info!("info message");
+    });
+
+    let output = writer.contents();
+    assert!(!output.contains("debug message"), "output: {}", output);
+    assert!(output.contains("info message"), "output: {}", output);
+}
@@ -131,7 +131,7 @@ impl Allocation {
 
     /// Get the size of this allocation in bytes.
     pub fn size(&self) -> Size {
-        Size::from_bits(self.bytes.len() as u64 * 8)
+        Size::from_bytes(self.bytes.len())
     }
 
     /// Get the alignment of this allocation.
@@ -0,0 +1,232 @@
+//! Analyses over TIR bodies.
+//!
+//! Unlike [`passes`](crate::passes), analyses never modify the body: they
+//! compute facts about it that optimizations can then act upon.
+
+use crate::body::TirBody;
+use crate::passes::predecessors;
+use crate::syntax::{
+    BasicBlock, ConstOperand, ConstScalar, ConstValue, Local, Operand, Place, Projection, RValue,
+    RawScalarValue, Statement, Terminator, RETURN_LOCAL,
+};
+use crate::visit::{PlaceContext, Visitor};
+use tidec_utils::{bit_set::BitSet, idx::Idx, index_vec::IdxVec};
+
+/// Computes the constant held by each local, if any.
+///
+/// A local is known when every assignment to it stores the same scalar
+/// constant, either directly (`_1 = const 5_i32`) or by copying a local
+/// known to hold it at that point (`_2 = _1`). Any other write invalidates
+/// the local for the whole body: a non-constant assignment, a store through
+/// a projection other than a dereference, setting its discriminant, binding
+/// it as a block parameter or as the destination of a call. Arguments are written by the caller and
+/// locals whose address is taken may be written through a pointer, so
+/// neither is ever known.
+///
+/// Copies are only resolved within a single block, as there are no joins
+/// yet: every block starts with no local known.
+///
+/// The result covers both `ret_and_args` and `locals`.
+pub fn const_prop(body: &TirBody) -> IdxVec<Local, Option<RawScalarValue>> {
+    struct AddressTaken(BitSet<Local>);
+
+    impl<'ctx> Visitor<'ctx> for AddressTaken {
+        fn visit_local(&mut self, local: Local, context: PlaceContext) {
+            if context == PlaceContext::AddressOf {
+                self.0.insert(local);
+            }
+        }
+    }
+
+    let num_locals = body.ret_and_args.len() + body.locals.len();
+    let mut address_taken = AddressTaken(BitSet::new(num_locals));
+    address_taken.visit_body(body);
+    let address_taken = address_taken.0;
+
+    let mut invalid = address_taken.clone();
+    for arg in 1..=body.arg_count() {
+        invalid.insert(Local::new(arg));
+    }
+
+    let mut known: IdxVec<Local, Option<RawScalarValue>> = IdxVec::from_elem_n(None, num_locals);
+    for data in body.basic_blocks.iter() {
+        for (param, _) in &data.params {
+            invalid.insert(*param);
+        }
+
+        // The values of the locals at the current point of the block.
+        let mut current: IdxVec<Local, Option<RawScalarValue>> =
+            IdxVec::from_elem_n(None, num_locals);
+        for stmt in &data.statements {
+            match stmt {
+                Statement::Assign(assign) => {
+                    let (place, rvalue) = &**assign;
+                    let Some(local) = place.try_local() else {
+                        // A store through a pointer only writes locals whose
+                        // address is taken, which are never known.
+                        if place.projection.first() != Some(&Projection::Deref) {
+                            invalid.insert(place.local);
+                            current[place.local] = None;
+                        }
+                        continue;
+                    };
+                    let value = match rvalue {
+                        RValue::Operand(Operand::Const(ConstOperand::Value(
+                            ConstValue::Scalar(ConstScalar::Value(raw)),
+                            _,
+                        ))) => Some(*raw),
+                        RValue::Operand(Operand::Use(src)) => {
+                            src.try_local().and_then(|src| current[src])
+                        }
+                        _ => None,
+                    };
+                    match value {
+                        Some(value) if known[local].is_none_or(|known| known == value) => {
+                            known[local] = Some(value);
+                        }
+                        _ => {
+                            invalid.insert(local);
+                        }
+                    }
+                    if !address_taken.contains(local) {
+                        current[local] = value;
+                    }
+                }
+                Statement::SetDiscriminant { place, .. } => {
+                    // Like a store through a projection, setting the tag
+                    // writes the local unless it goes through a pointer.
+                    if place.projection.first() != Some(&Projection::Deref) {
+                        invalid.insert(place.local);
+                        current[place.local] = None;
+                    }
+                }
+            }
+        }
+
+        if let Terminator::Call { destination, .. } = &data.terminator {
+            invalid.insert(destination.local);
+        }
+    }
+
+    for local in invalid.iter() {
+        known[local] = None;
+    }
+    known
+}
+
+/// The locals live on entry to and on exit from each basic block, computed
+/// by [`liveness`].
+#[derive(Debug)]
+pub struct Liveness {
+    live_in: IdxVec<BasicBlock, BitSet<Local>>,
+    live_out: IdxVec<BasicBlock, BitSet<Local>>,
+}
+
+impl Liveness {
+    /// Returns the locals whose value on entry to `bb` may be read before
+    /// being overwritten.
+    pub fn live_in(&self, bb: BasicBlock) -> &BitSet<Local> {
+        &self.live_in[bb]
+    }
+
+    /// Returns the locals live on entry to any successor of `bb`.
+    pub fn live_out(&self, bb: BasicBlock) -> &BitSet<Local> {
+        &self.live_out[bb]
+    }
+}
+
+/// The locals a statement or terminator reads (`uses`) and fully overwrites
+/// (`defs`).
+struct DefUse {
+    defs: BitSet<Local>,
+    uses: BitSet<Local>,
+}
+
+impl<'ctx> Visitor<'ctx> for DefUse {
+    fn visit_terminator(&mut self, terminator: &Terminator<'ctx>) {
+        // Returning reads the return place.
+        if let Terminator::Return = terminator {
+            self.uses.insert(RETURN_LOCAL);
+        }
+        self.super_terminator(terminator);
+    }
+
+    fn visit_place(&mut self, place: &Place<'ctx>, context: PlaceContext) {
+        // A store through a projection only writes part of the local (or
+        // goes through a pointer), so it reads the local instead.
+        match (context, place.try_local()) {
+            (PlaceContext::Store, Some(local)) => {
+                self.defs.insert(local);
+            }
+            _ => self.super_place(place, context),
+        }
+    }
+
+    fn visit_local(&mut self, local: Local, _context: PlaceContext) {
+        self.uses.insert(local);
+    }
+}
+
+/// Computes the live locals at the boundaries of every basic block.
+///
+/// A local is live at a point if its current value may be read later on
+/// some path through the CFG. This is a backward dataflow analysis: the
+/// locals live out of a block are those live into any of its successors,
+/// and the locals live into a block are those it reads before overwriting
+/// them, plus those live out of it that it does not overwrite.
+///
+/// Only a store to the whole local (an assignment or a call destination
+/// without projections, or a block parameter) overwrites it. Taking the
+/// address of a local counts as a read, and `Return` reads the return
+/// place.
+pub fn liveness(body: &TirBody) -> Liveness {
+    let num_locals = body.ret_and_args.len() + body.locals.len();
+    let num_blocks = body.basic_blocks.len();
+    let empty = BitSet::new(num_locals);
+
+    // The locals each block reads before writing (`gen`) and writes (`kill`).
+    let mut gen_sets = IdxVec::from_elem_n(empty.clone(), num_blocks);
+    let mut kill_sets = IdxVec::from_elem_n(empty.clone(), num_blocks);
+    for (bb, data) in body.basic_blocks.iter_enumerated() {
+        let (gen_set, kill_set) = (&mut gen_sets[bb], &mut kill_sets[bb]);
+        let mut transfer = |visit: &dyn Fn(&mut DefUse)| {
+            let mut def_use = DefUse {
+                defs: empty.clone(),
+                uses: empty.clone(),
+            };
+            visit(&mut def_use);
+            gen_set.subtract(&def_use.defs);
+            gen_set.union_with(&def_use.uses);
+            kill_set.union_with(&def_use.defs);
+        };
+        transfer(&|def_use| def_use.visit_terminator(&data.terminator));
+        for statement in data.statements.iter().rev() {
+            transfer(&|def_use| def_use.visit_statement(statement));
+        }
+        for (param, _) in &data.params {
+            gen_set.remove(*param);
+            kill_set.insert(*param);
+        }
+    }
+
+    let preds = predecessors(body);
+    let mut live_in = IdxVec::from_elem_n(empty.clone(), num_blocks);
+    let mut live_out = IdxVec::from_elem_n(empty, num_blocks);
+    let mut worklist: Vec<BasicBlock> = body.basic_blocks.indices().collect();
+    while let Some(bb) = worklist.pop() {
+        let mut out = live_out[bb].clone();
+        for succ in body.basic_blocks[bb].terminator.successors() {
+            out.union_with(&live_in[succ]);
+        }
+        let mut in_set = out.clone();
+        in_set.subtract(&kill_sets[bb]);
+        in_set.union_with(&gen_sets[bb]);
+        live_out[bb] = out;
+        if in_set != live_in[bb] {
+            live_in[bb] = in_set;
+            worklist.extend(preds[bb].iter().copied());
+        }
+    }
+
+    Liveness { live_in, live_out }
+}
@@ -1,10 +1,44 @@
-use crate::syntax::{BasicBlock, BasicBlockData, ConstValue, Local, LocalData};
-use crate::TirTy;
-use tidec_utils::{idx::Idx, index_vec::IdxVec};
+use crate::alloc::{AllocId, GlobalAlloc};
+use crate::ctx::TirCtx;
+use crate::syntax::{
+    AggregateKind, BasicBlock, BasicBlockData, BinaryOp, ConstOperand, ConstValue, Local,
+    LocalData, Location, Operand, Place, Projection, RValue, Statement, Terminator, RETURN_LOCAL,
+};
+use crate::visit::{MutVisitor, PlaceContext, Visitor};
+use crate::{ty, TirTy};
+use std::cell::Cell;
+use std::collections::{HashMap, HashSet};
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::mem;
+use tidec_abi::size_and_align::{Align, Size};
+use tidec_utils::{bit_set::BitSet, idx::Idx, index_vec::IdxVec};
 
 #[derive(Debug, Eq, PartialEq, Hash, Clone, Copy)]
 pub struct DefId(pub usize);
 
+/// Hands out [`DefId`]s in increasing order, starting from `DefId(0)`.
+///
+/// Every [`InternCtx`](crate::ctx::InternCtx) owns one, reachable through
+/// [`TirCtx::fresh_def_id`](crate::ctx::TirCtx::fresh_def_id), so that all
+/// the bodies of a session get distinct ids.
+#[derive(Debug, Default)]
+pub struct DefIdAllocator {
+    next: Cell<usize>,
+}
+
+impl DefIdAllocator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns a `DefId` never returned before by this allocator.
+    pub fn fresh(&self) -> DefId {
+        let id = self.next.get();
+        self.next.set(id + 1);
+        DefId(id)
+    }
+}
+
 #[derive(Clone, Copy, Debug)]
 /// Specifies the linkage of a symbol.
 /// All Global Variables and Functions have one of the following types of linkage.
@@ -206,6 +240,27 @@ pub enum CallConv {
     MaxID = 1023,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// How the program starts executing an entry-point function.
+pub enum EntryKind {
+    /// The C `main` function, called by the C runtime once it has set up
+    /// the process.
+    Main,
+    /// The raw `_start` symbol, jumped to by the loader with no runtime set
+    /// up. Such a function must never return.
+    Start,
+}
+
+impl EntryKind {
+    /// The symbol name the linker expects for this entry point.
+    pub fn symbol_name(self) -> &'static str {
+        match self {
+            EntryKind::Main => "main",
+            EntryKind::Start => "_start",
+        }
+    }
+}
+
 /// The kind of a TIR body.
 // TODO(bruzzone): add other kinds of body; e.g. virtual function, fn pointer, etc.
 // See: rustc_middle::ty::InstanceKind
@@ -237,6 +292,11 @@ pub struct TirBodyMetadata {
     /// Whether this is just a declaration (external function without body).
     /// If true, no code will be generated for the body.
     pub is_declaration: bool,
+    /// Whether this function is the program entry point, and of which kind.
+    ///
+    /// An entry point is emitted under the symbol name of its `EntryKind`
+    /// with external linkage, whatever its `name` and `linkage` are.
+    pub entry_point: Option<EntryKind>,
 }
 
 impl TirBodyMetadata {
@@ -251,6 +311,7 @@ impl TirBodyMetadata {
     /// - `call_conv`: `CallConv::C`
     /// - `is_varargs`: `false`
     /// - `is_declaration`: `false`
+    /// - `entry_point`: `None`
     ///
     /// # Example
     ///
@@ -269,6 +330,28 @@ impl TirBodyMetadata {
             call_conv: CallConv::C,
             is_varargs: false,
             is_declaration: false,
+            entry_point: None,
+        }
+    }
+
+    /// The symbol name of the function in the emitted module.
+    ///
+    /// This is `name`, unless the function is an entry point.
+    pub fn symbol_name(&self) -> &str {
+        match self.entry_point {
+            Some(entry_kind) => entry_kind.symbol_name(),
+            None => &self.name,
+        }
+    }
+
+    /// The linkage of the function in the emitted module.
+    ///
+    /// This is `linkage`, unless the function is an entry point, which must
+    /// be visible to the linker.
+    pub fn effective_linkage(&self) -> Linkage {
+        match self.entry_point {
+            Some(_) => Linkage::External,
+            None => self.linkage,
         }
     }
 }
@@ -277,6 +360,17 @@ impl TirBodyMetadata {
 /// A body identifier in the TIR. A body can be a function, a closure, etc.
 pub struct Body(usize);
 
+/// The stack frame of a body, computed by [`TirBody::frame_layout`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FrameLayout {
+    /// The total size of the frame, a multiple of `align`.
+    pub size: Size,
+    /// The alignment of the frame: the largest alignment of its locals.
+    pub align: Align,
+    /// The offset of each local from the start of the frame.
+    pub offsets: IdxVec<Local, Size>,
+}
+
 /// The body of a function in TIR. A body could be a function, a closure, a coroutine, etc.
 /// A body is expected to be monomorphized and specialized, that is, when generic parameters are
 /// involved, each instantiation of the generics should have its own body.
@@ -291,6 +385,12 @@ pub struct TirBody<'ctx> {
     /// The first local is the return value, and the rest are the arguments.
     pub ret_and_args: IdxVec<Local, LocalData<'ctx>>,
 
+    /// The number of arguments of the function, i.e. the number of entries
+    /// of `ret_and_args` after the return value. It must always agree with
+    /// the length of `ret_and_args`, which [`TirBody::arg_count`] and
+    /// [`TirBody::args`] check in debug builds.
+    pub arg_count: usize,
+
     /// The rest of the locals.
     pub locals: IdxVec<Local, LocalData<'ctx>>,
 
@@ -298,6 +398,365 @@ pub struct TirBody<'ctx> {
     pub basic_blocks: IdxVec<BasicBlock, BasicBlockData<'ctx>>,
 }
 
+impl<'ctx> TirBody<'ctx> {
+    /// Creates an empty body with room for `n_locals` locals (besides the
+    /// return value and arguments) and `n_blocks` basic blocks, so that
+    /// frontends generating large functions do not reallocate repeatedly.
+    ///
+    /// `ret_and_args` starts empty: callers pushing the return value and
+    /// arguments are expected to set `arg_count` accordingly.
+    pub fn with_capacity(metadata: TirBodyMetadata, n_locals: usize, n_blocks: usize) -> Self {
+        TirBody {
+            metadata,
+            ret_and_args: IdxVec::new(),
+            arg_count: 0,
+            locals: IdxVec::with_capacity(n_locals),
+            basic_blocks: IdxVec::with_capacity(n_blocks),
+        }
+    }
+
+    /// Reserves room for at least `additional` more basic blocks.
+    pub fn reserve_blocks(&mut self, additional: usize) {
+        self.basic_blocks.reserve(additional);
+    }
+
+    /// Reserves room for at least `additional` more locals in `locals`.
+    pub fn reserve_locals(&mut self, additional: usize) {
+        self.locals.reserve(additional);
+    }
+
+    /// Returns the local data of the return value (`RETURN_LOCAL`).
+    pub fn return_local_data(&self) -> &LocalData<'ctx> {
+        &self.ret_and_args[RETURN_LOCAL]
+    }
+
+    /// Iterates over the arguments, i.e. the entries of `ret_and_args` after
+    /// the return slot, together with their `Local` in the body (starting
+    /// from `_1`).
+    pub fn args(&self) -> impl ExactSizeIterator<Item = (Local, &LocalData<'ctx>)> {
+        self.debug_assert_arg_count();
+        self.ret_and_args
+            .iter_enumerated()
+            .skip(1)
+            .take(self.arg_count)
+    }
+
+    /// Returns the number of arguments of the function.
+    pub fn arg_count(&self) -> usize {
+        self.debug_assert_arg_count();
+        self.arg_count
+    }
+
+    /// Checks that `arg_count` agrees with the length of `ret_and_args`,
+    /// since both are public and can be updated independently.
+    fn debug_assert_arg_count(&self) {
+        // A body being built may not have its return slot yet.
+        debug_assert_eq!(
+            self.arg_count,
+            self.ret_and_args.len().saturating_sub(1),
+            "`arg_count` of `{}` is out of sync with `ret_and_args`",
+            self.metadata.name
+        );
+    }
+
+    /// Returns the declared type of `local`.
+    ///
+    /// Locals are numbered across `ret_and_args` first and then `locals`, so
+    /// `locals` is indexed with an offset of `ret_and_args.len()`.
+    pub fn local_ty(&self, local: Local) -> TirTy<'ctx> {
+        let ret_and_args_len = self.ret_and_args.len();
+        if local.idx() < ret_and_args_len {
+            self.ret_and_args[local].ty
+        } else {
+            self.locals[Local::new(local.idx() - ret_and_args_len)].ty
+        }
+    }
+
+    /// Returns mutable references to the data of two distinct locals, which
+    /// may live in `ret_and_args`, in `locals` or one in each.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `a == b` or if either local is out of bounds.
+    pub fn two_locals_mut(
+        &mut self,
+        a: Local,
+        b: Local,
+    ) -> (&mut LocalData<'ctx>, &mut LocalData<'ctx>) {
+        assert_ne!(a, b, "cannot borrow {a:?} mutably twice");
+        let ret_and_args_len = self.ret_and_args.len();
+        let in_locals = |local: Local| Local::new(local.idx() - ret_and_args_len);
+        match (a.idx() < ret_and_args_len, b.idx() < ret_and_args_len) {
+            (true, true) => self.ret_and_args.pick2_mut(a, b),
+            (false, false) => self.locals.pick2_mut(in_locals(a), in_locals(b)),
+            (true, false) => (&mut self.ret_and_args[a], &mut self.locals[in_locals(b)]),
+            (false, true) => (&mut self.locals[in_locals(a)], &mut self.ret_and_args[b]),
+        }
+    }
+
+    /// Returns the type of `place`: the type of its local, refined by each
+    /// projection in turn.
+    ///
+    /// `ctx` is needed to intern the array type produced by a `Subslice`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a projection does not apply to the type it is used on, e.g.
+    /// a `Deref` of a non-pointer or an `Index` of a non-array.
+    pub fn place_ty(&self, place: &Place<'ctx>, ctx: &TirCtx<'ctx>) -> TirTy<'ctx> {
+        place
+            .projection
+            .iter()
+            .fold(self.local_ty(place.local), |ty, projection| {
+                match (projection, &**ty) {
+                    (Projection::Field(_, field_ty), _) => *field_ty,
+                    (Projection::Deref, ty::TirTy::RawPtr(pointee, _, _)) => *pointee,
+                    (
+                        Projection::Index(_) | Projection::ConstantIndex { .. },
+                        ty::TirTy::Array(element_ty, _),
+                    ) => *element_ty,
+                    (
+                        Projection::Subslice { from, to, from_end },
+                        ty::TirTy::Array(element_ty, count),
+                    ) => {
+                        let len = if *from_end {
+                            count - from - to
+                        } else {
+                            to - from
+                        };
+                        ctx.intern_ty(ty::TirTy::Array(*element_ty, len))
+                    }
+                    (Projection::Downcast(variant), ty::TirTy::Enum { variants }) => {
+                        variants.as_slice()[*variant]
+                    }
+                    _ => panic!("projection {projection:?} does not apply to type {ty:?}"),
+                }
+            })
+    }
+
+    /// Lays out every local of the body in a single stack frame.
+    ///
+    /// Locals are placed in order (`ret_and_args` first, then `locals`),
+    /// each at the next offset aligned to its ABI alignment. The frame is
+    /// aligned to the largest alignment of its locals, and its size is
+    /// rounded up to that alignment. Zero-sized locals take no space.
+    pub fn frame_layout(&self, ctx: &TirCtx<'ctx>) -> FrameLayout {
+        let mut size = Size::ZERO;
+        let mut align = Align::from_bytes(1).unwrap();
+        let offsets = self
+            .ret_and_args
+            .iter()
+            .chain(self.locals.iter())
+            .map(|local_data| {
+                let layout = ctx.layout_of(local_data.ty);
+                let offset = size.align_to(layout.align.abi);
+                size = offset
+                    .checked_add(layout.size)
+                    .expect("stack frame size overflows a u64");
+                if layout.align.abi.bytes() > align.bytes() {
+                    align = layout.align.abi;
+                }
+                offset
+            })
+            .collect();
+        FrameLayout {
+            size: size.align_to(align),
+            align,
+            offsets,
+        }
+    }
+
+    /// Returns a hash of the body contents, ignoring its identity.
+    ///
+    /// The hash covers the locals, the basic blocks and the parts of the
+    /// metadata that affect the generated code (calling convention and
+    /// variadicity). The name, `DefId`, linkage and visibility are excluded,
+    /// so two bodies that differ only in their symbol hash to the same value.
+    ///
+    /// Types are hashed by their interned pointer, so hashes are only
+    /// comparable between bodies interned in the same `TirCtx`; see
+    /// [`structural_hash`](Self::structural_hash) for a hash that is not.
+    pub fn content_hash(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        (self.metadata.call_conv as u32).hash(&mut hasher);
+        self.metadata.is_varargs.hash(&mut hasher);
+        self.ret_and_args.hash(&mut hasher);
+        self.locals.hash(&mut hasher);
+        self.basic_blocks.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    /// Returns whether two bodies have the same contents, ignoring their
+    /// identity.
+    ///
+    /// This compares exactly what [`content_hash`](Self::content_hash)
+    /// hashes, so bodies that are `content_eq` hash to the same value. Equal
+    /// hashes alone do not prove two bodies identical: this is the check to
+    /// run on a hash match before treating them as interchangeable.
+    pub fn content_eq(&self, other: &TirBody<'ctx>) -> bool {
+        self.metadata.call_conv as u32 == other.metadata.call_conv as u32
+            && self.metadata.is_varargs == other.metadata.is_varargs
+            && self.ret_and_args == other.ret_and_args
+            && self.locals == other.locals
+            && self.basic_blocks == other.basic_blocks
+    }
+
+    /// Returns a hash of the body structure that does not depend on how its
+    /// types were interned.
+    ///
+    /// Like [`content_hash`](Self::content_hash), the name and `DefId` are
+    /// excluded. Unlike it, types are hashed by their contents and
+    /// statements and terminators by their shape (kinds, operators, places,
+    /// constants and successors), so the hash is stable across `TirCtx`s and
+    /// can key a memoization cache that outlives a single compilation.
+    pub fn structural_hash(&self) -> u64 {
+        let mut hasher = StructuralHasher {
+            hasher: DefaultHasher::new(),
+        };
+        (self.metadata.call_conv as u32).hash(&mut hasher.hasher);
+        self.metadata.is_varargs.hash(&mut hasher.hasher);
+        self.arg_count.hash(&mut hasher.hasher);
+        for local_data in self.ret_and_args.iter().chain(self.locals.iter()) {
+            hash_ty_structurally(local_data.ty, &mut hasher.hasher);
+            local_data.mutable.hash(&mut hasher.hasher);
+        }
+        self.basic_blocks.len().hash(&mut hasher.hasher);
+        hasher.visit_body(self);
+        hasher.hasher.finish()
+    }
+
+    /// Iterates over every statement of the body together with its location,
+    /// block by block and in order within each block.
+    ///
+    /// Terminators are not included.
+    pub fn statements_with_locations(&self) -> impl Iterator<Item = (Location, &Statement<'ctx>)> {
+        self.basic_blocks
+            .iter_enumerated()
+            .flat_map(|(block, data)| {
+                data.statements
+                    .iter()
+                    .enumerate()
+                    .map(move |(statement_index, statement)| {
+                        (
+                            Location {
+                                block,
+                                statement_index,
+                            },
+                            statement,
+                        )
+                    })
+            })
+    }
+
+    /// Checks that every terminator passes as many block arguments as each
+    /// of its successors declares parameters.
+    ///
+    /// A `SwitchInt` passes the same arguments to all its successors, so
+    /// they must all declare the same number of parameters. Codegen relies
+    /// on this, so a body should be checked before being lowered.
+    pub fn check_block_args(&self) -> Result<(), BlockArgsError> {
+        for (block, data) in self.basic_blocks.iter_enumerated() {
+            let found = data.terminator.block_args().len();
+            for successor in data.terminator.successors() {
+                let expected = self.basic_blocks[successor].params.len();
+                if expected != found {
+                    return Err(BlockArgsError {
+                        block,
+                        successor,
+                        expected,
+                        found,
+                    });
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Checks that no binary operation on SIMD vectors uses an operator that
+    /// has no lane-wise form.
+    ///
+    /// Checked operators report a single overflow flag and comparisons a
+    /// single `Bool`, neither of which a vector of lanes can produce. Codegen
+    /// relies on this, so a body should be checked before being lowered.
+    pub fn check_simd_binops(&self, ctx: &TirCtx<'ctx>) -> Result<(), SimdBinOpError> {
+        for (location, statement) in self.statements_with_locations() {
+            let Statement::Assign(assign) = statement else {
+                continue;
+            };
+            let RValue::BinaryOp(op, lhs, _) = &assign.1 else {
+                continue;
+            };
+            let lhs_ty = match lhs {
+                Operand::Use(place) => self.place_ty(place, ctx),
+                Operand::Const(constant) => constant.ty(),
+            };
+            if lhs_ty.is_simd() && !op.is_lanewise() {
+                return Err(SimdBinOpError {
+                    location,
+                    op: op.clone(),
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Checks that every operand of an inline assembly statement is an
+    /// immediate: a scalar or a SIMD vector.
+    ///
+    /// Inline assembly operands are bound to registers, which cannot hold
+    /// scalar pairs, aggregates or zero-sized values. Codegen relies on this,
+    /// so a body should be checked before being lowered.
+    pub fn check_inline_asm_operands(
+        &self,
+        ctx: &TirCtx<'ctx>,
+    ) -> Result<(), InlineAsmOperandError<'ctx>> {
+        for (location, statement) in self.statements_with_locations() {
+            let Statement::Assign(assign) = statement else {
+                continue;
+            };
+            let RValue::InlineAsm { operands, .. } = &assign.1 else {
+                continue;
+            };
+            for (operand_index, operand) in operands.iter().enumerate() {
+                let ty = match operand {
+                    Operand::Use(place) => self.place_ty(place, ctx),
+                    Operand::Const(constant) => constant.ty(),
+                };
+                if !ctx.layout_of(ty).is_immediate() {
+                    return Err(InlineAsmOperandError {
+                        location,
+                        operand_index,
+                        ty,
+                    });
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the set of locals referenced anywhere in the basic blocks.
+    ///
+    /// A local counts as used if it appears in a place (as the base local or
+    /// as the index of an `Index` projection), whether it is read, written or
+    /// has its address taken. Declared locals that never appear, including
+    /// unused arguments, are not in the set.
+    ///
+    /// The domain of the set covers both `ret_and_args` and `locals`.
+    pub fn used_locals(&self) -> BitSet<Local> {
+        struct UsedLocals(BitSet<Local>);
+
+        impl<'ctx> Visitor<'ctx> for UsedLocals {
+            fn visit_local(&mut self, local: Local, _context: PlaceContext) {
+                self.0.insert(local);
+            }
+        }
+
+        let mut visitor = UsedLocals(BitSet::new(self.ret_and_args.len() + self.locals.len()));
+        visitor.visit_body(self);
+        visitor.0
+    }
+}
+
 /// A unique identifier for a global variable within a `TirUnit`.
 ///
 /// `GlobalId` is a newtype index into `TirUnit::globals`, following the same
@@ -362,6 +821,329 @@ pub struct TirUnit<'ctx> {
     pub bodies: IdxVec<Body, TirBody<'ctx>>,
 }
 
+impl<'ctx> TirUnit<'ctx> {
+    /// Moves the bodies and globals of `other` into this unit, after the
+    /// existing ones.
+    ///
+    /// The unit keeps its own metadata. The globals of `other` get new
+    /// `GlobalId`s, shifted by the number of globals already in this unit,
+    /// and every `GlobalAlloc::Static` constant in the bodies and global
+    /// initializers of `other` is re-interned to point at the shifted id.
+    ///
+    /// Fails without modifying the unit if a `DefId` of `other` is already
+    /// used by a body of this unit (or by another body of `other`).
+    pub fn merge(
+        &mut self,
+        mut other: TirUnit<'ctx>,
+        ctx: &TirCtx<'ctx>,
+    ) -> Result<(), MergeError> {
+        let mut def_ids: HashSet<DefId> = self.bodies.iter().map(|b| b.metadata.def_id).collect();
+        if let Some(body) = other
+            .bodies
+            .iter()
+            .find(|b| !def_ids.insert(b.metadata.def_id))
+        {
+            return Err(MergeError::DuplicateDefId(body.metadata.def_id));
+        }
+
+        let offset = self.globals.len();
+        if offset > 0 {
+            let mut remapper = StaticRemapper {
+                ctx,
+                offset,
+                remapped: HashMap::new(),
+            };
+            for body in other.bodies.iter_mut() {
+                remapper.visit_body(body);
+            }
+            for global in other.globals.iter_mut() {
+                if let Some(initializer) = &mut global.initializer {
+                    remapper.remap(initializer);
+                }
+            }
+        }
+
+        self.bodies.raw.extend(other.bodies.raw);
+        self.globals.raw.extend(other.globals.raw);
+        Ok(())
+    }
+}
+
+/// Rewrites `GlobalAlloc::Static` constants so that they refer to globals
+/// shifted by `offset`, as done by [`TirUnit::merge`].
+struct StaticRemapper<'a, 'ctx> {
+    ctx: &'a TirCtx<'ctx>,
+    offset: usize,
+    /// Maps each old allocation to its re-interned replacement, so that a
+    /// global referenced several times keeps a single `AllocId`.
+    remapped: HashMap<AllocId, AllocId>,
+}
+
+impl StaticRemapper<'_, '_> {
+    fn remap(&mut self, value: &mut ConstValue) {
+        let ConstValue::Indirect { alloc_id, .. } = value else {
+            return;
+        };
+        let Some(GlobalAlloc::Static(global_id)) = self.ctx.get_global_alloc(*alloc_id) else {
+            return;
+        };
+        let (ctx, offset) = (self.ctx, self.offset);
+        *alloc_id = *self
+            .remapped
+            .entry(*alloc_id)
+            .or_insert_with(|| ctx.intern_static(GlobalId(global_id.0 + offset)));
+    }
+}
+
+impl<'ctx> MutVisitor<'ctx> for StaticRemapper<'_, 'ctx> {
+    fn ctx(&self) -> TirCtx<'ctx> {
+        *self.ctx
+    }
+
+    fn visit_const(&mut self, constant: &mut ConstOperand<'ctx>) {
+        let ConstOperand::Value(value, _) = constant;
+        self.remap(value);
+    }
+}
+
+/// Feeds the shape of every statement and terminator it visits into
+/// `hasher`, for [`TirBody::structural_hash`].
+struct StructuralHasher {
+    hasher: DefaultHasher,
+}
+
+impl<'ctx> Visitor<'ctx> for StructuralHasher {
+    fn visit_basic_block_data(&mut self, bb: BasicBlock, data: &BasicBlockData<'ctx>) {
+        data.params.len().hash(&mut self.hasher);
+        for (local, ty) in &data.params {
+            local.hash(&mut self.hasher);
+            hash_ty_structurally(*ty, &mut self.hasher);
+        }
+        data.statements.len().hash(&mut self.hasher);
+        self.super_basic_block_data(bb, data);
+    }
+
+    fn visit_statement(&mut self, statement: &Statement<'ctx>) {
+        mem::discriminant(statement).hash(&mut self.hasher);
+        if let Statement::SetDiscriminant { variant, .. } = statement {
+            variant.hash(&mut self.hasher);
+        }
+        self.super_statement(statement);
+    }
+
+    fn visit_terminator(&mut self, terminator: &Terminator<'ctx>) {
+        mem::discriminant(terminator).hash(&mut self.hasher);
+        match terminator {
+            Terminator::Return | Terminator::Unreachable => {}
+            Terminator::Goto { target, .. } => target.hash(&mut self.hasher),
+            Terminator::SwitchInt { targets, .. } => targets.hash(&mut self.hasher),
+            Terminator::Call { args, target, .. } => {
+                args.len().hash(&mut self.hasher);
+                target.hash(&mut self.hasher);
+            }
+            Terminator::Assert {
+                expected,
+                target,
+                msg,
+                ..
+            } => {
+                expected.hash(&mut self.hasher);
+                target.hash(&mut self.hasher);
+                mem::discriminant(msg).hash(&mut self.hasher);
+            }
+        }
+        terminator.block_args().len().hash(&mut self.hasher);
+        self.super_terminator(terminator);
+    }
+
+    fn visit_rvalue(&mut self, rvalue: &RValue<'ctx>) {
+        mem::discriminant(rvalue).hash(&mut self.hasher);
+        match rvalue {
+            RValue::Operand(_) => {}
+            RValue::UnaryOp(op, _) => op.hash(&mut self.hasher),
+            RValue::BinaryOp(op, _, _) => op.hash(&mut self.hasher),
+            RValue::Cast(kind, _, ty) => {
+                kind.hash(&mut self.hasher);
+                hash_ty_structurally(*ty, &mut self.hasher);
+            }
+            RValue::Aggregate(kind, operands) => {
+                mem::discriminant(kind).hash(&mut self.hasher);
+                match kind {
+                    AggregateKind::Struct(ty) | AggregateKind::Array(ty) => {
+                        hash_ty_structurally(*ty, &mut self.hasher)
+                    }
+                }
+                operands.len().hash(&mut self.hasher);
+            }
+            RValue::AddressOf(mutability, _) => mutability.hash(&mut self.hasher),
+            RValue::Discriminant(_) => {}
+            RValue::InlineAsm {
+                template,
+                operands,
+                clobbers,
+            } => {
+                template.hash(&mut self.hasher);
+                operands.len().hash(&mut self.hasher);
+                clobbers.hash(&mut self.hasher);
+            }
+        }
+        self.super_rvalue(rvalue);
+    }
+
+    fn visit_operand(&mut self, operand: &Operand<'ctx>) {
+        mem::discriminant(operand).hash(&mut self.hasher);
+        self.super_operand(operand);
+    }
+
+    fn visit_const(&mut self, constant: &ConstOperand<'ctx>) {
+        let ConstOperand::Value(value, ty) = constant;
+        value.hash(&mut self.hasher);
+        hash_ty_structurally(*ty, &mut self.hasher);
+    }
+
+    fn visit_place(&mut self, place: &Place<'ctx>, _context: PlaceContext) {
+        place.local.hash(&mut self.hasher);
+        place.projection.len().hash(&mut self.hasher);
+        for projection in place.projection {
+            match projection {
+                Projection::Field(field, ty) => {
+                    mem::discriminant(projection).hash(&mut self.hasher);
+                    field.hash(&mut self.hasher);
+                    hash_ty_structurally(*ty, &mut self.hasher);
+                }
+                _ => projection.hash(&mut self.hasher),
+            }
+        }
+    }
+}
+
+/// Hashes `ty` by its contents, recursing into pointees, fields and
+/// elements, instead of by its interned pointer.
+fn hash_ty_structurally(ty: TirTy, hasher: &mut impl Hasher) {
+    mem::discriminant(&**ty).hash(hasher);
+    match **ty {
+        ty::TirTy::RawPtr(pointee, mutability, address_space) => {
+            hash_ty_structurally(pointee, hasher);
+            mutability.hash(hasher);
+            address_space.hash(hasher);
+        }
+        ty::TirTy::Struct { fields, packed } => {
+            fields.as_slice().len().hash(hasher);
+            for field in fields.as_slice() {
+                hash_ty_structurally(*field, hasher);
+            }
+            packed.hash(hasher);
+        }
+        ty::TirTy::Array(element, len) => {
+            hash_ty_structurally(element, hasher);
+            len.hash(hasher);
+        }
+        ty::TirTy::Simd { elem, lanes } => {
+            elem.hash(hasher);
+            lanes.hash(hasher);
+        }
+        ty::TirTy::Enum { variants } => {
+            variants.as_slice().len().hash(hasher);
+            for variant in variants.as_slice() {
+                hash_ty_structurally(*variant, hasher);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// An error returned by [`TirUnit::merge`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MergeError {
+    /// Two bodies share the same `DefId`.
+    DuplicateDefId(DefId),
+}
+
+impl std::fmt::Display for MergeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MergeError::DuplicateDefId(def_id) => {
+                write!(f, "duplicate definition `DefId({})`", def_id.0)
+            }
+        }
+    }
+}
+
+impl std::error::Error for MergeError {}
+
+/// An error returned by [`TirBody::check_block_args`]: the terminator of
+/// `block` passes `found` arguments to `successor`, which declares
+/// `expected` parameters.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BlockArgsError {
+    pub block: BasicBlock,
+    pub successor: BasicBlock,
+    pub expected: usize,
+    pub found: usize,
+}
+
+impl std::fmt::Display for BlockArgsError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "block `bb{}` passes {} arguments to `bb{}`, which expects {}",
+            self.block.idx(),
+            self.found,
+            self.successor.idx(),
+            self.expected
+        )
+    }
+}
+
+impl std::error::Error for BlockArgsError {}
+
+/// An error returned by [`TirBody::check_simd_binops`]: the statement at
+/// `location` applies `op`, which has no lane-wise form, to SIMD vectors.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SimdBinOpError {
+    pub location: Location,
+    pub op: BinaryOp,
+}
+
+impl std::fmt::Display for SimdBinOpError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "`{:?}` in `bb{}[{}]` is not supported on SIMD vectors",
+            self.op,
+            self.location.block.idx(),
+            self.location.statement_index
+        )
+    }
+}
+
+impl std::error::Error for SimdBinOpError {}
+
+/// An error returned by [`TirBody::check_inline_asm_operands`]: operand
+/// `operand_index` of the inline assembly at `location` has type `ty`, which
+/// is not an immediate.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InlineAsmOperandError<'ctx> {
+    pub location: Location,
+    pub operand_index: usize,
+    pub ty: TirTy<'ctx>,
+}
+
+impl std::fmt::Display for InlineAsmOperandError<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "inline assembly operand {} in `bb{}[{}]` has type {:?}, which is not an immediate",
+            self.operand_index,
+            self.location.block.idx(),
+            self.location.statement_index,
+            self.ty
+        )
+    }
+}
+
+impl std::error::Error for InlineAsmOperandError<'_> {}
+
 impl Idx for Body {
     fn new(idx: usize) -> Self {
         Body(idx)
@@ -0,0 +1,37 @@
+//! Evaluation of constant operands.
+//!
+//! Passes that reason about constants (such as constant folding) use
+//! [`eval_const`] to extract the concrete value of a [`ConstOperand`] instead
+//! of matching on its representation themselves.
+
+use std::num::NonZero;
+
+use crate::ctx::TirCtx;
+use crate::syntax::{ConstOperand, ConstScalar, ConstValue, RawScalarValue};
+
+/// Evaluates a constant operand to its raw scalar value.
+///
+/// Scalars are returned as-is, and null pointers evaluate to a zero of the
+/// target pointer size. Returns `None` for constants that have no scalar
+/// value (ZSTs), that are zero-initialized aggregates (`ConstValue::ZeroInit`),
+/// that are wide pointers (`ConstValue::Slice`) or that live in memory
+/// (`ConstValue::Indirect`).
+pub fn eval_const(op: &ConstOperand, ctx: &TirCtx) -> Option<RawScalarValue> {
+    match op {
+        ConstOperand::Value(ConstValue::Scalar(ConstScalar::Value(raw)), _) => Some(*raw),
+        ConstOperand::Value(ConstValue::NullPtr, _) => {
+            let pointer_size = ctx.target().data_layout.pointer_size.bytes();
+            Some(RawScalarValue {
+                data: 0,
+                size: NonZero::new(pointer_size as u8)?,
+            })
+        }
+        ConstOperand::Value(
+            ConstValue::ZST
+            | ConstValue::ZeroInit
+            | ConstValue::Slice { .. }
+            | ConstValue::Indirect { .. },
+            _,
+        ) => None,
+    }
+}
@@ -2,25 +2,31 @@ use std::{
     borrow::Borrow,
     cell::{Cell, RefCell},
     collections::{HashMap, HashSet},
+    fmt,
     hash::Hash,
     ops::Deref,
+    path::PathBuf,
     ptr::NonNull,
+    str::FromStr,
 };
 
 use crate::{
     alloc::{AllocId, Allocation, GlobalAlloc},
-    body::DefId,
-    layout_ctx::LayoutCtx,
+    body::{DefId, DefIdAllocator, TirBody},
+    layout_ctx::{LayoutCtx, LayoutError},
+    syntax::{FieldIdx, Projection},
     ty, TirAllocation, TirTy,
 };
 use tidec_abi::{
-    layout::{self, TyAndLayout},
+    layout::{self, FieldsShape, TyAndLayout},
+    size_and_align::Size,
     target::{BackendKind, TirTarget},
     Layout,
 };
+use tidec_utils::idx::Idx;
 use tidec_utils::interner::{Interned, Interner};
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum EmitKind {
     Assembly,
     Object,
@@ -29,9 +35,139 @@ pub enum EmitKind {
     LlvmBitcode,
 }
 
-#[derive(Debug, Clone, Copy)]
+impl EmitKind {
+    /// Returns the canonical name of the emit kind, as printed by `Display`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EmitKind::Assembly => "assembly",
+            EmitKind::Object => "object",
+            EmitKind::Executable => "executable",
+            EmitKind::LlvmIr => "llvm-ir",
+            EmitKind::LlvmBitcode => "bitcode",
+        }
+    }
+
+    /// Returns the path of the file emitted for the unit `unit_name`, next to
+    /// the current directory: `<unit_name>.o`, `.s`, `.ll` or `.bc`, and the
+    /// bare unit name (`<unit_name>.exe` on Windows) for executables.
+    pub fn output_path(&self, unit_name: &str) -> PathBuf {
+        let extension = match self {
+            EmitKind::Assembly => "s",
+            EmitKind::Object => "o",
+            EmitKind::Executable if cfg!(target_os = "windows") => "exe",
+            EmitKind::Executable => return PathBuf::from(unit_name),
+            EmitKind::LlvmIr => "ll",
+            EmitKind::LlvmBitcode => "bc",
+        };
+        PathBuf::from(format!("{unit_name}.{extension}"))
+    }
+}
+
+impl FromStr for EmitKind {
+    type Err = UnknownEmitKind;
+
+    /// Parses an emit kind from its canonical name or one of its short forms,
+    /// e.g. `asm` for `assembly`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "assembly" | "asm" | "s" => Ok(EmitKind::Assembly),
+            "object" | "obj" | "o" => Ok(EmitKind::Object),
+            "executable" | "exe" => Ok(EmitKind::Executable),
+            "llvm-ir" | "ll" | "ir" => Ok(EmitKind::LlvmIr),
+            "bitcode" | "bc" | "llvm-bc" => Ok(EmitKind::LlvmBitcode),
+            _ => Err(UnknownEmitKind(s.to_string())),
+        }
+    }
+}
+
+impl fmt::Display for EmitKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// The error returned when parsing an [`EmitKind`] from an unknown name.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnknownEmitKind(pub String);
+
+impl fmt::Display for UnknownEmitKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "unknown emit kind `{}` (expected one of: assembly, object, executable, llvm-ir, bitcode)",
+            self.0
+        )
+    }
+}
+
+impl std::error::Error for UnknownEmitKind {}
+
+#[derive(Debug, Clone)]
 pub struct TirArgs {
     pub emit_kind: EmitKind,
+    /// Whether integer division and remainder check their divisor, and
+    /// panic on a division by zero or a signed `MIN / -1` overflow instead
+    /// of triggering undefined behavior.
+    pub checked_div: bool,
+    /// Whether the backend verifies the generated module before emitting it.
+    /// This is usually on in debug builds only, i.e. `cfg!(debug_assertions)`.
+    pub verify_module: bool,
+    /// Where the backend writes its output. If `None`, the path is derived
+    /// from the unit name, see [`EmitKind::output_path`].
+    pub output_path: Option<PathBuf>,
+    /// Target features enabled on every function, in the backend syntax
+    /// (e.g. `+sse4.2` for LLVM).
+    pub target_features: Vec<String>,
+    /// Module flags (e.g. `PIC Level` = 2) recorded on the generated module.
+    pub module_flags: Vec<ModuleFlag>,
+}
+
+impl Default for TirArgs {
+    /// Object output with unchecked division, verifying the module in debug
+    /// builds only, and no output path, target feature or module flag.
+    fn default() -> Self {
+        TirArgs {
+            emit_kind: EmitKind::Object,
+            checked_div: false,
+            verify_module: cfg!(debug_assertions),
+            output_path: None,
+            target_features: Vec::new(),
+            module_flags: Vec::new(),
+        }
+    }
+}
+
+/// A module flag: an integer the backend records on the generated module,
+/// e.g. `PIC Level` = 2, together with how it is merged when linking.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ModuleFlag {
+    pub name: String,
+    pub value: i32,
+    pub behavior: ModuleFlagBehavior,
+}
+
+impl ModuleFlag {
+    /// A flag whose modules must all agree on its value, which is what most
+    /// flags (e.g. `PIC Level`) expect.
+    pub fn new(name: impl Into<String>, value: i32) -> Self {
+        ModuleFlag {
+            name: name.into(),
+            value,
+            behavior: ModuleFlagBehavior::Error,
+        }
+    }
+}
+
+/// How the linker merges a module flag of two modules with different values.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ModuleFlagBehavior {
+    /// Linking fails.
+    #[default]
+    Error,
+    /// A warning is emitted and the value of the first module is kept.
+    Warning,
+    /// The value of this module is used, whatever the other module says.
+    Override,
 }
 
 #[derive(Debug)]
@@ -75,6 +211,20 @@ impl<T: Hash> Hash for ArenaPrt<'_, T> {
     }
 }
 
+// Implement ordering by comparing the underlying values, so that the order
+// does not depend on where the values were allocated.
+impl<T: PartialOrd> PartialOrd for ArenaPrt<'_, T> {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        self.0.partial_cmp(other.0)
+    }
+}
+
+impl<T: Ord> Ord for ArenaPrt<'_, T> {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.0.cmp(other.0)
+    }
+}
+
 // Allow borrowing the underlying value so InternedSet<T> can accept an R = underlying type.
 impl<'ctx, T> Borrow<T> for ArenaPrt<'ctx, T> {
     fn borrow(&self) -> &T {
@@ -102,41 +252,101 @@ pub struct ArenaDropless {
 
     /// The chunks of memory allocated in the arena.
     inner: RefCell<Vec<ArenaChunk>>,
+
+    /// The size in bytes of the first chunk.
+    chunk_size: usize,
+
+    /// The size in bytes of the most recently allocated chunk, or 0 if no
+    /// chunk has been allocated yet. Each new chunk doubles it, up to
+    /// `max_chunk_size`.
+    last_chunk_size: Cell<usize>,
+
+    /// The size past which chunks stop growing: [`ArenaDropless::MAX_CHUNK_SIZE`],
+    /// or the first chunk size if that is larger.
+    max_chunk_size: usize,
 }
 
 impl ArenaDropless {
+    /// The default size in bytes of the first chunk.
+    pub const DEFAULT_CHUNK_SIZE: usize = 1024;
+
+    /// The size in bytes past which chunks stop growing (2 MiB).
+    pub const MAX_CHUNK_SIZE: usize = 2 * 1024 * 1024;
+
+    /// Create an empty arena whose first chunk holds `chunk_size` bytes.
+    fn with_chunk_size(chunk_size: usize) -> Self {
+        let chunk_size = chunk_size.max(1);
+        Self {
+            start: Cell::new(std::ptr::null_mut()),
+            end: Cell::new(std::ptr::null_mut()),
+            inner: RefCell::new(Vec::new()),
+            chunk_size,
+            last_chunk_size: Cell::new(0),
+            max_chunk_size: std::cmp::max(chunk_size, Self::MAX_CHUNK_SIZE),
+        }
+    }
+
+    /// Returns the number of chunks allocated so far.
+    pub fn chunk_count(&self) -> usize {
+        self.inner.borrow().len()
+    }
+
+    /// Allocate a new chunk that can hold at least `size` bytes aligned to
+    /// `align`, and make it the current chunk.
+    ///
+    /// Chunk sizes grow geometrically so that long interning runs need only a
+    /// logarithmic number of allocations.
+    fn grow(&self, size: usize, align: usize) {
+        let base = match self.last_chunk_size.get() {
+            0 => self.chunk_size,
+            last => std::cmp::min(last.saturating_mul(2), self.max_chunk_size),
+        };
+
+        let chunk_size = std::cmp::max(base, size + align);
+        self.last_chunk_size.set(chunk_size);
+        let layout = std::alloc::Layout::from_size_align(chunk_size, align).unwrap();
+        let ptr = unsafe { std::alloc::alloc(layout) };
+        if ptr.is_null() {
+            std::alloc::handle_alloc_error(layout);
+        }
+        let chunk = ArenaChunk {
+            _mem: NonNull::slice_from_raw_parts(NonNull::new(ptr).unwrap(), chunk_size),
+        };
+        self.inner.borrow_mut().push(chunk);
+        self.start.set(ptr);
+        self.end.set(unsafe { ptr.add(chunk_size) });
+    }
+
     /// Allocates a new value in the arena, returning a pointer to it.
     ///
-    /// This function is safe to call, as long as the value is `Sized`.
-    /// The caller must ensure that the value is not dropped manually,
-    /// as it will be dropped when the arena is dropped.
+    /// The value is never dropped, so it must not own any resource: values
+    /// that do (e.g. a `Vec`) go in an [`ArenaTyped`] instead.
     pub fn alloc<T: Sized>(&self, value: T) -> &T {
+        debug_assert!(
+            !std::mem::needs_drop::<T>(),
+            "values allocated in a dropless arena are never dropped"
+        );
         let size = std::mem::size_of::<T>();
         let align = std::mem::align_of::<T>();
 
-        // Ensure we have enough space in the current chunk.
-        if unsafe { self.start.get().add(size) } > self.end.get() {
+        // Ensure we have enough space in the current chunk, once the start
+        // pointer is aligned.
+        let start = self.start.get() as usize;
+        let aligned_start = (start + align - 1) & !(align - 1);
+        let needed = aligned_start - start + size;
+
+        if unsafe { self.start.get().add(needed) } > self.end.get() {
             // Not enough space, allocate a new chunk.
-            let chunk_size = std::cmp::max(1024, size + align);
-            let layout = std::alloc::Layout::from_size_align(chunk_size, align).unwrap();
-            let ptr = unsafe { std::alloc::alloc(layout) };
-            if ptr.is_null() {
-                std::alloc::handle_alloc_error(layout);
-            }
-            let chunk = ArenaChunk {
-                _mem: NonNull::slice_from_raw_parts(NonNull::new(ptr).unwrap(), chunk_size),
-            };
-            self.inner.borrow_mut().push(chunk);
-            self.start.set(ptr);
-            self.end.set(unsafe { ptr.add(chunk_size) });
+            self.grow(size, align);
         }
 
         // Allocate the value in the current chunk.
-        let ptr = self.start.get() as *mut T;
+        let start = self.start.get() as usize;
+        let ptr = ((start + align - 1) & !(align - 1)) as *mut T;
         unsafe {
             ptr.write(value);
         }
-        self.start.set(unsafe { self.start.get().add(size) });
+        self.start.set(unsafe { (ptr as *mut u8).add(size) });
 
         unsafe { &*ptr }
     }
@@ -165,18 +375,7 @@ impl ArenaDropless {
 
         if unsafe { self.start.get().add(needed) } > self.end.get() {
             // Not enough space, allocate a new chunk.
-            let chunk_size = std::cmp::max(1024, size + align);
-            let layout = std::alloc::Layout::from_size_align(chunk_size, align).unwrap();
-            let ptr = unsafe { std::alloc::alloc(layout) };
-            if ptr.is_null() {
-                std::alloc::handle_alloc_error(layout);
-            }
-            let chunk = ArenaChunk {
-                _mem: NonNull::slice_from_raw_parts(NonNull::new(ptr).unwrap(), chunk_size),
-            };
-            self.inner.borrow_mut().push(chunk);
-            self.start.set(ptr);
-            self.end.set(unsafe { ptr.add(chunk_size) });
+            self.grow(size, align);
         }
 
         // Align the start pointer.
@@ -194,7 +393,62 @@ impl ArenaDropless {
     }
 }
 
-#[derive(Debug, Clone)]
+/// An arena for values of a single type `T` that own resources.
+///
+/// Unlike [`ArenaDropless`], it runs the destructors of its values when it is
+/// dropped. Values are stored in chunks that are never grown past their
+/// capacity, so a value never moves once allocated.
+pub struct ArenaTyped<T> {
+    /// The chunks of values; only the last one has spare capacity.
+    chunks: RefCell<Vec<Vec<T>>>,
+}
+
+impl<T> ArenaTyped<T> {
+    /// The number of values of the first chunk.
+    const FIRST_CHUNK_LEN: usize = 8;
+
+    /// The number of values past which chunks stop growing.
+    const MAX_CHUNK_LEN: usize = 1024;
+
+    /// Moves `value` into the arena, returning a reference to it.
+    pub fn alloc(&self, value: T) -> &T {
+        let mut chunks = self.chunks.borrow_mut();
+        let len = match chunks.last() {
+            Some(chunk) if chunk.len() < chunk.capacity() => None,
+            Some(chunk) => Some(std::cmp::min(chunk.capacity() * 2, Self::MAX_CHUNK_LEN)),
+            None => Some(Self::FIRST_CHUNK_LEN),
+        };
+        if let Some(len) = len {
+            chunks.push(Vec::with_capacity(len));
+        }
+        let chunk = chunks.last_mut().unwrap();
+        chunk.push(value);
+        let ptr: *const T = chunk.last().unwrap();
+        // SAFETY: the chunk had spare capacity, so the push did not move its
+        // elements, and chunks are only dropped with the arena, which
+        // outlives the returned reference.
+        unsafe { &*ptr }
+    }
+}
+
+impl<T> Default for ArenaTyped<T> {
+    fn default() -> Self {
+        Self {
+            chunks: RefCell::new(Vec::new()),
+        }
+    }
+}
+
+impl<T> fmt::Debug for ArenaTyped<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let chunks = self.chunks.borrow();
+        f.debug_struct("ArenaTyped")
+            .field("len", &chunks.iter().map(Vec::len).sum::<usize>())
+            .finish()
+    }
+}
+
+#[derive(Debug)]
 /// An arena for allocating TIR values.
 pub struct TirArena<'ctx> {
     // types: Vec<Box<ty::TirTy<TirCtx<'ctx>>>>,
@@ -204,6 +458,15 @@ pub struct TirArena<'ctx> {
     /// to worry about memory leaks.
     dropless: ArenaDropless,
 
+    /// The function bodies, which own their blocks and locals.
+    bodies: ArenaTyped<TirBody<'ctx>>,
+
+    /// The allocations, which own their bytes and relocations.
+    allocations: ArenaTyped<Allocation>,
+
+    /// The layouts, which own their field offsets.
+    layouts: ArenaTyped<layout::Layout>,
+
     /// The lifetime marker for the arena.
     /// This ensures that the arena lives as long as the context that uses it.
     _marker: std::marker::PhantomData<&'ctx ()>,
@@ -217,19 +480,29 @@ impl<'ctx> Deref for TirArena<'ctx> {
     }
 }
 
-impl<'ctx> Default for TirArena<'ctx> {
-    fn default() -> Self {
+impl<'ctx> TirArena<'ctx> {
+    /// Create an arena whose first chunk holds `bytes` bytes.
+    ///
+    /// Later chunks double in size as earlier ones fill up. Workloads that
+    /// intern many small values can pick a larger base size to allocate
+    /// fewer chunks. The default is [`ArenaDropless::DEFAULT_CHUNK_SIZE`].
+    pub fn with_chunk_size(bytes: usize) -> Self {
         Self {
-            dropless: ArenaDropless {
-                start: Cell::new(std::ptr::null_mut()),
-                end: Cell::new(std::ptr::null_mut()),
-                inner: RefCell::new(Vec::new()),
-            },
+            dropless: ArenaDropless::with_chunk_size(bytes),
+            bodies: ArenaTyped::default(),
+            allocations: ArenaTyped::default(),
+            layouts: ArenaTyped::default(),
             _marker: std::marker::PhantomData,
         }
     }
 }
 
+impl<'ctx> Default for TirArena<'ctx> {
+    fn default() -> Self {
+        Self::with_chunk_size(ArenaDropless::DEFAULT_CHUNK_SIZE)
+    }
+}
+
 #[derive(Debug, Clone)]
 /// A set of interned values of type `T`.
 ///
@@ -275,6 +548,41 @@ impl<T: Sized + Copy + Eq + std::hash::Hash> InternedSet<T> {
             new
         }
     }
+
+    /// Returns all the interned values, sorted.
+    ///
+    /// The underlying `HashSet` iterates in a different order on every run,
+    /// so this gives a reproducible dump for diagnostics and golden tests.
+    /// It copies and sorts the whole set: use it for debugging, not on hot
+    /// paths.
+    pub fn iter_sorted(&self) -> Vec<T>
+    where
+        T: Ord,
+    {
+        let mut values: Vec<T> = self.0.borrow().iter().copied().collect();
+        values.sort_unstable();
+        values
+    }
+}
+
+impl<'ctx, T: Copy + Eq + std::hash::Hash> InternedSet<&'ctx [T]> {
+    /// Interns a slice, returning the existing arena slice if an equal one
+    /// was already interned.
+    ///
+    /// This is the counterpart of [`InternedSet::intern`] for unsized
+    /// values, which cannot be passed by value.
+    pub fn intern_slice(
+        &self,
+        values: &[T],
+        intern_in_arena: impl FnOnce(&[T]) -> &'ctx [T],
+    ) -> &'ctx [T] {
+        let existing = self.0.borrow().get(values).copied();
+        existing.unwrap_or_else(|| {
+            let new = intern_in_arena(values);
+            self.0.borrow_mut().insert(new);
+            new
+        })
+    }
 }
 
 #[derive(Debug)]
@@ -296,9 +604,86 @@ pub struct InternCtx<'ctx> {
     layouts: InternedSet<ArenaPrt<'ctx, layout::Layout>>,
     /// A set of all interned allocations (for deduplication of identical allocations).
     allocations: InternedSet<ArenaPrt<'ctx, Allocation>>,
+    /// A set of all interned projection chains.
+    projections: InternedSet<&'ctx [Projection<'ctx>]>,
     /// Global allocation map for tracking allocations by ID.
     /// This maps AllocId to GlobalAlloc for lookup during codegen.
     alloc_map: GlobalAllocMap<'ctx>,
+    /// The bodies of the functions, so that callees can be resolved by DefId.
+    bodies: BodyMap<'ctx>,
+    /// The allocator of the `DefId`s of the session.
+    def_ids: DefIdAllocator,
+    /// The primitive types, interned up front.
+    common_types: CommonTypes<'ctx>,
+}
+
+/// Defines [`CommonTypes`] and the matching `TirCtx::mk_*` accessors.
+macro_rules! common_types {
+    ($($name:ident: $mk:ident => $variant:ident),* $(,)?) => {
+        #[derive(Debug, Clone, Copy)]
+        /// The primitive types, interned once when the [`InternCtx`] is
+        /// created, so that getting one of them is a field access rather
+        /// than a hash set lookup.
+        pub struct CommonTypes<'ctx> {
+            $(pub $name: TirTy<'ctx>,)*
+        }
+
+        impl<'ctx> CommonTypes<'ctx> {
+            fn new(mk: impl Fn(ty::TirTy<TirCtx<'ctx>>) -> TirTy<'ctx>) -> Self {
+                CommonTypes {
+                    $($name: mk(ty::TirTy::$variant),)*
+                }
+            }
+        }
+
+        impl<'ctx> TirCtx<'ctx> {
+            $(
+                #[doc = concat!("Returns the interned `", stringify!($name), "` type.")]
+                #[inline]
+                pub fn $mk(&self) -> TirTy<'ctx> {
+                    self.intern_ctx.common_types.$name
+                }
+            )*
+        }
+    };
+}
+
+common_types! {
+    unit: mk_unit => Unit,
+    bool: mk_bool => Bool,
+    i8: mk_i8 => I8,
+    i16: mk_i16 => I16,
+    i32: mk_i32 => I32,
+    i64: mk_i64 => I64,
+    i128: mk_i128 => I128,
+    isize: mk_isize => Isize,
+    u8: mk_u8 => U8,
+    u16: mk_u16 => U16,
+    u32: mk_u32 => U32,
+    u64: mk_u64 => U64,
+    u128: mk_u128 => U128,
+    usize: mk_usize => Usize,
+    f16: mk_f16 => F16,
+    f32: mk_f32 => F32,
+    f64: mk_f64 => F64,
+    f128: mk_f128 => F128,
+}
+
+#[derive(Default)]
+/// A map from DefIds to arena-allocated function bodies.
+pub struct BodyMap<'ctx> {
+    /// Uses RefCell for interior mutability.
+    bodies: RefCell<HashMap<DefId, &'ctx TirBody<'ctx>>>,
+}
+
+impl fmt::Debug for BodyMap<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut def_ids: Vec<_> = self.bodies.borrow().keys().map(|def_id| def_id.0).collect();
+        def_ids.sort_unstable();
+        f.debug_struct("BodyMap")
+            .field("def_ids", &def_ids)
+            .finish()
+    }
 }
 
 #[derive(Debug, Default)]
@@ -354,12 +739,22 @@ impl<'ctx> GlobalAllocMap<'ctx> {
 
 impl<'ctx> InternCtx<'ctx> {
     pub fn new(arena: &'ctx TirArena<'ctx>) -> Self {
+        let types = InternedSet::default();
+        let common_types = CommonTypes::new(|ty| {
+            TirTy(Interned::new(
+                types.intern(ty, |ty| ArenaPrt(arena.alloc(ty))).0,
+            ))
+        });
         Self {
             arena,
-            types: Default::default(),
+            types,
             layouts: Default::default(),
             allocations: Default::default(),
+            projections: Default::default(),
             alloc_map: GlobalAllocMap::new(),
+            bodies: BodyMap::default(),
+            def_ids: DefIdAllocator::new(),
+            common_types,
         }
     }
 
@@ -368,11 +763,23 @@ impl<'ctx> InternCtx<'ctx> {
     pub fn intern_allocation(&self, alloc: Allocation) -> TirAllocation<'ctx> {
         let interned_ref = self
             .allocations
-            .intern(alloc, |alloc| ArenaPrt(self.arena.alloc(alloc)))
+            .intern(alloc, |alloc| ArenaPrt(self.arena.allocations.alloc(alloc)))
             .0;
         TirAllocation(tidec_utils::interner::Interned::new(interned_ref))
     }
 
+    /// Returns all the interned types, sorted by content.
+    ///
+    /// This is meant for debugging and snapshot tests; see
+    /// [`InternedSet::iter_sorted`].
+    pub fn types_sorted(&self) -> Vec<TirTy<'ctx>> {
+        self.types
+            .iter_sorted()
+            .into_iter()
+            .map(|ty| TirTy(Interned::new(ty.0)))
+            .collect()
+    }
+
     /// Get a reference to the global allocation map.
     pub fn alloc_map(&self) -> &GlobalAllocMap<'ctx> {
         &self.alloc_map
@@ -409,10 +816,165 @@ impl<'ctx> TirCtx<'ctx> {
         self.target
     }
 
+    /// Computes the layout of `ty`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the layout cannot be computed; see
+    /// [`try_layout_of`](Self::try_layout_of) for the fallible variant.
     pub fn layout_of(self, ty: TirTy<'ctx>) -> TyAndLayout<'ctx, TirTy<'ctx>> {
+        self.try_layout_of(ty).unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    /// Computes the layout of `ty`, returning a [`LayoutError`] if the type
+    /// is too big for the target.
+    pub fn try_layout_of(
+        self,
+        ty: TirTy<'ctx>,
+    ) -> Result<TyAndLayout<'ctx, TirTy<'ctx>>, LayoutError<'ctx>> {
         let layout_ctx = LayoutCtx::new(self);
-        let layout = layout_ctx.compute_layout(ty);
-        TyAndLayout { ty, layout }
+        let layout = layout_ctx.compute_layout(ty)?;
+        Ok(TyAndLayout { ty, layout })
+    }
+
+    /// Returns true if values of `a` and `b` are laid out and passed the same
+    /// way, even if the types differ.
+    ///
+    /// The two layouts must agree on size, alignment and backend
+    /// representation. Integer signedness is ignored, so `i32` and `u32` are
+    /// compatible, while `i32` and `i64` are not.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the layout of either type cannot be computed.
+    pub fn abi_compatible(self, a: TirTy<'ctx>, b: TirTy<'ctx>) -> bool {
+        let (a, b) = (self.layout_of(a), self.layout_of(b));
+        a.size == b.size
+            && a.align == b.align
+            && a.backend_repr.erase_signedness() == b.backend_repr.erase_signedness()
+    }
+
+    /// Returns the type and layout of the field `field` of an aggregate.
+    ///
+    /// For structs this is the layout of the `field`-th field type; for
+    /// arrays every "field" is an element, so the element layout is returned.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `layout` is not a struct or array, or if `field` is out of
+    /// bounds for the struct.
+    pub fn field_layout_of(
+        self,
+        layout: TyAndLayout<'ctx, TirTy<'ctx>>,
+        field: FieldIdx,
+    ) -> TyAndLayout<'ctx, TirTy<'ctx>> {
+        match &**layout.ty {
+            ty::TirTy::Struct { fields, .. } => self.layout_of(fields.as_slice()[field.idx()]),
+            ty::TirTy::Array(element, _) => self.layout_of(*element),
+            _ => panic!("field_layout_of on non-aggregate type: {:?}", layout.ty),
+        }
+    }
+
+    /// Returns the layout of the elements a wide pointer points to, i.e.
+    /// `T` for a `{ *const T, usize }` struct such as the type of a
+    /// `ConstValue::Slice`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `layout` is not a struct whose first field is a raw pointer.
+    pub fn slice_elem_layout(
+        self,
+        layout: TyAndLayout<'ctx, TirTy<'ctx>>,
+    ) -> TyAndLayout<'ctx, TirTy<'ctx>> {
+        let ptr_layout = self.field_layout_of(layout, FieldIdx::new(0));
+        match &**ptr_layout.ty {
+            ty::TirTy::RawPtr(elem, _, _) => self.layout_of(*elem),
+            _ => panic!(
+                "slice_elem_layout on non-wide-pointer type: {:?}",
+                layout.ty
+            ),
+        }
+    }
+
+    /// Returns the number of elements in the bytes `data` of a
+    /// `ConstValue::Slice` of the wide pointer type of `layout`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the elements are zero-sized or if `data` is not a whole
+    /// number of elements.
+    pub fn const_slice_len(self, layout: TyAndLayout<'ctx, TirTy<'ctx>>, data: &[u8]) -> u64 {
+        let elem_layout = self.slice_elem_layout(layout);
+        let elem_size = elem_layout.size.bytes();
+        assert!(
+            elem_size != 0 && (data.len() as u64).is_multiple_of(elem_size),
+            "ConstValue::Slice of {} bytes is not a whole number of {:?}",
+            data.len(),
+            elem_layout.ty
+        );
+        data.len() as u64 / elem_size
+    }
+
+    /// Returns the byte offset of the field `field` within an aggregate.
+    ///
+    /// The offsets are recorded in the layout when it is computed, see
+    /// [`FieldsShape`], so this is a constant-time lookup.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `layout` is not a struct or array, or if `field` is out of
+    /// bounds.
+    pub fn offset_of(self, layout: TyAndLayout<'ctx, TirTy<'ctx>>, field: FieldIdx) -> Size {
+        match layout.fields {
+            FieldsShape::Primitive => {
+                panic!("offset_of on non-aggregate type: {:?}", layout.ty)
+            }
+            FieldsShape::Array { .. } | FieldsShape::Arbitrary { .. } => {
+                layout.fields.offset(field.idx())
+            }
+        }
+    }
+
+    /// Returns the type of the discriminant of the enum `ty`: the smallest
+    /// unsigned integer that can tag each of its variants.
+    ///
+    /// This is also the type of the tag in the layout of the enum, see
+    /// [`layout::Variants::Tagged`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `ty` is not an enum.
+    pub fn discriminant_ty(self, ty: TirTy<'ctx>) -> TirTy<'ctx> {
+        let ty::TirTy::Enum { variants } = &**ty else {
+            panic!("discriminant_ty on non-enum type: {ty:?}");
+        };
+        let count = variants.as_slice().len() as u64;
+        let tag = if count <= 1 << 8 {
+            ty::TirTy::U8
+        } else if count <= 1 << 16 {
+            ty::TirTy::U16
+        } else {
+            ty::TirTy::U32
+        };
+        self.intern_ty(tag)
+    }
+
+    /// Returns the type and layout of the payload of the variant `variant`
+    /// of an enum. The payload starts at the `payload_offset` of the enum's
+    /// [`layout::Variants::Tagged`] layout.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `layout` is not an enum, or if `variant` is out of bounds.
+    pub fn variant_layout_of(
+        self,
+        layout: TyAndLayout<'ctx, TirTy<'ctx>>,
+        variant: usize,
+    ) -> TyAndLayout<'ctx, TirTy<'ctx>> {
+        match &**layout.ty {
+            ty::TirTy::Enum { variants } => self.layout_of(variants.as_slice()[variant]),
+            _ => panic!("variant_layout_of on non-enum type: {:?}", layout.ty),
+        }
     }
 
     pub fn backend_kind(&self) -> &BackendKind {
@@ -423,13 +985,39 @@ impl<'ctx> TirCtx<'ctx> {
         &self.arguments.emit_kind
     }
 
+    pub fn checked_div(&self) -> bool {
+        self.arguments.checked_div
+    }
+
+    pub fn verify_module(&self) -> bool {
+        self.arguments.verify_module
+    }
+
+    pub fn target_features(&self) -> &[String] {
+        &self.arguments.target_features
+    }
+
+    pub fn module_flags(&self) -> &[ModuleFlag] {
+        &self.arguments.module_flags
+    }
+
+    /// Returns the path the output of the unit `unit_name` is written to:
+    /// `TirArgs::output_path` if set, the default path of the emit kind
+    /// otherwise.
+    pub fn output_path(&self, unit_name: &str) -> PathBuf {
+        self.arguments
+            .output_path
+            .clone()
+            .unwrap_or_else(|| self.arguments.emit_kind.output_path(unit_name))
+    }
+
     // ===== Direct inter =====
     pub fn intern_layout(&self, layout: layout::Layout) -> Layout<'ctx> {
         Layout(Interned::new(
             self.intern_ctx
                 .layouts
                 .intern(layout, |layout: layout::Layout| {
-                    ArenaPrt(self.intern_ctx.arena.alloc(layout))
+                    ArenaPrt(self.intern_ctx.arena.layouts.alloc(layout))
                 })
                 .0,
         ))
@@ -446,6 +1034,19 @@ impl<'ctx> TirCtx<'ctx> {
         ))
     }
 
+    /// Dumps every interned type, one per line, sorted by content.
+    ///
+    /// This is a debugging aid, e.g. to diff the interned types across runs
+    /// when chasing a layout bug. It is not meant for hot paths.
+    pub fn dump_interned_types(&self) -> String {
+        let mut out = String::new();
+        for ty in self.intern_ctx.types_sorted() {
+            out.push_str(&ty.to_string());
+            out.push('\n');
+        }
+        out
+    }
+
     /// Intern a list of types, returning an arena-allocated `TirTypeList`.
     ///
     /// If an identical list (by value) already exists, the existing allocation
@@ -465,6 +1066,19 @@ impl<'ctx> TirCtx<'ctx> {
         crate::TirTypeList::new(arena_slice)
     }
 
+    /// Intern a chain of projections, returning an arena-allocated slice.
+    ///
+    /// Equal chains share the same storage, so interned projections can be
+    /// compared by pointer and copied freely.
+    pub fn intern_projection(&self, elems: &[Projection<'ctx>]) -> &'ctx [Projection<'ctx>] {
+        if elems.is_empty() {
+            return &[];
+        }
+        self.intern_ctx
+            .projections
+            .intern_slice(elems, |elems| self.intern_ctx.arena.alloc_slice(elems))
+    }
+
     // ===== Allocation interning =====
 
     /// Intern an allocation in the arena and return an interned `TirAllocation`.
@@ -532,6 +1146,37 @@ impl<'ctx> TirCtx<'ctx> {
     pub fn iter_global_allocs(&self) -> Vec<(AllocId, GlobalAlloc<'ctx>)> {
         self.intern_ctx.alloc_map().iter()
     }
+
+    // ===== Bodies =====
+
+    /// Allocate `body` in the arena and register it under its `DefId`,
+    /// which is returned. The body is dropped with the arena.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a body with the same `DefId` was already interned.
+    pub fn intern_body(&self, body: TirBody<'ctx>) -> DefId {
+        let def_id = body.metadata.def_id;
+        let body = self.intern_ctx.arena.bodies.alloc(body);
+        let previous = self
+            .intern_ctx
+            .bodies
+            .bodies
+            .borrow_mut()
+            .insert(def_id, body);
+        assert!(previous.is_none(), "body {def_id:?} interned twice");
+        def_id
+    }
+
+    /// Get the body of the function with the given `DefId`.
+    pub fn body(&self, def_id: DefId) -> Option<&'ctx TirBody<'ctx>> {
+        self.intern_ctx.bodies.bodies.borrow().get(&def_id).copied()
+    }
+
+    /// Returns a `DefId` not yet handed out in this session.
+    pub fn fresh_def_id(&self) -> DefId {
+        self.intern_ctx.def_ids.fresh()
+    }
 }
 
 impl<'ctx> Interner for TirCtx<'ctx> {
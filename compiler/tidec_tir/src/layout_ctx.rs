@@ -1,11 +1,56 @@
 use crate::{ctx::TirCtx, ty, TirTy, TirTypeList};
 use tidec_abi::{
-    layout::{self, BackendRepr, Primitive},
+    layout::{self, BackendRepr, FieldsShape, Primitive, Variants},
     size_and_align::{AbiAndPrefAlign, Size},
-    target::AddressSpace,
     Layout,
 };
 
+/// An error produced while computing the layout of a type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LayoutError<'ctx> {
+    /// The size of the type does not fit in the target's address space:
+    /// either the byte count overflows a `u64`, or the size in bits does.
+    SizeOverflow(TirTy<'ctx>),
+    /// The number of lanes of a SIMD type is not a power of two (or is
+    /// zero), which the vector registers of the supported targets require.
+    InvalidSimdLanes(TirTy<'ctx>, u32),
+    /// An unsized type appears where its size must be known: as an array
+    /// element, as a struct field other than the last one, or as an enum
+    /// payload. The first type is the aggregate, the second the offending
+    /// element, field or payload.
+    UnsizedElement(TirTy<'ctx>, TirTy<'ctx>),
+}
+
+impl<'ctx> std::fmt::Display for LayoutError<'ctx> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LayoutError::SizeOverflow(ty) => {
+                write!(
+                    f,
+                    "the type `{:?}` is too big for the current architecture",
+                    ty
+                )
+            }
+            LayoutError::InvalidSimdLanes(ty, lanes) => {
+                write!(
+                    f,
+                    "the SIMD type `{:?}` has {} lanes, which is not a power of two",
+                    ty, lanes
+                )
+            }
+            LayoutError::UnsizedElement(ty, elem) => {
+                write!(
+                    f,
+                    "the type `{:?}` contains the unsized type `{:?}` where a sized type is required",
+                    ty, elem
+                )
+            }
+        }
+    }
+}
+
+impl<'ctx> std::error::Error for LayoutError<'ctx> {}
+
 pub struct LayoutCtx<'ctx> {
     tir_ctx: TirCtx<'ctx>,
 }
@@ -18,7 +63,14 @@ impl<'ctx> LayoutCtx<'ctx> {
 
     /// Computes the layout for a given type. We should cache the results
     /// to avoid recomputing the layout for the same type multiple times.
-    pub fn compute_layout(&self, ty: TirTy<'ctx>) -> Layout<'ctx> {
+    ///
+    /// Returns [`LayoutError::SizeOverflow`] if the type is a struct or an
+    /// array whose size does not fit in a `u64` (in bits),
+    /// [`LayoutError::InvalidSimdLanes`] if the type is a SIMD vector whose
+    /// lane count is not a power of two, and [`LayoutError::UnsizedElement`]
+    /// if an unsized type is used as an array element, a non-last field or
+    /// an enum payload.
+    pub fn compute_layout(&self, ty: TirTy<'ctx>) -> Result<Layout<'ctx>, LayoutError<'ctx>> {
         let data_layout = &self.tir_ctx.target().data_layout;
 
         let scalar = |primitive: Primitive| -> (Size, AbiAndPrefAlign, BackendRepr) {
@@ -38,13 +90,32 @@ impl<'ctx> LayoutCtx<'ctx> {
                 Primitive::F64 => (Size::from_bits(64), data_layout.float64_align),
                 Primitive::F128 => (Size::from_bits(128), data_layout.float128_align),
                 Primitive::Pointer(address_space) => (
-                    data_layout.pointer_size(),
+                    data_layout.pointer_size_in(address_space),
                     data_layout.pointer_align(address_space),
                 ),
             };
             (size, align, BackendRepr::Scalar(primitive))
         };
 
+        // `Isize`/`Usize` are as wide as a pointer in the default address
+        // space, and share its alignment.
+        let pointer_sized = |signed: bool| -> (Size, AbiAndPrefAlign, BackendRepr) {
+            let primitive = match (data_layout.pointer_size.bits(), signed) {
+                (16, true) => Primitive::I16,
+                (32, true) => Primitive::I32,
+                (64, true) => Primitive::I64,
+                (16, false) => Primitive::U16,
+                (32, false) => Primitive::U32,
+                (64, false) => Primitive::U64,
+                (bits, _) => unreachable!("unsupported pointer width: {bits} bits"),
+            };
+            (
+                data_layout.pointer_size,
+                data_layout.pointer_align,
+                BackendRepr::Scalar(primitive),
+            )
+        };
+
         let (size, align, backend_repr) = match &**ty {
             ty::TirTy::Unit => {
                 // Unit / void is a zero-sized type.
@@ -53,7 +124,7 @@ impl<'ctx> LayoutCtx<'ctx> {
                 (
                     Size::ZERO,
                     AbiAndPrefAlign::new(1, 1),
-                    BackendRepr::Memory,
+                    BackendRepr::Memory { sized: true },
                 )
             }
             ty::TirTy::Bool => {
@@ -66,48 +137,81 @@ impl<'ctx> LayoutCtx<'ctx> {
             ty::TirTy::I32 => scalar(Primitive::I32),
             ty::TirTy::I64 => scalar(Primitive::I64),
             ty::TirTy::I128 => scalar(Primitive::I128),
+            ty::TirTy::Isize => pointer_sized(true),
             ty::TirTy::U8 => scalar(Primitive::U8),
             ty::TirTy::U16 => scalar(Primitive::U16),
             ty::TirTy::U32 => scalar(Primitive::U32),
             ty::TirTy::U64 => scalar(Primitive::U64),
             ty::TirTy::U128 => scalar(Primitive::U128),
+            ty::TirTy::Usize => pointer_sized(false),
             ty::TirTy::F16 => scalar(Primitive::F16),
             ty::TirTy::F32 => scalar(Primitive::F32),
             ty::TirTy::F64 => scalar(Primitive::F64),
             ty::TirTy::F128 => scalar(Primitive::F128),
-            ty::TirTy::RawPtr(ref pointee, _) => {
+            ty::TirTy::RawPtr(ref pointee, _, address_space) => {
                 // We ignore the backend representation of the pointee type for now. This is because
                 // we are only interested in the pointer type itself, which has a fixed size and alignment
                 // regardless of the pointee type. However, in the future, we might want to consider
                 // the pointee type for more advanced optimizations or analyses.
-                let (size, align, _) = scalar(Primitive::Pointer(AddressSpace::DATA));
+                let (size, align, backend_repr) = scalar(Primitive::Pointer(*address_space));
 
                 if pointee.is_sized() {
-                    (size, align, BackendRepr::Scalar(Primitive::Pointer(AddressSpace::DATA)))
+                    (size, align, backend_repr)
                 } else {
-                    unimplemented!("Layout computation for unsized pointee types is not yet supported.")
+                    unimplemented!(
+                        "Layout computation for unsized pointee types is not yet supported."
+                    )
                 }
             }
             // TirTy::FnPty { param_tys, ret_ty } => {
             //     todo!()
             // }
-            // TODO: Implement layout computation for Metadata types (e.g., for unsized types or trait objects).
-            // Metadata represents type information for unsized types (such as slices or trait objects),
-            // which require special handling for their layout. Support for this will be added in a future release.
-            ty::TirTy::Metadata => unimplemented!("Layout computation for TirTy::Metadata (used for unsized types/trait objects) is not yet supported. See TODO comment for details."),
+            // Metadata has no size known at compile time: it is unsized, and
+            // its layout only describes an empty statically known prefix.
+            ty::TirTy::Metadata => (
+                Size::ZERO,
+                AbiAndPrefAlign::new(1, 1),
+                BackendRepr::Memory { sized: false },
+            ),
             ty::TirTy::Struct { fields, packed } => {
-                return self.compute_struct_layout(fields, *packed);
+                return self.compute_struct_layout(ty, fields, *packed);
             }
             ty::TirTy::Array(element_ty, count) => {
-                return self.compute_array_layout(*element_ty, *count);
+                return self.compute_array_layout(ty, *element_ty, *count);
+            }
+            ty::TirTy::Enum { variants } => {
+                return self.compute_enum_layout(ty, variants);
+            }
+            ty::TirTy::Simd { elem, lanes } => {
+                // A vector is `lanes` tightly packed elements, aligned to its
+                // whole size rounded up to a power of two, as in the x86-64
+                // and AArch64 vector ABIs.
+                if !lanes.is_power_of_two() {
+                    return Err(LayoutError::InvalidSimdLanes(ty, *lanes));
+                }
+                let (elem_size, _, _) = scalar(*elem);
+                let size = elem_size
+                    .checked_mul(u64::from(*lanes))
+                    .ok_or(LayoutError::SizeOverflow(ty))?;
+                let align = size.bytes().next_power_of_two();
+                (
+                    size,
+                    AbiAndPrefAlign::new(align, align),
+                    BackendRepr::Vector {
+                        element: *elem,
+                        count: u64::from(*lanes),
+                    },
+                )
             }
         };
 
-        self.tir_ctx.intern_layout(layout::Layout {
+        Ok(self.tir_ctx.intern_layout(layout::Layout {
             size,
             align,
             backend_repr,
-        })
+            fields: FieldsShape::Primitive,
+            variants: Variants::Single,
+        }))
     }
 
     /// Compute the layout for a struct type.
@@ -119,23 +223,51 @@ impl<'ctx> LayoutCtx<'ctx> {
     ///
     /// If `packed` is `true`, no alignment padding is inserted between fields
     /// and the struct's overall alignment is 1.
-    fn compute_struct_layout(&self, fields: &TirTypeList<'ctx>, packed: bool) -> Layout<'ctx> {
+    ///
+    /// A non-packed struct of exactly two scalar fields that fits in two
+    /// pointer-sized registers gets a [`BackendRepr::ScalarPair`]
+    /// representation, so that it is passed and returned in two registers.
+    ///
+    /// Only the last field may be unsized, in which case the struct is
+    /// unsized too and its size is that of its statically known prefix.
+    fn compute_struct_layout(
+        &self,
+        ty: TirTy<'ctx>,
+        fields: &TirTypeList<'ctx>,
+        packed: bool,
+    ) -> Result<Layout<'ctx>, LayoutError<'ctx>> {
         let field_types = fields.as_slice();
 
         if field_types.is_empty() {
             // Empty struct is a ZST.
-            return self.tir_ctx.intern_layout(layout::Layout {
+            return Ok(self.tir_ctx.intern_layout(layout::Layout {
                 size: Size::ZERO,
                 align: AbiAndPrefAlign::new(1, 1),
-                backend_repr: BackendRepr::Memory,
-            });
+                backend_repr: BackendRepr::Memory { sized: true },
+                fields: FieldsShape::Arbitrary {
+                    offsets: Vec::new(),
+                },
+                variants: Variants::Single,
+            }));
         }
 
+        let overflow = || LayoutError::SizeOverflow(ty);
+        let mut offsets = Vec::with_capacity(field_types.len());
         let mut struct_size: u64 = 0;
         let mut struct_align: u64 = 1;
+        let mut field_reprs = Vec::with_capacity(field_types.len());
+        let mut sized = true;
 
-        for field_ty in field_types {
-            let field_layout = self.compute_layout(*field_ty);
+        for (i, field_ty) in field_types.iter().enumerate() {
+            let field_layout = self.compute_layout(*field_ty)?;
+            field_reprs.push(field_layout.backend_repr);
+
+            if field_layout.is_unsized() {
+                if i + 1 != field_types.len() {
+                    return Err(LayoutError::UnsizedElement(ty, *field_ty));
+                }
+                sized = false;
+            }
 
             let field_align = if packed {
                 1
@@ -145,11 +277,14 @@ impl<'ctx> LayoutCtx<'ctx> {
 
             // Align the current offset to the field's alignment.
             if field_align > 0 {
-                struct_size = (struct_size + field_align - 1) & !(field_align - 1);
+                struct_size = align_up(struct_size, field_align).ok_or_else(overflow)?;
             }
+            offsets.push(Size::from_bytes(struct_size));
 
             // Advance past this field.
-            struct_size += field_layout.size.bytes();
+            struct_size = struct_size
+                .checked_add(field_layout.size.bytes())
+                .ok_or_else(overflow)?;
 
             // Track the maximum alignment.
             if field_align > struct_align {
@@ -162,45 +297,124 @@ impl<'ctx> LayoutCtx<'ctx> {
 
         // Round the total size up to the struct's alignment.
         if final_align > 0 {
-            struct_size = (struct_size + final_align - 1) & !(final_align - 1);
+            struct_size = align_up(struct_size, final_align).ok_or_else(overflow)?;
         }
 
-        self.tir_ctx.intern_layout(layout::Layout {
-            size: Size::from_bytes(struct_size),
+        let max_pair_size = self.tir_ctx.target().data_layout.pointer_size.bytes() * 2;
+        let backend_repr = match field_reprs.as_slice() {
+            [BackendRepr::Scalar(a), BackendRepr::Scalar(b)]
+                if !packed && struct_size <= max_pair_size =>
+            {
+                BackendRepr::ScalarPair(*a, *b)
+            }
+            _ => BackendRepr::Memory { sized },
+        };
+
+        Ok(self.tir_ctx.intern_layout(layout::Layout {
+            size: Size::from_bytes_checked(struct_size).ok_or_else(overflow)?,
             align: AbiAndPrefAlign::new(final_align, final_align),
-            backend_repr: BackendRepr::Memory,
-        })
+            backend_repr,
+            fields: FieldsShape::Arbitrary { offsets },
+            variants: Variants::Single,
+        }))
     }
 
     /// Compute the layout for an array type.
     ///
     /// The layout is: `element_size` (rounded up to element alignment) × `count`.
     /// An array of zero elements is a ZST.
-    fn compute_array_layout(&self, element_ty: TirTy<'ctx>, count: u64) -> Layout<'ctx> {
-        let elem_layout = self.compute_layout(element_ty);
+    fn compute_array_layout(
+        &self,
+        ty: TirTy<'ctx>,
+        element_ty: TirTy<'ctx>,
+        count: u64,
+    ) -> Result<Layout<'ctx>, LayoutError<'ctx>> {
+        let elem_layout = self.compute_layout(element_ty)?;
 
-        if count == 0 {
-            return self.tir_ctx.intern_layout(layout::Layout {
-                size: Size::ZERO,
-                align: elem_layout.align,
-                backend_repr: BackendRepr::Memory,
-            });
+        if elem_layout.is_unsized() {
+            return Err(LayoutError::UnsizedElement(ty, element_ty));
         }
 
-        // Element stride is the element size rounded up to its alignment.
-        let elem_align = elem_layout.align.abi.bytes();
-        let elem_stride = if elem_align > 0 {
-            (elem_layout.size.bytes() + elem_align - 1) & !(elem_align - 1)
-        } else {
-            elem_layout.size.bytes()
-        };
+        let overflow = || LayoutError::SizeOverflow(ty);
 
-        let total_size = elem_stride * count;
+        // Elements are spaced by their stride, not their size. Rounding the
+        // size up to the alignment can itself overflow, so do not go through
+        // the panicking `stride`.
+        let stride = elem_layout
+            .size
+            .checked_align_to(elem_layout.align.abi)
+            .ok_or_else(overflow)?;
+        let total_size = stride.checked_mul(count).ok_or_else(overflow)?;
 
-        self.tir_ctx.intern_layout(layout::Layout {
-            size: Size::from_bytes(total_size),
+        Ok(self.tir_ctx.intern_layout(layout::Layout {
+            size: total_size,
             align: elem_layout.align,
-            backend_repr: BackendRepr::Memory,
-        })
+            backend_repr: BackendRepr::Memory { sized: true },
+            fields: FieldsShape::Array { stride, count },
+            variants: Variants::Single,
+        }))
     }
+
+    /// Compute the layout for an enum type.
+    ///
+    /// The enum is laid out as a C tagged union: the tag, of the type given
+    /// by [`TirCtx::discriminant_ty`], sits at offset 0, and the payloads of
+    /// all variants share the storage that follows it, aligned to the
+    /// largest payload alignment. The total size is rounded up to the
+    /// alignment of the enum, the largest of the tag and payload alignments.
+    ///
+    /// An enum without variants has no values and is a ZST.
+    fn compute_enum_layout(
+        &self,
+        ty: TirTy<'ctx>,
+        variants: &TirTypeList<'ctx>,
+    ) -> Result<Layout<'ctx>, LayoutError<'ctx>> {
+        if variants.as_slice().is_empty() {
+            return Ok(self.tir_ctx.intern_layout(layout::Layout {
+                size: Size::ZERO,
+                align: AbiAndPrefAlign::new(1, 1),
+                backend_repr: BackendRepr::Memory { sized: true },
+                fields: FieldsShape::Primitive,
+                variants: Variants::Single,
+            }));
+        }
+
+        let overflow = || LayoutError::SizeOverflow(ty);
+        let tag_layout = self.compute_layout(self.tir_ctx.discriminant_ty(ty))?;
+        let mut payload_size: u64 = 0;
+        let mut payload_align: u64 = 1;
+        for variant_ty in variants.as_slice() {
+            let variant_layout = self.compute_layout(*variant_ty)?;
+            if variant_layout.is_unsized() {
+                return Err(LayoutError::UnsizedElement(ty, *variant_ty));
+            }
+            payload_size = payload_size.max(variant_layout.size.bytes());
+            payload_align = payload_align.max(variant_layout.align.abi.bytes());
+        }
+
+        let payload_offset =
+            align_up(tag_layout.size.bytes(), payload_align).ok_or_else(overflow)?;
+        let enum_align = payload_align.max(tag_layout.align.abi.bytes());
+        let enum_size = payload_offset
+            .checked_add(payload_size)
+            .and_then(|size| align_up(size, enum_align))
+            .ok_or_else(overflow)?;
+
+        Ok(self.tir_ctx.intern_layout(layout::Layout {
+            size: Size::from_bytes_checked(enum_size).ok_or_else(overflow)?,
+            align: AbiAndPrefAlign::new(enum_align, enum_align),
+            backend_repr: BackendRepr::Memory { sized: true },
+            fields: FieldsShape::Primitive,
+            variants: Variants::Tagged {
+                tag: tag_layout.backend_repr.to_primitive(),
+                payload_offset: Size::from_bytes(payload_offset),
+            },
+        }))
+    }
+}
+
+/// Rounds `offset` up to the next multiple of `align` (a power of two),
+/// returning `None` on overflow.
+pub(crate) fn align_up(offset: u64, align: u64) -> Option<u64> {
+    Some(offset.checked_add(align - 1)? & !(align - 1))
 }
@@ -1,11 +1,19 @@
 pub mod alloc;
+pub mod analysis;
 pub mod body;
+pub mod const_eval;
 pub mod ctx;
 pub mod layout_ctx;
+pub mod parse;
+pub mod passes;
+pub mod pretty;
 pub mod syntax;
 pub mod ty;
+pub mod visit;
 
 use crate::ctx::TirCtx;
+use std::cell::RefCell;
+use std::collections::HashSet;
 use std::ops::Deref;
 use tidec_utils::interner::{Interned, Ty, TypeList};
 
@@ -24,9 +32,33 @@ impl<'ctx> Deref for TirAllocation<'ctx> {
 pub struct TirTy<'ctx>(pub Interned<'ctx, crate::ty::TirTy<TirCtx<'ctx>>>);
 impl<'ctx> Ty<TirCtx<'ctx>> for TirTy<'ctx> {}
 
+thread_local! {
+    /// The addresses of the interned types whose `Debug` output is currently
+    /// being written on this thread.
+    static DEBUG_IN_PROGRESS: RefCell<HashSet<*const ()>> = RefCell::new(HashSet::new());
+}
+
+/// Prints the interned type, or a short `<ty#addr>` form when the type is
+/// already being printed further up the stack.
+///
+/// This cuts the recursion through types that (directly or indirectly) refer
+/// to themselves, which would otherwise overflow the stack.
 impl<'ctx> std::fmt::Debug for TirTy<'ctx> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{:?}", self.0)
+        let addr = &*self.0 as *const crate::ty::TirTy<TirCtx<'ctx>> as *const ();
+        if !DEBUG_IN_PROGRESS.with(|set| set.borrow_mut().insert(addr)) {
+            return write!(f, "<ty#{:p}>", addr);
+        }
+        let res = write!(f, "{:?}", self.0);
+        DEBUG_IN_PROGRESS.with(|set| set.borrow_mut().remove(&addr));
+        res
+    }
+}
+
+/// Prints the type the way it appears in textual TIR, e.g. `i32` or `()`.
+impl<'ctx> std::fmt::Display for TirTy<'ctx> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        crate::pretty::write_ty(*self, f)
     }
 }
 
@@ -47,6 +79,19 @@ impl<'ctx> PartialEq for TirTy<'ctx> {
 
 impl<'ctx> Eq for TirTy<'ctx> {} // Trivial if PartialEq is implemented correctly
 
+impl<'ctx> PartialOrd for TirTy<'ctx> {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<'ctx> Ord for TirTy<'ctx> {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        // Distinct interned types are ordered by content, see `Interned`.
+        self.0.cmp(&other.0)
+    }
+}
+
 impl<'ctx> std::hash::Hash for TirTy<'ctx> {
     fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
         // Hash only the Interned field, which internally will skip the non-Hashable parts.
@@ -100,6 +145,24 @@ impl<'ctx> PartialEq for TirTypeList<'ctx> {
 
 impl<'ctx> Eq for TirTypeList<'ctx> {}
 
+impl<'ctx> PartialOrd for TirTypeList<'ctx> {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<'ctx> Ord for TirTypeList<'ctx> {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        // Lists are ordered lexicographically by content, so that the
+        // ordering does not depend on where they were allocated.
+        if self == other {
+            std::cmp::Ordering::Equal
+        } else {
+            self.0.cmp(other.0)
+        }
+    }
+}
+
 impl<'ctx> std::hash::Hash for TirTypeList<'ctx> {
     fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
         // Hash the pointer and length for pointer-identity hashing.
@@ -0,0 +1,620 @@
+//! A parser for the textual TIR format written by [`crate::pretty::write_body`].
+//!
+//! Only a subset of TIR is supported: scalar types (`()`, `bool`, integers and
+//! floats), operand and unary/binary-op assignments to plain locals, block
+//! parameters, and the `goto`, `switchInt`, `assert`, `return` and
+//! `unreachable` terminators. Anything else is rejected with a [`ParseError`].
+
+use std::fmt;
+use std::num::NonZero;
+
+use crate::body::{DefId, TirBody, TirBodyMetadata};
+use crate::ctx::TirCtx;
+use crate::syntax::{
+    AssertMessage, BasicBlock, BasicBlockData, BinaryOp, ConstOperand, ConstScalar, ConstValue,
+    Local, LocalData, Operand, Place, RValue, RawScalarValue, Statement, SwitchTargets, Terminator,
+    UnaryOp,
+};
+use crate::ty;
+use crate::TirTy;
+use tidec_abi::size_and_align::Size;
+use tidec_utils::idx::Idx;
+use tidec_utils::index_vec::IdxVec;
+
+/// An error produced while parsing textual TIR.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    /// The 1-based line where the error was detected.
+    pub line: usize,
+    /// A description of what went wrong.
+    pub message: String,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Parses a single body in the textual TIR format.
+///
+/// Types are interned in `ctx`. The returned body has `DefId(0)` and the
+/// default metadata of [`TirBodyMetadata::function`]; callers that need a
+/// different identity should overwrite it.
+pub fn parse_body<'ctx>(ctx: &TirCtx<'ctx>, src: &str) -> Result<TirBody<'ctx>, ParseError> {
+    let tokens = lex(src)?;
+    let mut parser = Parser {
+        ctx,
+        tokens,
+        pos: 0,
+    };
+    let body = parser.body()?;
+    if let Some(tok) = parser.tokens.get(parser.pos) {
+        return Err(parser.error_at(tok.line, "expected end of input"));
+    }
+    Ok(body)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum TokenKind {
+    /// An identifier or keyword, e.g. `fn`, `_1`, `bb0`, `Add`.
+    Word(String),
+    /// A literal starting with a digit, including its type suffix, e.g. `1_i32`.
+    Number(String),
+    /// `->`
+    Arrow,
+    /// Any other single punctuation character.
+    Punct(char),
+}
+
+struct Token {
+    kind: TokenKind,
+    line: usize,
+}
+
+fn lex(src: &str) -> Result<Vec<Token>, ParseError> {
+    let mut tokens = Vec::new();
+    let mut chars = src.chars().peekable();
+    let mut line = 1;
+
+    while let Some(&c) = chars.peek() {
+        if c == '\n' {
+            line += 1;
+            chars.next();
+        } else if c.is_whitespace() {
+            chars.next();
+        } else if c.is_ascii_alphanumeric() || c == '_' {
+            let mut word = String::new();
+            while let Some(&c) = chars.peek() {
+                if !(c.is_ascii_alphanumeric() || c == '_') {
+                    break;
+                }
+                word.push(c);
+                chars.next();
+            }
+            let kind = if c.is_ascii_digit() {
+                TokenKind::Number(word)
+            } else {
+                TokenKind::Word(word)
+            };
+            tokens.push(Token { kind, line });
+        } else if c == '-' {
+            chars.next();
+            let kind = if chars.next_if_eq(&'>').is_some() {
+                TokenKind::Arrow
+            } else {
+                TokenKind::Punct('-')
+            };
+            tokens.push(Token { kind, line });
+        } else if "(){}[]:;,=".contains(c) {
+            chars.next();
+            tokens.push(Token {
+                kind: TokenKind::Punct(c),
+                line,
+            });
+        } else {
+            return Err(ParseError {
+                line,
+                message: format!("unexpected character `{c}`"),
+            });
+        }
+    }
+
+    Ok(tokens)
+}
+
+struct Parser<'a, 'ctx> {
+    ctx: &'a TirCtx<'ctx>,
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl<'a, 'ctx> Parser<'a, 'ctx> {
+    fn body(&mut self) -> Result<TirBody<'ctx>, ParseError> {
+        self.expect_word("fn")?;
+        let name = self.word()?;
+
+        self.expect_punct('(')?;
+        let mut arg_tys = Vec::new();
+        while !self.eat_punct(')') {
+            if !arg_tys.is_empty() {
+                self.expect_punct(',')?;
+            }
+            let local = self.local()?;
+            if local.idx() != arg_tys.len() + 1 {
+                return Err(self.error(format!("expected argument `_{}`", arg_tys.len() + 1)));
+            }
+            self.expect_punct(':')?;
+            arg_tys.push(self.ty()?);
+        }
+        self.expect(TokenKind::Arrow)?;
+        let ret_ty = self.ty()?;
+        self.expect_punct('{')?;
+
+        let mut decls = Vec::new();
+        while self.eat_word("let") {
+            let mutable = self.eat_word("mut");
+            let local = self.local()?;
+            if local.idx() != decls.len() {
+                return Err(self.error(format!("expected local `_{}`", decls.len())));
+            }
+            self.expect_punct(':')?;
+            let ty = self.ty()?;
+            self.expect_punct(';')?;
+            decls.push(LocalData { ty, mutable });
+        }
+
+        if decls.len() <= arg_tys.len() {
+            return Err(self.error("missing declarations for the return place and arguments"));
+        }
+        if decls[0].ty != ret_ty {
+            return Err(self.error("the type of `_0` does not match the return type"));
+        }
+        if let Some(i) = (0..arg_tys.len()).find(|&i| decls[i + 1].ty != arg_tys[i]) {
+            return Err(self.error(format!(
+                "the type of `_{}` does not match the signature",
+                i + 1
+            )));
+        }
+        let locals = decls.split_off(arg_tys.len() + 1);
+
+        let mut basic_blocks = IdxVec::new();
+        while !self.eat_punct('}') {
+            let bb = self.block()?;
+            if bb.idx() != basic_blocks.len() {
+                return Err(self.error(format!("expected block `bb{}`", basic_blocks.len())));
+            }
+            basic_blocks.push(self.block_data()?);
+        }
+
+        Ok(TirBody {
+            metadata: TirBodyMetadata::function(DefId(0), name),
+            ret_and_args: IdxVec::from_raw(decls),
+            arg_count: arg_tys.len(),
+            locals: IdxVec::from_raw(locals),
+            basic_blocks,
+        })
+    }
+
+    fn block_data(&mut self) -> Result<BasicBlockData<'ctx>, ParseError> {
+        let mut params = Vec::new();
+        if self.eat_punct('(') {
+            while !self.eat_punct(')') {
+                if !params.is_empty() {
+                    self.expect_punct(',')?;
+                }
+                let local = self.local()?;
+                self.expect_punct(':')?;
+                params.push((local, self.ty()?));
+            }
+        }
+        self.expect_punct(':')?;
+        self.expect_punct('{')?;
+
+        let mut statements = Vec::new();
+        let terminator = loop {
+            if let Some(TokenKind::Word(word)) = self.peek() {
+                if parse_local(word).is_some() {
+                    statements.push(self.statement()?);
+                    self.expect_punct(';')?;
+                    continue;
+                }
+            }
+            let terminator = self.terminator()?;
+            self.expect_punct(';')?;
+            break terminator;
+        };
+        self.expect_punct('}')?;
+
+        Ok(BasicBlockData {
+            params,
+            statements,
+            terminator,
+        })
+    }
+
+    fn statement(&mut self) -> Result<Statement<'ctx>, ParseError> {
+        let place = Place::from(self.local()?);
+        self.expect_punct('=')?;
+
+        let rvalue = match self.peek() {
+            Some(TokenKind::Word(word)) if parse_local(word).is_none() && word != "const" => {
+                let op = self.word()?;
+                self.expect_punct('(')?;
+                let lhs = self.operand()?;
+                let rvalue = if let Some(op) = unary_op(&op) {
+                    RValue::UnaryOp(op, lhs)
+                } else if let Some(op) = binary_op(&op) {
+                    self.expect_punct(',')?;
+                    RValue::BinaryOp(op, lhs, self.operand()?)
+                } else {
+                    return Err(self.error(format!("unknown operator `{op}`")));
+                };
+                self.expect_punct(')')?;
+                rvalue
+            }
+            _ => RValue::Operand(self.operand()?),
+        };
+
+        Ok(Statement::assign(place, rvalue))
+    }
+
+    fn terminator(&mut self) -> Result<Terminator<'ctx>, ParseError> {
+        let keyword = self.word()?;
+        match keyword.as_str() {
+            "return" => Ok(Terminator::Return),
+            "unreachable" => Ok(Terminator::Unreachable),
+            "goto" => {
+                self.expect(TokenKind::Arrow)?;
+                let target = self.block()?;
+                let block_args = self.block_args()?;
+                Ok(Terminator::Goto { target, block_args })
+            }
+            "switchInt" => {
+                self.expect_punct('(')?;
+                let discr = self.operand()?;
+                self.expect_punct(')')?;
+                self.expect(TokenKind::Arrow)?;
+                self.expect_punct('[')?;
+                let mut values = Vec::new();
+                let otherwise = loop {
+                    if self.eat_word("otherwise") {
+                        self.expect_punct(':')?;
+                        let otherwise = self.block()?;
+                        self.expect_punct(']')?;
+                        break otherwise;
+                    }
+                    let value = self.number()?;
+                    let value = value
+                        .parse::<u128>()
+                        .map_err(|_| self.error(format!("invalid switch value `{value}`")))?;
+                    self.expect_punct(':')?;
+                    values.push((value, self.block()?));
+                    self.expect_punct(',')?;
+                };
+                let block_args = self.block_args()?;
+                Ok(Terminator::SwitchInt {
+                    discr,
+                    targets: SwitchTargets::new(values, otherwise),
+                    block_args,
+                })
+            }
+            "assert" => {
+                self.expect_punct('(')?;
+                let cond = self.operand()?;
+                self.expect_punct(',')?;
+                let expected = match self.word()?.as_str() {
+                    "true" => true,
+                    "false" => false,
+                    other => return Err(self.error(format!("expected a bool, found `{other}`"))),
+                };
+                self.expect_punct(',')?;
+                let msg = match self.word()?.as_str() {
+                    "overflow" => AssertMessage::Overflow,
+                    "bounds_check" => {
+                        self.expect_punct('(')?;
+                        self.expect_word("len")?;
+                        self.expect_punct(':')?;
+                        let len = self.operand()?;
+                        self.expect_punct(',')?;
+                        self.expect_word("index")?;
+                        self.expect_punct(':')?;
+                        let index = self.operand()?;
+                        self.expect_punct(')')?;
+                        AssertMessage::BoundsCheck { len, index }
+                    }
+                    other => {
+                        return Err(self.error(format!("unknown assert message `{other}`")));
+                    }
+                };
+                self.expect_punct(')')?;
+                self.expect(TokenKind::Arrow)?;
+                let target = self.block()?;
+                let block_args = self.block_args()?;
+                Ok(Terminator::Assert {
+                    cond,
+                    expected,
+                    target,
+                    msg,
+                    block_args,
+                })
+            }
+            _ => Err(self.error(format!(
+                "expected a statement or terminator, found `{keyword}`"
+            ))),
+        }
+    }
+
+    fn block_args(&mut self) -> Result<Vec<Operand<'ctx>>, ParseError> {
+        let mut args = Vec::new();
+        if self.eat_punct('(') {
+            while !self.eat_punct(')') {
+                if !args.is_empty() {
+                    self.expect_punct(',')?;
+                }
+                args.push(self.operand()?);
+            }
+        }
+        Ok(args)
+    }
+
+    fn operand(&mut self) -> Result<Operand<'ctx>, ParseError> {
+        if !self.eat_word("const") {
+            return Ok(Operand::use_local(self.local()?));
+        }
+
+        if self.eat_punct('(') {
+            self.expect_punct(')')?;
+            let unit = self.ctx.intern_ty(ty::TirTy::Unit);
+            return Ok(Operand::Const(ConstOperand::Value(ConstValue::ZST, unit)));
+        }
+        if let Some(TokenKind::Word(word)) = self.peek() {
+            if word == "true" || word == "false" {
+                let value = self.word()? == "true";
+                let bool_ty = self.ctx.intern_ty(ty::TirTy::Bool);
+                let raw = RawScalarValue {
+                    data: u128::from(value),
+                    size: NonZero::new(1).unwrap(),
+                };
+                return Ok(scalar_operand(raw, bool_ty));
+            }
+        }
+
+        let negative = self.eat_punct('-');
+        let literal = self.number()?;
+        let Some((digits, suffix)) = literal.rsplit_once('_') else {
+            return Err(self.error(format!("missing type suffix in `{literal}`")));
+        };
+        let ty = self.scalar_ty(suffix)?;
+        let pointer_bits = || self.ctx.target().data_layout.pointer_size.bits() as u32;
+        let Some(bits) = ty
+            .bit_width()
+            .or_else(|| matches!(**ty, ty::TirTy::Isize | ty::TirTy::Usize).then(pointer_bits))
+        else {
+            return Err(self.error(format!("`{suffix}` is not a numeric type")));
+        };
+        let size = Size::from_bits(bits);
+
+        let data = if ty.is_floating_point() {
+            let hex = digits.strip_prefix("0x").filter(|_| !negative);
+            hex.and_then(|hex| u128::from_str_radix(hex, 16).ok())
+                .filter(|&data| size.truncate(data) == data)
+        } else if ty.is_signed_integer() {
+            let digits = if negative {
+                format!("-{digits}")
+            } else {
+                digits.to_string()
+            };
+            digits
+                .parse::<i128>()
+                .ok()
+                .map(|value| (value, size.truncate(value as u128)))
+                .filter(|&(value, data)| size.sign_extend(data) == value)
+                .map(|(_, data)| data)
+        } else {
+            digits
+                .parse::<u128>()
+                .ok()
+                .filter(|&data| !negative && size.truncate(data) == data)
+        };
+        let Some(data) = data else {
+            return Err(self.error(format!("invalid `{suffix}` constant `{literal}`")));
+        };
+
+        let raw = RawScalarValue {
+            data,
+            size: NonZero::new(size.bytes() as u8).unwrap(),
+        };
+        Ok(scalar_operand(raw, ty))
+    }
+
+    fn ty(&mut self) -> Result<TirTy<'ctx>, ParseError> {
+        if self.eat_punct('(') {
+            self.expect_punct(')')?;
+            return Ok(self.ctx.intern_ty(ty::TirTy::Unit));
+        }
+        let name = self.word()?;
+        self.scalar_ty(&name)
+    }
+
+    fn scalar_ty(&self, name: &str) -> Result<TirTy<'ctx>, ParseError> {
+        let ty = match name {
+            "bool" => ty::TirTy::Bool,
+            "i8" => ty::TirTy::I8,
+            "i16" => ty::TirTy::I16,
+            "i32" => ty::TirTy::I32,
+            "i64" => ty::TirTy::I64,
+            "i128" => ty::TirTy::I128,
+            "isize" => ty::TirTy::Isize,
+            "u8" => ty::TirTy::U8,
+            "u16" => ty::TirTy::U16,
+            "u32" => ty::TirTy::U32,
+            "u64" => ty::TirTy::U64,
+            "u128" => ty::TirTy::U128,
+            "usize" => ty::TirTy::Usize,
+            "f16" => ty::TirTy::F16,
+            "f32" => ty::TirTy::F32,
+            "f64" => ty::TirTy::F64,
+            "f128" => ty::TirTy::F128,
+            _ => return Err(self.error(format!("unknown type `{name}`"))),
+        };
+        Ok(self.ctx.intern_ty(ty))
+    }
+
+    fn local(&mut self) -> Result<Local, ParseError> {
+        let word = self.word()?;
+        parse_local(&word).ok_or_else(|| self.error(format!("expected a local, found `{word}`")))
+    }
+
+    fn block(&mut self) -> Result<BasicBlock, ParseError> {
+        let word = self.word()?;
+        word.strip_prefix("bb")
+            .and_then(|n| n.parse().ok())
+            .map(BasicBlock::new)
+            .ok_or_else(|| self.error(format!("expected a basic block, found `{word}`")))
+    }
+
+    fn word(&mut self) -> Result<String, ParseError> {
+        match self.next() {
+            Some(TokenKind::Word(word)) => Ok(word),
+            other => Err(self.unexpected(other, "an identifier")),
+        }
+    }
+
+    fn number(&mut self) -> Result<String, ParseError> {
+        match self.next() {
+            Some(TokenKind::Number(number)) => Ok(number),
+            other => Err(self.unexpected(other, "a number")),
+        }
+    }
+
+    fn expect(&mut self, kind: TokenKind) -> Result<(), ParseError> {
+        match self.next() {
+            Some(found) if found == kind => Ok(()),
+            other => Err(self.unexpected(other, &describe(&kind))),
+        }
+    }
+
+    fn expect_punct(&mut self, c: char) -> Result<(), ParseError> {
+        self.expect(TokenKind::Punct(c))
+    }
+
+    fn expect_word(&mut self, word: &str) -> Result<(), ParseError> {
+        self.expect(TokenKind::Word(word.to_string()))
+    }
+
+    fn eat_punct(&mut self, c: char) -> bool {
+        self.eat(|kind| *kind == TokenKind::Punct(c))
+    }
+
+    fn eat_word(&mut self, word: &str) -> bool {
+        self.eat(|kind| matches!(kind, TokenKind::Word(w) if w == word))
+    }
+
+    fn eat(&mut self, pred: impl FnOnce(&TokenKind) -> bool) -> bool {
+        if self.peek().is_some_and(pred) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn peek(&self) -> Option<&TokenKind> {
+        self.tokens.get(self.pos).map(|tok| &tok.kind)
+    }
+
+    fn next(&mut self) -> Option<TokenKind> {
+        let tok = self.tokens.get(self.pos)?;
+        self.pos += 1;
+        Some(tok.kind.clone())
+    }
+
+    /// The line of the last consumed token, or of the first one if none was consumed.
+    fn line(&self) -> usize {
+        let idx = self.pos.saturating_sub(1);
+        self.tokens.get(idx).map_or(1, |tok| tok.line)
+    }
+
+    fn error(&self, message: impl Into<String>) -> ParseError {
+        self.error_at(self.line(), message)
+    }
+
+    fn error_at(&self, line: usize, message: impl Into<String>) -> ParseError {
+        ParseError {
+            line,
+            message: message.into(),
+        }
+    }
+
+    fn unexpected(&self, found: Option<TokenKind>, expected: &str) -> ParseError {
+        match found {
+            Some(found) => self.error(format!("expected {expected}, found {}", describe(&found))),
+            None => self.error(format!("expected {expected}, found end of input")),
+        }
+    }
+}
+
+fn describe(kind: &TokenKind) -> String {
+    match kind {
+        TokenKind::Word(word) | TokenKind::Number(word) => format!("`{word}`"),
+        TokenKind::Arrow => "`->`".to_string(),
+        TokenKind::Punct(c) => format!("`{c}`"),
+    }
+}
+
+fn parse_local(word: &str) -> Option<Local> {
+    let n = word.strip_prefix('_')?;
+    if n.is_empty() || !n.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    n.parse().ok().map(Local::new)
+}
+
+fn scalar_operand(raw: RawScalarValue, ty: TirTy<'_>) -> Operand<'_> {
+    Operand::Const(ConstOperand::Value(
+        ConstValue::Scalar(ConstScalar::Value(raw)),
+        ty,
+    ))
+}
+
+fn unary_op(name: &str) -> Option<UnaryOp> {
+    Some(match name {
+        "Pos" => UnaryOp::Pos,
+        "Neg" => UnaryOp::Neg,
+        "Not" => UnaryOp::Not,
+        _ => return None,
+    })
+}
+
+fn binary_op(name: &str) -> Option<BinaryOp> {
+    Some(match name {
+        "Add" => BinaryOp::Add,
+        "AddUnchecked" => BinaryOp::AddUnchecked,
+        "Sub" => BinaryOp::Sub,
+        "SubUnchecked" => BinaryOp::SubUnchecked,
+        "Mul" => BinaryOp::Mul,
+        "MulUnchecked" => BinaryOp::MulUnchecked,
+        "AddChecked" => BinaryOp::AddChecked,
+        "SubChecked" => BinaryOp::SubChecked,
+        "MulChecked" => BinaryOp::MulChecked,
+        "SaturatingAdd" => BinaryOp::SaturatingAdd,
+        "SaturatingSub" => BinaryOp::SaturatingSub,
+        "Div" => BinaryOp::Div,
+        "Rem" => BinaryOp::Rem,
+        "BitAnd" => BinaryOp::BitAnd,
+        "BitOr" => BinaryOp::BitOr,
+        "BitXor" => BinaryOp::BitXor,
+        "Shl" => BinaryOp::Shl,
+        "Shr" => BinaryOp::Shr,
+        "Eq" => BinaryOp::Eq,
+        "Ne" => BinaryOp::Ne,
+        "Lt" => BinaryOp::Lt,
+        "Le" => BinaryOp::Le,
+        "Gt" => BinaryOp::Gt,
+        "Ge" => BinaryOp::Ge,
+        _ => return None,
+    })
+}
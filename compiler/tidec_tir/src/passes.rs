@@ -0,0 +1,269 @@
+//! Transformation passes over TIR bodies.
+//!
+//! Each pass takes a mutable [`TirBody`] and rewrites it in place. Passes are
+//! expected to leave the body in a well-formed state: every terminator target
+//! must refer to an existing basic block, and `ENTRY_BLOCK` must remain the
+//! entry point of the function.
+//!
+//! Passes implement [`TirPass`] and are chained with a [`PassManager`], which
+//! runs them in registration order.
+
+use crate::body::TirBody;
+use crate::ctx::TirCtx;
+use crate::syntax::{
+    BasicBlock, BasicBlockData, Local, Operand, RValue, Statement, Terminator, ENTRY_BLOCK,
+};
+use crate::visit::{MutVisitor, PlaceContext};
+use tidec_utils::{index_slice::IdxSlice, index_vec::IdxVec};
+use tracing::debug;
+
+/// A transformation pass over a single TIR body.
+pub trait TirPass {
+    /// The name of the pass, used for logging.
+    fn name(&self) -> &str;
+
+    /// Rewrites `body` in place.
+    fn run(&self, body: &mut TirBody);
+}
+
+/// An ordered pipeline of TIR passes.
+///
+/// Passes run in the order they were added, each one seeing the body as left
+/// by the previous one.
+#[derive(Default)]
+pub struct PassManager {
+    passes: Vec<Box<dyn TirPass>>,
+}
+
+impl PassManager {
+    /// Creates an empty pass manager.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Appends `pass` to the end of the pipeline.
+    pub fn add_pass(&mut self, pass: impl TirPass + 'static) -> &mut Self {
+        self.passes.push(Box::new(pass));
+        self
+    }
+
+    /// Runs every registered pass on `body`, in order.
+    pub fn run_all(&self, body: &mut TirBody) {
+        for pass in &self.passes {
+            debug!(
+                "Running TIR pass `{}` on `{}`",
+                pass.name(),
+                body.metadata.name
+            );
+            pass.run(body);
+        }
+    }
+}
+
+/// The [`remove_unreachable_blocks`] pass.
+pub struct RemoveUnreachableBlocks;
+
+impl TirPass for RemoveUnreachableBlocks {
+    fn name(&self) -> &str {
+        "remove_unreachable_blocks"
+    }
+
+    fn run(&self, body: &mut TirBody) {
+        remove_unreachable_blocks(body);
+    }
+}
+
+/// The [`merge_blocks`] pass.
+pub struct MergeBlocks;
+
+impl TirPass for MergeBlocks {
+    fn name(&self) -> &str {
+        "merge_blocks"
+    }
+
+    fn run(&self, body: &mut TirBody) {
+        merge_blocks(body);
+    }
+}
+
+/// The [`remove_redundant_copies`] pass.
+pub struct RemoveRedundantCopies;
+
+impl TirPass for RemoveRedundantCopies {
+    fn name(&self) -> &str {
+        "remove_redundant_copies"
+    }
+
+    fn run(&self, body: &mut TirBody) {
+        remove_redundant_copies(body);
+    }
+}
+
+/// Removes every basic block that is not reachable from `ENTRY_BLOCK`.
+///
+/// The remaining blocks keep their relative order and are renumbered densely,
+/// so `ENTRY_BLOCK` stays `bb0`. All terminator targets are rewritten through
+/// an old-to-new remap table.
+pub fn remove_unreachable_blocks(body: &mut TirBody) {
+    let reachable = reachable_blocks(body);
+    if reachable.iter().all(|r| *r) {
+        return;
+    }
+
+    let old_blocks = std::mem::take(&mut body.basic_blocks);
+    let mut remap: IdxVec<BasicBlock, Option<BasicBlock>> =
+        IdxVec::from_elem_n(None, old_blocks.len());
+    let mut new_blocks = IdxVec::with_capacity(old_blocks.len());
+
+    for (bb, data) in old_blocks.into_iter_enumerated() {
+        if reachable[bb] {
+            remap[bb] = Some(new_blocks.push(data));
+        }
+    }
+
+    for data in new_blocks.iter_mut() {
+        for target in data.terminator.successors_mut() {
+            // A reachable block can only jump to reachable blocks.
+            *target = remap[*target].expect("successor of a reachable block must be reachable");
+        }
+    }
+
+    body.basic_blocks = new_blocks;
+}
+
+/// Merges straight-line chains of blocks connected by unconditional gotos.
+///
+/// A block `B` is folded into its predecessor `A` when `A` ends in
+/// `Goto { target: B }` and `A` is the only predecessor of `B`. The statements
+/// of `B` are appended to `A`, and `A` takes over the terminator of `B`. The
+/// emptied blocks are then removed with [`remove_unreachable_blocks`].
+///
+/// Blocks with parameters are never absorbed, as their arguments would have to
+/// be turned into assignments.
+pub fn merge_blocks(body: &mut TirBody) {
+    let mut preds = predecessors(body);
+    let mut merged_any = false;
+
+    for bb in body.basic_blocks.indices() {
+        while let Terminator::Goto { target, .. } = body.basic_blocks[bb].terminator {
+            if target == bb
+                || target == ENTRY_BLOCK
+                || preds[target].len() != 1
+                || !body.basic_blocks[target].params.is_empty()
+            {
+                break;
+            }
+
+            let absorbed = std::mem::replace(
+                &mut body.basic_blocks[target],
+                BasicBlockData {
+                    params: vec![],
+                    statements: vec![],
+                    terminator: Terminator::Unreachable,
+                },
+            );
+            for succ in absorbed.terminator.successors() {
+                for pred in preds[succ].iter_mut().filter(|p| **p == target) {
+                    *pred = bb;
+                }
+            }
+            preds[target].clear();
+
+            let data = &mut body.basic_blocks[bb];
+            data.statements.extend(absorbed.statements);
+            data.terminator = absorbed.terminator;
+            merged_any = true;
+        }
+    }
+
+    if merged_any {
+        remove_unreachable_blocks(body);
+    }
+}
+
+/// Removes every self-assignment `place = place`.
+///
+/// An assignment is redundant when its rvalue is a plain use of the very
+/// place it assigns to, with the same local and the same projection. Such a
+/// copy has no effect, so the statement is dropped.
+pub fn remove_redundant_copies(body: &mut TirBody) {
+    for data in body.basic_blocks.iter_mut() {
+        data.statements.retain(|stmt| match stmt {
+            Statement::Assign(assign) => {
+                let (place, rvalue) = &**assign;
+                !matches!(rvalue, RValue::Operand(Operand::Use(src)) if src == place)
+            }
+            Statement::SetDiscriminant { .. } => true,
+        });
+    }
+}
+
+/// Rewrites every local referenced by `body` through `map`, so that `_i`
+/// becomes `map[_i]`.
+///
+/// This covers places (including `Index` projections) and block parameters,
+/// but not the local declarations: the caller renumbering the locals is
+/// responsible for reordering `ret_and_args` and `locals` to match.
+///
+/// Panics if a referenced local has no entry in `map`.
+pub fn remap_locals<'ctx>(
+    ctx: &TirCtx<'ctx>,
+    body: &mut TirBody<'ctx>,
+    map: &IdxSlice<Local, Local>,
+) {
+    struct Remap<'a, 'ctx>(&'a TirCtx<'ctx>, &'a IdxSlice<Local, Local>);
+
+    impl<'ctx> MutVisitor<'ctx> for Remap<'_, 'ctx> {
+        fn ctx(&self) -> TirCtx<'ctx> {
+            *self.0
+        }
+
+        fn visit_local(&mut self, local: &mut Local, _context: PlaceContext) {
+            *local = *self
+                .1
+                .get(*local)
+                .unwrap_or_else(|| panic!("{local:?} is missing from the remap table"));
+        }
+    }
+
+    Remap(ctx, map).visit_body(body);
+}
+
+/// Computes the predecessors of every basic block.
+///
+/// A block appears once in the predecessor list of a successor for every edge
+/// to it, so a `SwitchInt` with two arms targeting the same block yields two
+/// entries.
+pub fn predecessors(body: &TirBody) -> IdxVec<BasicBlock, Vec<BasicBlock>> {
+    let mut preds = IdxVec::from_elem_n(vec![], body.basic_blocks.len());
+    for (bb, data) in body.basic_blocks.iter_enumerated() {
+        for succ in data.terminator.successors() {
+            preds[succ].push(bb);
+        }
+    }
+    preds
+}
+
+/// Computes which basic blocks are reachable from `ENTRY_BLOCK`.
+///
+/// The result is indexed by [`BasicBlock`]. A body without blocks yields an
+/// empty vector.
+fn reachable_blocks(body: &TirBody) -> IdxVec<BasicBlock, bool> {
+    let mut reachable = IdxVec::from_elem_n(false, body.basic_blocks.len());
+    if body.basic_blocks.is_empty() {
+        return reachable;
+    }
+
+    let mut worklist = vec![ENTRY_BLOCK];
+    reachable[ENTRY_BLOCK] = true;
+    while let Some(bb) = worklist.pop() {
+        for succ in body.basic_blocks[bb].terminator.successors() {
+            if !reachable[succ] {
+                reachable[succ] = true;
+                worklist.push(succ);
+            }
+        }
+    }
+
+    reachable
+}
@@ -0,0 +1,319 @@
+//! Human-readable renderings of TIR bodies.
+
+use std::fmt::{self, Write};
+
+use crate::body::TirBody;
+use crate::syntax::{
+    AssertMessage, ConstOperand, ConstScalar, ConstValue, Local, LocalData, Operand, RValue,
+    Statement, Terminator,
+};
+use crate::ty;
+use crate::TirTy;
+use tidec_abi::size_and_align::Size;
+use tidec_utils::idx::Idx;
+
+/// Writes `body` in the textual TIR format.
+///
+/// The format mirrors the structure of the body:
+///
+/// ```text
+/// fn main(_1: i32) -> i32 {
+///     let mut _0: i32;
+///     let _1: i32;
+///     let mut _2: i32;
+///
+///     bb0: {
+///         _2 = Add(_1, const 1_i32);
+///         goto -> bb1;
+///     }
+///
+///     bb1: {
+///         _0 = _2;
+///         return;
+///     }
+/// }
+/// ```
+///
+/// Every local, including the return place and the arguments, gets a `let`
+/// declaration. Constructs that have no textual form yet (projections,
+/// aggregates, calls, non-scalar types, ...) are written with their `Debug`
+/// representation, so the output is always complete but only the subset
+/// understood by [`crate::parse::parse_body`] can be read back.
+pub fn write_body(body: &TirBody, f: &mut impl Write) -> fmt::Result {
+    write!(f, "fn {}(", body.metadata.name)?;
+    for (i, (local, arg)) in body.args().enumerate() {
+        if i > 0 {
+            write!(f, ", ")?;
+        }
+        write!(f, "_{}: ", local.idx())?;
+        write_ty(arg.ty, f)?;
+    }
+    write!(f, ") -> ")?;
+    write_ty(body.return_local_data().ty, f)?;
+    writeln!(f, " {{")?;
+
+    let locals = body.ret_and_args.iter().chain(body.locals.iter());
+    for (i, local) in locals.enumerate() {
+        write_local_decl(Local::new(i), local, f)?;
+    }
+
+    for (bb, data) in body.basic_blocks.iter_enumerated() {
+        writeln!(f)?;
+        write!(f, "    bb{}", bb.idx())?;
+        if !data.params.is_empty() {
+            write!(f, "(")?;
+            for (i, (local, ty)) in data.params.iter().enumerate() {
+                if i > 0 {
+                    write!(f, ", ")?;
+                }
+                write!(f, "_{}: ", local.idx())?;
+                write_ty(*ty, f)?;
+            }
+            write!(f, ")")?;
+        }
+        writeln!(f, ": {{")?;
+        for stmt in &data.statements {
+            write!(f, "        ")?;
+            write_statement(stmt, f)?;
+            writeln!(f, ";")?;
+        }
+        write!(f, "        ")?;
+        write_terminator(&data.terminator, f)?;
+        writeln!(f, ";")?;
+        writeln!(f, "    }}")?;
+    }
+
+    writeln!(f, "}}")
+}
+
+fn write_local_decl(local: Local, data: &LocalData, f: &mut impl Write) -> fmt::Result {
+    write!(f, "    let ")?;
+    if data.mutable {
+        write!(f, "mut ")?;
+    }
+    write!(f, "_{}: ", local.idx())?;
+    write_ty(data.ty, f)?;
+    writeln!(f, ";")
+}
+
+pub(crate) fn write_ty(ty: TirTy, f: &mut impl Write) -> fmt::Result {
+    let name = match **ty {
+        ty::TirTy::Unit => "()",
+        ty::TirTy::Bool => "bool",
+        ty::TirTy::I8 => "i8",
+        ty::TirTy::I16 => "i16",
+        ty::TirTy::I32 => "i32",
+        ty::TirTy::I64 => "i64",
+        ty::TirTy::I128 => "i128",
+        ty::TirTy::Isize => "isize",
+        ty::TirTy::U8 => "u8",
+        ty::TirTy::U16 => "u16",
+        ty::TirTy::U32 => "u32",
+        ty::TirTy::U64 => "u64",
+        ty::TirTy::U128 => "u128",
+        ty::TirTy::Usize => "usize",
+        ty::TirTy::F16 => "f16",
+        ty::TirTy::F32 => "f32",
+        ty::TirTy::F64 => "f64",
+        ty::TirTy::F128 => "f128",
+        _ => return write!(f, "{ty:?}"),
+    };
+    f.write_str(name)
+}
+
+fn write_statement(stmt: &Statement, f: &mut impl Write) -> fmt::Result {
+    match stmt {
+        Statement::Assign(assign) => {
+            let (place, rvalue) = &**assign;
+            match place.try_local() {
+                Some(local) => write!(f, "_{}", local.idx())?,
+                None => write!(f, "{place:?}")?,
+            }
+            write!(f, " = ")?;
+            write_rvalue(rvalue, f)
+        }
+        Statement::SetDiscriminant { place, variant } => {
+            write!(f, "discriminant(")?;
+            match place.try_local() {
+                Some(local) => write!(f, "_{}", local.idx())?,
+                None => write!(f, "{place:?}")?,
+            }
+            write!(f, ") = {variant}")
+        }
+    }
+}
+
+fn write_rvalue(rvalue: &RValue, f: &mut impl Write) -> fmt::Result {
+    match rvalue {
+        RValue::Operand(op) => write_operand(op, f),
+        RValue::UnaryOp(op, operand) => {
+            write!(f, "{op:?}(")?;
+            write_operand(operand, f)?;
+            write!(f, ")")
+        }
+        RValue::BinaryOp(op, lhs, rhs) => {
+            write!(f, "{op:?}(")?;
+            write_operand(lhs, f)?;
+            write!(f, ", ")?;
+            write_operand(rhs, f)?;
+            write!(f, ")")
+        }
+        RValue::Discriminant(place) => {
+            write!(f, "discriminant(")?;
+            match place.try_local() {
+                Some(local) => write!(f, "_{}", local.idx())?,
+                None => write!(f, "{place:?}")?,
+            }
+            write!(f, ")")
+        }
+        _ => write!(f, "{rvalue:?}"),
+    }
+}
+
+fn write_operand(op: &Operand, f: &mut impl Write) -> fmt::Result {
+    match op {
+        Operand::Use(place) => match place.try_local() {
+            Some(local) => write!(f, "_{}", local.idx()),
+            None => write!(f, "{place:?}"),
+        },
+        Operand::Const(ConstOperand::Value(value, ty)) => {
+            write!(f, "const ")?;
+            write_const(value, *ty, f)
+        }
+    }
+}
+
+/// Writes a constant as `<value>_<ty>`, e.g. `-1_i32` or `true`.
+///
+/// Integers are written in decimal and floats as their raw bits in
+/// hexadecimal, so that the value can be read back exactly.
+fn write_const(value: &ConstValue, ty: TirTy, f: &mut impl Write) -> fmt::Result {
+    match (value, &**ty) {
+        (ConstValue::ZST, ty::TirTy::Unit) => write!(f, "()"),
+        (ConstValue::Scalar(ConstScalar::Value(raw)), ty::TirTy::Bool) => {
+            write!(f, "{}", raw.data != 0)
+        }
+        (ConstValue::Scalar(ConstScalar::Value(raw)), kind) if kind.is_integer() => {
+            let size = Size::from_bytes(raw.size.get());
+            if kind.is_signed_integer() {
+                write!(f, "{}_", raw.to_signed(size))?;
+            } else {
+                write!(f, "{}_", raw.to_bits(size))?;
+            }
+            write_ty(ty, f)
+        }
+        (ConstValue::Scalar(ConstScalar::Value(raw)), kind) if kind.is_floating_point() => {
+            let data = raw.data;
+            write!(f, "{data:#x}_")?;
+            write_ty(ty, f)
+        }
+        _ => write!(f, "{value:?}: {ty:?}"),
+    }
+}
+
+fn write_terminator(term: &Terminator, f: &mut impl Write) -> fmt::Result {
+    match term {
+        Terminator::Return => write!(f, "return"),
+        Terminator::Unreachable => write!(f, "unreachable"),
+        Terminator::Goto { target, block_args } => {
+            write!(f, "goto -> bb{}", target.idx())?;
+            write_block_args(block_args, f)
+        }
+        Terminator::SwitchInt {
+            discr,
+            targets,
+            block_args,
+        } => {
+            write!(f, "switchInt(")?;
+            write_operand(discr, f)?;
+            write!(f, ") -> [")?;
+            for (value, bb) in targets.iter() {
+                write!(f, "{value}: bb{}, ", bb.idx())?;
+            }
+            write!(f, "otherwise: bb{}]", targets.otherwise.idx())?;
+            write_block_args(block_args, f)
+        }
+        Terminator::Call { .. } => write!(f, "{term:?}"),
+        Terminator::Assert {
+            cond,
+            expected,
+            target,
+            msg,
+            block_args,
+        } => {
+            write!(f, "assert(")?;
+            write_operand(cond, f)?;
+            write!(f, ", {expected}, ")?;
+            match msg {
+                AssertMessage::Overflow => write!(f, "overflow")?,
+                AssertMessage::BoundsCheck { len, index } => {
+                    write!(f, "bounds_check(len: ")?;
+                    write_operand(len, f)?;
+                    write!(f, ", index: ")?;
+                    write_operand(index, f)?;
+                    write!(f, ")")?;
+                }
+            }
+            write!(f, ") -> bb{}", target.idx())?;
+            write_block_args(block_args, f)
+        }
+    }
+}
+
+fn write_block_args(args: &[Operand], f: &mut impl Write) -> fmt::Result {
+    if args.is_empty() {
+        return Ok(());
+    }
+    write!(f, "(")?;
+    for (i, arg) in args.iter().enumerate() {
+        if i > 0 {
+            write!(f, ", ")?;
+        }
+        write_operand(arg, f)?;
+    }
+    write!(f, ")")
+}
+
+/// Writes the control-flow graph of `body` in Graphviz DOT format.
+///
+/// Each basic block becomes a node labelled with its statements and its
+/// terminator, and each successor of a terminator becomes an edge. The output
+/// can be rendered with, e.g., `dot -Tpng`.
+pub fn write_cfg_dot(body: &TirBody, f: &mut impl Write) -> fmt::Result {
+    writeln!(f, "digraph \"{}\" {{", escape_dot(&body.metadata.name))?;
+    writeln!(f, "    node [shape=box, fontname=\"monospace\"];")?;
+
+    for (bb, data) in body.basic_blocks.iter_enumerated() {
+        write!(f, "    bb{} [label=\"bb{}:\\l", bb.idx(), bb.idx())?;
+        for stmt in &data.statements {
+            write!(f, "{}\\l", escape_dot(&format!("{stmt:?}")))?;
+        }
+        writeln!(
+            f,
+            "{}\\l\"];",
+            escape_dot(&format!("{:?}", data.terminator))
+        )?;
+    }
+
+    for (bb, data) in body.basic_blocks.iter_enumerated() {
+        for succ in data.terminator.successors() {
+            writeln!(f, "    bb{} -> bb{};", bb.idx(), succ.idx())?;
+        }
+    }
+
+    writeln!(f, "}}")
+}
+
+/// Escapes characters that are not allowed verbatim inside a DOT string.
+fn escape_dot(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\l"),
+            _ => out.push(c),
+        }
+    }
+    out
+}
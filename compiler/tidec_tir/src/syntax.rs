@@ -1,9 +1,10 @@
 use crate::{alloc::AllocId, ctx::TirCtx, ty::Mutability, TirTy};
+use smallvec::{smallvec, SmallVec};
 use std::num::NonZero;
 use tidec_abi::size_and_align::Size;
 use tidec_utils::idx::Idx;
 
-#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
 /// A `Local` variable in the TIR.
 ///
 /// `Local` acts as an index into the set of local variables declared within a function or
@@ -26,12 +27,12 @@ impl<'ctx> From<Local> for Place<'ctx> {
     fn from(val: Local) -> Self {
         Place {
             local: val,
-            projection: vec![],
+            projection: &[],
         }
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 /// Represents a memory location (or "place") within TIR that can be used
 /// as the target of assignments or the source of loads.
 ///
@@ -61,12 +62,15 @@ pub struct Place<'ctx> {
 
     /// A (possibly empty) list of projections representing access to subparts
     /// of the base local, such as fields or dereferenced pointers.
-    pub projection: Vec<Projection<'ctx>>,
+    ///
+    /// The list is interned with `TirCtx::intern_projection`, so a `Place`
+    /// is `Copy` and equal chains share the same storage.
+    pub projection: &'ctx [Projection<'ctx>],
 }
 
 impl<'ctx> Place<'ctx> {
     #[inline]
-    pub fn try_local(&self) -> Option<Local> {
+    pub fn try_local(self) -> Option<Local> {
         if self.projection.is_empty() {
             Some(self.local)
         } else {
@@ -75,7 +79,14 @@ impl<'ctx> Place<'ctx> {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+/// The index of a field within a struct (or tuple) type.
+///
+/// `FieldIdx` is a dedicated index type so that field indices cannot be
+/// confused with `Local` or `BasicBlock` indices.
+pub struct FieldIdx(usize);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 /// Represents a single step in a `Place` projection path.
 ///
 /// A `Projection` allows navigation into more complex data structures
@@ -93,11 +104,11 @@ impl<'ctx> Place<'ctx> {
 pub enum Projection<'ctx> {
     /// Access a field of a struct, tuple, or union.
     ///
-    /// The `usize` is the zero-based field index, and the `TirTy` is the
+    /// The `FieldIdx` is the zero-based field index, and the `TirTy` is the
     /// type of the field (needed for layout computation during codegen).
-    Field(usize, TirTy<'ctx>),
+    Field(FieldIdx, TirTy<'ctx>),
 
-    /// Dereference a raw pointer. The base local must have type `RawPtr(T, _)`,
+    /// Dereference a raw pointer. The base local must have type `RawPtr(T, _, _)`,
     /// and the projection yields a place of type `T`.
     Deref,
 
@@ -126,13 +137,13 @@ pub enum Projection<'ctx> {
 
     /// Select a specific variant of an enum (tagged union).
     ///
-    /// The `usize` is the variant index. This projection does not change
-    /// the pointer, but changes the type context so that subsequent `Field`
-    /// projections refer to the fields of that variant.
+    /// The `usize` is the variant index. The projection yields the payload
+    /// of that variant, past the tag, so that subsequent `Field` projections
+    /// refer to the fields of the payload. It does not check the tag.
     Downcast(usize),
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 /// The kind of a type cast operation.
 ///
 /// Each variant specifies a category of cast; the codegen layer selects the
@@ -161,7 +172,7 @@ pub enum CastKind {
     PtrToPtr,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 /// Represents a right-hand side (RValue) in TIR during code generation.
 ///
 /// An `RValue` is something that can be **evaluated to produce a value**.
@@ -212,7 +223,7 @@ pub enum RValue<'ctx> {
     /// Take the address of a place, producing a raw pointer.
     ///
     /// `AddressOf(mutability, place)` evaluates `place` to a memory
-    /// location and returns its address as a `RawPtr(T, mutability)`.
+    /// location and returns its address as a `RawPtr(T, mutability, AddressSpace::DATA)`.
     ///
     /// # Examples
     ///
@@ -221,9 +232,53 @@ pub enum RValue<'ctx> {
     /// RValue::AddressOf(Mutability::Mut, Place::from(x_local))
     /// ```
     AddressOf(Mutability, Place<'ctx>),
+    /// Read the discriminant of the enum stored at a place.
+    ///
+    /// The result is the index of the active variant, as a value of the tag
+    /// type of the enum (see `TirCtx::discriminant_ty`). It is meant to be
+    /// fed to `Terminator::SwitchInt`, whose targets are keyed by the
+    /// discriminant value. The place must have an enum type.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// // _2 = discriminant(_1); switchInt(_2) -> [0: bb1, otherwise: bb2]
+    /// RValue::Discriminant(Place::from(Local::new(1)))
+    /// ```
+    Discriminant(Place<'ctx>),
+    /// Raw inline assembly, emitted verbatim by the backend.
+    ///
+    /// This is an escape hatch for low-level runtime support and is kept
+    /// intentionally minimal: every operand is passed in a register (`r`
+    /// constraint), the assembly produces no value (the destination must be
+    /// a ZST such as `()`), and it is always treated as having side effects.
+    ///
+    /// # Safety
+    ///
+    /// The compiler does not look inside `template`. It is up to the author
+    /// to list in `clobbers` every register or resource (e.g. `"memory"`)
+    /// the assembly modifies; anything else is undefined behavior.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// RValue::InlineAsm {
+    ///     template: "nop".to_string(),
+    ///     operands: vec![],
+    ///     clobbers: vec!["memory".to_string()],
+    /// }
+    /// ```
+    InlineAsm {
+        /// The assembly template, in the backend's syntax (e.g. `"nop"`).
+        template: String,
+        /// The input operands, referred to as `$0`, `$1`, ... in `template`.
+        operands: Vec<Operand<'ctx>>,
+        /// The registers and resources clobbered by the assembly.
+        clobbers: Vec<String>,
+    },
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 /// The kind of aggregate being constructed in `RValue::Aggregate`.
 pub enum AggregateKind<'ctx> {
     /// A struct aggregate. The `TirTy` is the struct type being constructed.
@@ -238,7 +293,7 @@ pub enum AggregateKind<'ctx> {
     Array(TirTy<'ctx>),
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum UnaryOp {
     /// Artihmetic positive (no-op).
     Pos,
@@ -251,9 +306,13 @@ pub enum UnaryOp {
     Not,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum BinaryOp {
     /// Addition.
+    ///
+    /// For integers this wraps around on overflow (two's complement), which
+    /// gives the semantics of Rust's `wrapping_add`. The same holds for `Sub`
+    /// and `Mul`.
     Add,
     /// Addition but with UB on overflow (Integer only)
     AddUnchecked,
@@ -265,6 +324,22 @@ pub enum BinaryOp {
     Mul,
     /// Multiplication but with UB on overflow (Integer only).
     MulUnchecked,
+    /// Addition that panics on overflow (Integer only).
+    ///
+    /// The backend computes the overflow bit alongside the result and branches
+    /// to the function's overflow-panic landing pad when it is set.
+    AddChecked,
+    /// Subtraction that panics on overflow (Integer only).
+    SubChecked,
+    /// Multiplication that panics on overflow (Integer only).
+    MulChecked,
+    /// Addition that clamps to the bounds of the type on overflow (Integer only).
+    ///
+    /// Whether the signed or unsigned bounds are used is derived from the
+    /// operand type.
+    SaturatingAdd,
+    /// Subtraction that clamps to the bounds of the type on overflow (Integer only).
+    SaturatingSub,
     /// Division.
     ///
     /// For integer types, division by zero is UB.
@@ -316,6 +391,25 @@ pub enum BinaryOp {
 }
 
 impl BinaryOp {
+    /// Returns `true` if the operator applies lane by lane to SIMD vectors.
+    ///
+    /// Checked operators and comparisons are not lane-wise: they produce a
+    /// single overflow flag or `Bool` for the whole operation.
+    pub fn is_lanewise(&self) -> bool {
+        !matches!(
+            self,
+            BinaryOp::AddChecked
+                | BinaryOp::SubChecked
+                | BinaryOp::MulChecked
+                | BinaryOp::Eq
+                | BinaryOp::Ne
+                | BinaryOp::Lt
+                | BinaryOp::Le
+                | BinaryOp::Gt
+                | BinaryOp::Ge
+        )
+    }
+
     /// Returns the resulting type of the binary operation, which is the same as the operand types.
     pub fn ty<'ctx>(
         &self,
@@ -332,6 +426,11 @@ impl BinaryOp {
             | BinaryOp::SubUnchecked
             | BinaryOp::Mul
             | BinaryOp::MulUnchecked
+            | BinaryOp::AddChecked
+            | BinaryOp::SubChecked
+            | BinaryOp::MulChecked
+            | BinaryOp::SaturatingAdd
+            | BinaryOp::SaturatingSub
             | BinaryOp::Div
             | BinaryOp::Rem
             | BinaryOp::BitAnd
@@ -350,7 +449,7 @@ impl BinaryOp {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 /// An operand in TIR.
 /// Semantically, an operand is a value that can be used in expressions.
 // TODO(bruzzone): consider to switch to `copy` and `move` semantic, instead of `use`
@@ -388,7 +487,7 @@ impl<'ctx> Operand<'ctx> {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 /// Semantically, a constant is already a value; it cannot change.
 // TODO(bruzzone): Add more variants for different constant types.
 pub enum ConstOperand<'ctx> {
@@ -411,11 +510,8 @@ impl<'ctx> ConstOperand<'ctx> {
     }
 }
 
-#[derive(Debug, Clone, Eq, PartialEq)]
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
 /// Represents a constant value.
-// TODO(bruzzone): Add indirect variant. A value not representable by the other variants; needs to be stored in-memory.
-// TODO(bruzzone): Add slice variant for strings, arrays, etc. We could use the `Invariant` variant
-// to avoid this optimization.
 pub enum ConstValue {
     /// A constant value that is a zero-sized type (ZST).
     ZST,
@@ -426,9 +522,29 @@ pub enum ConstValue {
     /// user to know the pointer size. The `ConstOperand` that wraps
     /// this must carry a `TirTy::RawPtr(...)` type.
     NullPtr,
+    /// An all-zero value of any type, in the style of `mem::zeroed`.
+    ///
+    /// Like `NullPtr`, this carries no payload: the type to zero is the one
+    /// of the wrapping `ConstOperand`. It is mostly useful to initialize
+    /// aggregates without spelling out every field.
+    ZeroInit,
     /// A constant scalar value.
     /// The consts with this variant have typically a layout that is compatible with scalar types, such as integers, floats, or pointers. That is, the backend representation of the constant is a scalar value.
     Scalar(ConstScalar),
+    /// A constant slice, such as a string or byte-array literal.
+    ///
+    /// The elements are stored in a private, read-only global and the value
+    /// is the wide pointer `(ptr, len)` to them. The `ConstOperand` that
+    /// wraps this must carry a two-field struct type `{ *const T, usize }`,
+    /// which is laid out as a `ScalarPair`; `len` is the number of `T`
+    /// elements in `data`.
+    ///
+    /// Unlike `Indirect`, the bytes are carried inline, so no allocation has
+    /// to be interned up front.
+    Slice {
+        /// The raw bytes of the elements.
+        data: Vec<u8>,
+    },
     /// A value not representable by the other variants; needs to be stored in-memory.
     ///
     /// This is used for constants such as strings, slices, functions, and large or
@@ -475,7 +591,7 @@ pub enum ConstValue {
     },
 }
 
-#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
 /// Represents a constant scalar value.
 // TODO(bruzzone): Add pointer variant for constants that are pointers to other constants or memory locations.
 pub enum ConstScalar {
@@ -518,7 +634,7 @@ pub enum ConstScalar {
     // },
 }
 
-#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
 /// A compact representation of the raw bytes of a scalar value.
 ///
 /// This type is used in tide's value model (e.g. in [`Scalar`]) to represent
@@ -587,6 +703,83 @@ pub struct RawScalarValue {
 }
 
 impl RawScalarValue {
+    /// Creates a scalar of `size` bytes from a signed value, storing its
+    /// two's-complement bit pattern masked to `size`.
+    ///
+    /// For example, `-1i32` is stored as `0xFFFF_FFFF` with `size = 4`.
+    ///
+    /// Panics if `size` is not in `1..=16` or if `value` does not fit in a
+    /// signed integer of `size` bytes.
+    pub fn from_signed(value: i128, size: Size) -> Self {
+        let data = size.truncate(value as u128);
+        assert_eq!(
+            size.sign_extend(data),
+            value,
+            "Signed value {value} does not fit in {} bytes",
+            size.bytes()
+        );
+        RawScalarValue {
+            data,
+            size: Self::checked_size(size),
+        }
+    }
+
+    /// Creates a scalar of `size` bytes from an unsigned value.
+    ///
+    /// Panics if `size` is not in `1..=16` or if `value` does not fit in an
+    /// unsigned integer of `size` bytes.
+    pub fn from_unsigned(value: u128, size: Size) -> Self {
+        assert_eq!(
+            size.truncate(value),
+            value,
+            "Unsigned value {value} does not fit in {} bytes",
+            size.bytes()
+        );
+        RawScalarValue {
+            data: value,
+            size: Self::checked_size(size),
+        }
+    }
+
+    fn checked_size(size: Size) -> NonZero<u8> {
+        assert!(
+            (1..=16).contains(&size.bytes()),
+            "Scalar size must be in 1..=16 bytes, got {}",
+            size.bytes()
+        );
+        NonZero::new(size.bytes() as u8).unwrap()
+    }
+
+    /// Interprets the bits of this scalar as a signed integer of
+    /// `target_size`, sign-extending them to an `i128`.
+    pub fn to_signed(&self, target_size: Size) -> i128 {
+        target_size.sign_extend(self.to_bits(target_size))
+    }
+
+    /// Returns the bits of this scalar zero-extended to a `u128`, whatever
+    /// its size.
+    pub fn as_u128(&self) -> u128 {
+        self.to_bits(Size::from_bytes(self.size.get()))
+    }
+
+    /// Returns this scalar as a signed integer of `signed_from` bytes,
+    /// sign-extended to an `i128`.
+    ///
+    /// Panics if `signed_from` is not the size of the scalar.
+    pub fn as_i128(&self, signed_from: Size) -> i128 {
+        self.to_signed(signed_from)
+    }
+
+    /// Returns this scalar as an `f32`, or `None` if it is not 4 bytes wide.
+    pub fn as_f32(&self) -> Option<f32> {
+        (self.size.get() == 4).then(|| f32::from_bits(self.data as u32))
+    }
+
+    /// Returns this scalar as an `f64`, or `None` if it is not 8 bytes wide.
+    pub fn as_f64(&self) -> Option<f64> {
+        (self.size.get() == 8).then(|| f64::from_bits(self.data as u64))
+    }
+
     /// Attempts to convert this raw scalar value to a bit representation of the specified size.
     /// It fails if the sizes do not match. That is, if `self.size` is not equal to `taget_size`.
     pub fn to_bits(&self, taget_size: Size) -> u128 {
@@ -598,17 +791,24 @@ impl RawScalarValue {
                 self.size
             );
         }
-        self.data
+        let data = self.data;
+        debug_assert_eq!(
+            taget_size.truncate(data),
+            data,
+            "Scalar data {data:#x} has bits set above its {} bytes",
+            taget_size.bytes()
+        );
+        data
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct LocalData<'ctx> {
     pub ty: TirTy<'ctx>,
     pub mutable: bool,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 /// A statement in a basic block.
 ///
 /// A statement is an operation that does not transfer control to another block (i.e., it is not a
@@ -616,6 +816,18 @@ pub struct LocalData<'ctx> {
 pub enum Statement<'ctx> {
     // An assignment statement. We use a Box to keep the size small.
     Assign(Box<(Place<'ctx>, RValue<'ctx>)>),
+    /// Write the tag of `variant` into the enum stored at `place`.
+    ///
+    /// This is the counterpart of [`RValue::Discriminant`]: an enum value is
+    /// built by storing the payload through a `Downcast` projection and then
+    /// setting its discriminant. The place must have an enum type with more
+    /// than `variant` variants.
+    SetDiscriminant {
+        /// The place holding the enum whose variant is set.
+        place: Place<'ctx>,
+        /// The index of the variant.
+        variant: u32,
+    },
 }
 
 impl<'ctx> Statement<'ctx> {
@@ -634,7 +846,7 @@ impl<'ctx> Statement<'ctx> {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 /// The terminator of a basic block.
 ///
 /// The terminator of a basic block is the last statement of the block.
@@ -653,6 +865,8 @@ pub enum Terminator<'ctx> {
     Goto {
         /// The basic block to branch to.
         target: BasicBlock,
+        /// The arguments bound to the parameters of `target`.
+        block_args: Vec<Operand<'ctx>>,
     },
     /// A multi-way branch based on an integer discriminant.
     ///
@@ -668,6 +882,12 @@ pub enum Terminator<'ctx> {
         /// The switch targets: a list of `(value, BasicBlock)` arms plus
         /// a mandatory `otherwise` (default) block.
         targets: SwitchTargets,
+        /// The arguments bound to the parameters of the chosen successor.
+        ///
+        /// The same arguments are passed to every successor, so all of them
+        /// must declare matching parameters (or none, when this is empty),
+        /// as checked by [`TirBody::check_block_args`](crate::body::TirBody::check_block_args).
+        block_args: Vec<Operand<'ctx>>,
     },
     /// Indicates unreachable code.
     ///
@@ -687,10 +907,138 @@ pub enum Terminator<'ctx> {
         destination: Place<'ctx>,
         /// The basic block to continue execution at after the call.
         target: BasicBlock,
+        /// The arguments bound to the parameters of `target`.
+        ///
+        /// They are evaluated after the call returns, so they may read
+        /// `destination`.
+        block_args: Vec<Operand<'ctx>>,
+    },
+    /// A runtime check, e.g. for arithmetic overflow or an out of bounds
+    /// index.
+    ///
+    /// If `cond` evaluates to `expected`, execution continues at `target`.
+    /// Otherwise the program panics with `msg`, and never returns.
+    Assert {
+        /// The boolean condition that is checked.
+        cond: Operand<'ctx>,
+        /// The value `cond` must have for the check to pass.
+        expected: bool,
+        /// The basic block to continue execution at if the check passes.
+        target: BasicBlock,
+        /// The reason of the panic if the check fails.
+        msg: AssertMessage<'ctx>,
+        /// The arguments bound to the parameters of `target`.
+        block_args: Vec<Operand<'ctx>>,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+/// The reason of the panic raised by a failing [`Terminator::Assert`].
+pub enum AssertMessage<'ctx> {
+    /// An arithmetic operation overflowed.
+    Overflow,
+    /// An index was out of the bounds of an array.
+    ///
+    /// Both operands are `u64` and are reported by the panic.
+    BoundsCheck {
+        /// The length of the indexed array.
+        len: Operand<'ctx>,
+        /// The index that was out of bounds.
+        index: Operand<'ctx>,
     },
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+/// The kind of a [`Terminator`], without its operands.
+///
+/// This is useful for quick dispatch and logging.
+pub enum TerminatorKind {
+    Return,
+    Goto,
+    SwitchInt,
+    Unreachable,
+    Call,
+    Assert,
+}
+
+impl<'ctx> Terminator<'ctx> {
+    /// Returns the kind of this terminator.
+    pub fn kind(&self) -> TerminatorKind {
+        match self {
+            Terminator::Return => TerminatorKind::Return,
+            Terminator::Goto { .. } => TerminatorKind::Goto,
+            Terminator::SwitchInt { .. } => TerminatorKind::SwitchInt,
+            Terminator::Unreachable => TerminatorKind::Unreachable,
+            Terminator::Call { .. } => TerminatorKind::Call,
+            Terminator::Assert { .. } => TerminatorKind::Assert,
+        }
+    }
+
+    /// Returns the basic blocks this terminator may transfer control to.
+    ///
+    /// For `SwitchInt` the arms are returned in order, followed by the
+    /// `otherwise` block. Duplicated targets are not removed.
+    pub fn successors(&self) -> SmallVec<[BasicBlock; 2]> {
+        match self {
+            Terminator::Return | Terminator::Unreachable => smallvec![],
+            Terminator::Goto { target, .. }
+            | Terminator::Call { target, .. }
+            | Terminator::Assert { target, .. } => smallvec![*target],
+            Terminator::SwitchInt { targets, .. } => targets
+                .values
+                .iter()
+                .map(|(_, bb)| *bb)
+                .chain(std::iter::once(targets.otherwise))
+                .collect(),
+        }
+    }
+
+    /// Returns mutable references to the successor blocks of this terminator,
+    /// in the same order as [`successors`](Self::successors).
+    ///
+    /// This is used by passes that renumber basic blocks.
+    pub fn successors_mut(&mut self) -> Vec<&mut BasicBlock> {
+        match self {
+            Terminator::Return | Terminator::Unreachable => vec![],
+            Terminator::Goto { target, .. }
+            | Terminator::Call { target, .. }
+            | Terminator::Assert { target, .. } => vec![target],
+            Terminator::SwitchInt { targets, .. } => targets
+                .values
+                .iter_mut()
+                .map(|(_, bb)| bb)
+                .chain(std::iter::once(&mut targets.otherwise))
+                .collect(),
+        }
+    }
+
+    /// Returns the arguments this terminator passes to the parameters of its
+    /// successors.
+    ///
+    /// Terminators without successors pass no arguments.
+    pub fn block_args(&self) -> &[Operand<'ctx>] {
+        match self {
+            Terminator::Return | Terminator::Unreachable => &[],
+            Terminator::Goto { block_args, .. }
+            | Terminator::SwitchInt { block_args, .. }
+            | Terminator::Call { block_args, .. }
+            | Terminator::Assert { block_args, .. } => block_args,
+        }
+    }
+
+    /// Mutable counterpart of [`block_args`](Self::block_args).
+    pub fn block_args_mut(&mut self) -> &mut [Operand<'ctx>] {
+        match self {
+            Terminator::Return | Terminator::Unreachable => &mut [],
+            Terminator::Goto { block_args, .. }
+            | Terminator::SwitchInt { block_args, .. }
+            | Terminator::Call { block_args, .. }
+            | Terminator::Assert { block_args, .. } => block_args,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 /// Targets for a `SwitchInt` terminator.
 ///
 /// Contains a list of `(value, BasicBlock)` arms and a mandatory `otherwise`
@@ -741,20 +1089,61 @@ impl SwitchTargets {
     }
 }
 
-#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
 pub struct BasicBlock(usize);
 pub const ENTRY_BLOCK: BasicBlock = BasicBlock(0);
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 /// The data of a basic block.
 ///
 /// A basic block is a sequence of statements that ends with a terminator.
 /// The terminator is the last statement of the block and transfers control to another block.
+///
+/// A block may declare parameters, in the style of SSA block arguments: every
+/// terminator jumping to the block binds one operand (its `block_args`) to each
+/// parameter, in order. The backend lowers parameters to phi nodes.
 pub struct BasicBlockData<'ctx> {
+    /// The parameters of the block, each bound to a local of the body.
+    ///
+    /// The locals must be declared in the body like any other local. The
+    /// entry block cannot have parameters, as it has no predecessors.
+    pub params: Vec<(Local, TirTy<'ctx>)>,
     pub statements: Vec<Statement<'ctx>>,
     pub terminator: Terminator<'ctx>,
 }
 
+impl<'ctx> BasicBlockData<'ctx> {
+    /// Create a block without parameters or statements, ending with
+    /// `terminator`.
+    pub fn new(terminator: Terminator<'ctx>) -> Self {
+        BasicBlockData {
+            params: vec![],
+            statements: vec![],
+            terminator,
+        }
+    }
+
+    /// Append `statement` to the statements of the block.
+    pub fn push_statement(&mut self, statement: Statement<'ctx>) {
+        self.statements.push(statement);
+    }
+
+    /// Append the assignment `place = rvalue` to the statements of the block.
+    pub fn push_assign(&mut self, place: Place<'ctx>, rvalue: RValue<'ctx>) {
+        self.push_statement(Statement::assign(place, rvalue));
+    }
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+/// The position of a statement or terminator within a body.
+///
+/// `statement_index` is the index of the statement within its block; the
+/// terminator of a block is at `statement_index == statements.len()`.
+pub struct Location {
+    pub block: BasicBlock,
+    pub statement_index: usize,
+}
+
 ////////// Trait implementations  //////////
 
 impl Idx for BasicBlock {
@@ -794,3 +1183,23 @@ impl Idx for Local {
         self.0 += by;
     }
 }
+
+////////// Trait implementations  //////////
+
+impl Idx for FieldIdx {
+    fn new(idx: usize) -> Self {
+        FieldIdx(idx)
+    }
+
+    fn idx(&self) -> usize {
+        self.0
+    }
+
+    fn incr(&mut self) {
+        self.0 += 1;
+    }
+
+    fn incr_by(&mut self, by: usize) {
+        self.0 += by;
+    }
+}
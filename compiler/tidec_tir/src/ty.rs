@@ -1,4 +1,7 @@
+use std::cmp::Ordering;
 use std::hash::Hash;
+use tidec_abi::layout::Primitive;
+use tidec_abi::target::AddressSpace;
 use tidec_utils::interner::Interner;
 
 #[derive(Debug, Clone, Copy)]
@@ -22,6 +25,9 @@ pub enum TirTy<I: Interner> {
     I32,
     I64,
     I128,
+    /// A signed integer as wide as a pointer in the default address space
+    /// of the target.
+    Isize,
 
     // Unsigned integers
     U8,
@@ -29,6 +35,9 @@ pub enum TirTy<I: Interner> {
     U32,
     U64,
     U128,
+    /// An unsigned integer as wide as a pointer in the default address
+    /// space of the target.
+    Usize,
 
     // Floating-point types
     F16,
@@ -42,7 +51,10 @@ pub enum TirTy<I: Interner> {
     /// For example, `*imm T` or `*mut T`.
     ///
     /// Note that when mutable is a c-style pointer.
-    RawPtr(I::Ty, Mutability),
+    ///
+    /// The `AddressSpace` is the address space the pointer points into;
+    /// most pointers live in [`AddressSpace::DATA`].
+    RawPtr(I::Ty, Mutability, AddressSpace),
 
     /// A struct (product) type.
     ///
@@ -76,6 +88,42 @@ pub enum TirTy<I: Interner> {
     /// ```
     Array(I::Ty, u64),
 
+    /// A SIMD vector of `lanes` values of the same primitive type.
+    ///
+    /// Unlike an array, a vector is a single value that the backend keeps in
+    /// a vector register. The number of lanes must be a power of two.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// // <4 x i32>
+    /// TirTy::Simd { elem: Primitive::I32, lanes: 4 }
+    /// ```
+    Simd {
+        /// The type of each lane.
+        elem: Primitive,
+        /// The number of lanes.
+        lanes: u32,
+    },
+
+    /// An enum (sum) type: a tagged union of its variants.
+    ///
+    /// Each variant carries a single payload type: `Unit` for a variant
+    /// without data, or a struct for a variant with several fields. The
+    /// payload of a variant is reached through a `Downcast` projection, and
+    /// the variant itself through the discriminant.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// // enum { None, Some(i32) }
+    /// TirTy::Enum { variants: intern_type_list(&[unit_ty, i32_ty]) }
+    /// ```
+    Enum {
+        /// The payload types of the variants, in discriminant order.
+        variants: I::TypeList,
+    },
+
     /// A function pointer.
     // FnPty {
     //     param_tys: Vec<TirTy>,
@@ -94,7 +142,7 @@ impl<I: Interner> TirTy<I> {
     pub fn is_signed_integer(&self) -> bool {
         matches!(
             self,
-            TirTy::I8 | TirTy::I16 | TirTy::I32 | TirTy::I64 | TirTy::I128
+            TirTy::I8 | TirTy::I16 | TirTy::I32 | TirTy::I64 | TirTy::I128 | TirTy::Isize
         )
     }
 
@@ -108,17 +156,35 @@ impl<I: Interner> TirTy<I> {
                 | TirTy::I32
                 | TirTy::I64
                 | TirTy::I128
+                | TirTy::Isize
                 | TirTy::U8
                 | TirTy::U16
                 | TirTy::U32
                 | TirTy::U64
                 | TirTy::U128
+                | TirTy::Usize
         )
     }
 
+    /// Returns the width in bits of an integer or floating-point type.
+    ///
+    /// Returns `None` for every other type, including `Bool`, pointers and
+    /// the pointer-sized `Isize`/`Usize`, whose width is given by their
+    /// layout.
+    pub fn bit_width(&self) -> Option<u32> {
+        match self {
+            TirTy::I8 | TirTy::U8 => Some(8),
+            TirTy::I16 | TirTy::U16 | TirTy::F16 => Some(16),
+            TirTy::I32 | TirTy::U32 | TirTy::F32 => Some(32),
+            TirTy::I64 | TirTy::U64 | TirTy::F64 => Some(64),
+            TirTy::I128 | TirTy::U128 | TirTy::F128 => Some(128),
+            _ => None,
+        }
+    }
+
     /// Returns `true` if this type is a raw pointer type.
     pub fn is_pointer(&self) -> bool {
-        matches!(self, TirTy::RawPtr(_, _))
+        matches!(self, TirTy::RawPtr(_, _, _))
     }
 
     /// Returns `true` if this type is the boolean type.
@@ -141,6 +207,16 @@ impl<I: Interner> TirTy<I> {
         matches!(self, TirTy::Array(_, _))
     }
 
+    /// Returns `true` if this type is a SIMD vector type.
+    pub fn is_simd(&self) -> bool {
+        matches!(self, TirTy::Simd { .. })
+    }
+
+    /// Returns `true` if this type is an enum type.
+    pub fn is_enum(&self) -> bool {
+        matches!(self, TirTy::Enum { .. })
+    }
+
     /// This function returns true if the type is a sized type.
     /// That is, it has a known size at compile time.
     pub fn is_sized(&self) -> bool {
@@ -152,25 +228,29 @@ impl<I: Interner> TirTy<I> {
             | TirTy::I32
             | TirTy::I64
             | TirTy::I128
+            | TirTy::Isize
             | TirTy::U8
             | TirTy::U16
             | TirTy::U32
             | TirTy::U64
             | TirTy::U128
+            | TirTy::Usize
             | TirTy::F16
             | TirTy::F32
             | TirTy::F64
             | TirTy::F128 => true,
-            TirTy::RawPtr(_, _) => true,
+            TirTy::RawPtr(_, _, _) => true,
             TirTy::Struct { .. } => true,
             TirTy::Array(_, _) => true,
+            TirTy::Simd { .. } => true,
+            TirTy::Enum { .. } => true,
             // TirTy::FnPty { .. } => true,
             TirTy::Metadata => false,
         }
     }
 }
 
-#[derive(Debug, Hash, Copy, Clone, Eq, PartialEq)]
+#[derive(Debug, Hash, Copy, Clone, Eq, PartialEq, Ord, PartialOrd)]
 pub enum Mutability {
     Mut,
     Imm,
@@ -186,16 +266,20 @@ impl<I: Interner> PartialEq for TirTy<I> {
             | (TirTy::I32, TirTy::I32)
             | (TirTy::I64, TirTy::I64)
             | (TirTy::I128, TirTy::I128)
+            | (TirTy::Isize, TirTy::Isize)
             | (TirTy::U8, TirTy::U8)
             | (TirTy::U16, TirTy::U16)
             | (TirTy::U32, TirTy::U32)
             | (TirTy::U64, TirTy::U64)
             | (TirTy::U128, TirTy::U128)
+            | (TirTy::Usize, TirTy::Usize)
             | (TirTy::F16, TirTy::F16)
             | (TirTy::F32, TirTy::F32)
             | (TirTy::F64, TirTy::F64)
             | (TirTy::F128, TirTy::F128) => true,
-            (TirTy::RawPtr(ty1, mut1), TirTy::RawPtr(ty2, mut2)) => ty1 == ty2 && mut1 == mut2,
+            (TirTy::RawPtr(ty1, mut1, as1), TirTy::RawPtr(ty2, mut2, as2)) => {
+                ty1 == ty2 && mut1 == mut2 && as1 == as2
+            }
             (
                 TirTy::Struct {
                     fields: f1,
@@ -207,6 +291,17 @@ impl<I: Interner> PartialEq for TirTy<I> {
                 },
             ) => f1 == f2 && p1 == p2,
             (TirTy::Array(ty1, len1), TirTy::Array(ty2, len2)) => ty1 == ty2 && len1 == len2,
+            (
+                TirTy::Simd {
+                    elem: e1,
+                    lanes: l1,
+                },
+                TirTy::Simd {
+                    elem: e2,
+                    lanes: l2,
+                },
+            ) => e1 == e2 && l1 == l2,
+            (TirTy::Enum { variants: v1 }, TirTy::Enum { variants: v2 }) => v1 == v2,
             (TirTy::Metadata, TirTy::Metadata) => true,
             _ => false,
         }
@@ -215,6 +310,84 @@ impl<I: Interner> PartialEq for TirTy<I> {
 
 impl<I: Interner> Eq for TirTy<I> {}
 
+impl<I: Interner> TirTy<I> {
+    /// The position of the variant in the ordering of types, following the
+    /// declaration order.
+    fn variant_index(&self) -> u8 {
+        match self {
+            TirTy::Unit => 0,
+            TirTy::Bool => 1,
+            TirTy::I8 => 2,
+            TirTy::I16 => 3,
+            TirTy::I32 => 4,
+            TirTy::I64 => 5,
+            TirTy::I128 => 6,
+            TirTy::Isize => 7,
+            TirTy::U8 => 8,
+            TirTy::U16 => 9,
+            TirTy::U32 => 10,
+            TirTy::U64 => 11,
+            TirTy::U128 => 12,
+            TirTy::Usize => 13,
+            TirTy::F16 => 14,
+            TirTy::F32 => 15,
+            TirTy::F64 => 16,
+            TirTy::F128 => 17,
+            TirTy::RawPtr(_, _, _) => 18,
+            TirTy::Struct { .. } => 19,
+            TirTy::Array(_, _) => 20,
+            TirTy::Simd { .. } => 21,
+            TirTy::Enum { .. } => 22,
+            TirTy::Metadata => 23,
+        }
+    }
+}
+
+impl<I: Interner> PartialOrd for TirTy<I> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Types are ordered by variant (in declaration order), then by their
+/// fields. The ordering is stable across runs, as interned types and type
+/// lists are compared by content.
+impl<I: Interner> Ord for TirTy<I> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self, other) {
+            (TirTy::RawPtr(ty1, mut1, as1), TirTy::RawPtr(ty2, mut2, as2)) => ty1
+                .cmp(ty2)
+                .then_with(|| mut1.cmp(mut2))
+                .then_with(|| as1.cmp(as2)),
+            (
+                TirTy::Struct {
+                    fields: f1,
+                    packed: p1,
+                },
+                TirTy::Struct {
+                    fields: f2,
+                    packed: p2,
+                },
+            ) => f1.cmp(f2).then_with(|| p1.cmp(p2)),
+            (TirTy::Array(ty1, len1), TirTy::Array(ty2, len2)) => {
+                ty1.cmp(ty2).then_with(|| len1.cmp(len2))
+            }
+            (
+                TirTy::Simd {
+                    elem: e1,
+                    lanes: l1,
+                },
+                TirTy::Simd {
+                    elem: e2,
+                    lanes: l2,
+                },
+            ) => e1.cmp(e2).then_with(|| l1.cmp(l2)),
+            (TirTy::Enum { variants: v1 }, TirTy::Enum { variants: v2 }) => v1.cmp(v2),
+            _ => self.variant_index().cmp(&other.variant_index()),
+        }
+    }
+}
+
 impl<I: Interner> Hash for TirTy<I> {
     fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
         match self {
@@ -225,31 +398,43 @@ impl<I: Interner> Hash for TirTy<I> {
             TirTy::I32 => 4.hash(state),
             TirTy::I64 => 5.hash(state),
             TirTy::I128 => 6.hash(state),
-            TirTy::U8 => 7.hash(state),
-            TirTy::U16 => 8.hash(state),
-            TirTy::U32 => 9.hash(state),
-            TirTy::U64 => 10.hash(state),
-            TirTy::U128 => 11.hash(state),
-            TirTy::F16 => 12.hash(state),
-            TirTy::F32 => 13.hash(state),
-            TirTy::F64 => 14.hash(state),
-            TirTy::F128 => 15.hash(state),
-            TirTy::RawPtr(ty, mutability) => {
-                16.hash(state);
+            TirTy::Isize => 7.hash(state),
+            TirTy::U8 => 8.hash(state),
+            TirTy::U16 => 9.hash(state),
+            TirTy::U32 => 10.hash(state),
+            TirTy::U64 => 11.hash(state),
+            TirTy::U128 => 12.hash(state),
+            TirTy::Usize => 13.hash(state),
+            TirTy::F16 => 14.hash(state),
+            TirTy::F32 => 15.hash(state),
+            TirTy::F64 => 16.hash(state),
+            TirTy::F128 => 17.hash(state),
+            TirTy::RawPtr(ty, mutability, address_space) => {
+                18.hash(state);
                 ty.hash(state);
                 mutability.hash(state);
+                address_space.hash(state);
             }
             TirTy::Struct { fields, packed } => {
-                17.hash(state);
+                19.hash(state);
                 fields.hash(state);
                 packed.hash(state);
             }
             TirTy::Array(ty, len) => {
-                18.hash(state);
+                20.hash(state);
                 ty.hash(state);
                 len.hash(state);
             }
-            TirTy::Metadata => 19.hash(state),
+            TirTy::Simd { elem, lanes } => {
+                21.hash(state);
+                elem.hash(state);
+                lanes.hash(state);
+            }
+            TirTy::Enum { variants } => {
+                22.hash(state);
+                variants.hash(state);
+            }
+            TirTy::Metadata => 23.hash(state),
         }
     }
 }
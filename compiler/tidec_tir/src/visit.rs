@@ -0,0 +1,320 @@
+//! Traversal of TIR bodies.
+//!
+//! The [`Visitor`] trait follows the `rustc` MIR visitor: every `visit_*`
+//! method defaults to the matching `super_*` method, which walks the children
+//! of the node. An analysis overrides only the `visit_*` methods it cares
+//! about, and calls `super_*` from them when it still wants the children to
+//! be visited.
+//!
+//! [`MutVisitor`] is the same traversal over mutable references, for passes
+//! that rewrite the nodes they visit in place. As projections are interned,
+//! it needs a [`TirCtx`] to intern the ones it rewrites.
+
+use crate::body::TirBody;
+use crate::ctx::TirCtx;
+use crate::syntax::{
+    AssertMessage, BasicBlock, BasicBlockData, ConstOperand, Local, Operand, Place, Projection,
+    RValue, Statement, Terminator,
+};
+
+/// The position of a local within the TIR, passed to [`Visitor::visit_local`]
+/// and [`MutVisitor::visit_local`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlaceContext {
+    /// The local is written, e.g. the destination of an assignment or a call.
+    Store,
+    /// The local is read, e.g. through an operand or an index projection.
+    Load,
+    /// The address of the local is taken.
+    AddressOf,
+}
+
+/// A read-only visitor over a [`TirBody`].
+pub trait Visitor<'ctx> {
+    fn visit_body(&mut self, body: &TirBody<'ctx>) {
+        self.super_body(body);
+    }
+
+    fn visit_basic_block_data(&mut self, bb: BasicBlock, data: &BasicBlockData<'ctx>) {
+        self.super_basic_block_data(bb, data);
+    }
+
+    fn visit_statement(&mut self, statement: &Statement<'ctx>) {
+        self.super_statement(statement);
+    }
+
+    fn visit_terminator(&mut self, terminator: &Terminator<'ctx>) {
+        self.super_terminator(terminator);
+    }
+
+    fn visit_rvalue(&mut self, rvalue: &RValue<'ctx>) {
+        self.super_rvalue(rvalue);
+    }
+
+    fn visit_operand(&mut self, operand: &Operand<'ctx>) {
+        self.super_operand(operand);
+    }
+
+    fn visit_const(&mut self, constant: &ConstOperand<'ctx>) {
+        self.super_const(constant);
+    }
+
+    fn visit_place(&mut self, place: &Place<'ctx>, context: PlaceContext) {
+        self.super_place(place, context);
+    }
+
+    fn visit_local(&mut self, _local: Local, _context: PlaceContext) {}
+
+    // The `super_*` methods walk the children of a node. They are not meant
+    // to be overridden.
+
+    fn super_body(&mut self, body: &TirBody<'ctx>) {
+        for (bb, data) in body.basic_blocks.iter_enumerated() {
+            self.visit_basic_block_data(bb, data);
+        }
+    }
+
+    fn super_basic_block_data(&mut self, _bb: BasicBlock, data: &BasicBlockData<'ctx>) {
+        for (param, _) in &data.params {
+            self.visit_local(*param, PlaceContext::Store);
+        }
+        for statement in &data.statements {
+            self.visit_statement(statement);
+        }
+        self.visit_terminator(&data.terminator);
+    }
+
+    fn super_statement(&mut self, statement: &Statement<'ctx>) {
+        match statement {
+            Statement::Assign(assign) => {
+                let (place, rvalue) = &**assign;
+                self.visit_rvalue(rvalue);
+                self.visit_place(place, PlaceContext::Store);
+            }
+            Statement::SetDiscriminant { place, .. } => {
+                self.visit_place(place, PlaceContext::Store);
+            }
+        }
+    }
+
+    fn super_terminator(&mut self, terminator: &Terminator<'ctx>) {
+        match terminator {
+            Terminator::Return | Terminator::Goto { .. } | Terminator::Unreachable => {}
+            Terminator::SwitchInt { discr, .. } => self.visit_operand(discr),
+            Terminator::Call {
+                func,
+                args,
+                destination,
+                ..
+            } => {
+                self.visit_operand(func);
+                for arg in args {
+                    self.visit_operand(arg);
+                }
+                self.visit_place(destination, PlaceContext::Store);
+            }
+            Terminator::Assert { cond, msg, .. } => {
+                self.visit_operand(cond);
+                match msg {
+                    AssertMessage::Overflow => {}
+                    AssertMessage::BoundsCheck { len, index } => {
+                        self.visit_operand(len);
+                        self.visit_operand(index);
+                    }
+                }
+            }
+        }
+        for block_arg in terminator.block_args() {
+            self.visit_operand(block_arg);
+        }
+    }
+
+    fn super_rvalue(&mut self, rvalue: &RValue<'ctx>) {
+        match rvalue {
+            RValue::Operand(operand)
+            | RValue::UnaryOp(_, operand)
+            | RValue::Cast(_, operand, _) => self.visit_operand(operand),
+            RValue::BinaryOp(_, lhs, rhs) => {
+                self.visit_operand(lhs);
+                self.visit_operand(rhs);
+            }
+            RValue::Aggregate(_, operands) | RValue::InlineAsm { operands, .. } => {
+                for operand in operands {
+                    self.visit_operand(operand);
+                }
+            }
+            RValue::AddressOf(_, place) => self.visit_place(place, PlaceContext::AddressOf),
+            RValue::Discriminant(place) => self.visit_place(place, PlaceContext::Load),
+        }
+    }
+
+    fn super_operand(&mut self, operand: &Operand<'ctx>) {
+        match operand {
+            Operand::Use(place) => self.visit_place(place, PlaceContext::Load),
+            Operand::Const(constant) => self.visit_const(constant),
+        }
+    }
+
+    fn super_const(&mut self, _constant: &ConstOperand<'ctx>) {}
+
+    fn super_place(&mut self, place: &Place<'ctx>, context: PlaceContext) {
+        self.visit_local(place.local, context);
+        for projection in place.projection {
+            if let Projection::Index(index) = projection {
+                self.visit_local(*index, PlaceContext::Load);
+            }
+        }
+    }
+}
+
+/// A visitor over a [`TirBody`] that may rewrite the nodes it visits.
+///
+/// It walks the body in the same order as [`Visitor`].
+pub trait MutVisitor<'ctx> {
+    /// The context used to intern the rewritten projections of a place.
+    fn ctx(&self) -> TirCtx<'ctx>;
+
+    fn visit_body(&mut self, body: &mut TirBody<'ctx>) {
+        self.super_body(body);
+    }
+
+    fn visit_basic_block_data(&mut self, bb: BasicBlock, data: &mut BasicBlockData<'ctx>) {
+        self.super_basic_block_data(bb, data);
+    }
+
+    fn visit_statement(&mut self, statement: &mut Statement<'ctx>) {
+        self.super_statement(statement);
+    }
+
+    fn visit_terminator(&mut self, terminator: &mut Terminator<'ctx>) {
+        self.super_terminator(terminator);
+    }
+
+    fn visit_rvalue(&mut self, rvalue: &mut RValue<'ctx>) {
+        self.super_rvalue(rvalue);
+    }
+
+    fn visit_operand(&mut self, operand: &mut Operand<'ctx>) {
+        self.super_operand(operand);
+    }
+
+    fn visit_const(&mut self, constant: &mut ConstOperand<'ctx>) {
+        self.super_const(constant);
+    }
+
+    fn visit_place(&mut self, place: &mut Place<'ctx>, context: PlaceContext) {
+        self.super_place(place, context);
+    }
+
+    fn visit_local(&mut self, _local: &mut Local, _context: PlaceContext) {}
+
+    // The `super_*` methods walk the children of a node. They are not meant
+    // to be overridden.
+
+    fn super_body(&mut self, body: &mut TirBody<'ctx>) {
+        for (bb, data) in body.basic_blocks.iter_enumerated_mut() {
+            self.visit_basic_block_data(bb, data);
+        }
+    }
+
+    fn super_basic_block_data(&mut self, _bb: BasicBlock, data: &mut BasicBlockData<'ctx>) {
+        for (param, _) in &mut data.params {
+            self.visit_local(param, PlaceContext::Store);
+        }
+        for statement in &mut data.statements {
+            self.visit_statement(statement);
+        }
+        self.visit_terminator(&mut data.terminator);
+    }
+
+    fn super_statement(&mut self, statement: &mut Statement<'ctx>) {
+        match statement {
+            Statement::Assign(assign) => {
+                let (place, rvalue) = &mut **assign;
+                self.visit_rvalue(rvalue);
+                self.visit_place(place, PlaceContext::Store);
+            }
+            Statement::SetDiscriminant { place, .. } => {
+                self.visit_place(place, PlaceContext::Store);
+            }
+        }
+    }
+
+    fn super_terminator(&mut self, terminator: &mut Terminator<'ctx>) {
+        match terminator {
+            Terminator::Return | Terminator::Goto { .. } | Terminator::Unreachable => {}
+            Terminator::SwitchInt { discr, .. } => self.visit_operand(discr),
+            Terminator::Call {
+                func,
+                args,
+                destination,
+                ..
+            } => {
+                self.visit_operand(func);
+                for arg in args {
+                    self.visit_operand(arg);
+                }
+                self.visit_place(destination, PlaceContext::Store);
+            }
+            Terminator::Assert { cond, msg, .. } => {
+                self.visit_operand(cond);
+                match msg {
+                    AssertMessage::Overflow => {}
+                    AssertMessage::BoundsCheck { len, index } => {
+                        self.visit_operand(len);
+                        self.visit_operand(index);
+                    }
+                }
+            }
+        }
+        for block_arg in terminator.block_args_mut() {
+            self.visit_operand(block_arg);
+        }
+    }
+
+    fn super_rvalue(&mut self, rvalue: &mut RValue<'ctx>) {
+        match rvalue {
+            RValue::Operand(operand)
+            | RValue::UnaryOp(_, operand)
+            | RValue::Cast(_, operand, _) => self.visit_operand(operand),
+            RValue::BinaryOp(_, lhs, rhs) => {
+                self.visit_operand(lhs);
+                self.visit_operand(rhs);
+            }
+            RValue::Aggregate(_, operands) | RValue::InlineAsm { operands, .. } => {
+                for operand in operands {
+                    self.visit_operand(operand);
+                }
+            }
+            RValue::AddressOf(_, place) => self.visit_place(place, PlaceContext::AddressOf),
+            RValue::Discriminant(place) => self.visit_place(place, PlaceContext::Load),
+        }
+    }
+
+    fn super_operand(&mut self, operand: &mut Operand<'ctx>) {
+        match operand {
+            Operand::Use(place) => self.visit_place(place, PlaceContext::Load),
+            Operand::Const(constant) => self.visit_const(constant),
+        }
+    }
+
+    fn super_const(&mut self, _constant: &mut ConstOperand<'ctx>) {}
+
+    fn super_place(&mut self, place: &mut Place<'ctx>, context: PlaceContext) {
+        self.visit_local(&mut place.local, context);
+        if !place
+            .projection
+            .iter()
+            .any(|projection| matches!(projection, Projection::Index(_)))
+        {
+            return;
+        }
+        let mut projection = place.projection.to_vec();
+        for elem in &mut projection {
+            if let Projection::Index(index) = elem {
+                self.visit_local(index, PlaceContext::Load);
+            }
+        }
+        place.projection = self.ctx().intern_projection(&projection);
+    }
+}
@@ -0,0 +1,224 @@
+use std::num::NonZero;
+
+use tidec_abi::target::AddressSpace;
+use tidec_tir::analysis::{const_prop, liveness};
+use tidec_tir::ctx::TirCtx;
+use tidec_tir::syntax::*;
+use tidec_tir::ty;
+use tidec_utils::idx::Idx;
+
+mod common;
+
+use common::{make_body, with_ctx};
+
+/// A block without parameters that runs `statements` and returns.
+fn returning_block<'ctx>(statements: Vec<Statement<'ctx>>) -> BasicBlockData<'ctx> {
+    BasicBlockData {
+        params: vec![],
+        statements,
+        terminator: Terminator::Return,
+    }
+}
+
+fn raw_i32(value: i32) -> RawScalarValue {
+    RawScalarValue {
+        data: value as u32 as u128,
+        size: NonZero::new(4).unwrap(),
+    }
+}
+
+fn const_i32<'ctx>(ctx: &TirCtx<'ctx>, value: i32) -> RValue<'ctx> {
+    RValue::Operand(Operand::Const(ConstOperand::Value(
+        ConstValue::Scalar(ConstScalar::Value(raw_i32(value))),
+        ctx.intern_ty(ty::TirTy::I32),
+    )))
+}
+
+fn copy<'ctx>(local: usize) -> RValue<'ctx> {
+    RValue::Operand(Operand::use_local(Local::new(local)))
+}
+
+fn assign<'ctx>(local: usize, rvalue: RValue<'ctx>) -> Statement<'ctx> {
+    Statement::assign(Place::from(Local::new(local)), rvalue)
+}
+
+#[test]
+fn const_prop_follows_copies_in_a_linear_block() {
+    with_ctx(|ctx| {
+        let i32_ty = ctx.intern_ty(ty::TirTy::I32);
+        // _2 = 5; _3 = _2; _0 = _3
+        let body = make_body(
+            &[i32_ty, i32_ty],
+            &[i32_ty, i32_ty],
+            vec![returning_block(vec![
+                assign(2, const_i32(&ctx, 5)),
+                assign(3, copy(2)),
+                assign(0, copy(3)),
+            ])],
+        );
+
+        let known = const_prop(&body);
+
+        assert_eq!(known.len(), 4);
+        assert_eq!(known[Local::new(2)], Some(raw_i32(5)));
+        assert_eq!(known[Local::new(3)], Some(raw_i32(5)));
+        assert_eq!(known[RETURN_LOCAL], Some(raw_i32(5)));
+        // The argument is written by the caller.
+        assert_eq!(known[Local::new(1)], None);
+    });
+}
+
+#[test]
+fn const_prop_invalidates_on_other_assignments() {
+    with_ctx(|ctx| {
+        let i32_ty = ctx.intern_ty(ty::TirTy::I32);
+        // _2 = 5; _2 = _1; _3 = 7; _3 = 8; _4 = 9; _4 = 9
+        let body = make_body(
+            &[i32_ty, i32_ty],
+            &[i32_ty, i32_ty, i32_ty],
+            vec![returning_block(vec![
+                assign(2, const_i32(&ctx, 5)),
+                assign(2, copy(1)),
+                assign(3, const_i32(&ctx, 7)),
+                assign(3, const_i32(&ctx, 8)),
+                assign(4, const_i32(&ctx, 9)),
+                assign(4, const_i32(&ctx, 9)),
+            ])],
+        );
+
+        let known = const_prop(&body);
+
+        assert_eq!(known[Local::new(2)], None);
+        assert_eq!(known[Local::new(3)], None);
+        assert_eq!(known[Local::new(4)], Some(raw_i32(9)));
+    });
+}
+
+#[test]
+fn const_prop_ignores_address_taken_locals() {
+    with_ctx(|ctx| {
+        let i32_ty = ctx.intern_ty(ty::TirTy::I32);
+        let ptr_ty = ctx.intern_ty(ty::TirTy::RawPtr(
+            i32_ty,
+            ty::Mutability::Mut,
+            AddressSpace::DATA,
+        ));
+        // _2 = 5; _3 = &mut _2; (*_3) = 6; _4 = _2
+        let body = make_body(
+            &[i32_ty, i32_ty],
+            &[i32_ty, ptr_ty, i32_ty],
+            vec![returning_block(vec![
+                assign(2, const_i32(&ctx, 5)),
+                assign(
+                    3,
+                    RValue::AddressOf(ty::Mutability::Mut, Place::from(Local::new(2))),
+                ),
+                Statement::assign(
+                    Place {
+                        local: Local::new(3),
+                        projection: ctx.intern_projection(&[Projection::Deref]),
+                    },
+                    const_i32(&ctx, 6),
+                ),
+                assign(4, copy(2)),
+            ])],
+        );
+
+        let known = const_prop(&body);
+
+        assert_eq!(known[Local::new(2)], None);
+        assert_eq!(known[Local::new(4)], None);
+    });
+}
+
+#[test]
+fn liveness_tracks_local_across_blocks() {
+    with_ctx(|ctx| {
+        let i32_ty = ctx.intern_ty(ty::TirTy::I32);
+        // bb0: _2 = 5; goto bb1
+        // bb1: _0 = _2; return
+        let mut body = make_body(
+            &[i32_ty, i32_ty],
+            &[i32_ty],
+            vec![returning_block(vec![assign(2, const_i32(&ctx, 5))])],
+        );
+        body.basic_blocks[BasicBlock::new(0)].terminator = Terminator::Goto {
+            target: BasicBlock::new(1),
+            block_args: vec![],
+        };
+        body.basic_blocks.push(BasicBlockData {
+            params: vec![],
+            statements: vec![assign(0, copy(2))],
+            terminator: Terminator::Return,
+        });
+
+        let liveness = liveness(&body);
+        let (bb0, bb1) = (BasicBlock::new(0), BasicBlock::new(1));
+
+        assert!(liveness.live_out(bb0).contains(Local::new(2)));
+        assert!(liveness.live_in(bb1).contains(Local::new(2)));
+        assert!(!liveness.live_in(bb0).contains(Local::new(2)));
+        assert!(liveness.live_out(bb1).is_empty());
+        // The return place is written before `Return` reads it.
+        assert!(!liveness.live_in(bb1).contains(RETURN_LOCAL));
+        // The argument is never read.
+        assert!(!liveness.live_in(bb0).contains(Local::new(1)));
+    });
+}
+
+#[test]
+fn liveness_propagates_around_loops() {
+    with_ctx(|ctx| {
+        let i32_ty = ctx.intern_ty(ty::TirTy::I32);
+        let bool_ty = ctx.intern_ty(ty::TirTy::Bool);
+        // bb0: goto bb1(_1)
+        // bb1(_2: i32): _3 = Lt(_2, _1); switch _3 -> [1: bb1(_2), otherwise: bb2(_2)]
+        // bb2(_4: i32): _0 = _4; return
+        let mut body = make_body(
+            &[i32_ty, i32_ty],
+            &[i32_ty, bool_ty, i32_ty],
+            vec![returning_block(vec![])],
+        );
+        body.basic_blocks[BasicBlock::new(0)].terminator = Terminator::Goto {
+            target: BasicBlock::new(1),
+            block_args: vec![Operand::use_local(Local::new(1))],
+        };
+        body.basic_blocks.push(BasicBlockData {
+            params: vec![(Local::new(2), i32_ty)],
+            statements: vec![assign(
+                3,
+                RValue::BinaryOp(
+                    BinaryOp::Lt,
+                    Operand::use_local(Local::new(2)),
+                    Operand::use_local(Local::new(1)),
+                ),
+            )],
+            terminator: Terminator::SwitchInt {
+                discr: Operand::use_local(Local::new(3)),
+                targets: SwitchTargets::if_then(BasicBlock::new(1), BasicBlock::new(2)),
+                block_args: vec![Operand::use_local(Local::new(2))],
+            },
+        });
+        body.basic_blocks.push(BasicBlockData {
+            params: vec![(Local::new(4), i32_ty)],
+            statements: vec![assign(0, copy(4))],
+            terminator: Terminator::Return,
+        });
+
+        let liveness = liveness(&body);
+        let (bb0, bb1, bb2) = (BasicBlock::new(0), BasicBlock::new(1), BasicBlock::new(2));
+
+        // The bound `_1` is read on every iteration, so it stays live
+        // around the back edge.
+        assert!(liveness.live_in(bb0).contains(Local::new(1)));
+        assert!(liveness.live_in(bb1).contains(Local::new(1)));
+        assert!(liveness.live_out(bb1).contains(Local::new(1)));
+        // Block parameters are defined on entry, and the block arguments
+        // that bind them are read by the terminator of the predecessor.
+        assert!(!liveness.live_in(bb1).contains(Local::new(2)));
+        assert!(!liveness.live_out(bb1).contains(Local::new(2)));
+        assert!(!liveness.live_in(bb2).contains(Local::new(4)));
+        assert!(!liveness.live_out(bb1).contains(Local::new(3)));
+        assert!(liveness.live_in(bb2).is_empty());
+    });
+}
@@ -0,0 +1,982 @@
+use tidec_abi::layout::Primitive;
+use tidec_abi::size_and_align::Size;
+use tidec_abi::target::AddressSpace;
+use tidec_tir::alloc::GlobalAlloc;
+use tidec_tir::body::{
+    BlockArgsError, DefId, DefIdAllocator, EntryKind, GlobalId, InlineAsmOperandError, Linkage,
+    MergeError, SimdBinOpError, TirBody, TirBodyMetadata, TirGlobal, TirUnit, TirUnitMetadata,
+    UnnamedAddress, Visibility,
+};
+use tidec_tir::ctx::TirCtx;
+use tidec_tir::syntax::*;
+use tidec_tir::ty;
+use tidec_tir::visit::{PlaceContext, Visitor};
+use tidec_utils::idx::Idx;
+use tidec_utils::index_vec::IdxVec;
+
+mod common;
+
+use common::{make_body, with_ctx};
+
+/// Build a `fn(i64, bool) -> i32` body with a single returning block.
+fn i64_bool_body<'ctx>(ctx: &TirCtx<'ctx>) -> TirBody<'ctx> {
+    make_body(
+        &[ctx.mk_i32(), ctx.mk_i64(), ctx.mk_bool()],
+        &[],
+        vec![BasicBlockData {
+            params: vec![],
+            statements: vec![],
+            terminator: Terminator::Return,
+        }],
+    )
+}
+
+// ---- Return and argument accessor tests ----
+
+#[test]
+fn return_local_data_is_first_entry() {
+    with_ctx(|ctx| {
+        let body = i64_bool_body(&ctx);
+        assert_eq!(body.return_local_data().ty, ctx.intern_ty(ty::TirTy::I32));
+    });
+}
+
+#[test]
+fn args_skip_the_return_slot() {
+    with_ctx(|ctx| {
+        let body = i64_bool_body(&ctx);
+
+        assert_eq!(body.arg_count(), 2);
+        assert_eq!(body.args().len(), 2);
+
+        let args: Vec<_> = body.args().map(|(local, arg)| (local, arg.ty)).collect();
+        assert_eq!(
+            args,
+            vec![
+                (Local::new(1), ctx.intern_ty(ty::TirTy::I64)),
+                (Local::new(2), ctx.intern_ty(ty::TirTy::Bool))
+            ]
+        );
+    });
+}
+
+#[cfg(debug_assertions)]
+#[test]
+#[should_panic(expected = "out of sync")]
+fn arg_count_out_of_sync_is_caught() {
+    with_ctx(|ctx| {
+        let mut body = i64_bool_body(&ctx);
+        body.ret_and_args.push(LocalData {
+            ty: ctx.mk_i32(),
+            mutable: false,
+        });
+
+        let _ = body.args();
+    });
+}
+
+#[test]
+fn body_without_args_has_empty_args() {
+    with_ctx(|ctx| {
+        let mut body = i64_bool_body(&ctx);
+        body.ret_and_args.raw.truncate(1);
+        body.arg_count = 0;
+
+        assert_eq!(body.arg_count(), 0);
+        assert_eq!(body.args().len(), 0);
+    });
+}
+
+#[test]
+fn with_capacity_has_no_args() {
+    let body = TirBody::with_capacity(TirBodyMetadata::function(DefId(0), "empty_fn"), 0, 0);
+    assert_eq!(body.arg_count(), 0);
+    assert_eq!(body.args().len(), 0);
+}
+
+// ---- Capacity tests ----
+
+#[test]
+fn with_capacity_pre_sizes_locals_and_blocks() {
+    let mut body = TirBody::with_capacity(TirBodyMetadata::function(DefId(0), "big_fn"), 64, 32);
+    assert!(body.locals.is_empty());
+    assert!(body.basic_blocks.is_empty());
+    assert!(body.locals.capacity() >= 64);
+    assert!(body.basic_blocks.capacity() >= 32);
+
+    body.reserve_locals(128);
+    body.reserve_blocks(256);
+    assert!(body.locals.capacity() >= 128);
+    assert!(body.basic_blocks.capacity() >= 256);
+}
+
+// ---- Frame layout tests ----
+
+#[test]
+fn frame_layout_aligns_each_local() {
+    with_ctx(|ctx| {
+        let local = |ty| LocalData { ty, mutable: true };
+        let mut body = i64_bool_body(&ctx);
+        body.ret_and_args = IdxVec::from_raw(vec![local(ctx.intern_ty(ty::TirTy::Unit))]);
+        body.arg_count = 0;
+        body.locals = IdxVec::from_raw(vec![
+            local(ctx.intern_ty(ty::TirTy::I8)),
+            local(ctx.intern_ty(ty::TirTy::I64)),
+        ]);
+
+        // The ABI alignment of `i64` depends on the target (4 bytes by
+        // default, 8 on x86_64).
+        let i64_align = ctx.layout_of(ctx.intern_ty(ty::TirTy::I64)).align.abi;
+
+        let frame = body.frame_layout(&ctx);
+
+        assert_eq!(frame.offsets.len(), 3);
+        assert_eq!(frame.offsets[RETURN_LOCAL], Size::ZERO);
+        assert_eq!(frame.offsets[Local::new(1)], Size::ZERO);
+        // The `i64` skips the padding after the `i8`.
+        let i64_offset = Size::from_bytes(i64_align.bytes());
+        assert_eq!(frame.offsets[Local::new(2)], i64_offset);
+        assert_eq!(frame.align, i64_align);
+        assert_eq!(frame.size, Size::from_bytes(i64_offset.bytes() + 8));
+    });
+}
+
+// ---- local_ty / place_ty tests ----
+
+#[test]
+fn local_ty_covers_args_and_locals() {
+    with_ctx(|ctx| {
+        let mut body = i64_bool_body(&ctx);
+        let f64_ty = ctx.intern_ty(ty::TirTy::F64);
+        body.locals.push(LocalData {
+            ty: f64_ty,
+            mutable: false,
+        });
+
+        assert_eq!(body.local_ty(RETURN_LOCAL), ctx.intern_ty(ty::TirTy::I32));
+        assert_eq!(body.local_ty(Local::new(2)), ctx.intern_ty(ty::TirTy::Bool));
+        assert_eq!(body.local_ty(Local::new(3)), f64_ty);
+    });
+}
+
+#[test]
+fn two_locals_mut_spans_args_and_locals() {
+    with_ctx(|ctx| {
+        let mut body = i64_bool_body(&ctx);
+        body.locals = IdxVec::from_raw(vec![LocalData {
+            ty: ctx.intern_ty(ty::TirTy::U8),
+            mutable: false,
+        }]);
+
+        // `_1` is an argument and `_3` the first non-argument local.
+        let (arg, local) = body.two_locals_mut(Local::new(1), Local::new(3));
+        arg.mutable = true;
+        local.mutable = true;
+        let (local, ret) = body.two_locals_mut(Local::new(3), RETURN_LOCAL);
+        assert_eq!(local.ty, ctx.intern_ty(ty::TirTy::U8));
+        assert_eq!(ret.ty, ctx.intern_ty(ty::TirTy::I32));
+
+        assert!(body.ret_and_args[Local::new(1)].mutable);
+        assert!(body.locals[Local::new(0)].mutable);
+        assert!(!body.ret_and_args[RETURN_LOCAL].mutable);
+    });
+}
+
+#[test]
+#[should_panic(expected = "mutably twice")]
+fn two_locals_mut_rejects_the_same_local() {
+    with_ctx(|ctx| {
+        let mut body = i64_bool_body(&ctx);
+        body.two_locals_mut(Local::new(1), Local::new(1));
+    });
+}
+
+#[test]
+fn place_ty_follows_field_and_deref_projections() {
+    with_ctx(|ctx| {
+        let mut body = i64_bool_body(&ctx);
+        let i8_ty = ctx.intern_ty(ty::TirTy::I8);
+        let u16_ty = ctx.intern_ty(ty::TirTy::U16);
+        let struct_ty = ctx.intern_ty(ty::TirTy::Struct {
+            fields: ctx.intern_type_list(&[i8_ty, u16_ty]),
+            packed: false,
+        });
+        let ptr_ty = ctx.intern_ty(ty::TirTy::RawPtr(
+            struct_ty,
+            ty::Mutability::Imm,
+            AddressSpace::DATA,
+        ));
+        let array_ty = ctx.intern_ty(ty::TirTy::Array(u16_ty, 8));
+        body.locals.push(LocalData {
+            ty: ptr_ty,
+            mutable: false,
+        });
+        body.locals.push(LocalData {
+            ty: array_ty,
+            mutable: false,
+        });
+
+        // `(*_3).1`
+        let field = Place {
+            local: Local::new(3),
+            projection: ctx.intern_projection(&[
+                Projection::Deref,
+                Projection::Field(FieldIdx::new(1), u16_ty),
+            ]),
+        };
+        assert_eq!(body.place_ty(&Place::from(Local::new(3)), &ctx), ptr_ty);
+        assert_eq!(
+            body.place_ty(
+                &Place {
+                    local: Local::new(3),
+                    projection: ctx.intern_projection(&[Projection::Deref]),
+                },
+                &ctx
+            ),
+            struct_ty
+        );
+        assert_eq!(body.place_ty(&field, &ctx), u16_ty);
+
+        // `_4[2..5]` and `_4[1]`
+        let subslice = Place {
+            local: Local::new(4),
+            projection: ctx.intern_projection(&[Projection::Subslice {
+                from: 2,
+                to: 5,
+                from_end: false,
+            }]),
+        };
+        assert_eq!(
+            body.place_ty(&subslice, &ctx),
+            ctx.intern_ty(ty::TirTy::Array(u16_ty, 3))
+        );
+        let index = Place {
+            local: Local::new(4),
+            projection: ctx.intern_projection(&[Projection::Index(Local::new(1))]),
+        };
+        assert_eq!(body.place_ty(&index, &ctx), u16_ty);
+    });
+}
+
+#[test]
+fn place_ty_of_a_downcast_is_the_variant_payload() {
+    with_ctx(|ctx| {
+        let mut body = i64_bool_body(&ctx);
+        let unit_ty = ctx.intern_ty(ty::TirTy::Unit);
+        let i8_ty = ctx.intern_ty(ty::TirTy::I8);
+        let u16_ty = ctx.intern_ty(ty::TirTy::U16);
+        let payload_ty = ctx.intern_ty(ty::TirTy::Struct {
+            fields: ctx.intern_type_list(&[i8_ty, u16_ty]),
+            packed: false,
+        });
+        let enum_ty = ctx.intern_ty(ty::TirTy::Enum {
+            variants: ctx.intern_type_list(&[unit_ty, payload_ty]),
+        });
+        body.locals.push(LocalData {
+            ty: enum_ty,
+            mutable: true,
+        });
+
+        // `(_3 as 1)` and `(_3 as 1).1`
+        let downcast = Place {
+            local: Local::new(3),
+            projection: ctx.intern_projection(&[Projection::Downcast(1)]),
+        };
+        let field = Place {
+            local: Local::new(3),
+            projection: ctx.intern_projection(&[
+                Projection::Downcast(1),
+                Projection::Field(FieldIdx::new(1), u16_ty),
+            ]),
+        };
+        assert_eq!(body.place_ty(&downcast, &ctx), payload_ty);
+        assert_eq!(body.place_ty(&field, &ctx), u16_ty);
+    });
+}
+
+// ---- content_hash tests ----
+
+#[test]
+fn content_hash_ignores_identity_but_not_structure() {
+    with_ctx(|ctx| {
+        let body = i64_bool_body(&ctx);
+        let mut renamed = i64_bool_body(&ctx);
+        renamed.metadata.name = "other_fn".to_string();
+        renamed.metadata.def_id = DefId(42);
+        assert_eq!(body.content_hash(), renamed.content_hash());
+
+        let mut changed = i64_bool_body(&ctx);
+        changed.basic_blocks[BasicBlock::new(0)]
+            .statements
+            .push(Statement::assign(
+                Place::from(RETURN_LOCAL),
+                RValue::Operand(Operand::use_local(Local::new(1))),
+            ));
+        assert_ne!(body.content_hash(), changed.content_hash());
+    });
+}
+
+#[test]
+fn content_eq_ignores_identity_but_not_structure() {
+    with_ctx(|ctx| {
+        let body = i64_bool_body(&ctx);
+        let mut renamed = i64_bool_body(&ctx);
+        renamed.metadata.name = "other_fn".to_string();
+        renamed.metadata.def_id = DefId(42);
+        assert!(body.content_eq(&renamed));
+
+        let mut changed = i64_bool_body(&ctx);
+        changed.locals.push(LocalData {
+            ty: ctx.intern_ty(ty::TirTy::U8),
+            mutable: true,
+        });
+        assert!(!body.content_eq(&changed));
+    });
+}
+
+// ---- structural_hash tests ----
+
+#[test]
+fn structural_hash_ignores_identity_but_not_structure() {
+    with_ctx(|ctx| {
+        let body = i64_bool_body(&ctx);
+        let mut renamed = i64_bool_body(&ctx);
+        renamed.metadata.name = "other_fn".to_string();
+        renamed.metadata.def_id = DefId(42);
+        assert_eq!(body.structural_hash(), renamed.structural_hash());
+
+        let mut changed = i64_bool_body(&ctx);
+        changed.basic_blocks[BasicBlock::new(0)]
+            .statements
+            .push(Statement::assign(
+                Place::from(RETURN_LOCAL),
+                RValue::Operand(Operand::use_local(Local::new(1))),
+            ));
+        assert_ne!(body.structural_hash(), changed.structural_hash());
+    });
+}
+
+#[test]
+fn structural_hash_is_stable_across_ctxs() {
+    with_ctx(|ctx| {
+        with_ctx(|other_ctx| {
+            let body = i64_bool_body(&ctx);
+            let other = i64_bool_body(&other_ctx);
+            assert_ne!(body.content_hash(), other.content_hash());
+            assert_eq!(body.structural_hash(), other.structural_hash());
+        });
+    });
+}
+
+// ---- statements_with_locations tests ----
+
+#[test]
+fn statements_with_locations_flattens_blocks() {
+    with_ctx(|ctx| {
+        let i32_ty = ctx.intern_ty(ty::TirTy::I32);
+        let assign_ret = || {
+            Statement::assign(
+                Place::from(RETURN_LOCAL),
+                RValue::Operand(Operand::use_local(RETURN_LOCAL)),
+            )
+        };
+        let mut body = i64_bool_body(&ctx);
+        // bb0: two statements; goto bb1
+        // bb1: one statement; return
+        body.basic_blocks = IdxVec::from_raw(vec![
+            BasicBlockData {
+                params: vec![],
+                statements: vec![
+                    Statement::assign(
+                        Place::from(RETURN_LOCAL),
+                        RValue::Operand(Operand::Const(ConstOperand::Value(
+                            ConstValue::ZST,
+                            i32_ty,
+                        ))),
+                    ),
+                    assign_ret(),
+                ],
+                terminator: Terminator::Goto {
+                    target: BasicBlock::new(1),
+                    block_args: vec![],
+                },
+            },
+            BasicBlockData {
+                params: vec![],
+                statements: vec![assign_ret()],
+                terminator: Terminator::Return,
+            },
+        ]);
+
+        let locations: Vec<_> = body
+            .statements_with_locations()
+            .map(|(location, _)| (location.block, location.statement_index))
+            .collect();
+
+        assert_eq!(
+            locations,
+            vec![
+                (BasicBlock::new(0), 0),
+                (BasicBlock::new(0), 1),
+                (BasicBlock::new(1), 0),
+            ]
+        );
+        let (location, statement) = body.statements_with_locations().nth(2).unwrap();
+        assert_eq!(
+            location,
+            Location {
+                block: BasicBlock::new(1),
+                statement_index: 0,
+            }
+        );
+        assert!(std::ptr::eq(
+            statement,
+            &body.basic_blocks[BasicBlock::new(1)].statements[0]
+        ));
+    });
+}
+
+// ---- check_block_args tests ----
+
+#[test]
+fn check_block_args_rejects_mismatched_switch_successors() {
+    with_ctx(|ctx| {
+        let i64_ty = ctx.intern_ty(ty::TirTy::I64);
+        let mut body = i64_bool_body(&ctx);
+        // bb0: switch _2 -> [1: bb1, otherwise: bb2](_1)
+        // bb1(_3: i64): return
+        // bb2: return
+        body.locals.push(LocalData {
+            ty: i64_ty,
+            mutable: false,
+        });
+        body.basic_blocks[BasicBlock::new(0)].terminator = Terminator::SwitchInt {
+            discr: Operand::use_local(Local::new(2)),
+            targets: SwitchTargets::if_then(BasicBlock::new(1), BasicBlock::new(2)),
+            block_args: vec![Operand::use_local(Local::new(1))],
+        };
+        body.basic_blocks.push(BasicBlockData {
+            params: vec![(Local::new(3), i64_ty)],
+            statements: vec![],
+            terminator: Terminator::Return,
+        });
+        body.basic_blocks
+            .push(BasicBlockData::new(Terminator::Return));
+
+        assert_eq!(
+            body.check_block_args(),
+            Err(BlockArgsError {
+                block: BasicBlock::new(0),
+                successor: BasicBlock::new(2),
+                expected: 0,
+                found: 1,
+            })
+        );
+
+        body.basic_blocks[BasicBlock::new(2)].params = vec![(Local::new(3), i64_ty)];
+        assert_eq!(body.check_block_args(), Ok(()));
+    });
+}
+
+// ---- check_simd_binops tests ----
+
+#[test]
+fn check_simd_binops_rejects_only_non_lanewise_operators() {
+    with_ctx(|ctx| {
+        let simd_ty = ctx.intern_ty(ty::TirTy::Simd {
+            elem: Primitive::I32,
+            lanes: 4,
+        });
+        let local = |ty| LocalData { ty, mutable: false };
+        let mut body = i64_bool_body(&ctx);
+        body.locals = IdxVec::from_raw(vec![local(simd_ty), local(simd_ty)]);
+        let binop = |op| {
+            Statement::assign(
+                Place::from(Local::new(4)),
+                RValue::BinaryOp(
+                    op,
+                    Operand::use_local(Local::new(3)),
+                    Operand::use_local(Local::new(4)),
+                ),
+            )
+        };
+
+        body.basic_blocks[BasicBlock::new(0)].statements =
+            vec![binop(BinaryOp::Add), binop(BinaryOp::SaturatingSub)];
+        assert_eq!(body.check_simd_binops(&ctx), Ok(()));
+
+        body.basic_blocks[BasicBlock::new(0)]
+            .statements
+            .push(binop(BinaryOp::MulChecked));
+        let err = body.check_simd_binops(&ctx).unwrap_err();
+        assert_eq!(
+            err,
+            SimdBinOpError {
+                location: Location {
+                    block: BasicBlock::new(0),
+                    statement_index: 2,
+                },
+                op: BinaryOp::MulChecked,
+            }
+        );
+        assert_eq!(
+            err.to_string(),
+            "`MulChecked` in `bb0[2]` is not supported on SIMD vectors"
+        );
+    });
+}
+
+// ---- check_inline_asm_operands tests ----
+
+#[test]
+fn check_inline_asm_operands_rejects_non_immediates() {
+    with_ctx(|ctx| {
+        let unit_ty = ctx.intern_ty(ty::TirTy::Unit);
+        let mut body = i64_bool_body(&ctx);
+        body.locals = IdxVec::from_raw(vec![LocalData {
+            ty: unit_ty,
+            mutable: false,
+        }]);
+        let asm = |operands| {
+            Statement::assign(
+                Place::from(Local::new(3)),
+                RValue::InlineAsm {
+                    template: "".to_string(),
+                    operands,
+                    clobbers: vec![],
+                },
+            )
+        };
+
+        body.basic_blocks[BasicBlock::new(0)].statements =
+            vec![asm(vec![Operand::use_local(Local::new(1))])];
+        assert_eq!(body.check_inline_asm_operands(&ctx), Ok(()));
+
+        body.basic_blocks[BasicBlock::new(0)]
+            .statements
+            .push(asm(vec![
+                Operand::use_local(Local::new(1)),
+                Operand::use_local(Local::new(3)),
+            ]));
+        let err = body.check_inline_asm_operands(&ctx).unwrap_err();
+        assert_eq!(
+            err,
+            InlineAsmOperandError {
+                location: Location {
+                    block: BasicBlock::new(0),
+                    statement_index: 1,
+                },
+                operand_index: 1,
+                ty: unit_ty,
+            }
+        );
+        assert_eq!(
+            err.to_string(),
+            "inline assembly operand 1 in `bb0[1]` has type Unit, which is not an immediate"
+        );
+    });
+}
+
+// ---- used_locals tests ----
+
+#[test]
+fn used_locals_collects_exactly_the_referenced_locals() {
+    with_ctx(|ctx| {
+        let i64_ty = ctx.intern_ty(ty::TirTy::I64);
+        let mut body = i64_bool_body(&ctx);
+        // _3 is used, _4 is declared but never referenced.
+        body.locals = IdxVec::from_raw(vec![
+            LocalData {
+                ty: i64_ty,
+                mutable: true,
+            },
+            LocalData {
+                ty: i64_ty,
+                mutable: true,
+            },
+        ]);
+        // bb0: _3 = Add(_1, const); _0 = IntToInt(_3); return
+        body.basic_blocks = IdxVec::from_raw(vec![BasicBlockData {
+            params: vec![],
+            statements: vec![
+                Statement::assign(
+                    Place::from(Local::new(3)),
+                    RValue::BinaryOp(
+                        BinaryOp::Add,
+                        Operand::use_local(Local::new(1)),
+                        Operand::Const(ConstOperand::Value(ConstValue::ZST, i64_ty)),
+                    ),
+                ),
+                Statement::assign(
+                    Place::from(RETURN_LOCAL),
+                    RValue::Cast(
+                        CastKind::IntToInt,
+                        Operand::use_local(Local::new(3)),
+                        ctx.intern_ty(ty::TirTy::I32),
+                    ),
+                ),
+            ],
+            terminator: Terminator::Return,
+        }]);
+
+        let used = body.used_locals();
+
+        assert_eq!(used.domain_size(), 5);
+        assert_eq!(
+            used.iter().collect::<Vec<_>>(),
+            vec![RETURN_LOCAL, Local::new(1), Local::new(3)]
+        );
+        assert!(!used.contains(Local::new(2)));
+        assert!(!used.contains(Local::new(4)));
+    });
+}
+
+#[test]
+fn used_locals_includes_terminator_and_index_locals() {
+    with_ctx(|ctx| {
+        let mut body = i64_bool_body(&ctx);
+        // bb0: switchInt(_2) -> [1: bb1, otherwise: bb1]
+        // bb1: _0[_1] = call _0(); return
+        body.basic_blocks = IdxVec::from_raw(vec![
+            BasicBlockData {
+                params: vec![],
+                statements: vec![],
+                terminator: Terminator::SwitchInt {
+                    discr: Operand::use_local(Local::new(2)),
+                    targets: SwitchTargets::if_then(BasicBlock::new(1), BasicBlock::new(1)),
+                    block_args: vec![],
+                },
+            },
+            BasicBlockData {
+                params: vec![],
+                statements: vec![],
+                terminator: Terminator::Call {
+                    func: Operand::Const(ConstOperand::Value(
+                        ConstValue::ZST,
+                        ctx.intern_ty(ty::TirTy::I32),
+                    )),
+                    args: vec![],
+                    destination: Place {
+                        local: RETURN_LOCAL,
+                        projection: ctx.intern_projection(&[Projection::Index(Local::new(1))]),
+                    },
+                    target: BasicBlock::new(1),
+                    block_args: vec![],
+                },
+            },
+        ]);
+
+        let used = body.used_locals();
+
+        assert_eq!(
+            used.iter().collect::<Vec<_>>(),
+            vec![RETURN_LOCAL, Local::new(1), Local::new(2)]
+        );
+    });
+}
+
+#[test]
+fn used_locals_includes_block_params_and_args() {
+    with_ctx(|ctx| {
+        let i64_ty = ctx.intern_ty(ty::TirTy::I64);
+        let mut body = i64_bool_body(&ctx);
+        body.locals = IdxVec::from_raw(vec![LocalData {
+            ty: i64_ty,
+            mutable: false,
+        }]);
+        // bb0: goto bb1(_1)
+        // bb1(_3: i64): return
+        body.basic_blocks = IdxVec::from_raw(vec![
+            BasicBlockData {
+                params: vec![],
+                statements: vec![],
+                terminator: Terminator::Goto {
+                    target: BasicBlock::new(1),
+                    block_args: vec![Operand::use_local(Local::new(1))],
+                },
+            },
+            BasicBlockData {
+                params: vec![(Local::new(3), i64_ty)],
+                statements: vec![],
+                terminator: Terminator::Return,
+            },
+        ]);
+
+        let used = body.used_locals();
+
+        assert_eq!(
+            used.iter().collect::<Vec<_>>(),
+            vec![Local::new(1), Local::new(3)]
+        );
+    });
+}
+
+// ---- Visitor tests ----
+
+/// Counts every constant operand of a body.
+struct ConstCounter(usize);
+
+impl<'ctx> Visitor<'ctx> for ConstCounter {
+    fn visit_const(&mut self, _constant: &ConstOperand<'ctx>) {
+        self.0 += 1;
+    }
+}
+
+#[test]
+fn visitor_visits_every_constant() {
+    with_ctx(|ctx| {
+        let i32_ty = ctx.intern_ty(ty::TirTy::I32);
+        let i32_const = || Operand::Const(ConstOperand::Value(ConstValue::ZST, i32_ty));
+        let mut body = i64_bool_body(&ctx);
+        // bb0: _0 = Add(const, const); _0 = Neg(const); goto bb1(const)
+        // bb1(_1: i32): _0 = call const(_1, const) -> bb2
+        // bb2: return
+        body.basic_blocks = IdxVec::from_raw(vec![
+            BasicBlockData {
+                params: vec![],
+                statements: vec![
+                    Statement::assign(
+                        Place::from(RETURN_LOCAL),
+                        RValue::BinaryOp(BinaryOp::Add, i32_const(), i32_const()),
+                    ),
+                    Statement::assign(
+                        Place::from(RETURN_LOCAL),
+                        RValue::UnaryOp(UnaryOp::Neg, i32_const()),
+                    ),
+                ],
+                terminator: Terminator::Goto {
+                    target: BasicBlock::new(1),
+                    block_args: vec![i32_const()],
+                },
+            },
+            BasicBlockData {
+                params: vec![(Local::new(1), i32_ty)],
+                statements: vec![],
+                terminator: Terminator::Call {
+                    func: i32_const(),
+                    args: vec![Operand::use_local(Local::new(1)), i32_const()],
+                    destination: Place::from(RETURN_LOCAL),
+                    target: BasicBlock::new(2),
+                    block_args: vec![],
+                },
+            },
+            BasicBlockData {
+                params: vec![],
+                statements: vec![],
+                terminator: Terminator::Return,
+            },
+        ]);
+
+        let mut counter = ConstCounter(0);
+        counter.visit_body(&body);
+        assert_eq!(counter.0, 6);
+    });
+}
+
+#[test]
+fn visitor_visits_set_discriminant_place_as_store() {
+    /// Records every local visited together with its context.
+    struct LocalRecorder(Vec<(Local, PlaceContext)>);
+
+    impl<'ctx> Visitor<'ctx> for LocalRecorder {
+        fn visit_local(&mut self, local: Local, context: PlaceContext) {
+            self.0.push((local, context));
+        }
+    }
+
+    with_ctx(|ctx| {
+        let mut body = i64_bool_body(&ctx);
+        body.basic_blocks[BasicBlock::new(0)]
+            .statements
+            .push(Statement::SetDiscriminant {
+                place: Place::from(Local::new(1)),
+                variant: 1,
+            });
+
+        let mut recorder = LocalRecorder(vec![]);
+        recorder.visit_body(&body);
+        assert_eq!(recorder.0, vec![(Local::new(1), PlaceContext::Store)]);
+    });
+}
+
+// ---- Body map tests ----
+
+#[test]
+fn interned_body_is_found_by_def_id() {
+    with_ctx(|ctx| {
+        let mut body = i64_bool_body(&ctx);
+        body.metadata.def_id = DefId(7);
+
+        let def_id = ctx.intern_body(body);
+        assert_eq!(def_id, DefId(7));
+
+        let found = ctx.body(def_id).expect("the body was interned");
+        assert_eq!(found.metadata.name, "test_fn");
+        // Every lookup returns the same arena allocation.
+        assert!(std::ptr::eq(found, ctx.body(def_id).unwrap()));
+        assert!(ctx.body(DefId(0)).is_none());
+    });
+}
+
+#[test]
+#[should_panic(expected = "interned twice")]
+fn interning_a_body_twice_panics() {
+    with_ctx(|ctx| {
+        ctx.intern_body(i64_bool_body(&ctx));
+        ctx.intern_body(i64_bool_body(&ctx));
+    });
+}
+
+// ---- DefId allocation tests ----
+
+#[test]
+fn def_id_allocator_is_monotonic() {
+    let def_ids = DefIdAllocator::new();
+    assert_eq!(def_ids.fresh(), DefId(0));
+    assert_eq!(def_ids.fresh(), DefId(1));
+    assert_eq!(def_ids.fresh(), DefId(2));
+}
+
+#[test]
+fn fresh_def_ids_are_shared_by_ctx_copies() {
+    with_ctx(|ctx| {
+        let copy = ctx;
+        assert_eq!(ctx.fresh_def_id(), DefId(0));
+        assert_eq!(copy.fresh_def_id(), DefId(1));
+        assert_eq!(ctx.fresh_def_id(), DefId(2));
+    });
+}
+
+// ---- TirUnit::merge tests ----
+
+fn make_unit<'ctx>(ctx: &TirCtx<'ctx>, name: &str, def_ids: &[usize]) -> TirUnit<'ctx> {
+    let bodies = def_ids
+        .iter()
+        .map(|&id| {
+            let mut body = i64_bool_body(ctx);
+            body.metadata.def_id = DefId(id);
+            body.metadata.name = format!("f{id}");
+            body
+        })
+        .collect();
+    TirUnit {
+        metadata: TirUnitMetadata {
+            unit_name: name.to_string(),
+        },
+        globals: IdxVec::new(),
+        bodies: IdxVec::from_raw(bodies),
+    }
+}
+
+#[test]
+fn merge_appends_bodies_with_disjoint_def_ids() {
+    with_ctx(|ctx| {
+        let mut unit = make_unit(&ctx, "a", &[0, 1]);
+        let other = make_unit(&ctx, "b", &[2, 3]);
+
+        assert_eq!(unit.merge(other, &ctx), Ok(()));
+
+        assert_eq!(unit.metadata.unit_name, "a");
+        let def_ids: Vec<_> = unit.bodies.iter().map(|b| b.metadata.def_id).collect();
+        assert_eq!(def_ids, vec![DefId(0), DefId(1), DefId(2), DefId(3)]);
+    });
+}
+
+#[test]
+fn merge_rejects_overlapping_def_ids() {
+    with_ctx(|ctx| {
+        let mut unit = make_unit(&ctx, "a", &[0, 1]);
+        let other = make_unit(&ctx, "b", &[2, 1]);
+
+        let err = unit.merge(other, &ctx).unwrap_err();
+
+        assert_eq!(err, MergeError::DuplicateDefId(DefId(1)));
+        assert_eq!(err.to_string(), "duplicate definition `DefId(1)`");
+        assert_eq!(unit.bodies.len(), 2);
+    });
+}
+
+/// Build a unit with a single global (`GlobalId::new(0)`) and a single body,
+/// with `def_id`, that stores the address of the global into its return
+/// local.
+fn make_unit_with_global<'ctx>(ctx: &TirCtx<'ctx>, def_id: usize) -> TirUnit<'ctx> {
+    let i32_ty = ctx.intern_ty(ty::TirTy::I32);
+    let ptr_ty = ctx.intern_ty(ty::TirTy::RawPtr(
+        i32_ty,
+        ty::Mutability::Imm,
+        AddressSpace::DATA,
+    ));
+    let mut unit = make_unit(ctx, &format!("u{def_id}"), &[def_id]);
+    unit.globals.push(TirGlobal {
+        name: format!("g{def_id}"),
+        ty: i32_ty,
+        initializer: None,
+        mutable: false,
+        linkage: Linkage::External,
+        visibility: Visibility::Default,
+        unnamed_address: UnnamedAddress::None,
+    });
+    let global_ref = ConstValue::Indirect {
+        alloc_id: ctx.intern_static(GlobalId::new(0)),
+        offset: Size::ZERO,
+    };
+    unit.bodies.raw[0].basic_blocks.raw[0].statements = vec![Statement::Assign(Box::new((
+        Place::from(RETURN_LOCAL),
+        RValue::Operand(Operand::Const(ConstOperand::Value(global_ref, ptr_ty))),
+    )))];
+    unit
+}
+
+/// Returns the global referenced by the first statement of `body`.
+fn referenced_global(ctx: &TirCtx<'_>, body: &TirBody<'_>) -> GlobalAlloc<'static> {
+    let Statement::Assign(assign) = &body.basic_blocks.raw[0].statements[0] else {
+        panic!("expected an assignment");
+    };
+    let RValue::Operand(Operand::Const(ConstOperand::Value(
+        ConstValue::Indirect { alloc_id, .. },
+        _,
+    ))) = &assign.1
+    else {
+        panic!("expected an indirect constant");
+    };
+    match ctx.get_global_alloc(*alloc_id) {
+        Some(GlobalAlloc::Static(global_id)) => GlobalAlloc::Static(global_id),
+        other => panic!("expected a static, got {other:?}"),
+    }
+}
+
+#[test]
+fn merge_remaps_static_references_of_other() {
+    with_ctx(|ctx| {
+        let mut unit = make_unit_with_global(&ctx, 0);
+        let other = make_unit_with_global(&ctx, 1);
+
+        assert_eq!(unit.merge(other, &ctx), Ok(()));
+
+        assert_eq!(unit.globals.len(), 2);
+        assert_eq!(unit.globals.raw[1].name, "g1");
+        assert_eq!(
+            referenced_global(&ctx, &unit.bodies.raw[0]),
+            GlobalAlloc::Static(GlobalId::new(0))
+        );
+        assert_eq!(
+            referenced_global(&ctx, &unit.bodies.raw[1]),
+            GlobalAlloc::Static(GlobalId::new(1))
+        );
+    });
+}
+
+#[test]
+fn entry_point_overrides_symbol_name_and_linkage() {
+    let mut metadata = TirBodyMetadata::function(DefId(0), "my_start");
+    metadata.linkage = Linkage::Internal;
+    assert_eq!(metadata.symbol_name(), "my_start");
+    assert!(matches!(metadata.effective_linkage(), Linkage::Internal));
+
+    metadata.entry_point = Some(EntryKind::Start);
+    assert_eq!(metadata.symbol_name(), "_start");
+    assert!(matches!(metadata.effective_linkage(), Linkage::External));
+
+    metadata.entry_point = Some(EntryKind::Main);
+    assert_eq!(metadata.symbol_name(), "main");
+}
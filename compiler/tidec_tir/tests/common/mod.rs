@@ -0,0 +1,44 @@
+#![allow(dead_code)]
+
+//! Helpers shared by the TIR integration tests.
+
+use tidec_abi::target::{BackendKind, TirTarget};
+use tidec_tir::body::{DefId, TirBody, TirBodyMetadata};
+use tidec_tir::ctx::{InternCtx, TirArena, TirArgs, TirCtx};
+use tidec_tir::syntax::{BasicBlockData, LocalData};
+use tidec_tir::TirTy;
+use tidec_utils::index_vec::IdxVec;
+
+/// Helper to create a TirCtx for interning types in tests.
+pub fn with_ctx<F, R>(f: F) -> R
+where
+    F: for<'ctx> FnOnce(TirCtx<'ctx>) -> R,
+{
+    let target = TirTarget::new(BackendKind::Llvm);
+    let args = TirArgs::default();
+    let arena = TirArena::default();
+    let intern_ctx = InternCtx::new(&arena);
+    let tir_ctx = TirCtx::new(&target, &args, &intern_ctx);
+    f(tir_ctx)
+}
+
+/// Build the body of `test_fn`, whose return and argument types are
+/// `ret_and_args` (the return type first), with the given extra locals and
+/// basic blocks. No local is mutable.
+pub fn make_body<'ctx>(
+    ret_and_args: &[TirTy<'ctx>],
+    locals: &[TirTy<'ctx>],
+    basic_blocks: Vec<BasicBlockData<'ctx>>,
+) -> TirBody<'ctx> {
+    let local_data = |ty: &TirTy<'ctx>| LocalData {
+        ty: *ty,
+        mutable: false,
+    };
+    TirBody {
+        metadata: TirBodyMetadata::function(DefId(0), "test_fn"),
+        ret_and_args: IdxVec::from_raw(ret_and_args.iter().map(local_data).collect()),
+        arg_count: ret_and_args.len() - 1,
+        locals: IdxVec::from_raw(locals.iter().map(local_data).collect()),
+        basic_blocks: IdxVec::from_raw(basic_blocks),
+    }
+}
@@ -0,0 +1,90 @@
+use std::num::NonZero;
+
+use tidec_abi::size_and_align::Size;
+use tidec_abi::target::{AddressSpace, BackendKind, TirTarget};
+use tidec_tir::const_eval::eval_const;
+use tidec_tir::ctx::{InternCtx, TirArena, TirArgs, TirCtx};
+use tidec_tir::syntax::{ConstOperand, ConstScalar, ConstValue, RawScalarValue};
+use tidec_tir::ty::{self, Mutability};
+
+fn make_tir_ctx_components() -> (TirTarget, TirArgs) {
+    let target = TirTarget::new(BackendKind::Llvm);
+    let args = TirArgs::default();
+    (target, args)
+}
+
+#[test]
+fn eval_i32_scalar() {
+    let (target, args) = make_tir_ctx_components();
+    let arena = TirArena::default();
+    let intern_ctx = InternCtx::new(&arena);
+    let ctx = TirCtx::new(&target, &args, &intern_ctx);
+
+    let i32_ty = ctx.intern_ty(ty::TirTy::I32);
+    let op = ConstOperand::Value(
+        ConstValue::Scalar(ConstScalar::Value(RawScalarValue {
+            data: (-7i32) as u32 as u128,
+            size: NonZero::new(4).unwrap(),
+        })),
+        i32_ty,
+    );
+
+    let raw = eval_const(&op, &ctx).expect("scalar constants should evaluate");
+    assert_eq!(raw.to_bits(Size::from_bytes(4)) as u32 as i32, -7);
+}
+
+#[test]
+fn eval_null_ptr_is_pointer_sized_zero() {
+    let (target, args) = make_tir_ctx_components();
+    let arena = TirArena::default();
+    let intern_ctx = InternCtx::new(&arena);
+    let ctx = TirCtx::new(&target, &args, &intern_ctx);
+
+    let i8_ty = ctx.intern_ty(ty::TirTy::I8);
+    let ptr_ty = ctx.intern_ty(ty::TirTy::RawPtr(
+        i8_ty,
+        Mutability::Imm,
+        AddressSpace::DATA,
+    ));
+    let op = ConstOperand::Value(ConstValue::NullPtr, ptr_ty);
+
+    let raw = eval_const(&op, &ctx).expect("null pointers should evaluate");
+    let pointer_size = target.data_layout.pointer_size;
+    assert_eq!(raw.to_bits(pointer_size), 0);
+}
+
+#[test]
+fn eval_non_scalar_is_none() {
+    let (target, args) = make_tir_ctx_components();
+    let arena = TirArena::default();
+    let intern_ctx = InternCtx::new(&arena);
+    let ctx = TirCtx::new(&target, &args, &intern_ctx);
+
+    let unit_ty = ctx.intern_ty(ty::TirTy::Unit);
+    let zst = ConstOperand::Value(ConstValue::ZST, unit_ty);
+    assert!(eval_const(&zst, &ctx).is_none());
+
+    let i8_ty = ctx.intern_ty(ty::TirTy::I8);
+    let ptr_ty = ctx.intern_ty(ty::TirTy::RawPtr(
+        i8_ty,
+        Mutability::Imm,
+        AddressSpace::DATA,
+    ));
+    let alloc_id = ctx.intern_c_str("hi");
+    let indirect = ConstOperand::Value(
+        ConstValue::Indirect {
+            alloc_id,
+            offset: Size::ZERO,
+        },
+        ptr_ty,
+    );
+    assert!(eval_const(&indirect, &ctx).is_none());
+
+    let fields = ctx.intern_type_list(&[i8_ty, i8_ty]);
+    let struct_ty = ctx.intern_ty(ty::TirTy::Struct {
+        fields,
+        packed: false,
+    });
+    let zeroed = ConstOperand::Value(ConstValue::ZeroInit, struct_ty);
+    assert!(eval_const(&zeroed, &ctx).is_none());
+}
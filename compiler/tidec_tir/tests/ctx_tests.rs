@@ -1,17 +1,21 @@
+use std::path::PathBuf;
+
 use tidec_abi::size_and_align::Size;
-use tidec_abi::target::{BackendKind, TirTarget};
+use tidec_abi::target::{AddressSpace, BackendKind, TirTarget};
 use tidec_tir::alloc::{Allocation, GlobalAlloc};
 use tidec_tir::body::{DefId, GlobalId};
-use tidec_tir::ctx::{EmitKind, GlobalAllocMap, InternCtx, TirArena, TirArgs, TirCtx};
+use tidec_tir::ctx::{
+    ArenaTyped, EmitKind, GlobalAllocMap, InternCtx, TirArena, TirArgs, TirCtx, UnknownEmitKind,
+};
+use tidec_tir::syntax::{FieldIdx, Local, Place, Projection};
 use tidec_tir::ty;
 use tidec_utils::idx::Idx;
+use tidec_utils::interner::Interned;
 
 /// Helper to build a `TirCtx` for type-interning tests.
 fn make_tir_ctx_components() -> (TirTarget, TirArgs) {
     let target = TirTarget::new(BackendKind::Llvm);
-    let args = TirArgs {
-        emit_kind: EmitKind::Object,
-    };
+    let args = TirArgs::default();
     (target, args)
 }
 
@@ -110,12 +114,59 @@ fn test_raw_ptr_interning() {
     let ctx = TirCtx::new(&target, &args, &intern_ctx);
 
     let i32_ty = ctx.intern_ty(ty::TirTy::I32);
-    let ptr1 = ctx.intern_ty(ty::TirTy::RawPtr(i32_ty, ty::Mutability::Imm));
-    let ptr2 = ctx.intern_ty(ty::TirTy::RawPtr(i32_ty, ty::Mutability::Imm));
+    let ptr1 = ctx.intern_ty(ty::TirTy::RawPtr(
+        i32_ty,
+        ty::Mutability::Imm,
+        AddressSpace::DATA,
+    ));
+    let ptr2 = ctx.intern_ty(ty::TirTy::RawPtr(
+        i32_ty,
+        ty::Mutability::Imm,
+        AddressSpace::DATA,
+    ));
 
     assert_eq!(ptr1, ptr2, "Identical pointer types should be deduplicated");
 }
 
+/// Builds the `{ *const u32, usize }` wide pointer type of a slice of `u32`s.
+fn u32_slice_ty<'ctx>(ctx: &TirCtx<'ctx>) -> tidec_tir::TirTy<'ctx> {
+    let u32_ty = ctx.intern_ty(ty::TirTy::U32);
+    let ptr_ty = ctx.intern_ty(ty::TirTy::RawPtr(
+        u32_ty,
+        ty::Mutability::Imm,
+        AddressSpace::DATA,
+    ));
+    let usize_ty = ctx.intern_ty(ty::TirTy::Usize);
+    ctx.intern_ty(ty::TirTy::Struct {
+        fields: ctx.intern_type_list(&[ptr_ty, usize_ty]),
+        packed: false,
+    })
+}
+
+#[test]
+fn test_const_slice_len_counts_elements() {
+    let (target, args) = make_tir_ctx_components();
+    let arena = TirArena::default();
+    let intern_ctx = InternCtx::new(&arena);
+    let ctx = TirCtx::new(&target, &args, &intern_ctx);
+
+    let layout = ctx.layout_of(u32_slice_ty(&ctx));
+    assert_eq!(ctx.const_slice_len(layout, &[0; 8]), 2);
+    assert_eq!(ctx.const_slice_len(layout, &[]), 0);
+}
+
+#[test]
+#[should_panic(expected = "is not a whole number of")]
+fn test_const_slice_len_rejects_partial_elements() {
+    let (target, args) = make_tir_ctx_components();
+    let arena = TirArena::default();
+    let intern_ctx = InternCtx::new(&arena);
+    let ctx = TirCtx::new(&target, &args, &intern_ctx);
+
+    let layout = ctx.layout_of(u32_slice_ty(&ctx));
+    ctx.const_slice_len(layout, &[0; 6]);
+}
+
 #[test]
 fn test_raw_ptr_mut_differs_from_imm() {
     let (target, args) = make_tir_ctx_components();
@@ -124,8 +175,16 @@ fn test_raw_ptr_mut_differs_from_imm() {
     let ctx = TirCtx::new(&target, &args, &intern_ctx);
 
     let i32_ty = ctx.intern_ty(ty::TirTy::I32);
-    let ptr_imm = ctx.intern_ty(ty::TirTy::RawPtr(i32_ty, ty::Mutability::Imm));
-    let ptr_mut = ctx.intern_ty(ty::TirTy::RawPtr(i32_ty, ty::Mutability::Mut));
+    let ptr_imm = ctx.intern_ty(ty::TirTy::RawPtr(
+        i32_ty,
+        ty::Mutability::Imm,
+        AddressSpace::DATA,
+    ));
+    let ptr_mut = ctx.intern_ty(ty::TirTy::RawPtr(
+        i32_ty,
+        ty::Mutability::Mut,
+        AddressSpace::DATA,
+    ));
 
     assert_ne!(ptr_imm, ptr_mut);
 }
@@ -205,3 +264,396 @@ fn test_intern_static_coexists_with_fn_and_memory() {
         GlobalAlloc::Memory(_)
     ));
 }
+
+// ---- Interned type Debug tests ----
+
+/// A pointer type whose pointee is itself. Interning cannot produce such a
+/// type, but a static can refer to its own address.
+static SELF_PTR: ty::TirTy<TirCtx<'static>> = ty::TirTy::RawPtr(
+    tidec_tir::TirTy(Interned::new(&SELF_PTR)),
+    ty::Mutability::Mut,
+    AddressSpace::DATA,
+);
+
+#[test]
+fn test_debug_of_self_referential_type_terminates() {
+    let self_ptr = tidec_tir::TirTy(Interned::new(&SELF_PTR));
+    let printed = format!("{:?}", self_ptr);
+    let addr = format!("{:p}", &SELF_PTR);
+    assert_eq!(
+        printed,
+        format!("RawPtr(<ty#{}>, Mut, AddressSpace(0))", addr)
+    );
+}
+
+#[test]
+fn test_debug_prints_repeated_types_in_full() {
+    let (target, args) = make_tir_ctx_components();
+    let arena = TirArena::default();
+    let intern_ctx = InternCtx::new(&arena);
+    let ctx = TirCtx::new(&target, &args, &intern_ctx);
+
+    // The same type appearing twice is not a cycle, so both are printed.
+    let i32_ty = ctx.intern_ty(ty::TirTy::I32);
+    let fields = ctx.intern_type_list(&[i32_ty, i32_ty]);
+    let struct_ty = ctx.intern_ty(ty::TirTy::Struct {
+        fields,
+        packed: false,
+    });
+    let printed = format!("{:?}", struct_ty);
+    assert_eq!(printed.matches("I32").count(), 2, "{printed}");
+    assert!(!printed.contains("<ty#"), "{printed}");
+}
+
+// ---- Interned type ordering tests ----
+
+#[test]
+fn test_interned_types_sort_by_content() {
+    let (target, args) = make_tir_ctx_components();
+    let arena = TirArena::default();
+    let intern_ctx = InternCtx::new(&arena);
+    let ctx = TirCtx::new(&target, &args, &intern_ctx);
+
+    // Intern in reverse order, so that allocation order disagrees with the
+    // expected content order.
+    let i64_ty = ctx.intern_ty(ty::TirTy::I64);
+    let i32_ty = ctx.intern_ty(ty::TirTy::I32);
+    let wide = ctx.intern_type_list(&[i64_ty]);
+    let narrow = ctx.intern_type_list(&[i32_ty]);
+    let wide_struct = ctx.intern_ty(ty::TirTy::Struct {
+        fields: wide,
+        packed: false,
+    });
+    let narrow_struct = ctx.intern_ty(ty::TirTy::Struct {
+        fields: narrow,
+        packed: false,
+    });
+
+    let mut types = vec![wide_struct, i64_ty, narrow_struct, i32_ty];
+    types.sort();
+
+    assert_eq!(types, vec![i32_ty, i64_ty, narrow_struct, wide_struct]);
+}
+
+// ---- Sorted dump tests ----
+
+#[test]
+fn test_types_sorted_is_stable_and_deduplicated() {
+    let (target, args) = make_tir_ctx_components();
+    let arena = TirArena::default();
+    let intern_ctx = InternCtx::new(&arena);
+    let ctx = TirCtx::new(&target, &args, &intern_ctx);
+
+    // Intern out of order, with a duplicate.
+    ctx.intern_ty(ty::TirTy::F64);
+    ctx.intern_ty(ty::TirTy::I32);
+    ctx.intern_ty(ty::TirTy::Bool);
+    ctx.intern_ty(ty::TirTy::I32);
+    ctx.intern_ty(ty::TirTy::Unit);
+
+    let dump = intern_ctx.types_sorted();
+    // Primitives are pre-interned, so the duplicates add nothing.
+    assert_eq!(dump.len(), 18);
+    assert!(dump.windows(2).all(|pair| pair[0] < pair[1]));
+    assert_eq!(dump, intern_ctx.types_sorted());
+
+    // Types are ordered by their variant, independently of interning order.
+    let position = |ty| dump.iter().position(|t| *t == ctx.intern_ty(ty)).unwrap();
+    assert!(position(ty::TirTy::Unit) < position(ty::TirTy::Bool));
+    assert!(position(ty::TirTy::Bool) < position(ty::TirTy::I32));
+    assert!(position(ty::TirTy::I32) < position(ty::TirTy::F64));
+}
+
+#[test]
+fn test_dump_interned_types_lists_types_in_sorted_order() {
+    let (target, args) = make_tir_ctx_components();
+    let arena = TirArena::default();
+    let intern_ctx = InternCtx::new(&arena);
+    let ctx = TirCtx::new(&target, &args, &intern_ctx);
+
+    let i8_ty = ctx.intern_ty(ty::TirTy::I8);
+    let ptr_ty = ctx.intern_ty(ty::TirTy::RawPtr(
+        i8_ty,
+        ty::Mutability::Imm,
+        AddressSpace::DATA,
+    ));
+
+    let dump = ctx.dump_interned_types();
+    let lines: Vec<&str> = dump.lines().collect();
+    let expected: Vec<String> = intern_ctx
+        .types_sorted()
+        .iter()
+        .map(|ty| ty.to_string())
+        .collect();
+
+    assert_eq!(lines, expected);
+    assert_eq!(lines.len(), 19);
+    assert_eq!(lines[0], "()");
+    assert!(lines.contains(&"i32"));
+    assert!(lines.contains(&ptr_ty.to_string().as_str()));
+    let position = |name| lines.iter().position(|line| *line == name).unwrap();
+    assert!(position("()") < position("bool"));
+    assert!(position("bool") < position("i8"));
+    assert!(position("i8") < position("u8"));
+    assert_eq!(dump, ctx.dump_interned_types());
+}
+
+#[test]
+fn test_abi_compatible_ignores_signedness() {
+    let (target, args) = make_tir_ctx_components();
+    let arena = TirArena::default();
+    let intern_ctx = InternCtx::new(&arena);
+    let ctx = TirCtx::new(&target, &args, &intern_ctx);
+
+    let i32_ty = ctx.mk_i32();
+    let i64_ty = ctx.intern_ty(ty::TirTy::I64);
+    let f32_ty = ctx.intern_ty(ty::TirTy::F32);
+
+    assert!(ctx.abi_compatible(i32_ty, i32_ty));
+    assert!(ctx.abi_compatible(i32_ty, ctx.mk_u32()));
+    assert!(!ctx.abi_compatible(i32_ty, i64_ty));
+    // Same size and alignment, but floats live in different registers.
+    assert!(!ctx.abi_compatible(i32_ty, f32_ty));
+}
+
+#[test]
+fn test_abi_compatible_aggregates() {
+    let (target, args) = make_tir_ctx_components();
+    let arena = TirArena::default();
+    let intern_ctx = InternCtx::new(&arena);
+    let ctx = TirCtx::new(&target, &args, &intern_ctx);
+
+    let i32_ty = ctx.mk_i32();
+    let fields = ctx.intern_type_list(&[i32_ty]);
+    let struct_ty = ctx.intern_ty(ty::TirTy::Struct {
+        fields,
+        packed: false,
+    });
+    let array_ty = ctx.intern_ty(ty::TirTy::Array(i32_ty, 1));
+
+    // Both are a 4-byte aggregate kept in memory.
+    assert!(ctx.abi_compatible(struct_ty, array_ty));
+    // A scalar is passed differently from an aggregate wrapping it.
+    assert!(!ctx.abi_compatible(struct_ty, i32_ty));
+}
+
+#[test]
+fn test_mk_primitive_types_are_pre_interned() {
+    let (target, args) = make_tir_ctx_components();
+    let arena = TirArena::default();
+    let intern_ctx = InternCtx::new(&arena);
+    let ctx = TirCtx::new(&target, &args, &intern_ctx);
+
+    let a = ctx.mk_i32();
+    let b = ctx.mk_i32();
+    assert!(std::ptr::eq(&**a, &**b));
+    // The fast path agrees with regular interning.
+    assert_eq!(a, ctx.intern_ty(ty::TirTy::I32));
+    assert_eq!(ctx.mk_bool(), ctx.intern_ty(ty::TirTy::Bool));
+    assert_ne!(ctx.mk_u32(), ctx.mk_i32());
+}
+
+#[test]
+fn test_parse_emit_kind() {
+    let cases = [
+        ("asm", EmitKind::Assembly),
+        ("assembly", EmitKind::Assembly),
+        ("obj", EmitKind::Object),
+        ("object", EmitKind::Object),
+        ("exe", EmitKind::Executable),
+        ("executable", EmitKind::Executable),
+        ("llvm-ir", EmitKind::LlvmIr),
+        ("ll", EmitKind::LlvmIr),
+        ("bitcode", EmitKind::LlvmBitcode),
+        ("bc", EmitKind::LlvmBitcode),
+    ];
+    for (name, kind) in cases {
+        assert_eq!(name.parse::<EmitKind>(), Ok(kind), "parsing `{name}`");
+    }
+}
+
+#[test]
+fn test_parse_unknown_emit_kind() {
+    assert_eq!(
+        "wasm".parse::<EmitKind>(),
+        Err(UnknownEmitKind("wasm".to_string()))
+    );
+}
+
+#[test]
+fn test_emit_kind_display_round_trips() {
+    for kind in [
+        EmitKind::Assembly,
+        EmitKind::Object,
+        EmitKind::Executable,
+        EmitKind::LlvmIr,
+        EmitKind::LlvmBitcode,
+    ] {
+        assert_eq!(kind.to_string().parse::<EmitKind>(), Ok(kind));
+    }
+    assert_eq!(EmitKind::LlvmIr.to_string(), "llvm-ir");
+}
+
+#[test]
+fn test_emit_kind_default_output_paths() {
+    let cases = [
+        (EmitKind::Assembly, "main.s"),
+        (EmitKind::Object, "main.o"),
+        (EmitKind::LlvmIr, "main.ll"),
+        (EmitKind::LlvmBitcode, "main.bc"),
+        (
+            EmitKind::Executable,
+            if cfg!(target_os = "windows") {
+                "main.exe"
+            } else {
+                "main"
+            },
+        ),
+    ];
+    for (kind, path) in cases {
+        assert_eq!(kind.output_path("main"), PathBuf::from(path), "{kind}");
+    }
+    // The unit name is kept whole, even if it contains a dot.
+    assert_eq!(
+        EmitKind::Object.output_path("my.unit"),
+        PathBuf::from("my.unit.o")
+    );
+}
+
+#[test]
+fn test_output_path_override() {
+    let (target, args) = make_tir_ctx_components();
+    let overridden = TirArgs {
+        output_path: Some(PathBuf::from("out/prog.obj")),
+        ..args.clone()
+    };
+    let arena = TirArena::default();
+    let intern_ctx = InternCtx::new(&arena);
+
+    let tir_ctx = TirCtx::new(&target, &args, &intern_ctx);
+    assert_eq!(tir_ctx.output_path("main"), PathBuf::from("main.o"));
+
+    let tir_ctx = TirCtx::new(&target, &overridden, &intern_ctx);
+    assert_eq!(tir_ctx.output_path("main"), PathBuf::from("out/prog.obj"));
+}
+
+#[test]
+fn test_intern_projection_shares_equal_chains() {
+    let (target, args) = make_tir_ctx_components();
+    let arena = TirArena::default();
+    let intern_ctx = InternCtx::new(&arena);
+    let tir_ctx = TirCtx::new(&target, &args, &intern_ctx);
+
+    let i32_ty = tir_ctx.mk_i32();
+    let chain = |field| {
+        [
+            Projection::Deref,
+            Projection::Field(FieldIdx::new(field), i32_ty),
+        ]
+    };
+
+    let a = tir_ctx.intern_projection(&chain(1));
+    let b = tir_ctx.intern_projection(&chain(1));
+    let c = tir_ctx.intern_projection(&chain(2));
+
+    assert_eq!(a, chain(1));
+    assert!(std::ptr::eq(a, b), "equal chains should share storage");
+    assert!(!std::ptr::eq(a, c), "different chains should not be shared");
+    assert!(tir_ctx.intern_projection(&[]).is_empty());
+}
+
+#[test]
+fn test_places_share_interned_projections() {
+    let (target, args) = make_tir_ctx_components();
+    let arena = TirArena::default();
+    let intern_ctx = InternCtx::new(&arena);
+    let tir_ctx = TirCtx::new(&target, &args, &intern_ctx);
+
+    let chain = [
+        Projection::Deref,
+        Projection::Field(FieldIdx::new(1), tir_ctx.mk_i32()),
+    ];
+    let place = Place {
+        local: Local::new(3),
+        projection: tir_ctx.intern_projection(&chain),
+    };
+    let copy = place;
+
+    assert_eq!(copy, place);
+    assert_eq!(place.try_local(), None);
+    assert!(std::ptr::eq(
+        place.projection,
+        tir_ctx.intern_projection(&chain)
+    ));
+    assert_eq!(Place::from(Local::new(1)).try_local(), Some(Local::new(1)));
+}
+
+#[test]
+fn test_arena_large_chunk_size_allocates_fewer_chunks() {
+    let default_arena = TirArena::default();
+    let large_arena = TirArena::with_chunk_size(1 << 20);
+
+    for i in 0..100_000u64 {
+        assert_eq!(*default_arena.alloc(i), i);
+        assert_eq!(*large_arena.alloc(i), i);
+    }
+
+    // 800 KB of values fit in a single 1 MiB chunk, while the default arena
+    // has to grow geometrically from 1 KiB.
+    assert_eq!(large_arena.chunk_count(), 1);
+    assert!(default_arena.chunk_count() >= 10);
+}
+
+#[test]
+fn test_arena_value_larger_than_chunk_size() {
+    let arena = TirArena::with_chunk_size(16);
+    let value = [7u64; 64];
+
+    assert_eq!(*arena.alloc(value), value);
+    assert_eq!(arena.alloc_slice(&value[..]), &value[..]);
+    assert_eq!(arena.chunk_count(), 2);
+}
+
+#[test]
+fn test_arena_chunk_count_grows_logarithmically() {
+    let arena = TirArena::default();
+
+    let mut chunks_after = Vec::new();
+    for _ in 0..4 {
+        for i in 0..10_000u32 {
+            arena.alloc(i);
+        }
+        chunks_after.push(arena.chunk_count());
+    }
+
+    // 40_000 four-byte values fill 160 KB: 1 KiB chunks that double each
+    // time need 8 chunks, fixed 1 KiB chunks would need 157.
+    assert!(chunks_after[3] <= 8, "{chunks_after:?}");
+
+    // Doubling the amount of data adds about one chunk instead of doubling
+    // the chunk count.
+    assert!(chunks_after[1] <= chunks_after[0] + 1, "{chunks_after:?}");
+}
+
+#[test]
+fn test_arena_aligns_values() {
+    let arena = TirArena::default();
+
+    arena.alloc(1u8);
+    let value: *const u64 = arena.alloc(2u64);
+
+    assert_eq!(value as usize % std::mem::align_of::<u64>(), 0);
+}
+
+#[test]
+fn test_typed_arena_drops_its_values() {
+    let value = std::rc::Rc::new(());
+    {
+        let arena = ArenaTyped::default();
+        for _ in 0..100 {
+            arena.alloc(value.clone());
+        }
+        assert_eq!(std::rc::Rc::strong_count(&value), 101);
+    }
+    assert_eq!(std::rc::Rc::strong_count(&value), 1);
+}
@@ -1,16 +1,17 @@
-use tidec_abi::layout::{BackendRepr, Primitive};
-use tidec_abi::size_and_align::Size;
-use tidec_abi::target::{BackendKind, TirTarget};
-use tidec_tir::ctx::{EmitKind, InternCtx, TirArena, TirArgs, TirCtx};
-use tidec_tir::layout_ctx::LayoutCtx;
+use tidec_abi::calling_convention::function::{classify_arg, PassMode};
+use tidec_abi::layout::{BackendRepr, Primitive, Variants};
+use tidec_abi::size_and_align::{AbiAndPrefAlign, Size};
+use tidec_abi::target::{AddressSpace, BackendKind, TirTarget};
+use tidec_tir::ctx::{InternCtx, TirArena, TirArgs, TirCtx};
+use tidec_tir::layout_ctx::{LayoutCtx, LayoutError};
+use tidec_tir::syntax::FieldIdx;
 use tidec_tir::ty;
+use tidec_utils::idx::Idx;
 
 /// Creates a `TirCtx` for testing. Uses the default LLVM target configuration.
-fn make_ctx() -> (TirTarget, TirArgs, TirArena<'static>) {
+fn make_ctx<'ctx>() -> (TirTarget, TirArgs, TirArena<'ctx>) {
     let target = TirTarget::new(BackendKind::Llvm);
-    let args = TirArgs {
-        emit_kind: EmitKind::Object,
-    };
+    let args = TirArgs::default();
     let arena = TirArena::default();
     (target, args, arena)
 }
@@ -23,7 +24,7 @@ fn unit_layout_is_zero_sized() {
 
     let unit_ty = tir_ctx.intern_ty(ty::TirTy::Unit);
     let layout_ctx = LayoutCtx::new(tir_ctx);
-    let layout = layout_ctx.compute_layout(unit_ty);
+    let layout = layout_ctx.compute_layout(unit_ty).unwrap();
 
     assert_eq!(layout.size, Size::ZERO, "Unit type should have size 0");
 }
@@ -36,15 +37,50 @@ fn unit_layout_has_memory_repr() {
 
     let unit_ty = tir_ctx.intern_ty(ty::TirTy::Unit);
     let layout_ctx = LayoutCtx::new(tir_ctx);
-    let layout = layout_ctx.compute_layout(unit_ty);
+    let layout = layout_ctx.compute_layout(unit_ty).unwrap();
 
     assert!(
-        matches!(layout.backend_repr, BackendRepr::Memory),
+        matches!(layout.backend_repr, BackendRepr::Memory { .. }),
         "Unit type should have Memory backend repr, got {:?}",
         layout.backend_repr
     );
 }
 
+#[test]
+fn unit_and_empty_struct_layouts_are_zst() {
+    let (target, args, arena) = make_ctx();
+    let intern_ctx = InternCtx::new(&arena);
+    let tir_ctx = TirCtx::new(&target, &args, &intern_ctx);
+
+    let unit_ty = tir_ctx.intern_ty(ty::TirTy::Unit);
+    let empty_struct_ty = tir_ctx.intern_ty(ty::TirTy::Struct {
+        fields: tir_ctx.intern_type_list(&[]),
+        packed: false,
+    });
+    let i32_ty = tir_ctx.intern_ty(ty::TirTy::I32);
+
+    for ty in [unit_ty, empty_struct_ty] {
+        let layout = tir_ctx.layout_of(ty);
+        assert!(layout.is_zst(), "{ty:?} should be a ZST");
+        assert!(!layout.is_unsized(), "{ty:?} should be sized");
+    }
+    assert!(!tir_ctx.layout_of(i32_ty).is_zst());
+}
+
+#[test]
+fn metadata_layout_is_unsized() {
+    let (target, args, arena) = make_ctx();
+    let intern_ctx = InternCtx::new(&arena);
+    let tir_ctx = TirCtx::new(&target, &args, &intern_ctx);
+
+    let metadata_ty = tir_ctx.intern_ty(ty::TirTy::Metadata);
+    let layout = tir_ctx.layout_of(metadata_ty);
+
+    assert!(layout.is_unsized());
+    assert!(!layout.is_zst(), "an unsized layout is never a ZST");
+    assert_eq!(layout.backend_repr, BackendRepr::Memory { sized: false });
+}
+
 #[test]
 fn i32_layout_is_4_bytes() {
     let (target, args, arena) = make_ctx();
@@ -53,7 +89,7 @@ fn i32_layout_is_4_bytes() {
 
     let i32_ty = tir_ctx.intern_ty(ty::TirTy::I32);
     let layout_ctx = LayoutCtx::new(tir_ctx);
-    let layout = layout_ctx.compute_layout(i32_ty);
+    let layout = layout_ctx.compute_layout(i32_ty).unwrap();
 
     assert_eq!(layout.size, Size::from_bytes(4), "I32 should be 4 bytes");
 }
@@ -65,9 +101,13 @@ fn pointer_layout_is_8_bytes_on_64bit() {
     let tir_ctx = TirCtx::new(&target, &args, &intern_ctx);
 
     let i32_ty = tir_ctx.intern_ty(ty::TirTy::I32);
-    let ptr_ty = tir_ctx.intern_ty(ty::TirTy::RawPtr(i32_ty, ty::Mutability::Imm));
+    let ptr_ty = tir_ctx.intern_ty(ty::TirTy::RawPtr(
+        i32_ty,
+        ty::Mutability::Imm,
+        AddressSpace::DATA,
+    ));
     let layout_ctx = LayoutCtx::new(tir_ctx);
-    let layout = layout_ctx.compute_layout(ptr_ty);
+    let layout = layout_ctx.compute_layout(ptr_ty).unwrap();
 
     // Default target has 64-bit pointers
     assert_eq!(
@@ -77,6 +117,65 @@ fn pointer_layout_is_8_bytes_on_64bit() {
     );
 }
 
+#[test]
+fn pointer_layout_uses_address_space_spec() {
+    let (mut target, args, arena) = make_ctx();
+    let addr_space = AddressSpace(1);
+    target.data_layout.address_space_pointers =
+        vec![(addr_space, Size::from_bits(32), AbiAndPrefAlign::new(4, 4))];
+    let intern_ctx = InternCtx::new(&arena);
+    let tir_ctx = TirCtx::new(&target, &args, &intern_ctx);
+
+    let i32_ty = tir_ctx.intern_ty(ty::TirTy::I32);
+    let ptr_ty = tir_ctx.intern_ty(ty::TirTy::RawPtr(i32_ty, ty::Mutability::Imm, addr_space));
+    let layout_ctx = LayoutCtx::new(tir_ctx);
+    let layout = layout_ctx.compute_layout(ptr_ty).unwrap();
+
+    assert_eq!(layout.size, target.data_layout.pointer_size_in(addr_space));
+    assert_eq!(layout.align, target.data_layout.pointer_align(addr_space));
+    assert_eq!(layout.size, Size::from_bytes(4));
+    assert_eq!(
+        layout.backend_repr,
+        BackendRepr::Scalar(Primitive::Pointer(addr_space))
+    );
+}
+
+#[test]
+fn usize_layout_is_8_bytes_on_64bit() {
+    let (target, args, arena) = make_ctx();
+    let intern_ctx = InternCtx::new(&arena);
+    let tir_ctx = TirCtx::new(&target, &args, &intern_ctx);
+
+    let usize_ty = tir_ctx.intern_ty(ty::TirTy::Usize);
+    let layout_ctx = LayoutCtx::new(tir_ctx);
+    let layout = layout_ctx.compute_layout(usize_ty).unwrap();
+
+    // Default target has 64-bit pointers
+    assert_eq!(layout.size, Size::from_bytes(8));
+    assert_eq!(layout.align, target.data_layout.pointer_align);
+    assert_eq!(layout.backend_repr, BackendRepr::Scalar(Primitive::U64));
+    assert!(usize_ty.is_integer() && !usize_ty.is_signed_integer());
+}
+
+#[test]
+fn isize_layout_is_signed_and_pointer_sized() {
+    let (mut target, args, arena) = make_ctx();
+    target.data_layout.pointer_size = Size::from_bits(32);
+    target.data_layout.pointer_align = AbiAndPrefAlign::new(4, 4);
+    let intern_ctx = InternCtx::new(&arena);
+    let tir_ctx = TirCtx::new(&target, &args, &intern_ctx);
+
+    let isize_ty = tir_ctx.intern_ty(ty::TirTy::Isize);
+    let layout_ctx = LayoutCtx::new(tir_ctx);
+    let layout = layout_ctx.compute_layout(isize_ty).unwrap();
+
+    assert_eq!(layout.size, Size::from_bytes(4));
+    assert_eq!(layout.align, AbiAndPrefAlign::new(4, 4));
+    assert_eq!(layout.backend_repr, BackendRepr::Scalar(Primitive::I32));
+    assert!(isize_ty.is_integer() && isize_ty.is_signed_integer());
+    assert_eq!(isize_ty.bit_width(), None);
+}
+
 #[test]
 fn bool_layout_is_1_byte() {
     let (target, args, arena) = make_ctx();
@@ -85,7 +184,7 @@ fn bool_layout_is_1_byte() {
 
     let bool_ty = tir_ctx.intern_ty(ty::TirTy::Bool);
     let layout_ctx = LayoutCtx::new(tir_ctx);
-    let layout = layout_ctx.compute_layout(bool_ty);
+    let layout = layout_ctx.compute_layout(bool_ty).unwrap();
 
     assert_eq!(layout.size, Size::from_bytes(1), "Bool should be 1 byte");
 }
@@ -98,7 +197,7 @@ fn bool_layout_has_scalar_u8_repr() {
 
     let bool_ty = tir_ctx.intern_ty(ty::TirTy::Bool);
     let layout_ctx = LayoutCtx::new(tir_ctx);
-    let layout = layout_ctx.compute_layout(bool_ty);
+    let layout = layout_ctx.compute_layout(bool_ty).unwrap();
 
     assert!(
         matches!(layout.backend_repr, BackendRepr::Scalar(Primitive::U8)),
@@ -121,11 +220,11 @@ fn struct_empty_layout_is_zero_sized() {
         packed: false,
     });
     let layout_ctx = LayoutCtx::new(tir_ctx);
-    let layout = layout_ctx.compute_layout(struct_ty);
+    let layout = layout_ctx.compute_layout(struct_ty).unwrap();
 
     assert_eq!(layout.size, Size::ZERO, "Empty struct should have size 0");
     assert!(
-        matches!(layout.backend_repr, BackendRepr::Memory),
+        matches!(layout.backend_repr, BackendRepr::Memory { .. }),
         "Empty struct should have Memory backend repr, got {:?}",
         layout.backend_repr
     );
@@ -144,7 +243,7 @@ fn struct_single_i32_field_layout() {
         packed: false,
     });
     let layout_ctx = LayoutCtx::new(tir_ctx);
-    let layout = layout_ctx.compute_layout(struct_ty);
+    let layout = layout_ctx.compute_layout(struct_ty).unwrap();
 
     assert_eq!(
         layout.size,
@@ -152,7 +251,7 @@ fn struct_single_i32_field_layout() {
         "Struct {{ i32 }} should be 4 bytes"
     );
     assert!(
-        matches!(layout.backend_repr, BackendRepr::Memory),
+        matches!(layout.backend_repr, BackendRepr::Memory { .. }),
         "Struct should have Memory backend repr"
     );
 }
@@ -170,7 +269,7 @@ fn struct_two_i32_fields_layout() {
         packed: false,
     });
     let layout_ctx = LayoutCtx::new(tir_ctx);
-    let layout = layout_ctx.compute_layout(struct_ty);
+    let layout = layout_ctx.compute_layout(struct_ty).unwrap();
 
     assert_eq!(
         layout.size,
@@ -193,7 +292,7 @@ fn struct_i8_i32_padding_layout() {
         packed: false,
     });
     let layout_ctx = LayoutCtx::new(tir_ctx);
-    let layout = layout_ctx.compute_layout(struct_ty);
+    let layout = layout_ctx.compute_layout(struct_ty).unwrap();
 
     // C layout: i8 (1 byte) + 3 bytes padding + i32 (4 bytes) = 8 bytes
     assert_eq!(
@@ -217,7 +316,7 @@ fn struct_packed_no_padding() {
         packed: true,
     });
     let layout_ctx = LayoutCtx::new(tir_ctx);
-    let layout = layout_ctx.compute_layout(struct_ty);
+    let layout = layout_ctx.compute_layout(struct_ty).unwrap();
 
     // Packed: i8 (1 byte) + i32 (4 bytes) = 5 bytes, no padding
     assert_eq!(
@@ -241,7 +340,7 @@ fn struct_f64_i8_alignment() {
         packed: false,
     });
     let layout_ctx = LayoutCtx::new(tir_ctx);
-    let layout = layout_ctx.compute_layout(struct_ty);
+    let layout = layout_ctx.compute_layout(struct_ty).unwrap();
 
     // C layout: f64 (8 bytes) + i8 (1 byte) + 7 bytes tail padding = 16 bytes
     assert_eq!(
@@ -251,6 +350,64 @@ fn struct_f64_i8_alignment() {
     );
 }
 
+#[test]
+fn struct_two_i64_fields_is_scalar_pair() {
+    let (target, args, arena) = make_ctx();
+    let intern_ctx = InternCtx::new(&arena);
+    let tir_ctx = TirCtx::new(&target, &args, &intern_ctx);
+
+    let i64_ty = tir_ctx.intern_ty(ty::TirTy::I64);
+    let fields = tir_ctx.intern_type_list(&[i64_ty, i64_ty]);
+    let struct_ty = tir_ctx.intern_ty(ty::TirTy::Struct {
+        fields,
+        packed: false,
+    });
+    let layout = tir_ctx.layout_of(struct_ty);
+
+    assert_eq!(
+        layout.backend_repr,
+        BackendRepr::ScalarPair(Primitive::I64, Primitive::I64)
+    );
+    assert_eq!(layout.size, Size::from_bytes(16));
+    assert_eq!(tir_ctx.offset_of(layout, FieldIdx::new(0)), Size::ZERO);
+    assert_eq!(
+        tir_ctx.offset_of(layout, FieldIdx::new(1)),
+        Size::from_bytes(8)
+    );
+    assert_eq!(classify_arg(tir_ctx.target(), &layout), PassMode::Direct);
+}
+
+#[test]
+fn struct_not_scalar_pair_when_packed_or_too_big() {
+    let (target, args, arena) = make_ctx();
+    let intern_ctx = InternCtx::new(&arena);
+    let tir_ctx = TirCtx::new(&target, &args, &intern_ctx);
+
+    let i8_ty = tir_ctx.intern_ty(ty::TirTy::I8);
+    let i32_ty = tir_ctx.intern_ty(ty::TirTy::I32);
+    let i128_ty = tir_ctx.intern_ty(ty::TirTy::I128);
+    let packed_ty = tir_ctx.intern_ty(ty::TirTy::Struct {
+        fields: tir_ctx.intern_type_list(&[i8_ty, i32_ty]),
+        packed: true,
+    });
+    let wide_ty = tir_ctx.intern_ty(ty::TirTy::Struct {
+        fields: tir_ctx.intern_type_list(&[i128_ty, i128_ty]),
+        packed: false,
+    });
+    let triple_ty = tir_ctx.intern_ty(ty::TirTy::Struct {
+        fields: tir_ctx.intern_type_list(&[i8_ty, i8_ty, i8_ty]),
+        packed: false,
+    });
+
+    for ty in [packed_ty, wide_ty, triple_ty] {
+        assert_eq!(
+            tir_ctx.layout_of(ty).backend_repr,
+            BackendRepr::Memory { sized: true },
+            "{ty:?} should not be a scalar pair"
+        );
+    }
+}
+
 // ---- Array layout tests ----
 
 #[test]
@@ -262,7 +419,7 @@ fn array_i32_3_layout() {
     let i32_ty = tir_ctx.intern_ty(ty::TirTy::I32);
     let array_ty = tir_ctx.intern_ty(ty::TirTy::Array(i32_ty, 3));
     let layout_ctx = LayoutCtx::new(tir_ctx);
-    let layout = layout_ctx.compute_layout(array_ty);
+    let layout = layout_ctx.compute_layout(array_ty).unwrap();
 
     assert_eq!(
         layout.size,
@@ -270,7 +427,7 @@ fn array_i32_3_layout() {
         "[i32; 3] should be 12 bytes"
     );
     assert!(
-        matches!(layout.backend_repr, BackendRepr::Memory),
+        matches!(layout.backend_repr, BackendRepr::Memory { .. }),
         "Array should have Memory backend repr"
     );
 }
@@ -284,7 +441,7 @@ fn array_f64_2_layout() {
     let f64_ty = tir_ctx.intern_ty(ty::TirTy::F64);
     let array_ty = tir_ctx.intern_ty(ty::TirTy::Array(f64_ty, 2));
     let layout_ctx = LayoutCtx::new(tir_ctx);
-    let layout = layout_ctx.compute_layout(array_ty);
+    let layout = layout_ctx.compute_layout(array_ty).unwrap();
 
     assert_eq!(
         layout.size,
@@ -302,11 +459,11 @@ fn array_zero_length_is_zero_sized() {
     let i32_ty = tir_ctx.intern_ty(ty::TirTy::I32);
     let array_ty = tir_ctx.intern_ty(ty::TirTy::Array(i32_ty, 0));
     let layout_ctx = LayoutCtx::new(tir_ctx);
-    let layout = layout_ctx.compute_layout(array_ty);
+    let layout = layout_ctx.compute_layout(array_ty).unwrap();
 
     assert_eq!(layout.size, Size::ZERO, "[i32; 0] should have size 0");
     assert!(
-        matches!(layout.backend_repr, BackendRepr::Memory),
+        matches!(layout.backend_repr, BackendRepr::Memory { .. }),
         "Zero-length array should have Memory backend repr"
     );
 }
@@ -320,7 +477,7 @@ fn array_i8_5_layout() {
     let i8_ty = tir_ctx.intern_ty(ty::TirTy::I8);
     let array_ty = tir_ctx.intern_ty(ty::TirTy::Array(i8_ty, 5));
     let layout_ctx = LayoutCtx::new(tir_ctx);
-    let layout = layout_ctx.compute_layout(array_ty);
+    let layout = layout_ctx.compute_layout(array_ty).unwrap();
 
     assert_eq!(
         layout.size,
@@ -328,3 +485,440 @@ fn array_i8_5_layout() {
         "[i8; 5] should be 5 bytes"
     );
 }
+
+#[test]
+fn field_layout_of_struct_and_array() {
+    let (target, args, arena) = make_ctx();
+    let intern_ctx = InternCtx::new(&arena);
+    let tir_ctx = TirCtx::new(&target, &args, &intern_ctx);
+
+    let i8_ty = tir_ctx.intern_ty(ty::TirTy::I8);
+    let i64_ty = tir_ctx.intern_ty(ty::TirTy::I64);
+    let fields = tir_ctx.intern_type_list(&[i8_ty, i64_ty]);
+    let struct_ty = tir_ctx.intern_ty(ty::TirTy::Struct {
+        fields,
+        packed: false,
+    });
+    let array_ty = tir_ctx.intern_ty(ty::TirTy::Array(i64_ty, 3));
+
+    let field = tir_ctx.field_layout_of(tir_ctx.layout_of(struct_ty), FieldIdx::new(1));
+    assert_eq!(field.ty, i64_ty);
+    assert_eq!(field.layout.size, Size::from_bytes(8));
+
+    let element = tir_ctx.field_layout_of(tir_ctx.layout_of(array_ty), FieldIdx::new(2));
+    assert_eq!(element.ty, i64_ty);
+}
+
+#[test]
+fn offset_of_struct_fields_and_array_elements() {
+    let (target, args, arena) = make_ctx();
+    let intern_ctx = InternCtx::new(&arena);
+    let tir_ctx = TirCtx::new(&target, &args, &intern_ctx);
+
+    let i8_ty = tir_ctx.intern_ty(ty::TirTy::I8);
+    let i32_ty = tir_ctx.intern_ty(ty::TirTy::I32);
+    let fields = tir_ctx.intern_type_list(&[i8_ty, i32_ty, i8_ty]);
+    let struct_layout =
+        |packed| tir_ctx.layout_of(tir_ctx.intern_ty(ty::TirTy::Struct { fields, packed }));
+    let offsets = |layout| {
+        (0..3)
+            .map(|i| tir_ctx.offset_of(layout, FieldIdx::new(i)).bytes())
+            .collect::<Vec<_>>()
+    };
+
+    // { i8, i32, i8 }: the i32 is aligned to 4.
+    assert_eq!(offsets(struct_layout(false)), vec![0, 4, 8]);
+    // Packed: no padding between fields.
+    assert_eq!(offsets(struct_layout(true)), vec![0, 1, 5]);
+
+    let array_ty = tir_ctx.intern_ty(ty::TirTy::Array(i32_ty, 4));
+    let array_layout = tir_ctx.layout_of(array_ty);
+    assert_eq!(
+        tir_ctx.offset_of(array_layout, FieldIdx::new(3)),
+        Size::from_bytes(12)
+    );
+}
+
+#[test]
+fn array_layout_overflow_is_an_error() {
+    let (target, args, arena) = make_ctx();
+    let intern_ctx = InternCtx::new(&arena);
+    let tir_ctx = TirCtx::new(&target, &args, &intern_ctx);
+
+    // The element itself is well within bounds; only the outer count
+    // pushes the total past the address space.
+    let u8_ty = tir_ctx.intern_ty(ty::TirTy::U8);
+    let big_ty = tir_ctx.intern_ty(ty::TirTy::Array(u8_ty, 1 << 40));
+    let nested_ty = tir_ctx.intern_ty(ty::TirTy::Array(big_ty, 1 << 30));
+    let layout_ctx = LayoutCtx::new(tir_ctx);
+
+    assert_eq!(
+        layout_ctx
+            .compute_layout(big_ty)
+            .map(|layout| layout.size.bytes()),
+        Ok(1 << 40)
+    );
+    assert_eq!(
+        layout_ctx.compute_layout(nested_ty),
+        Err(LayoutError::SizeOverflow(nested_ty)),
+        "[[u8; 1 << 40]; 1 << 30] should overflow"
+    );
+    assert!(tir_ctx.try_layout_of(nested_ty).is_err());
+}
+
+#[test]
+fn struct_layout_overflow_is_an_error() {
+    let (target, args, arena) = make_ctx();
+    let intern_ctx = InternCtx::new(&arena);
+    let tir_ctx = TirCtx::new(&target, &args, &intern_ctx);
+
+    let u8_ty = tir_ctx.intern_ty(ty::TirTy::U8);
+    let big_ty = tir_ctx.intern_ty(ty::TirTy::Array(u8_ty, u64::MAX / 16));
+    let fields = tir_ctx.intern_type_list(&[big_ty, big_ty, big_ty]);
+    let struct_ty = tir_ctx.intern_ty(ty::TirTy::Struct {
+        fields,
+        packed: false,
+    });
+    let layout_ctx = LayoutCtx::new(tir_ctx);
+
+    assert_eq!(
+        layout_ctx.compute_layout(struct_ty),
+        Err(LayoutError::SizeOverflow(struct_ty))
+    );
+}
+
+#[test]
+fn struct_with_unsized_tail_is_unsized() {
+    let (target, args, arena) = make_ctx();
+    let intern_ctx = InternCtx::new(&arena);
+    let tir_ctx = TirCtx::new(&target, &args, &intern_ctx);
+
+    let i64_ty = tir_ctx.intern_ty(ty::TirTy::I64);
+    let metadata_ty = tir_ctx.intern_ty(ty::TirTy::Metadata);
+    let fields = tir_ctx.intern_type_list(&[i64_ty, metadata_ty]);
+    let struct_ty = tir_ctx.intern_ty(ty::TirTy::Struct {
+        fields,
+        packed: false,
+    });
+    let layout_ctx = LayoutCtx::new(tir_ctx);
+    let layout = layout_ctx.compute_layout(struct_ty).unwrap();
+
+    assert!(layout.is_unsized());
+    assert_eq!(layout.backend_repr, BackendRepr::Memory { sized: false });
+    assert_eq!(
+        layout.size,
+        Size::from_bytes(8),
+        "the size of the sized prefix"
+    );
+}
+
+#[test]
+fn unsized_struct_field_before_the_last_is_an_error() {
+    let (target, args, arena) = make_ctx();
+    let intern_ctx = InternCtx::new(&arena);
+    let tir_ctx = TirCtx::new(&target, &args, &intern_ctx);
+
+    let i64_ty = tir_ctx.intern_ty(ty::TirTy::I64);
+    let metadata_ty = tir_ctx.intern_ty(ty::TirTy::Metadata);
+    let fields = tir_ctx.intern_type_list(&[metadata_ty, i64_ty]);
+    let struct_ty = tir_ctx.intern_ty(ty::TirTy::Struct {
+        fields,
+        packed: false,
+    });
+    let layout_ctx = LayoutCtx::new(tir_ctx);
+
+    assert_eq!(
+        layout_ctx.compute_layout(struct_ty),
+        Err(LayoutError::UnsizedElement(struct_ty, metadata_ty))
+    );
+}
+
+#[test]
+fn array_of_unsized_elements_is_an_error() {
+    let (target, args, arena) = make_ctx();
+    let intern_ctx = InternCtx::new(&arena);
+    let tir_ctx = TirCtx::new(&target, &args, &intern_ctx);
+
+    let metadata_ty = tir_ctx.intern_ty(ty::TirTy::Metadata);
+    let array_ty = tir_ctx.intern_ty(ty::TirTy::Array(metadata_ty, 4));
+    let layout_ctx = LayoutCtx::new(tir_ctx);
+
+    assert_eq!(
+        layout_ctx.compute_layout(array_ty),
+        Err(LayoutError::UnsizedElement(array_ty, metadata_ty))
+    );
+}
+
+// ---- Enum layout tests ----
+
+#[test]
+fn enum_layout_puts_the_tag_before_the_payloads() {
+    let (target, args, arena) = make_ctx();
+    let intern_ctx = InternCtx::new(&arena);
+    let tir_ctx = TirCtx::new(&target, &args, &intern_ctx);
+
+    // enum { A, B(f64), C(i8) }
+    let unit_ty = tir_ctx.intern_ty(ty::TirTy::Unit);
+    let f64_ty = tir_ctx.intern_ty(ty::TirTy::F64);
+    let i8_ty = tir_ctx.intern_ty(ty::TirTy::I8);
+    let variants = tir_ctx.intern_type_list(&[unit_ty, f64_ty, i8_ty]);
+    let enum_ty = tir_ctx.intern_ty(ty::TirTy::Enum { variants });
+    let layout = tir_ctx.layout_of(enum_ty);
+
+    assert_eq!(
+        layout.variants,
+        Variants::Tagged {
+            tag: Primitive::U8,
+            payload_offset: Size::from_bytes(8),
+        },
+        "the payload is aligned for the f64 variant"
+    );
+    assert_eq!(layout.size, Size::from_bytes(16));
+    assert_eq!(layout.align, AbiAndPrefAlign::new(8, 8));
+    assert_eq!(layout.backend_repr, BackendRepr::Memory { sized: true });
+}
+
+#[test]
+fn enum_of_zero_sized_payloads_is_its_tag() {
+    let (target, args, arena) = make_ctx();
+    let intern_ctx = InternCtx::new(&arena);
+    let tir_ctx = TirCtx::new(&target, &args, &intern_ctx);
+
+    let unit_ty = tir_ctx.intern_ty(ty::TirTy::Unit);
+    let variants = tir_ctx.intern_type_list(&[unit_ty, unit_ty]);
+    let enum_ty = tir_ctx.intern_ty(ty::TirTy::Enum { variants });
+    let layout = tir_ctx.layout_of(enum_ty);
+
+    assert_eq!(layout.size, Size::from_bytes(1));
+    assert_eq!(layout.align, AbiAndPrefAlign::new(1, 1));
+    assert!(!layout.is_zst());
+}
+
+#[test]
+fn enum_with_more_than_256_variants_has_a_u16_tag() {
+    let (target, args, arena) = make_ctx();
+    let intern_ctx = InternCtx::new(&arena);
+    let tir_ctx = TirCtx::new(&target, &args, &intern_ctx);
+
+    let unit_ty = tir_ctx.intern_ty(ty::TirTy::Unit);
+    let variants = tir_ctx.intern_type_list(&[unit_ty; 257]);
+    let enum_ty = tir_ctx.intern_ty(ty::TirTy::Enum { variants });
+
+    assert!(matches!(
+        tir_ctx.layout_of(enum_ty).variants,
+        Variants::Tagged {
+            tag: Primitive::U16,
+            ..
+        }
+    ));
+    assert_eq!(
+        tir_ctx.discriminant_ty(enum_ty),
+        tir_ctx.intern_ty(ty::TirTy::U16)
+    );
+}
+
+#[test]
+fn enum_without_variants_is_a_zst() {
+    let (target, args, arena) = make_ctx();
+    let intern_ctx = InternCtx::new(&arena);
+    let tir_ctx = TirCtx::new(&target, &args, &intern_ctx);
+
+    let variants = tir_ctx.intern_type_list(&[]);
+    let enum_ty = tir_ctx.intern_ty(ty::TirTy::Enum { variants });
+    let layout = tir_ctx.layout_of(enum_ty);
+
+    assert!(layout.is_zst());
+    assert_eq!(layout.variants, Variants::Single);
+}
+
+#[test]
+fn enum_with_unsized_payload_is_an_error() {
+    let (target, args, arena) = make_ctx();
+    let intern_ctx = InternCtx::new(&arena);
+    let tir_ctx = TirCtx::new(&target, &args, &intern_ctx);
+
+    let i32_ty = tir_ctx.intern_ty(ty::TirTy::I32);
+    let metadata_ty = tir_ctx.intern_ty(ty::TirTy::Metadata);
+    let variants = tir_ctx.intern_type_list(&[i32_ty, metadata_ty]);
+    let enum_ty = tir_ctx.intern_ty(ty::TirTy::Enum { variants });
+    let layout_ctx = LayoutCtx::new(tir_ctx);
+
+    assert_eq!(
+        layout_ctx.compute_layout(enum_ty),
+        Err(LayoutError::UnsizedElement(enum_ty, metadata_ty))
+    );
+}
+
+// ---- SIMD layout tests ----
+
+#[test]
+fn simd_4_x_i32_layout() {
+    let (target, args, arena) = make_ctx();
+    let intern_ctx = InternCtx::new(&arena);
+    let tir_ctx = TirCtx::new(&target, &args, &intern_ctx);
+
+    let simd_ty = tir_ctx.intern_ty(ty::TirTy::Simd {
+        elem: Primitive::I32,
+        lanes: 4,
+    });
+    let layout_ctx = LayoutCtx::new(tir_ctx);
+    let layout = layout_ctx.compute_layout(simd_ty).unwrap();
+
+    assert_eq!(layout.size, Size::from_bytes(16));
+    assert_eq!(layout.align, AbiAndPrefAlign::new(16, 16));
+    assert_eq!(
+        layout.backend_repr,
+        BackendRepr::Vector {
+            element: Primitive::I32,
+            count: 4
+        }
+    );
+    assert!(!layout.is_zst());
+
+    let ty_and_layout = tir_ctx.layout_of(simd_ty);
+    assert_eq!(classify_arg(&target, &ty_and_layout), PassMode::Direct);
+}
+
+#[test]
+fn simd_align_is_the_whole_vector_size() {
+    let (target, args, arena) = make_ctx();
+    let intern_ctx = InternCtx::new(&arena);
+    let tir_ctx = TirCtx::new(&target, &args, &intern_ctx);
+
+    let simd_ty = tir_ctx.intern_ty(ty::TirTy::Simd {
+        elem: Primitive::F64,
+        lanes: 8,
+    });
+    let layout_ctx = LayoutCtx::new(tir_ctx);
+    let layout = layout_ctx.compute_layout(simd_ty).unwrap();
+
+    assert_eq!(layout.size, Size::from_bytes(64));
+    assert_eq!(layout.align.abi.bytes(), 64);
+}
+
+#[test]
+fn simd_lanes_must_be_a_power_of_two() {
+    let (target, args, arena) = make_ctx();
+    let intern_ctx = InternCtx::new(&arena);
+    let tir_ctx = TirCtx::new(&target, &args, &intern_ctx);
+    let layout_ctx = LayoutCtx::new(tir_ctx);
+
+    for lanes in [0, 3, 6] {
+        let simd_ty = tir_ctx.intern_ty(ty::TirTy::Simd {
+            elem: Primitive::I32,
+            lanes,
+        });
+        assert_eq!(
+            layout_ctx.compute_layout(simd_ty),
+            Err(LayoutError::InvalidSimdLanes(simd_ty, lanes)),
+            "{lanes} lanes should be rejected"
+        );
+    }
+}
+
+// ---- Argument classification tests ----
+
+#[test]
+fn classify_small_struct_is_direct() {
+    let (target, args, arena) = make_ctx();
+    let intern_ctx = InternCtx::new(&arena);
+    let tir_ctx = TirCtx::new(&target, &args, &intern_ctx);
+
+    let i32_ty = tir_ctx.intern_ty(ty::TirTy::I32);
+    let fields = tir_ctx.intern_type_list(&[i32_ty, i32_ty]);
+    let struct_ty = tir_ctx.intern_ty(ty::TirTy::Struct {
+        fields,
+        packed: false,
+    });
+    let layout = tir_ctx.layout_of(struct_ty);
+
+    assert_eq!(
+        classify_arg(tir_ctx.target(), &layout),
+        PassMode::Direct,
+        "{{ i32, i32 }} fits in registers and should be passed directly"
+    );
+}
+
+#[test]
+fn classify_small_aggregate_is_cast_to_integers() {
+    let (target, args, arena) = make_ctx();
+    let intern_ctx = InternCtx::new(&arena);
+    let tir_ctx = TirCtx::new(&target, &args, &intern_ctx);
+
+    let i32_ty = tir_ctx.intern_ty(ty::TirTy::I32);
+    let fields = tir_ctx.intern_type_list(&[i32_ty, i32_ty, i32_ty]);
+    let struct_ty = tir_ctx.intern_ty(ty::TirTy::Struct {
+        fields,
+        packed: false,
+    });
+    let layout = tir_ctx.layout_of(struct_ty);
+
+    let PassMode::Cast(cast) = classify_arg(tir_ctx.target(), &layout) else {
+        panic!("{{ i32, i32, i32 }} fits in two registers and should be cast");
+    };
+    assert_eq!(cast.size, Size::from_bytes(12));
+    assert_eq!(
+        cast.chunks(),
+        vec![Size::from_bytes(8), Size::from_bytes(4)],
+        "{{ i32, i32, i32 }} should be passed as {{ i64, i32 }}"
+    );
+}
+
+#[test]
+fn classify_large_array_is_indirect() {
+    let (target, args, arena) = make_ctx();
+    let intern_ctx = InternCtx::new(&arena);
+    let tir_ctx = TirCtx::new(&target, &args, &intern_ctx);
+
+    let i64_ty = tir_ctx.intern_ty(ty::TirTy::I64);
+    let array_ty = tir_ctx.intern_ty(ty::TirTy::Array(i64_ty, 8));
+    let layout = tir_ctx.layout_of(array_ty);
+
+    assert_eq!(
+        classify_arg(tir_ctx.target(), &layout),
+        PassMode::Indirect,
+        "[i64; 8] is 64 bytes and should be passed indirectly"
+    );
+}
+
+#[test]
+fn classify_scalar_and_zst() {
+    let (target, args, arena) = make_ctx();
+    let intern_ctx = InternCtx::new(&arena);
+    let tir_ctx = TirCtx::new(&target, &args, &intern_ctx);
+
+    let i64_layout = tir_ctx.layout_of(tir_ctx.intern_ty(ty::TirTy::I64));
+    let unit_layout = tir_ctx.layout_of(tir_ctx.intern_ty(ty::TirTy::Unit));
+
+    assert_eq!(
+        classify_arg(tir_ctx.target(), &i64_layout),
+        PassMode::Direct
+    );
+    assert_eq!(
+        classify_arg(tir_ctx.target(), &unit_layout),
+        PassMode::Ignore
+    );
+}
+
+#[test]
+fn array_elements_are_spaced_by_their_stride() {
+    let (target, args, arena) = make_ctx();
+    let intern_ctx = InternCtx::new(&arena);
+    let tir_ctx = TirCtx::new(&target, &args, &intern_ctx);
+
+    let i8_ty = tir_ctx.intern_ty(ty::TirTy::I8);
+    let i32_ty = tir_ctx.intern_ty(ty::TirTy::I32);
+    let fields = tir_ctx.intern_type_list(&[i32_ty, i8_ty]);
+    let struct_ty = tir_ctx.intern_ty(ty::TirTy::Struct {
+        fields,
+        packed: false,
+    });
+    let array_ty = tir_ctx.intern_ty(ty::TirTy::Array(struct_ty, 3));
+
+    let elem = tir_ctx.layout_of(struct_ty);
+    let array = tir_ctx.layout_of(array_ty);
+
+    assert_eq!(elem.stride(), Size::from_bytes(8));
+    assert_eq!(array.size, elem.stride().checked_mul(3).unwrap());
+    assert_eq!(array.align, elem.align);
+}
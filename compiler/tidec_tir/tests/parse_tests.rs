@@ -0,0 +1,285 @@
+use std::num::NonZero;
+
+use tidec_tir::body::{DefId, TirBody, TirBodyMetadata};
+use tidec_tir::ctx::TirCtx;
+use tidec_tir::parse::parse_body;
+use tidec_tir::pretty::write_body;
+use tidec_tir::syntax::*;
+use tidec_tir::ty;
+use tidec_tir::TirTy;
+use tidec_utils::idx::Idx;
+use tidec_utils::index_vec::IdxVec;
+
+mod common;
+
+use common::with_ctx;
+
+fn const_scalar<'ctx>(data: u128, size: u8, ty: TirTy<'ctx>) -> Operand<'ctx> {
+    Operand::Const(ConstOperand::Value(
+        ConstValue::Scalar(ConstScalar::Value(RawScalarValue {
+            data,
+            size: NonZero::new(size).unwrap(),
+        })),
+        ty,
+    ))
+}
+
+fn print(body: &TirBody) -> String {
+    let mut out = String::new();
+    write_body(body, &mut out).unwrap();
+    out
+}
+
+/// Builds:
+///
+/// ```text
+/// fn main(_1: i32) -> i32 {
+///     bb0: { _2 = Add(_1, const -7_i32); _3 = Lt(_2, const 0_i32); switchInt(_3) -> [1: bb1, otherwise: bb2]; }
+///     bb1: { goto -> bb3(const 1.5f64); }
+///     bb2: { _0 = Neg(_2); goto -> bb3(const 0.0f64); }
+///     bb3(_4: f64): { return; }
+/// }
+/// ```
+fn make_main<'ctx>(ctx: &TirCtx<'ctx>) -> TirBody<'ctx> {
+    let i32_ty = ctx.intern_ty(ty::TirTy::I32);
+    let bool_ty = ctx.intern_ty(ty::TirTy::Bool);
+    let f64_ty = ctx.intern_ty(ty::TirTy::F64);
+    TirBody {
+        metadata: TirBodyMetadata::function(DefId(0), "main"),
+        ret_and_args: IdxVec::from_raw(vec![
+            LocalData {
+                ty: i32_ty,
+                mutable: true,
+            },
+            LocalData {
+                ty: i32_ty,
+                mutable: false,
+            },
+        ]),
+        arg_count: 1,
+        locals: IdxVec::from_raw(vec![
+            LocalData {
+                ty: i32_ty,
+                mutable: false,
+            },
+            LocalData {
+                ty: bool_ty,
+                mutable: false,
+            },
+            LocalData {
+                ty: f64_ty,
+                mutable: false,
+            },
+        ]),
+        basic_blocks: IdxVec::from_raw(vec![
+            BasicBlockData {
+                params: vec![],
+                statements: vec![
+                    Statement::assign(
+                        Place::from(Local::new(2)),
+                        RValue::BinaryOp(
+                            BinaryOp::Add,
+                            Operand::use_local(Local::new(1)),
+                            const_scalar(-7i32 as u32 as u128, 4, i32_ty),
+                        ),
+                    ),
+                    Statement::assign(
+                        Place::from(Local::new(3)),
+                        RValue::BinaryOp(
+                            BinaryOp::Lt,
+                            Operand::use_local(Local::new(2)),
+                            const_scalar(0, 4, i32_ty),
+                        ),
+                    ),
+                ],
+                terminator: Terminator::SwitchInt {
+                    discr: Operand::use_local(Local::new(3)),
+                    targets: SwitchTargets::if_then(BasicBlock::new(1), BasicBlock::new(2)),
+                    block_args: vec![],
+                },
+            },
+            BasicBlockData {
+                params: vec![],
+                statements: vec![],
+                terminator: Terminator::Goto {
+                    target: BasicBlock::new(3),
+                    block_args: vec![const_scalar(1.5f64.to_bits() as u128, 8, f64_ty)],
+                },
+            },
+            BasicBlockData {
+                params: vec![],
+                statements: vec![Statement::assign(
+                    Place::from(RETURN_LOCAL),
+                    RValue::UnaryOp(UnaryOp::Neg, Operand::use_local(Local::new(2))),
+                )],
+                terminator: Terminator::Goto {
+                    target: BasicBlock::new(3),
+                    block_args: vec![const_scalar(0, 8, f64_ty)],
+                },
+            },
+            BasicBlockData {
+                params: vec![(Local::new(4), f64_ty)],
+                statements: vec![],
+                terminator: Terminator::Return,
+            },
+        ]),
+    }
+}
+
+#[test]
+fn write_body_prints_locals_blocks_and_terminators() {
+    with_ctx(|ctx| {
+        let text = print(&make_main(&ctx));
+
+        assert!(text.starts_with("fn main(_1: i32) -> i32 {\n"));
+        assert!(text.contains("    let mut _0: i32;\n"));
+        assert!(text.contains("    let _1: i32;\n"));
+        assert!(text.contains("        _2 = Add(_1, const -7_i32);\n"));
+        assert!(text.contains("        switchInt(_3) -> [1: bb1, otherwise: bb2];\n"));
+        assert!(text.contains("        goto -> bb3(const 0x3ff8000000000000_f64);\n"));
+        assert!(text.contains("    bb3(_4: f64): {\n        return;\n    }\n"));
+        assert!(text.ends_with("}\n"));
+    });
+}
+
+#[test]
+fn parse_body_round_trips_main() {
+    with_ctx(|ctx| {
+        let body = make_main(&ctx);
+        let text = print(&body);
+
+        let parsed = parse_body(&ctx, &text).unwrap();
+
+        assert_eq!(parsed.metadata.name, "main");
+        assert_eq!(parsed.arg_count(), body.arg_count());
+        assert_eq!(parsed.locals.len(), body.locals.len());
+        assert_eq!(parsed.basic_blocks.len(), body.basic_blocks.len());
+        assert_eq!(parsed.content_hash(), body.content_hash());
+        assert_eq!(print(&parsed), text);
+    });
+}
+
+#[test]
+fn parse_body_reads_unit_and_bool_constants() {
+    with_ctx(|ctx| {
+        let src = "\
+fn f() -> () {
+    let mut _0: ();
+    let mut _1: bool;
+
+    bb0: {
+        _1 = const true;
+        _1 = Not(_1);
+        _0 = const ();
+        return;
+    }
+}
+";
+        let body = parse_body(&ctx, src).unwrap();
+
+        assert_eq!(body.arg_count(), 0);
+        assert!(body.return_local_data().ty.is_unit());
+        assert_eq!(body.basic_blocks[BasicBlock::new(0)].statements.len(), 3);
+        assert_eq!(print(&body), src);
+    });
+}
+
+#[test]
+fn parse_body_rejects_out_of_range_constants() {
+    with_ctx(|ctx| {
+        let src = "fn f() -> u8 {\n    let _0: u8;\n    bb0: {\n        _0 = const 256_u8;\n        return;\n    }\n}\n";
+        let err = parse_body(&ctx, src).err().unwrap();
+
+        assert_eq!(err.line, 4);
+        assert!(err.message.contains("256_u8"), "{err}");
+    });
+}
+
+#[test]
+fn parse_body_sizes_pointer_sized_constants_by_target() {
+    with_ctx(|ctx| {
+        let src = "fn f() -> isize {\n    let _0: isize;\n\n    bb0: {\n        _0 = const -1_isize;\n        return;\n    }\n}\n";
+        let body = parse_body(&ctx, src).unwrap();
+
+        assert_eq!(print(&body), src);
+        // The default target has 64-bit pointers.
+        let Statement::Assign(assign) = &body.basic_blocks[BasicBlock::new(0)].statements[0] else {
+            panic!("expected an assignment");
+        };
+        let RValue::Operand(Operand::Const(ConstOperand::Value(
+            ConstValue::Scalar(ConstScalar::Value(raw)),
+            ty,
+        ))) = &assign.1
+        else {
+            panic!("expected a scalar constant");
+        };
+        assert_eq!({ raw.data }, u64::MAX as u128);
+        assert_eq!({ raw.size }.get(), 8);
+        assert_eq!(*ty, ctx.mk_isize());
+    });
+}
+
+#[test]
+fn parse_body_rejects_mismatched_signature() {
+    with_ctx(|ctx| {
+        let src = "fn f(_1: i32) -> i32 {\n    let _0: i32;\n    let _1: i64;\n    bb0: {\n        return;\n    }\n}\n";
+        let err = parse_body(&ctx, src).err().unwrap();
+
+        assert!(err.message.contains("`_1`"), "{err}");
+    });
+}
+
+#[test]
+fn parse_body_rejects_unknown_terminator() {
+    with_ctx(|ctx| {
+        let src = "fn f() -> () {\n    let _0: ();\n    bb0: {\n        jump bb1;\n    }\n}\n";
+        let err = parse_body(&ctx, src).err().unwrap();
+
+        assert_eq!(err.line, 4);
+        assert_eq!(
+            err.to_string(),
+            "line 4: expected a statement or terminator, found `jump`"
+        );
+    });
+}
+
+#[test]
+fn parse_body_round_trips_assert() {
+    with_ctx(|ctx| {
+        let src = "\
+fn f(_1: u64, _2: bool) -> () {
+    let mut _0: ();
+    let _1: u64;
+    let _2: bool;
+    let _3: bool;
+
+    bb0: {
+        _3 = Lt(_1, const 4_u64);
+        assert(_3, true, bounds_check(len: const 4_u64, index: _1)) -> bb1;
+    }
+
+    bb1: {
+        assert(_2, false, overflow) -> bb2;
+    }
+
+    bb2: {
+        return;
+    }
+}
+";
+        let body = parse_body(&ctx, src).unwrap();
+
+        let bb0 = &body.basic_blocks[BasicBlock::new(0)].terminator;
+        assert!(matches!(
+            bb0,
+            Terminator::Assert {
+                expected: true,
+                msg: AssertMessage::BoundsCheck { .. },
+                ..
+            }
+        ));
+        assert_eq!(bb0.kind(), TerminatorKind::Assert);
+        assert_eq!(bb0.successors().as_slice(), &[BasicBlock::new(1)]);
+        assert_eq!(print(&body), src);
+    });
+}
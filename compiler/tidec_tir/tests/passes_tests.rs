@@ -0,0 +1,445 @@
+use tidec_tir::body::TirBody;
+use tidec_tir::passes::{self, PassManager, TirPass};
+use tidec_tir::syntax::*;
+use tidec_tir::ty;
+use tidec_utils::idx::Idx;
+use tidec_utils::index_vec::IdxVec;
+
+mod common;
+
+use common::{make_body, with_ctx};
+
+fn block<'ctx>(terminator: Terminator<'ctx>) -> BasicBlockData<'ctx> {
+    BasicBlockData {
+        params: vec![],
+        statements: vec![],
+        terminator,
+    }
+}
+
+// ---- remove_unreachable_blocks tests ----
+
+#[test]
+fn remove_unreachable_blocks_drops_dead_block_and_renumbers() {
+    with_ctx(|ctx| {
+        // bb0: goto bb2
+        // bb1: unreachable (dead)
+        // bb2: return
+        let mut body = make_body(
+            &[ctx.mk_i32()],
+            &[],
+            vec![
+                block(Terminator::Goto {
+                    target: BasicBlock::new(2),
+                    block_args: vec![],
+                }),
+                block(Terminator::Unreachable),
+                block(Terminator::Return),
+            ],
+        );
+
+        passes::remove_unreachable_blocks(&mut body);
+
+        assert_eq!(body.basic_blocks.len(), 2);
+        assert!(matches!(
+            body.basic_blocks[BasicBlock::new(0)].terminator,
+            Terminator::Goto { target, .. } if target == BasicBlock::new(1)
+        ));
+        assert!(matches!(
+            body.basic_blocks[BasicBlock::new(1)].terminator,
+            Terminator::Return
+        ));
+    });
+}
+
+#[test]
+fn remove_unreachable_blocks_keeps_fully_reachable_body() {
+    with_ctx(|ctx| {
+        let discr = Operand::use_local(RETURN_LOCAL);
+        let mut body = make_body(
+            &[ctx.mk_i32()],
+            &[],
+            vec![
+                block(Terminator::SwitchInt {
+                    discr,
+                    targets: SwitchTargets::if_then(BasicBlock::new(1), BasicBlock::new(2)),
+                    block_args: vec![],
+                }),
+                block(Terminator::Return),
+                block(Terminator::Return),
+            ],
+        );
+
+        passes::remove_unreachable_blocks(&mut body);
+
+        assert_eq!(body.basic_blocks.len(), 3);
+    });
+}
+
+// ---- merge_blocks tests ----
+
+#[test]
+fn merge_blocks_collapses_goto_chain() {
+    with_ctx(|ctx| {
+        let assign = |local: usize| {
+            Statement::assign(
+                Place::from(RETURN_LOCAL),
+                RValue::Operand(Operand::use_local(Local::new(local))),
+            )
+        };
+        // bb0: _0 = _0; goto bb1
+        // bb1: _0 = _1; goto bb2
+        // bb2: _0 = _2; return
+        let mut body = make_body(
+            &[ctx.mk_i32()],
+            &[],
+            vec![
+                BasicBlockData {
+                    params: vec![],
+                    statements: vec![assign(0)],
+                    terminator: Terminator::Goto {
+                        target: BasicBlock::new(1),
+                        block_args: vec![],
+                    },
+                },
+                BasicBlockData {
+                    params: vec![],
+                    statements: vec![assign(1)],
+                    terminator: Terminator::Goto {
+                        target: BasicBlock::new(2),
+                        block_args: vec![],
+                    },
+                },
+                BasicBlockData {
+                    params: vec![],
+                    statements: vec![assign(2)],
+                    terminator: Terminator::Return,
+                },
+            ],
+        );
+
+        passes::merge_blocks(&mut body);
+
+        assert_eq!(body.basic_blocks.len(), 1);
+        let entry = &body.basic_blocks[ENTRY_BLOCK];
+        assert_eq!(entry.statements.len(), 3);
+        assert!(matches!(entry.terminator, Terminator::Return));
+        for (i, stmt) in entry.statements.iter().enumerate() {
+            let Statement::Assign(assign) = stmt else {
+                panic!("expected an assignment");
+            };
+            assert!(matches!(
+                &assign.1,
+                RValue::Operand(Operand::Use(place)) if place.local == Local::new(i)
+            ));
+        }
+    });
+}
+
+#[test]
+fn merge_blocks_keeps_join_block_with_multiple_predecessors() {
+    with_ctx(|ctx| {
+        // bb0: switch -> [bb1, bb2]
+        // bb1: goto bb3
+        // bb2: goto bb3
+        // bb3: return
+        let discr = Operand::use_local(RETURN_LOCAL);
+        let mut body = make_body(
+            &[ctx.mk_i32()],
+            &[],
+            vec![
+                block(Terminator::SwitchInt {
+                    discr,
+                    targets: SwitchTargets::if_then(BasicBlock::new(1), BasicBlock::new(2)),
+                    block_args: vec![],
+                }),
+                block(Terminator::Goto {
+                    target: BasicBlock::new(3),
+                    block_args: vec![],
+                }),
+                block(Terminator::Goto {
+                    target: BasicBlock::new(3),
+                    block_args: vec![],
+                }),
+                block(Terminator::Return),
+            ],
+        );
+
+        passes::merge_blocks(&mut body);
+
+        assert_eq!(body.basic_blocks.len(), 4);
+        let preds = passes::predecessors(&body);
+        assert_eq!(preds[BasicBlock::new(3)].len(), 2);
+    });
+}
+
+#[test]
+fn merge_blocks_keeps_block_with_params() {
+    with_ctx(|ctx| {
+        // bb0: goto bb1(_0)
+        // bb1(_0: i32): return
+        let i32_ty = ctx.intern_ty(ty::TirTy::I32);
+        let mut body = make_body(
+            &[ctx.mk_i32()],
+            &[],
+            vec![
+                block(Terminator::Goto {
+                    target: BasicBlock::new(1),
+                    block_args: vec![Operand::use_local(RETURN_LOCAL)],
+                }),
+                BasicBlockData {
+                    params: vec![(RETURN_LOCAL, i32_ty)],
+                    statements: vec![],
+                    terminator: Terminator::Return,
+                },
+            ],
+        );
+
+        passes::merge_blocks(&mut body);
+
+        assert_eq!(body.basic_blocks.len(), 2);
+        assert_eq!(body.basic_blocks[BasicBlock::new(1)].params.len(), 1);
+    });
+}
+
+// ---- remove_redundant_copies tests ----
+
+#[test]
+fn remove_redundant_copies_drops_self_assignment_only() {
+    with_ctx(|ctx| {
+        let i32_ty = ctx.intern_ty(ty::TirTy::I32);
+        let mut body = make_body(&[ctx.mk_i32()], &[], vec![block(Terminator::Return)]);
+        body.locals = IdxVec::from_raw(vec![LocalData {
+            ty: i32_ty,
+            mutable: true,
+        }]);
+        let field = |local| Place {
+            local: Local::new(local),
+            projection: ctx.intern_projection(&[Projection::Field(FieldIdx::new(0), i32_ty)]),
+        };
+
+        let statements = &mut body.basic_blocks[ENTRY_BLOCK].statements;
+        // _1 = _1 (redundant)
+        statements.push(Statement::assign(
+            Place::from(Local::new(1)),
+            RValue::Operand(Operand::use_local(Local::new(1))),
+        ));
+        // _0 = _1 (genuine copy)
+        statements.push(Statement::assign(
+            Place::from(RETURN_LOCAL),
+            RValue::Operand(Operand::use_local(Local::new(1))),
+        ));
+        // _1.0 = _1 (same local, different projection)
+        statements.push(Statement::assign(
+            field(1),
+            RValue::Operand(Operand::use_local(Local::new(1))),
+        ));
+        // _1.0 = _1.0 (redundant)
+        statements.push(Statement::assign(
+            field(1),
+            RValue::Operand(Operand::Use(field(1))),
+        ));
+
+        passes::remove_redundant_copies(&mut body);
+
+        let statements = &body.basic_blocks[ENTRY_BLOCK].statements;
+        assert_eq!(statements.len(), 2);
+        let Statement::Assign(first) = &statements[0] else {
+            panic!("expected an assignment");
+        };
+        assert_eq!(first.0, Place::from(RETURN_LOCAL));
+        let Statement::Assign(second) = &statements[1] else {
+            panic!("expected an assignment");
+        };
+        assert_eq!(second.0, field(1));
+        assert!(matches!(
+            &second.1,
+            RValue::Operand(Operand::Use(src)) if *src == Place::from(Local::new(1))
+        ));
+    });
+}
+
+// ---- remap_locals tests ----
+
+#[test]
+fn remap_locals_rewrites_every_place() {
+    with_ctx(|ctx| {
+        let i32_ty = ctx.intern_ty(ty::TirTy::I32);
+        // bb0: _1 = _0; goto bb1(_1)
+        // bb1(_1: i32): _0 = _1; return
+        let mut body = make_body(
+            &[ctx.mk_i32()],
+            &[],
+            vec![
+                BasicBlockData {
+                    params: vec![],
+                    statements: vec![Statement::assign(
+                        Place::from(Local::new(1)),
+                        RValue::Operand(Operand::use_local(RETURN_LOCAL)),
+                    )],
+                    terminator: Terminator::Goto {
+                        target: BasicBlock::new(1),
+                        block_args: vec![Operand::use_local(Local::new(1))],
+                    },
+                },
+                BasicBlockData {
+                    params: vec![(Local::new(1), i32_ty)],
+                    statements: vec![Statement::assign(
+                        Place::from(RETURN_LOCAL),
+                        RValue::Operand(Operand::use_local(Local::new(1))),
+                    )],
+                    terminator: Terminator::Return,
+                },
+            ],
+        );
+        let map: IdxVec<Local, Local> = IdxVec::from_raw(vec![RETURN_LOCAL, Local::new(2)]);
+
+        passes::remap_locals(&ctx, &mut body, &map);
+
+        let (bb0, bb1) = (
+            &body.basic_blocks[BasicBlock::new(0)],
+            &body.basic_blocks[BasicBlock::new(1)],
+        );
+        let Statement::Assign(assign) = &bb0.statements[0] else {
+            panic!("expected an assignment");
+        };
+        assert_eq!(assign.0, Place::from(Local::new(2)));
+        assert!(matches!(
+            &assign.1,
+            RValue::Operand(Operand::Use(src)) if *src == Place::from(RETURN_LOCAL)
+        ));
+        assert!(matches!(
+            bb0.terminator.block_args(),
+            [Operand::Use(arg)] if *arg == Place::from(Local::new(2))
+        ));
+        assert_eq!(bb1.params[0].0, Local::new(2));
+        let Statement::Assign(assign) = &bb1.statements[0] else {
+            panic!("expected an assignment");
+        };
+        assert_eq!(assign.0, Place::from(RETURN_LOCAL));
+        assert!(matches!(
+            &assign.1,
+            RValue::Operand(Operand::Use(src)) if *src == Place::from(Local::new(2))
+        ));
+    });
+}
+
+#[test]
+fn remap_locals_reinterns_index_projections() {
+    with_ctx(|ctx| {
+        let array_ty = ctx.intern_ty(ty::TirTy::Array(ctx.mk_i32(), 4));
+        // bb0: _0 = _1[_2]; return
+        let mut body = make_body(
+            &[ctx.mk_i32(), array_ty, ctx.mk_i64()],
+            &[],
+            vec![BasicBlockData {
+                params: vec![],
+                statements: vec![Statement::assign(
+                    Place::from(RETURN_LOCAL),
+                    RValue::Operand(Operand::Use(Place {
+                        local: Local::new(1),
+                        projection: ctx.intern_projection(&[Projection::Index(Local::new(2))]),
+                    })),
+                )],
+                terminator: Terminator::Return,
+            }],
+        );
+        let map: IdxVec<Local, Local> =
+            IdxVec::from_raw(vec![RETURN_LOCAL, Local::new(2), Local::new(1)]);
+
+        passes::remap_locals(&ctx, &mut body, &map);
+
+        let Statement::Assign(assign) = &body.basic_blocks[ENTRY_BLOCK].statements[0] else {
+            panic!("expected an assignment");
+        };
+        let expected = Place {
+            local: Local::new(2),
+            projection: ctx.intern_projection(&[Projection::Index(Local::new(1))]),
+        };
+        assert!(matches!(
+            &assign.1,
+            RValue::Operand(Operand::Use(src))
+                if *src == expected && std::ptr::eq(src.projection, expected.projection)
+        ));
+    });
+}
+
+#[test]
+#[should_panic(expected = "missing from the remap table")]
+fn remap_locals_panics_on_missing_local() {
+    with_ctx(|ctx| {
+        let mut body = make_body(&[ctx.mk_i32()], &[], vec![block(Terminator::Return)]);
+        body.basic_blocks[ENTRY_BLOCK]
+            .statements
+            .push(Statement::assign(
+                Place::from(Local::new(1)),
+                RValue::Operand(Operand::use_local(RETURN_LOCAL)),
+            ));
+        let map: IdxVec<Local, Local> = IdxVec::from_raw(vec![RETURN_LOCAL]);
+
+        passes::remap_locals(&ctx, &mut body, &map);
+    });
+}
+
+// ---- PassManager tests ----
+
+/// A test pass appending a suffix to the body name, to record that it ran.
+struct AppendToName(&'static str);
+
+impl TirPass for AppendToName {
+    fn name(&self) -> &str {
+        self.0
+    }
+
+    fn run(&self, body: &mut TirBody) {
+        body.metadata.name.push_str(self.0);
+    }
+}
+
+#[test]
+fn pass_manager_runs_passes_in_order() {
+    with_ctx(|ctx| {
+        let mut body = make_body(&[ctx.mk_i32()], &[], vec![block(Terminator::Return)]);
+
+        let mut pass_manager = PassManager::new();
+        pass_manager
+            .add_pass(AppendToName("_first"))
+            .add_pass(AppendToName("_second"));
+        pass_manager.run_all(&mut body);
+
+        assert_eq!(body.metadata.name, "test_fn_first_second");
+    });
+}
+
+#[test]
+fn pass_manager_runs_builtin_passes() {
+    with_ctx(|ctx| {
+        // bb0: goto bb2
+        // bb1: return (unreachable)
+        // bb2: return
+        let mut body = make_body(
+            &[ctx.mk_i32()],
+            &[],
+            vec![
+                block(Terminator::Goto {
+                    target: BasicBlock::new(2),
+                    block_args: vec![],
+                }),
+                block(Terminator::Return),
+                block(Terminator::Return),
+            ],
+        );
+
+        let mut pass_manager = PassManager::new();
+        pass_manager
+            .add_pass(passes::RemoveUnreachableBlocks)
+            .add_pass(passes::MergeBlocks);
+        pass_manager.run_all(&mut body);
+
+        assert_eq!(body.basic_blocks.len(), 1);
+        assert!(matches!(
+            body.basic_blocks[ENTRY_BLOCK].terminator,
+            Terminator::Return
+        ));
+    });
+}
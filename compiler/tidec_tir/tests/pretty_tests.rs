@@ -0,0 +1,134 @@
+use tidec_tir::body::{DefId, TirBody, TirBodyMetadata};
+use tidec_tir::pretty::{write_body, write_cfg_dot};
+use tidec_tir::syntax::*;
+use tidec_tir::ty;
+use tidec_utils::idx::Idx;
+use tidec_utils::index_vec::IdxVec;
+
+mod common;
+
+use common::{make_body, with_ctx};
+
+// ---- write_cfg_dot tests ----
+
+#[test]
+fn cfg_dot_has_one_edge_per_successor() {
+    with_ctx(|ctx| {
+        let bool_ty = ctx.intern_ty(ty::TirTy::Bool);
+        // bb0: switch _0 -> [1: bb1, otherwise: bb2]
+        // bb1: goto bb3
+        // bb2: goto bb3
+        // bb3: return
+        let body = TirBody {
+            metadata: TirBodyMetadata::function(DefId(0), "branchy"),
+            ret_and_args: IdxVec::from_raw(vec![LocalData {
+                ty: bool_ty,
+                mutable: false,
+            }]),
+            arg_count: 0,
+            locals: IdxVec::new(),
+            basic_blocks: IdxVec::from_raw(vec![
+                BasicBlockData {
+                    params: vec![],
+                    statements: vec![],
+                    terminator: Terminator::SwitchInt {
+                        discr: Operand::use_local(RETURN_LOCAL),
+                        targets: SwitchTargets::if_then(BasicBlock::new(1), BasicBlock::new(2)),
+                        block_args: vec![],
+                    },
+                },
+                BasicBlockData {
+                    params: vec![],
+                    statements: vec![],
+                    terminator: Terminator::Goto {
+                        target: BasicBlock::new(3),
+                        block_args: vec![],
+                    },
+                },
+                BasicBlockData {
+                    params: vec![],
+                    statements: vec![],
+                    terminator: Terminator::Goto {
+                        target: BasicBlock::new(3),
+                        block_args: vec![],
+                    },
+                },
+                BasicBlockData {
+                    params: vec![],
+                    statements: vec![],
+                    terminator: Terminator::Return,
+                },
+            ]),
+        };
+
+        let mut dot = String::new();
+        write_cfg_dot(&body, &mut dot).unwrap();
+
+        assert!(dot.starts_with("digraph \"branchy\" {"));
+        assert!(dot.trim_end().ends_with('}'));
+        assert_eq!(dot.matches("->").count(), 4);
+        assert!(dot.contains("bb0 -> bb1;"));
+        assert!(dot.contains("bb0 -> bb2;"));
+        assert!(dot.contains("bb1 -> bb3;"));
+        assert!(dot.contains("bb2 -> bb3;"));
+        for i in 0..4 {
+            assert!(dot.contains(&format!("bb{i} [label=")));
+        }
+    });
+}
+
+// ---- write_body tests ----
+
+#[test]
+fn write_body_prints_discriminant() {
+    with_ctx(|ctx| {
+        let unit_ty = ctx.intern_ty(ty::TirTy::Unit);
+        let u8_ty = ctx.intern_ty(ty::TirTy::U8);
+        let enum_ty = ctx.intern_ty(ty::TirTy::Enum {
+            variants: ctx.intern_type_list(&[unit_ty, u8_ty]),
+        });
+        let body = make_body(
+            &[u8_ty, enum_ty],
+            &[],
+            vec![BasicBlockData {
+                params: vec![],
+                statements: vec![Statement::assign(
+                    Place::from(RETURN_LOCAL),
+                    RValue::Discriminant(Place::from(Local::new(1))),
+                )],
+                terminator: Terminator::Return,
+            }],
+        );
+
+        let mut out = String::new();
+        write_body(&body, &mut out).unwrap();
+        assert!(out.contains("        _0 = discriminant(_1);\n"), "{out}");
+    });
+}
+
+#[test]
+fn write_body_prints_set_discriminant() {
+    with_ctx(|ctx| {
+        let unit_ty = ctx.intern_ty(ty::TirTy::Unit);
+        let u8_ty = ctx.intern_ty(ty::TirTy::U8);
+        let enum_ty = ctx.intern_ty(ty::TirTy::Enum {
+            variants: ctx.intern_type_list(&[unit_ty, u8_ty]),
+        });
+        let body = make_body(
+            &[unit_ty, enum_ty],
+            &[],
+            vec![BasicBlockData {
+                params: vec![],
+                statements: vec![Statement::SetDiscriminant {
+                    place: Place::from(Local::new(1)),
+                    variant: 1,
+                }],
+                terminator: Terminator::Return,
+            }],
+        );
+
+        let mut out = String::new();
+        write_body(&body, &mut out).unwrap();
+        assert!(out.contains("        discriminant(_1) = 1;\n"), "{out}");
+    });
+}
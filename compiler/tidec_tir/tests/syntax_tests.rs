@@ -1,25 +1,10 @@
-use tidec_abi::target::{BackendKind, TirTarget};
-use tidec_tir::ctx::{EmitKind, InternCtx, TirArena, TirArgs, TirCtx};
+use tidec_abi::size_and_align::Size;
+use tidec_abi::target::AddressSpace;
 use tidec_tir::syntax::*;
 use tidec_tir::ty;
 use tidec_utils::idx::Idx;
 use tidec_utils::interner::TypeList;
 
-/// Helper to create a TirCtx for interning types in tests.
-fn with_ctx<F, R>(f: F) -> R
-where
-    F: for<'ctx> FnOnce(TirCtx<'ctx>) -> R,
-{
-    let target = TirTarget::new(BackendKind::Llvm);
-    let args = TirArgs {
-        emit_kind: EmitKind::Object,
-    };
-    let arena = TirArena::default();
-    let intern_ctx = InternCtx::new(&arena);
-    let tir_ctx = TirCtx::new(&target, &args, &intern_ctx);
-    f(tir_ctx)
-}
-
 // ---- Local tests ----
 
 #[test]
@@ -65,7 +50,7 @@ fn place_try_local_with_projection_returns_none() {
         let i32_ty = ctx.intern_ty(ty::TirTy::I32);
         let place: Place<'_> = Place {
             local: Local::new(0),
-            projection: vec![Projection::Field(0, i32_ty)],
+            projection: ctx.intern_projection(&[Projection::Field(FieldIdx::new(0), i32_ty)]),
         };
         assert!(place.try_local().is_none());
     });
@@ -83,10 +68,11 @@ fn projection_deref_variant() {
 fn projection_field_variant() {
     with_ctx(|ctx| {
         let i32_ty = ctx.intern_ty(ty::TirTy::I32);
-        let proj = Projection::Field(2, i32_ty);
+        let proj = Projection::Field(FieldIdx::new(2), i32_ty);
         match proj {
             Projection::Field(idx, ty) => {
-                assert_eq!(idx, 2);
+                assert_eq!(idx, FieldIdx::new(2));
+                assert_eq!(idx.idx(), 2);
                 assert_eq!(ty, i32_ty);
             }
             _ => panic!("Expected Field variant"),
@@ -158,12 +144,15 @@ fn place_with_deref_and_field_chain() {
         let i32_ty = ctx.intern_ty(ty::TirTy::I32);
         let place: Place<'_> = Place {
             local: Local::new(1),
-            projection: vec![Projection::Deref, Projection::Field(0, i32_ty)],
+            projection: ctx.intern_projection(&[
+                Projection::Deref,
+                Projection::Field(FieldIdx::new(0), i32_ty),
+            ]),
         };
         assert_eq!(place.local, Local::new(1));
         assert_eq!(place.projection.len(), 2);
         assert!(matches!(place.projection[0], Projection::Deref));
-        assert!(matches!(place.projection[1], Projection::Field(0, _)));
+        assert!(matches!(place.projection[1], Projection::Field(f, _) if f == FieldIdx::new(0)));
     });
 }
 
@@ -192,9 +181,12 @@ fn terminator_return() {
 #[test]
 fn terminator_goto() {
     let target = BasicBlock::new(3);
-    let term: Terminator<'_> = Terminator::Goto { target };
+    let term: Terminator<'_> = Terminator::Goto {
+        target,
+        block_args: vec![],
+    };
     match term {
-        Terminator::Goto { target: t } => assert_eq!(t, BasicBlock::new(3)),
+        Terminator::Goto { target: t, .. } => assert_eq!(t, BasicBlock::new(3)),
         _ => panic!("Expected Goto variant"),
     }
 }
@@ -220,11 +212,52 @@ fn terminator_switch_int() {
             vec![(0, BasicBlock::new(1)), (1, BasicBlock::new(2))],
             BasicBlock::new(3),
         );
-        let term = Terminator::SwitchInt { discr, targets };
+        let term = Terminator::SwitchInt {
+            discr,
+            targets,
+            block_args: vec![],
+        };
         assert!(matches!(term, Terminator::SwitchInt { .. }));
     });
 }
 
+#[test]
+fn terminator_kind() {
+    let goto: Terminator<'_> = Terminator::Goto {
+        target: BasicBlock::new(1),
+        block_args: vec![],
+    };
+    assert_eq!(goto.kind(), TerminatorKind::Goto);
+    assert_eq!(Terminator::Return.kind(), TerminatorKind::Return);
+    assert_eq!(Terminator::Unreachable.kind(), TerminatorKind::Unreachable);
+}
+
+#[test]
+fn terminator_return_has_no_successors() {
+    assert!(Terminator::Return.successors().is_empty());
+    assert!(Terminator::Unreachable.successors().is_empty());
+}
+
+#[test]
+fn terminator_switch_int_successors() {
+    with_ctx(|ctx| {
+        let bool_ty = ctx.intern_ty(ty::TirTy::Bool);
+        let term = Terminator::SwitchInt {
+            discr: Operand::Const(ConstOperand::Value(ConstValue::ZST, bool_ty)),
+            targets: SwitchTargets::new(
+                vec![(0, BasicBlock::new(1)), (1, BasicBlock::new(2))],
+                BasicBlock::new(3),
+            ),
+            block_args: vec![],
+        };
+        assert_eq!(term.kind(), TerminatorKind::SwitchInt);
+        assert_eq!(
+            term.successors().as_slice(),
+            &[BasicBlock::new(1), BasicBlock::new(2), BasicBlock::new(3)]
+        );
+    });
+}
+
 // ---- SwitchTargets tests ----
 
 #[test]
@@ -440,6 +473,34 @@ fn unchecked_arithmetic_ops_return_lhs_type() {
     });
 }
 
+// ---- Checked arithmetic ops return lhs type ----
+
+#[test]
+fn checked_arithmetic_ops_return_lhs_type() {
+    with_ctx(|ctx| {
+        let u8_ty = ctx.intern_ty(ty::TirTy::U8);
+        for op in &[
+            BinaryOp::AddChecked,
+            BinaryOp::SubChecked,
+            BinaryOp::MulChecked,
+        ] {
+            assert_eq!(op.ty(&ctx, u8_ty, u8_ty), u8_ty);
+        }
+    });
+}
+
+// ---- Saturating arithmetic ops return lhs type ----
+
+#[test]
+fn saturating_arithmetic_ops_return_lhs_type() {
+    with_ctx(|ctx| {
+        let i8_ty = ctx.intern_ty(ty::TirTy::I8);
+        for op in &[BinaryOp::SaturatingAdd, BinaryOp::SaturatingSub] {
+            assert_eq!(op.ty(&ctx, i8_ty, i8_ty), i8_ty);
+        }
+    });
+}
+
 // ---- RValue construction tests ----
 
 #[test]
@@ -551,6 +612,41 @@ fn basic_block_idx_trait() {
     assert_eq!(bb.idx(), 8);
 }
 
+#[test]
+fn basic_block_data_helpers_keep_statement_order() {
+    with_ctx(|ctx| {
+        let i32_ty = ctx.intern_ty(ty::TirTy::I32);
+        let one = Operand::Const(ConstOperand::Value(
+            ConstValue::Scalar(ConstScalar::Value(RawScalarValue {
+                data: 1,
+                size: std::num::NonZero::new(4).unwrap(),
+            })),
+            i32_ty,
+        ));
+
+        let mut data = BasicBlockData::new(Terminator::Return);
+        assert!(data.params.is_empty());
+        assert!(data.statements.is_empty());
+
+        data.push_assign(Place::from(Local::new(1)), RValue::Operand(one));
+        data.push_statement(Statement::assign(
+            Place::from(RETURN_LOCAL),
+            RValue::Operand(Operand::use_local(Local::new(1))),
+        ));
+
+        let destinations: Vec<_> = data
+            .statements
+            .iter()
+            .map(|stmt| match stmt {
+                Statement::Assign(assign) => assign.0.local,
+                other => panic!("expected an assignment, got {other:?}"),
+            })
+            .collect();
+        assert_eq!(destinations, vec![Local::new(1), RETURN_LOCAL]);
+        assert!(matches!(data.terminator, Terminator::Return));
+    });
+}
+
 // ---- CastKind tests ----
 
 #[test]
@@ -675,7 +771,11 @@ fn rvalue_cast_float_to_int_construction() {
 fn rvalue_cast_int_to_ptr_construction() {
     with_ctx(|ctx| {
         let u64_ty = ctx.intern_ty(ty::TirTy::U64);
-        let ptr_ty = ctx.intern_ty(ty::TirTy::RawPtr(u64_ty, ty::Mutability::Imm));
+        let ptr_ty = ctx.intern_ty(ty::TirTy::RawPtr(
+            u64_ty,
+            ty::Mutability::Imm,
+            AddressSpace::DATA,
+        ));
         let operand = Operand::Const(ConstOperand::Value(
             ConstValue::Scalar(ConstScalar::Value(RawScalarValue {
                 data: 0xDEAD_BEEF,
@@ -692,7 +792,11 @@ fn rvalue_cast_int_to_ptr_construction() {
 fn rvalue_cast_ptr_to_int_construction() {
     with_ctx(|ctx| {
         let u64_ty = ctx.intern_ty(ty::TirTy::U64);
-        let ptr_ty = ctx.intern_ty(ty::TirTy::RawPtr(u64_ty, ty::Mutability::Imm));
+        let ptr_ty = ctx.intern_ty(ty::TirTy::RawPtr(
+            u64_ty,
+            ty::Mutability::Imm,
+            AddressSpace::DATA,
+        ));
         let operand = Operand::Const(ConstOperand::Value(
             ConstValue::Scalar(ConstScalar::Value(RawScalarValue {
                 data: 0xCAFE_BABE,
@@ -727,8 +831,16 @@ fn rvalue_cast_ptr_to_ptr_construction() {
     with_ctx(|ctx| {
         let i32_ty = ctx.intern_ty(ty::TirTy::I32);
         let i64_ty = ctx.intern_ty(ty::TirTy::I64);
-        let ptr_i32 = ctx.intern_ty(ty::TirTy::RawPtr(i32_ty, ty::Mutability::Imm));
-        let ptr_i64 = ctx.intern_ty(ty::TirTy::RawPtr(i64_ty, ty::Mutability::Mut));
+        let ptr_i32 = ctx.intern_ty(ty::TirTy::RawPtr(
+            i32_ty,
+            ty::Mutability::Imm,
+            AddressSpace::DATA,
+        ));
+        let ptr_i64 = ctx.intern_ty(ty::TirTy::RawPtr(
+            i64_ty,
+            ty::Mutability::Mut,
+            AddressSpace::DATA,
+        ));
         let operand = Operand::Const(ConstOperand::Value(
             ConstValue::Scalar(ConstScalar::Value(RawScalarValue {
                 data: 0x1000,
@@ -764,7 +876,11 @@ fn tir_ty_is_integer() {
 fn tir_ty_is_pointer() {
     with_ctx(|ctx| {
         let i32_ty = ctx.intern_ty(ty::TirTy::I32);
-        let ptr_ty = ctx.intern_ty(ty::TirTy::RawPtr(i32_ty, ty::Mutability::Imm));
+        let ptr_ty = ctx.intern_ty(ty::TirTy::RawPtr(
+            i32_ty,
+            ty::Mutability::Imm,
+            AddressSpace::DATA,
+        ));
         let f64_ty = ctx.intern_ty(ty::TirTy::F64);
 
         assert!(ptr_ty.is_pointer());
@@ -1027,23 +1143,25 @@ fn place_with_field_projection_on_struct() {
         let i32_ty = ctx.intern_ty(ty::TirTy::I32);
         let place = Place {
             local: Local::new(1),
-            projection: vec![Projection::Field(0, i32_ty)],
+            projection: ctx.intern_projection(&[Projection::Field(FieldIdx::new(0), i32_ty)]),
         };
         assert!(place.try_local().is_none());
         assert_eq!(place.projection.len(), 1);
-        assert!(matches!(place.projection[0], Projection::Field(0, _)));
+        assert!(matches!(place.projection[0], Projection::Field(f, _) if f == FieldIdx::new(0)));
     });
 }
 
 #[test]
 fn place_with_index_projection_on_array() {
-    let place: Place<'_> = Place {
-        local: Local::new(1),
-        projection: vec![Projection::Index(Local::new(2))],
-    };
-    assert!(place.try_local().is_none());
-    assert_eq!(place.projection.len(), 1);
-    assert!(matches!(place.projection[0], Projection::Index(_)));
+    with_ctx(|ctx| {
+        let place: Place<'_> = Place {
+            local: Local::new(1),
+            projection: ctx.intern_projection(&[Projection::Index(Local::new(2))]),
+        };
+        assert!(place.try_local().is_none());
+        assert_eq!(place.projection.len(), 1);
+        assert!(matches!(place.projection[0], Projection::Index(_)));
+    });
 }
 
 #[test]
@@ -1053,13 +1171,13 @@ fn place_with_field_then_index() {
         // Access: _1.field0[_2]
         let place = Place {
             local: Local::new(1),
-            projection: vec![
-                Projection::Field(0, i32_ty),
+            projection: ctx.intern_projection(&[
+                Projection::Field(FieldIdx::new(0), i32_ty),
                 Projection::Index(Local::new(2)),
-            ],
+            ]),
         };
         assert_eq!(place.projection.len(), 2);
-        assert!(matches!(place.projection[0], Projection::Field(0, _)));
+        assert!(matches!(place.projection[0], Projection::Field(f, _) if f == FieldIdx::new(0)));
         assert!(matches!(place.projection[1], Projection::Index(_)));
     });
 }
@@ -1124,7 +1242,7 @@ fn rvalue_address_of_with_projection() {
         let i32_ty = ctx.intern_ty(ty::TirTy::I32);
         let place = Place {
             local: Local::new(1),
-            projection: vec![Projection::Field(0, i32_ty)],
+            projection: ctx.intern_projection(&[Projection::Field(FieldIdx::new(0), i32_ty)]),
         };
         let rvalue: RValue<'_> = RValue::AddressOf(ty::Mutability::Mut, place);
         match rvalue {
@@ -1140,11 +1258,11 @@ fn rvalue_address_of_with_projection() {
 
 #[test]
 fn rvalue_address_of_array_element() {
-    with_ctx(|_ctx| {
+    with_ctx(|ctx| {
         // &arr[idx] → AddressOf(Imm, Place { local: arr, projection: [Index(idx)] })
         let place = Place {
             local: Local::new(1),
-            projection: vec![Projection::Index(Local::new(2))],
+            projection: ctx.intern_projection(&[Projection::Index(Local::new(2))]),
         };
         let rvalue: RValue<'_> = RValue::AddressOf(ty::Mutability::Imm, place);
         match rvalue {
@@ -1174,7 +1292,11 @@ fn const_value_null_ptr_ne_zst() {
 fn const_operand_with_null_ptr() {
     with_ctx(|ctx| {
         let i32_ty = ctx.intern_ty(ty::TirTy::I32);
-        let ptr_ty = ctx.intern_ty(ty::TirTy::RawPtr(i32_ty, ty::Mutability::Imm));
+        let ptr_ty = ctx.intern_ty(ty::TirTy::RawPtr(
+            i32_ty,
+            ty::Mutability::Imm,
+            AddressSpace::DATA,
+        ));
         let operand = ConstOperand::Value(ConstValue::NullPtr, ptr_ty);
         assert_eq!(operand.ty(), ptr_ty);
         assert_eq!(operand.value(), ConstValue::NullPtr);
@@ -1185,7 +1307,11 @@ fn const_operand_with_null_ptr() {
 fn operand_const_null_ptr() {
     with_ctx(|ctx| {
         let i32_ty = ctx.intern_ty(ty::TirTy::I32);
-        let ptr_ty = ctx.intern_ty(ty::TirTy::RawPtr(i32_ty, ty::Mutability::Mut));
+        let ptr_ty = ctx.intern_ty(ty::TirTy::RawPtr(
+            i32_ty,
+            ty::Mutability::Mut,
+            AddressSpace::DATA,
+        ));
         let op = Operand::Const(ConstOperand::Value(ConstValue::NullPtr, ptr_ty));
         assert!(matches!(
             op,
@@ -1198,7 +1324,11 @@ fn operand_const_null_ptr() {
 fn statement_assign_null_ptr_to_place() {
     with_ctx(|ctx| {
         let i32_ty = ctx.intern_ty(ty::TirTy::I32);
-        let ptr_ty = ctx.intern_ty(ty::TirTy::RawPtr(i32_ty, ty::Mutability::Mut));
+        let ptr_ty = ctx.intern_ty(ty::TirTy::RawPtr(
+            i32_ty,
+            ty::Mutability::Mut,
+            AddressSpace::DATA,
+        ));
         let stmt = Statement::Assign(Box::new((
             Place::from(Local::new(0)),
             RValue::Operand(Operand::Const(ConstOperand::Value(
@@ -1221,6 +1351,7 @@ fn statement_assign_address_of() {
             Statement::Assign(assig) => {
                 assert!(matches!(assig.1, RValue::AddressOf(_, _)));
             }
+            other => panic!("expected an assignment, got {other:?}"),
         }
     });
 }
@@ -1233,6 +1364,10 @@ use tidec_tir::body::{
 };
 use tidec_utils::index_vec::IdxVec;
 
+mod common;
+
+use common::with_ctx;
+
 #[test]
 fn global_id_idx_trait() {
     let mut g = GlobalId::new(0);
@@ -1323,7 +1458,11 @@ fn tir_global_constant_immutable() {
 fn tir_global_null_ptr_initializer() {
     with_ctx(|ctx| {
         let i32_ty = ctx.intern_ty(ty::TirTy::I32);
-        let ptr_ty = ctx.intern_ty(ty::TirTy::RawPtr(i32_ty, ty::Mutability::Mut));
+        let ptr_ty = ctx.intern_ty(ty::TirTy::RawPtr(
+            i32_ty,
+            ty::Mutability::Mut,
+            AddressSpace::DATA,
+        ));
         let global = TirGlobal {
             name: "null_ptr_global".to_string(),
             ty: ptr_ty,
@@ -1502,6 +1641,7 @@ fn statement_assign_creates_assign_variant() {
                 assert!(p.projection.is_empty());
                 assert!(matches!(rv, RValue::Operand(_)));
             }
+            other => panic!("expected an assignment, got {other:?}"),
         }
     });
 }
@@ -1512,7 +1652,7 @@ fn statement_assign_preserves_place_projections() {
         let bool_ty = ctx.intern_ty(ty::TirTy::Bool);
         let place = Place {
             local: Local::new(2),
-            projection: vec![Projection::Field(0, bool_ty)],
+            projection: ctx.intern_projection(&[Projection::Field(FieldIdx::new(0), bool_ty)]),
         };
         let rvalue = RValue::Operand(Operand::Const(ConstOperand::Value(
             ConstValue::Scalar(ConstScalar::Value(RawScalarValue {
@@ -1527,8 +1667,11 @@ fn statement_assign_preserves_place_projections() {
                 let (p, _) = inner.as_ref();
                 assert_eq!(p.local, Local::new(2));
                 assert_eq!(p.projection.len(), 1);
-                assert!(matches!(p.projection[0], Projection::Field(0, _)));
+                assert!(
+                    matches!(p.projection[0], Projection::Field(f, _) if f == FieldIdx::new(0))
+                );
             }
+            other => panic!("expected an assignment, got {other:?}"),
         }
     });
 }
@@ -1555,3 +1698,71 @@ fn operand_use_local_return_local() {
         _ => panic!("Expected Use operand"),
     }
 }
+
+// ---- RawScalarValue signed/unsigned construction ----
+
+#[test]
+fn raw_scalar_from_signed_masks_to_size() {
+    let minus_one = RawScalarValue::from_signed(-1, Size::from_bytes(4));
+    assert_eq!(minus_one.to_bits(Size::from_bytes(4)), 0xFFFF_FFFF);
+    assert_eq!(minus_one.to_signed(Size::from_bytes(4)), -1);
+
+    let i8_min = RawScalarValue::from_signed(i8::MIN as i128, Size::from_bytes(1));
+    assert_eq!(i8_min.to_bits(Size::from_bytes(1)), 0x80);
+    assert_eq!(i8_min.to_signed(Size::from_bytes(1)), -128);
+
+    let i128_min = RawScalarValue::from_signed(i128::MIN, Size::from_bytes(16));
+    assert_eq!(i128_min.to_signed(Size::from_bytes(16)), i128::MIN);
+}
+
+#[test]
+fn raw_scalar_from_unsigned_keeps_large_values() {
+    let u64_max = RawScalarValue::from_unsigned(u64::MAX as u128, Size::from_bytes(8));
+    assert_eq!(u64_max.to_bits(Size::from_bytes(8)), u64::MAX as u128);
+    // The same bits read back as a signed `i64` are `-1`.
+    assert_eq!(u64_max.to_signed(Size::from_bytes(8)), -1);
+
+    let u128_max = RawScalarValue::from_unsigned(u128::MAX, Size::from_bytes(16));
+    assert_eq!(u128_max.to_bits(Size::from_bytes(16)), u128::MAX);
+}
+
+#[test]
+#[should_panic(expected = "does not fit")]
+fn raw_scalar_from_signed_rejects_out_of_range() {
+    RawScalarValue::from_signed(128, Size::from_bytes(1));
+}
+
+#[test]
+#[should_panic(expected = "does not fit")]
+fn raw_scalar_from_unsigned_rejects_out_of_range() {
+    RawScalarValue::from_unsigned(256, Size::from_bytes(1));
+}
+
+// ---- RawScalarValue typed reads ----
+
+#[test]
+fn raw_scalar_signed_reads_round_trip() {
+    for (value, bytes) in [(-1, 1), (i16::MIN as i128, 2), (-42, 4), (i128::MIN, 16)] {
+        let size = Size::from_bytes(bytes);
+        let raw = RawScalarValue::from_signed(value, size);
+        assert_eq!(raw.as_i128(size), value);
+        assert_eq!(raw.as_u128(), size.truncate(value as u128));
+    }
+}
+
+#[test]
+#[should_panic(expected = "Mismatched sizes")]
+fn raw_scalar_signed_read_rejects_other_width() {
+    RawScalarValue::from_signed(-1, Size::from_bytes(4)).as_i128(Size::from_bytes(8));
+}
+
+#[test]
+fn raw_scalar_float_reads_round_trip() {
+    let f32_raw = RawScalarValue::from_unsigned(1.5f32.to_bits() as u128, Size::from_bytes(4));
+    assert_eq!(f32_raw.as_f32(), Some(1.5));
+    assert_eq!(f32_raw.as_f64(), None);
+
+    let f64_raw = RawScalarValue::from_unsigned((-0.25f64).to_bits() as u128, Size::from_bytes(8));
+    assert_eq!(f64_raw.as_f64(), Some(-0.25));
+    assert_eq!(f64_raw.as_f32(), None);
+}
@@ -1,5 +1,6 @@
 use std::collections::hash_map::DefaultHasher;
 use std::hash::{Hash, Hasher};
+use tidec_abi::target::AddressSpace;
 use tidec_tir::ty::{Mutability, TirTy};
 use tidec_utils::interner::{Interner, Ty, TypeList};
 
@@ -7,11 +8,11 @@ use tidec_utils::interner::{Interner, Ty, TypeList};
 #[derive(Debug, Clone, Copy)]
 struct DummyInterner;
 
-#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash)]
 struct DummyTy;
 
 /// A dummy type list for testing — wraps a static slice reference.
-#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash)]
 struct DummyTypeList;
 
 impl Interner for DummyInterner {
@@ -193,3 +194,76 @@ fn mutability_equality() {
     assert_eq!(Mutability::Imm, Mutability::Imm);
     assert_ne!(Mutability::Mut, Mutability::Imm);
 }
+
+// ---- Ordering tests ----
+
+#[test]
+fn sorting_orders_by_variant_then_fields() {
+    let mut types: Vec<TirTy<DummyInterner>> = vec![
+        TirTy::Metadata,
+        TirTy::Array(DummyTy, 4),
+        TirTy::F64,
+        TirTy::RawPtr(DummyTy, Mutability::Imm, AddressSpace::DATA),
+        TirTy::U8,
+        TirTy::Array(DummyTy, 2),
+        TirTy::RawPtr(DummyTy, Mutability::Mut, AddressSpace::DATA),
+        TirTy::I32,
+        TirTy::Bool,
+        TirTy::Unit,
+    ];
+    types.sort();
+
+    assert_eq!(
+        types,
+        vec![
+            TirTy::Unit,
+            TirTy::Bool,
+            TirTy::I32,
+            TirTy::U8,
+            TirTy::F64,
+            TirTy::RawPtr(DummyTy, Mutability::Mut, AddressSpace::DATA),
+            TirTy::RawPtr(DummyTy, Mutability::Imm, AddressSpace::DATA),
+            TirTy::Array(DummyTy, 2),
+            TirTy::Array(DummyTy, 4),
+            TirTy::Metadata,
+        ]
+    );
+}
+
+#[test]
+fn ordering_is_consistent_with_equality() {
+    let a: TirTy<DummyInterner> = TirTy::Array(DummyTy, 3);
+    let b: TirTy<DummyInterner> = TirTy::Array(DummyTy, 3);
+    assert_eq!(a.cmp(&b), std::cmp::Ordering::Equal);
+    assert!(TirTy::<DummyInterner>::I64 < TirTy::U8);
+}
+
+#[test]
+fn numeric_predicates() {
+    let f32_ty: TirTy<DummyInterner> = TirTy::F32;
+    let i32_ty: TirTy<DummyInterner> = TirTy::I32;
+    let u32_ty: TirTy<DummyInterner> = TirTy::U32;
+
+    assert!(f32_ty.is_floating_point());
+    assert!(!f32_ty.is_integer());
+    assert!(i32_ty.is_signed_integer());
+    assert!(i32_ty.is_integer());
+    assert!(!i32_ty.is_floating_point());
+    assert!(!u32_ty.is_signed_integer());
+    assert!(u32_ty.is_integer());
+}
+
+#[test]
+fn bit_width_of_numeric_types() {
+    let i64_ty: TirTy<DummyInterner> = TirTy::I64;
+    let u8_ty: TirTy<DummyInterner> = TirTy::U8;
+    let f16_ty: TirTy<DummyInterner> = TirTy::F16;
+    let bool_ty: TirTy<DummyInterner> = TirTy::Bool;
+    let unit_ty: TirTy<DummyInterner> = TirTy::Unit;
+
+    assert_eq!(i64_ty.bit_width(), Some(64));
+    assert_eq!(u8_ty.bit_width(), Some(8));
+    assert_eq!(f16_ty.bit_width(), Some(16));
+    assert_eq!(bool_ty.bit_width(), None);
+    assert_eq!(unit_ty.bit_width(), None);
+}
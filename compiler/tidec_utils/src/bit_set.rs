@@ -0,0 +1,161 @@
+//! A fixed-size set of indices, stored as a dense bit vector.
+//!
+//! It is inspired by the `DenseBitSet` type from the `rustc` compiler.
+
+use crate::idx::Idx;
+use std::{fmt, marker::PhantomData};
+
+type Word = u64;
+const WORD_BITS: usize = Word::BITS as usize;
+
+/// A set of `I`s drawn from the domain `0..domain_size`, one bit per index.
+///
+/// All indices passed to the set must be smaller than its domain size;
+/// the methods panic otherwise.
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct BitSet<I: Idx> {
+    domain_size: usize,
+    words: Vec<Word>,
+    _marker: PhantomData<I>,
+}
+
+impl<I: Idx> BitSet<I> {
+    /// Creates an empty set over the domain `0..domain_size`.
+    pub fn new(domain_size: usize) -> Self {
+        BitSet {
+            domain_size,
+            words: vec![0; domain_size.div_ceil(WORD_BITS)],
+            _marker: PhantomData,
+        }
+    }
+
+    /// Returns the size of the domain of the set.
+    #[inline]
+    pub fn domain_size(&self) -> usize {
+        self.domain_size
+    }
+
+    /// Inserts `elem`, returning `true` if it was not already in the set.
+    #[inline]
+    pub fn insert(&mut self, elem: I) -> bool {
+        let (word, mask) = self.word_and_mask(&elem);
+        let old = self.words[word];
+        self.words[word] |= mask;
+        old != self.words[word]
+    }
+
+    /// Removes `elem`, returning `true` if it was in the set.
+    #[inline]
+    pub fn remove(&mut self, elem: I) -> bool {
+        let (word, mask) = self.word_and_mask(&elem);
+        let old = self.words[word];
+        self.words[word] &= !mask;
+        old != self.words[word]
+    }
+
+    /// Returns `true` if `elem` is in the set.
+    #[inline]
+    pub fn contains(&self, elem: I) -> bool {
+        let (word, mask) = self.word_and_mask(&elem);
+        self.words[word] & mask != 0
+    }
+
+    /// Returns the number of elements in the set.
+    pub fn count(&self) -> usize {
+        self.words
+            .iter()
+            .map(|word| word.count_ones() as usize)
+            .sum()
+    }
+
+    /// Returns `true` if the set has no elements.
+    pub fn is_empty(&self) -> bool {
+        self.words.iter().all(|word| *word == 0)
+    }
+
+    /// Adds every element of `other` to `self`, returning `true` if `self`
+    /// changed.
+    ///
+    /// Panics if the two sets have different domain sizes.
+    pub fn union_with(&mut self, other: &BitSet<I>) -> bool {
+        self.bitwise_with(other, |a, b| a | b)
+    }
+
+    /// Removes from `self` every element not in `other`, returning `true` if
+    /// `self` changed.
+    ///
+    /// Panics if the two sets have different domain sizes.
+    pub fn intersect_with(&mut self, other: &BitSet<I>) -> bool {
+        self.bitwise_with(other, |a, b| a & b)
+    }
+
+    /// Removes from `self` every element of `other`, returning `true` if
+    /// `self` changed.
+    ///
+    /// Panics if the two sets have different domain sizes.
+    pub fn subtract(&mut self, other: &BitSet<I>) -> bool {
+        self.bitwise_with(other, |a, b| a & !b)
+    }
+
+    /// Iterates over the elements of the set in increasing order.
+    pub fn iter(&self) -> impl Iterator<Item = I> + '_ {
+        self.words
+            .iter()
+            .enumerate()
+            .flat_map(|(word_idx, &word)| BitIter {
+                word,
+                base: word_idx * WORD_BITS,
+            })
+            .map(I::new)
+    }
+
+    fn bitwise_with(&mut self, other: &BitSet<I>, op: impl Fn(Word, Word) -> Word) -> bool {
+        assert_eq!(
+            self.domain_size, other.domain_size,
+            "bit sets have different domain sizes"
+        );
+        let mut changed = false;
+        for (word, &other_word) in self.words.iter_mut().zip(&other.words) {
+            let new = op(*word, other_word);
+            changed |= new != *word;
+            *word = new;
+        }
+        changed
+    }
+
+    fn word_and_mask(&self, elem: &I) -> (usize, Word) {
+        let idx = elem.idx();
+        assert!(
+            idx < self.domain_size,
+            "index {idx} out of the bit set domain of size {}",
+            self.domain_size
+        );
+        (idx / WORD_BITS, 1 << (idx % WORD_BITS))
+    }
+}
+
+impl<I: Idx + fmt::Debug> fmt::Debug for BitSet<I> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+/// Yields the positions of the set bits of a single word, offset by `base`.
+struct BitIter {
+    word: Word,
+    base: usize,
+}
+
+impl Iterator for BitIter {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        if self.word == 0 {
+            return None;
+        }
+        let bit = self.word.trailing_zeros() as usize;
+        // Clear the lowest set bit.
+        self.word &= self.word - 1;
+        Some(self.base + bit)
+    }
+}
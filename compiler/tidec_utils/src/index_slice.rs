@@ -72,6 +72,29 @@ impl<I: Idx, T> IdxSlice<I, T> {
         self.raw.iter().enumerate().map(|(n, t)| (I::new(n), t))
     }
 
+    /// Iterates over `self` and `other` in lockstep, yielding the shared
+    /// index together with both elements.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the two slices have different lengths.
+    #[inline]
+    pub fn zip<'a, U>(
+        &'a self,
+        other: &'a IdxSlice<I, U>,
+    ) -> impl DoubleEndedIterator<Item = (I, &'a T, &'a U)> + ExactSizeIterator {
+        assert_eq!(
+            self.len(),
+            other.len(),
+            "cannot zip index slices of different lengths"
+        );
+        self.raw
+            .iter()
+            .zip(other.raw.iter())
+            .enumerate()
+            .map(|(n, (t, u))| (I::new(n), t, u))
+    }
+
     #[inline]
     pub fn indices(
         &self,
@@ -177,6 +200,13 @@ impl<I: Idx, T, R: IntoSliceIdx<I, [T]>> IndexMut<R> for IdxSlice<I, T> {
     }
 }
 
+impl<I: Idx, T> AsRef<[T]> for IdxSlice<I, T> {
+    #[inline]
+    fn as_ref(&self) -> &[T] {
+        &self.raw
+    }
+}
+
 impl<'a, I: Idx, T> IntoIterator for &'a IdxSlice<I, T> {
     type Item = &'a T;
     type IntoIter = slice::Iter<'a, T>;
@@ -6,6 +6,7 @@ use crate::idx::Idx;
 use crate::index_slice::IdxSlice;
 use std::{
     borrow::{Borrow, BorrowMut},
+    fmt,
     marker::PhantomData,
     ops::{Deref, DerefMut, RangeBounds},
     slice, vec,
@@ -30,6 +31,29 @@ pub struct IdxVec<I: Idx, T> {
     pub raw: Vec<T>,
 }
 
+/// The error returned by [`IdxVec::from_iter_enumerated`] when the indices
+/// are not contiguous.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NonContiguousIndex<I> {
+    /// The index that was expected at this position.
+    pub expected: I,
+    /// The index that was found instead.
+    pub found: I,
+}
+
+impl<I: Idx> fmt::Display for NonContiguousIndex<I> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "non-contiguous index: expected {}, found {}",
+            self.expected.idx(),
+            self.found.idx()
+        )
+    }
+}
+
+impl<I: Idx + fmt::Debug> std::error::Error for NonContiguousIndex<I> {}
+
 impl<I: Idx, T> Default for IdxVec<I, T> {
     fn default() -> Self {
         Self::new()
@@ -101,6 +125,37 @@ impl<I: Idx, T> IdxVec<I, T> {
         IdxVec::from_raw((0..n).map(I::new).map(func).collect())
     }
 
+    /// Create an `IdxVec` with one element per index of `universe`, where
+    /// the value of each element is the result of `func(i)`.
+    ///
+    /// Like [`IdxVec::from_elem`], taking the universe rather than a raw
+    /// length ensures the result is indexed by the same index type.
+    #[inline]
+    pub fn from_fn<S>(func: impl FnMut(I) -> T, universe: &IdxSlice<I, S>) -> Self {
+        Self::from_fn_n(func, universe.len())
+    }
+
+    /// Creates an `IdxVec` from `(index, value)` pairs whose indices must be
+    /// exactly `0..n`, in order.
+    ///
+    /// Returns [`NonContiguousIndex`] carrying the first index that is out of
+    /// sequence (a gap, a repetition or a reordering).
+    pub fn from_iter_enumerated(
+        iter: impl Iterator<Item = (I, T)>,
+    ) -> Result<Self, NonContiguousIndex<I>> {
+        let mut vec = IdxVec::with_capacity(iter.size_hint().0);
+        for (index, value) in iter {
+            if index.idx() != vec.len() {
+                return Err(NonContiguousIndex {
+                    expected: vec.next_index(),
+                    found: index,
+                });
+            }
+            vec.push(value);
+        }
+        Ok(vec)
+    }
+
     #[inline]
     pub fn as_slice(&self) -> &IdxSlice<I, T> {
         IdxSlice::from_raw(&self.raw)
@@ -126,6 +181,16 @@ impl<I: Idx, T> IdxVec<I, T> {
             .map(|(n, t)| (I::new(n), t))
     }
 
+    /// Iterates over `(index, &element)` pairs without consuming the vector.
+    ///
+    /// This forwards to [`IdxSlice::iter_enumerated`], which is also reachable
+    /// through `Deref`; it is spelled out here so it shows up alongside
+    /// [`IdxVec::into_iter_enumerated`].
+    #[inline]
+    pub fn iter_enumerated(&self) -> impl DoubleEndedIterator<Item = (I, &T)> + ExactSizeIterator {
+        self.as_slice().iter_enumerated()
+    }
+
     #[inline]
     pub fn drain<R: RangeBounds<usize>>(
         &mut self,
@@ -150,16 +215,73 @@ impl<I: Idx, T> IdxVec<I, T> {
             .map(move |(n, t)| (I::new(begin + n), t))
     }
 
+    /// Removes the elements for which `f` returns `true` and yields them in
+    /// order, keeping the others. See [`Vec::extract_if`].
+    ///
+    /// The remaining elements are shifted down to fill the gaps, so their
+    /// indices change: an `I` taken before the call may refer to a different
+    /// element afterwards. Elements not yet visited when the iterator is
+    /// dropped are kept.
+    #[inline]
+    pub fn extract_if<F>(&mut self, mut f: F) -> impl Iterator<Item = T> + use<'_, F, I, T>
+    where
+        F: FnMut(&T) -> bool,
+    {
+        self.raw.extract_if(.., move |elem| f(elem))
+    }
+
     #[inline]
     pub fn shrink_to_fit(&mut self) {
         self.raw.shrink_to_fit()
     }
 
+    /// Shrinks the capacity of the vector with a lower bound of
+    /// `min_capacity`. See [`Vec::shrink_to`].
+    #[inline]
+    pub fn shrink_to(&mut self, min_capacity: usize) {
+        self.raw.shrink_to(min_capacity)
+    }
+
+    /// Reserves capacity for at least `additional` more elements.
+    /// See [`Vec::reserve`].
+    #[inline]
+    pub fn reserve(&mut self, additional: usize) {
+        self.raw.reserve(additional)
+    }
+
+    /// Reserves capacity for exactly `additional` more elements.
+    /// See [`Vec::reserve_exact`].
+    #[inline]
+    pub fn reserve_exact(&mut self, additional: usize) {
+        self.raw.reserve_exact(additional)
+    }
+
+    /// Returns the number of elements the vector can hold without
+    /// reallocating.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.raw.capacity()
+    }
+
     #[inline]
     pub fn truncate(&mut self, a: usize) {
         self.raw.truncate(a)
     }
 
+    /// Removes all the elements, keeping the allocated capacity.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.raw.clear()
+    }
+
+    /// Asserts, in debug builds only, that the vector has exactly `n`
+    /// elements.
+    #[inline]
+    #[track_caller]
+    pub fn expect_len(&self, n: usize) {
+        debug_assert_eq!(self.len(), n, "index vector has an unexpected length");
+    }
+
     /// Grows the index vector so that it contains an entry for
     /// `elem`; if that is already true, then has no
     /// effect. Otherwise, inserts new values as needed by invoking
@@ -176,6 +298,37 @@ impl<I: Idx, T> IdxVec<I, T> {
         &mut self[elem]
     }
 
+    /// Returns a mutable reference to the `index` entry, growing the vector
+    /// with `T::default()` values as needed.
+    ///
+    /// An entry that already exists is returned as is.
+    #[inline]
+    pub fn entry(&mut self, index: I) -> &mut T
+    where
+        T: Default,
+    {
+        self.ensure_contains_elem(index, T::default)
+    }
+
+    /// Returns a mutable reference to the `index` entry, inserting the value
+    /// returned by `f` if the vector does not contain it yet.
+    ///
+    /// Slots between the previous length and `index` are filled with
+    /// `T::default()`.
+    #[inline]
+    pub fn get_or_insert_with(&mut self, index: I, f: impl FnOnce() -> T) -> &mut T
+    where
+        T: Default,
+    {
+        let idx = index.idx();
+        if self.len() <= idx {
+            self.raw.resize_with(idx, T::default);
+            self.raw.push(f());
+        }
+
+        &mut self[index]
+    }
+
     #[inline]
     pub fn resize(&mut self, new_len: usize, value: T)
     where
@@ -194,6 +347,12 @@ impl<I: Idx, T> IdxVec<I, T> {
     pub fn append(&mut self, other: &mut Self) {
         self.raw.append(&mut other.raw);
     }
+
+    /// Consumes the `IdxVec`, returning the underlying `Vec<T>`.
+    #[inline]
+    pub fn into_vec(self) -> Vec<T> {
+        self.raw
+    }
 }
 
 ////////// Trait implementations  //////////
@@ -214,6 +373,13 @@ impl<I: Idx, T> DerefMut for IdxVec<I, T> {
     }
 }
 
+impl<I: Idx, T> AsRef<[T]> for IdxVec<I, T> {
+    #[inline]
+    fn as_ref(&self) -> &[T] {
+        &self.raw
+    }
+}
+
 impl<I: Idx, T> Borrow<IdxSlice<I, T>> for IdxVec<I, T> {
     fn borrow(&self) -> &IdxSlice<I, T> {
         self
@@ -4,7 +4,10 @@ use std::hash::{Hash, Hasher};
 use std::ops::Deref;
 use std::ptr;
 
-pub trait Ty<I: Interner<Ty = Self>>: Sized + Clone + Copy + Debug + Eq + PartialEq + Hash {}
+pub trait Ty<I: Interner<Ty = Self>>:
+    Sized + Clone + Copy + Debug + Eq + PartialEq + Ord + PartialOrd + Hash
+{
+}
 
 /// A list of types, used to represent struct fields and function parameter lists.
 ///
@@ -12,7 +15,7 @@ pub trait Ty<I: Interner<Ty = Self>>: Sized + Clone + Copy + Debug + Eq + Partia
 /// `TirCtx<'ctx>` interner, the implementation is `&'ctx [TirTy<'ctx>]` —
 /// an arena-allocated slice, which is `Copy`.
 pub trait TypeList<I: Interner<TypeList = Self>>:
-    Sized + Clone + Copy + Debug + Eq + PartialEq + Hash
+    Sized + Clone + Copy + Debug + Eq + PartialEq + Ord + PartialOrd + Hash
 {
     /// Returns the types in this list as a slice.
     fn as_slice(&self) -> &[I::Ty];
@@ -40,6 +43,11 @@ pub trait Interner: Sized + Clone + Copy {
 /// `Interned<T>`s, they both refer to the same value, at a single location in
 /// memory. This means that equality and hashing can be done on the value's
 /// address rather than the value's contents, which can improve performance.
+///
+/// Ordering, on the other hand, cannot be done on addresses, as it would
+/// depend on the allocation order. Two distinct `Interned<T>`s are ordered by
+/// their contents, which never compare equal thanks to the uniqueness
+/// constraint, so the ordering is deterministic and consistent with `Eq`.
 pub struct Interned<'a, T>(&'a T);
 
 impl<T> Interned<'_, T> {
@@ -48,7 +56,7 @@ impl<T> Interned<'_, T> {
     /// This function is *not* unsafe to call, but the caller must ensure that
     /// the value is unique. That is, there must not be any other `Interned`
     /// values that refer to the same value.
-    pub fn new<'a>(value: &'a T) -> Interned<'a, T> {
+    pub const fn new<'a>(value: &'a T) -> Interned<'a, T> {
         Interned(value)
     }
 }
@@ -1,3 +1,4 @@
+pub mod bit_set;
 pub mod idx;
 pub mod index_slice;
 pub mod index_vec;
@@ -0,0 +1,118 @@
+use tidec_utils::bit_set::BitSet;
+use tidec_utils::idx::Idx;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct TestIdx(usize);
+
+impl Idx for TestIdx {
+    fn new(idx: usize) -> Self {
+        TestIdx(idx)
+    }
+
+    fn idx(&self) -> usize {
+        self.0
+    }
+
+    fn incr(&mut self) {
+        self.0 += 1;
+    }
+
+    fn incr_by(&mut self, by: usize) {
+        self.0 += by;
+    }
+}
+
+fn set_of(domain_size: usize, elems: &[usize]) -> BitSet<TestIdx> {
+    let mut set = BitSet::new(domain_size);
+    for &elem in elems {
+        set.insert(TestIdx::new(elem));
+    }
+    set
+}
+
+fn elems(set: &BitSet<TestIdx>) -> Vec<usize> {
+    set.iter().map(|idx| idx.idx()).collect()
+}
+
+#[test]
+fn test_new_is_empty() {
+    let set: BitSet<TestIdx> = BitSet::new(100);
+    assert_eq!(set.domain_size(), 100);
+    assert_eq!(set.count(), 0);
+    assert!(set.is_empty());
+    assert_eq!(set.iter().next(), None);
+}
+
+#[test]
+fn test_insert_and_contains() {
+    let mut set: BitSet<TestIdx> = BitSet::new(200);
+    assert!(set.insert(TestIdx::new(3)));
+    assert!(!set.insert(TestIdx::new(3)));
+    assert!(set.insert(TestIdx::new(64)));
+
+    assert!(set.contains(TestIdx::new(3)));
+    assert!(set.contains(TestIdx::new(64)));
+    assert!(!set.contains(TestIdx::new(63)));
+    assert_eq!(set.count(), 2);
+}
+
+#[test]
+fn test_remove() {
+    let mut set = set_of(10, &[1, 5]);
+    assert!(set.remove(TestIdx::new(1)));
+    assert!(!set.remove(TestIdx::new(1)));
+    assert!(!set.contains(TestIdx::new(1)));
+    assert_eq!(elems(&set), vec![5]);
+}
+
+#[test]
+fn test_iter_scattered_in_order() {
+    let set = set_of(300, &[299, 0, 130, 63, 64, 7, 128]);
+    assert_eq!(elems(&set), vec![0, 7, 63, 64, 128, 130, 299]);
+    assert_eq!(set.count(), 7);
+}
+
+#[test]
+fn test_union_with() {
+    let mut a = set_of(150, &[1, 70, 140]);
+    let b = set_of(150, &[2, 70, 149]);
+
+    assert!(a.union_with(&b));
+    assert_eq!(elems(&a), vec![1, 2, 70, 140, 149]);
+    assert!(!a.union_with(&b));
+}
+
+#[test]
+fn test_intersect_with() {
+    let mut a = set_of(150, &[1, 70, 140]);
+    let b = set_of(150, &[2, 70, 140]);
+
+    assert!(a.intersect_with(&b));
+    assert_eq!(elems(&a), vec![70, 140]);
+    assert!(!a.intersect_with(&b));
+}
+
+#[test]
+fn test_subtract() {
+    let mut a = set_of(150, &[1, 70, 140]);
+    let b = set_of(150, &[2, 70, 140]);
+
+    assert!(a.subtract(&b));
+    assert_eq!(elems(&a), vec![1]);
+    assert!(!a.subtract(&b));
+}
+
+#[test]
+#[should_panic]
+fn test_insert_out_of_domain_panics() {
+    let mut set: BitSet<TestIdx> = BitSet::new(10);
+    set.insert(TestIdx::new(10));
+}
+
+#[test]
+#[should_panic]
+fn test_union_with_mismatched_domains_panics() {
+    let mut a: BitSet<TestIdx> = BitSet::new(10);
+    let b: BitSet<TestIdx> = BitSet::new(20);
+    a.union_with(&b);
+}
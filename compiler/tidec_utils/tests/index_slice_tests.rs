@@ -285,3 +285,37 @@ fn test_into_iterator_mut() {
     }
     assert_eq!(raw, [3, 6, 9, 12]);
 }
+
+#[test]
+fn test_as_ref_slice() {
+    let raw = [4, 5, 6];
+    let slice: &IdxSlice<TestIdx, i32> = IdxSlice::from_raw(&raw);
+    let as_slice: &[i32] = slice.as_ref();
+    assert_eq!(as_slice, &[4, 5, 6]);
+}
+
+#[test]
+fn test_zip() {
+    let tys = ["i32", "bool", "f64"];
+    let states = [true, false, true];
+    let tys: &IdxSlice<TestIdx, &str> = IdxSlice::from_raw(&tys);
+    let states: &IdxSlice<TestIdx, bool> = IdxSlice::from_raw(&states);
+
+    let zipped: Vec<_> = tys.zip(states).collect();
+    assert_eq!(
+        zipped,
+        vec![
+            (TestIdx::new(0), &"i32", &true),
+            (TestIdx::new(1), &"bool", &false),
+            (TestIdx::new(2), &"f64", &true),
+        ]
+    );
+}
+
+#[test]
+#[should_panic(expected = "cannot zip index slices of different lengths")]
+fn test_zip_mismatched_lengths() {
+    let a: &IdxSlice<TestIdx, i32> = IdxSlice::from_raw(&[1, 2, 3]);
+    let b: &IdxSlice<TestIdx, i32> = IdxSlice::from_raw(&[1, 2]);
+    let _ = a.zip(b);
+}
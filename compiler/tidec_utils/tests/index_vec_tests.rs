@@ -1,5 +1,5 @@
 use tidec_utils::idx::Idx;
-use tidec_utils::index_vec::IdxVec;
+use tidec_utils::index_vec::{IdxVec, NonContiguousIndex};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 struct TestIdx(usize);
@@ -46,6 +46,57 @@ fn test_with_capacity() {
     assert!(vec.raw.capacity() >= 10);
 }
 
+#[test]
+fn test_reserve() {
+    let mut vec: IdxVec<TestIdx, i32> = IdxVec::from_raw(vec![1, 2]);
+    vec.reserve(100);
+    assert!(vec.capacity() >= 102);
+
+    let mut vec: IdxVec<TestIdx, i32> = IdxVec::new();
+    vec.reserve_exact(100);
+    assert!(vec.capacity() >= 100);
+    assert_eq!(vec.len(), 0);
+}
+
+#[test]
+fn test_shrink_to() {
+    let mut vec: IdxVec<TestIdx, i32> = IdxVec::with_capacity(100);
+    vec.push(1);
+    vec.push(2);
+    vec.shrink_to(10);
+    assert!(vec.capacity() >= 10);
+    assert!(vec.capacity() < 100);
+
+    // The capacity never drops below the length.
+    vec.shrink_to(0);
+    assert!(vec.capacity() >= 2);
+    assert_eq!(vec.raw, vec![1, 2]);
+}
+
+#[test]
+fn test_clear() {
+    let mut vec: IdxVec<TestIdx, i32> = IdxVec::from_raw(vec![1, 2, 3]);
+    let capacity = vec.capacity();
+    vec.clear();
+    assert_eq!(vec.len(), 0);
+    assert_eq!(vec.capacity(), capacity);
+    assert_eq!(vec.push(4), TestIdx::new(0));
+}
+
+#[test]
+fn test_expect_len() {
+    let vec: IdxVec<TestIdx, i32> = IdxVec::from_raw(vec![1, 2, 3]);
+    vec.expect_len(3);
+}
+
+#[test]
+#[cfg(debug_assertions)]
+#[should_panic(expected = "index vector has an unexpected length")]
+fn test_expect_len_mismatch() {
+    let vec: IdxVec<TestIdx, i32> = IdxVec::from_raw(vec![1, 2, 3]);
+    vec.expect_len(2);
+}
+
 #[test]
 fn test_push() {
     let mut vec: IdxVec<TestIdx, i32> = IdxVec::new();
@@ -77,6 +128,25 @@ fn test_from_fn_n() {
     assert_eq!(vec[TestIdx::new(2)], 4);
 }
 
+#[test]
+fn test_from_fn() {
+    let universe: IdxVec<TestIdx, &str> = IdxVec::from_raw(vec!["a", "b", "c"]);
+    let mut seen = Vec::new();
+    let vec: IdxVec<TestIdx, usize> = IdxVec::from_fn(
+        |idx: TestIdx| {
+            seen.push(idx);
+            universe[idx].len() + idx.idx()
+        },
+        &universe,
+    );
+    assert_eq!(vec.len(), universe.len());
+    assert_eq!(
+        seen,
+        vec![TestIdx::new(0), TestIdx::new(1), TestIdx::new(2)]
+    );
+    assert_eq!(vec[TestIdx::new(2)], 3);
+}
+
 #[test]
 fn test_pop() {
     let mut vec: IdxVec<TestIdx, i32> = IdxVec::new();
@@ -100,6 +170,26 @@ fn test_into_iter_enumerated() {
     assert_eq!(items[2], (TestIdx::new(2), 30));
 }
 
+#[test]
+fn test_iter_enumerated_keeps_ownership() {
+    let vec: IdxVec<TestIdx, i32> = IdxVec::from_raw(vec![10, 20, 30]);
+    let items: Vec<_> = IdxVec::iter_enumerated(&vec).collect();
+
+    assert_eq!(
+        items,
+        vec![
+            (TestIdx::new(0), &10),
+            (TestIdx::new(1), &20),
+            (TestIdx::new(2), &30)
+        ]
+    );
+    assert_eq!(vec.len(), 3);
+    assert_eq!(
+        vec.iter_enumerated().next_back(),
+        Some((TestIdx::new(2), &30))
+    );
+}
+
 #[test]
 fn test_drain() {
     let mut vec: IdxVec<TestIdx, i32> = IdxVec::from_raw(vec![1, 2, 3, 4, 5]);
@@ -125,6 +215,16 @@ fn test_drain_enumerated() {
     assert_eq!(vec.raw, vec![10, 50]);
 }
 
+#[test]
+fn test_extract_if() {
+    let mut vec: IdxVec<TestIdx, i32> = IdxVec::from_raw(vec![1, 2, 3, 4, 5, 6, 7]);
+    let odd: Vec<_> = vec.extract_if(|x| x % 2 == 1).collect();
+
+    assert_eq!(odd, vec![1, 3, 5, 7]);
+    assert_eq!(vec.raw, vec![2, 4, 6]);
+    assert_eq!(vec[TestIdx::new(1)], 4);
+}
+
 #[test]
 fn test_ensure_contains_elem() {
     let mut vec: IdxVec<TestIdx, i32> = IdxVec::new();
@@ -241,3 +341,70 @@ fn test_default() {
     assert_eq!(vec.len(), 0);
     assert!(vec.is_empty());
 }
+
+#[test]
+fn test_into_vec() {
+    let vec: IdxVec<TestIdx, i32> = IdxVec::from_raw(vec![1, 2, 3]);
+    let raw: Vec<i32> = vec.into_vec();
+    assert_eq!(raw, vec![1, 2, 3]);
+}
+
+#[test]
+fn test_as_ref_slice() {
+    fn sum(values: &[i32]) -> i32 {
+        values.iter().sum()
+    }
+
+    let vec: IdxVec<TestIdx, i32> = IdxVec::from_raw(vec![1, 2, 3]);
+    assert_eq!(sum(vec.as_ref()), 6);
+    assert_eq!(sum(vec.as_slice().as_ref()), 6);
+}
+
+#[test]
+fn test_entry_grows_with_defaults() {
+    let mut vec: IdxVec<TestIdx, i32> = IdxVec::from_raw(vec![1]);
+    *vec.entry(TestIdx::new(4)) = 42;
+    assert_eq!(vec.raw, vec![1, 0, 0, 0, 42]);
+
+    // A second call returns the existing entry without overwriting it.
+    assert_eq!(*vec.entry(TestIdx::new(4)), 42);
+    assert_eq!(*vec.entry(TestIdx::new(0)), 1);
+    assert_eq!(vec.len(), 5);
+}
+
+#[test]
+fn test_get_or_insert_with() {
+    let mut vec: IdxVec<TestIdx, i32> = IdxVec::new();
+    assert_eq!(*vec.get_or_insert_with(TestIdx::new(2), || 7), 7);
+    assert_eq!(vec.raw, vec![0, 0, 7]);
+
+    // The closure is not called for an existing entry.
+    assert_eq!(*vec.get_or_insert_with(TestIdx::new(2), || 9), 7);
+    assert_eq!(*vec.get_or_insert_with(TestIdx::new(1), || 9), 0);
+    assert_eq!(vec.len(), 3);
+}
+
+#[test]
+fn test_from_iter_enumerated_contiguous() {
+    let pairs = vec![
+        (TestIdx::new(0), 'a'),
+        (TestIdx::new(1), 'b'),
+        (TestIdx::new(2), 'c'),
+    ];
+    let vec = IdxVec::from_iter_enumerated(pairs.into_iter()).unwrap();
+    assert_eq!(vec.raw, vec!['a', 'b', 'c']);
+}
+
+#[test]
+fn test_from_iter_enumerated_gap() {
+    let pairs = vec![(TestIdx::new(0), 'a'), (TestIdx::new(2), 'c')];
+    let err = IdxVec::from_iter_enumerated(pairs.into_iter()).unwrap_err();
+    assert_eq!(
+        err,
+        NonContiguousIndex {
+            expected: TestIdx::new(1),
+            found: TestIdx::new(2),
+        }
+    );
+    assert_eq!(err.to_string(), "non-contiguous index: expected 1, found 2");
+}